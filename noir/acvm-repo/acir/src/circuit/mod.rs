@@ -2,15 +2,17 @@ pub mod black_box_functions;
 pub mod brillig;
 pub mod directives;
 pub mod opcodes;
+mod serialization;
+pub mod text;
 
 use crate::native_types::Witness;
 pub use opcodes::Opcode;
+pub use serialization::{CircuitDeserializationError, CIRCUIT_FORMAT_VERSION};
 use thiserror::Error;
 
-use std::{io::prelude::*, num::ParseIntError, str::FromStr};
+use std::{num::ParseIntError, str::FromStr};
 
 use base64::Engine;
-use flate2::Compression;
 use serde::{de::Error as DeserializationError, Deserialize, Deserializer, Serialize, Serializer};
 
 use std::collections::BTreeSet;
@@ -126,30 +128,17 @@ impl Circuit {
         PublicInputs(public_inputs)
     }
 
-    fn write<W: std::io::Write>(&self, writer: W) -> std::io::Result<()> {
-        let buf = bincode::serialize(self).unwrap();
-        let mut encoder = flate2::write::GzEncoder::new(writer, Compression::default());
-        encoder.write_all(&buf)?;
-        encoder.finish()?;
-        Ok(())
-    }
-
-    fn read<R: std::io::Read>(reader: R) -> std::io::Result<Self> {
-        let mut gz_decoder = flate2::read::GzDecoder::new(reader);
-        let mut buf_d = Vec::new();
-        gz_decoder.read_to_end(&mut buf_d)?;
-        bincode::deserialize(&buf_d)
-            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))
-    }
-
     pub fn serialize_circuit(circuit: &Circuit) -> Vec<u8> {
         let mut circuit_bytes: Vec<u8> = Vec::new();
-        circuit.write(&mut circuit_bytes).expect("expected circuit to be serializable");
+        serialization::write(circuit, &mut circuit_bytes)
+            .expect("expected circuit to be serializable");
         circuit_bytes
     }
 
-    pub fn deserialize_circuit(serialized_circuit: &[u8]) -> std::io::Result<Self> {
-        Circuit::read(serialized_circuit)
+    pub fn deserialize_circuit(
+        serialized_circuit: &[u8],
+    ) -> Result<Self, CircuitDeserializationError> {
+        serialization::read(serialized_circuit)
     }
 
     // Serialize and base64 encode circuit
@@ -233,10 +222,11 @@ mod tests {
 
     use super::{
         opcodes::{BlackBoxFuncCall, FunctionInput},
-        Circuit, Compression, Opcode, PublicInputs,
+        Circuit, Opcode, PublicInputs,
     };
     use crate::native_types::Witness;
     use acir_field::FieldElement;
+    use flate2::Compression;
 
     fn and_opcode() -> Opcode {
         Opcode::BlackBoxFuncCall(BlackBoxFuncCall::AND {
@@ -0,0 +1,243 @@
+//! Serialized circuits are gzip compressed bincode, whose gzip header carries an "extra field"
+//! identifying the version of the serialization format and the field the circuit was compiled for.
+//!
+//! The extra field is ignored by gzip decoders which don't know about it, so backends which only
+//! decompress the circuit are unaffected by it.
+
+use std::io::Read;
+
+use acir_field::FieldElement;
+use flate2::{read::GzDecoder, Compression, GzBuilder};
+use thiserror::Error;
+
+use super::Circuit;
+
+/// The version of the serialization format of [`Circuit`]s.
+///
+/// This must be incremented with any change to the serialized types, such as adding a variant to
+/// [`Opcode`][super::Opcode], along with a way to decode circuits serialized with the previous version.
+pub const CIRCUIT_FORMAT_VERSION: u32 = 1;
+
+/// The version of circuits serialized before the format header was introduced.
+const LEGACY_FORMAT_VERSION: u32 = 0;
+
+/// The identifier of the gzip extra subfield holding the format header.
+const SUBFIELD_ID: [u8; 2] = *b"AC";
+/// The magic bytes at the start of the format header.
+const MAGIC: [u8; 4] = *b"ACIR";
+
+#[derive(Debug, Error)]
+pub enum CircuitDeserializationError {
+    #[error("Failed to decompress circuit: {0}")]
+    Decompression(#[from] std::io::Error),
+    #[error("Circuit has a malformed format header")]
+    MalformedHeader,
+    #[error("Circuit was serialized with format version {found}, but only versions up to {supported} are supported. Try upgrading the toolchain")]
+    UnsupportedVersion { found: u32, supported: u32 },
+    #[error("Circuit was compiled for the field with modulus 0x{found}, but ACIR is compiled for the field with modulus 0x{expected}")]
+    FieldMismatch { expected: String, found: String },
+    #[error("Failed to deserialize circuit with format version {version}: {source}. It may have to be recompiled")]
+    Deserialization { version: u32, source: bincode::Error },
+}
+
+/// The header identifying the format of a serialized circuit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FormatHeader {
+    version: u32,
+    /// The big-endian bytes of the modulus of the field the circuit was compiled for.
+    field_modulus: Vec<u8>,
+}
+
+impl FormatHeader {
+    fn current() -> Self {
+        FormatHeader {
+            version: CIRCUIT_FORMAT_VERSION,
+            field_modulus: FieldElement::modulus().to_bytes_be(),
+        }
+    }
+
+    /// Encodes the header as a gzip extra field holding a single subfield.
+    fn to_extra_field(&self) -> Vec<u8> {
+        let mut data = MAGIC.to_vec();
+        data.extend_from_slice(&self.version.to_le_bytes());
+        data.extend_from_slice(&self.field_modulus);
+
+        let mut extra = SUBFIELD_ID.to_vec();
+        extra.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        extra.extend_from_slice(&data);
+        extra
+    }
+
+    /// Decodes the header from a gzip extra field, returning `None` if it holds no header.
+    fn from_extra_field(mut extra: &[u8]) -> Result<Option<Self>, CircuitDeserializationError> {
+        while extra.len() >= 4 {
+            let id = &extra[0..2];
+            let length = u16::from_le_bytes([extra[2], extra[3]]) as usize;
+            let data =
+                extra.get(4..4 + length).ok_or(CircuitDeserializationError::MalformedHeader)?;
+            if id == SUBFIELD_ID {
+                if data.len() < 8 || data[0..4] != MAGIC {
+                    return Err(CircuitDeserializationError::MalformedHeader);
+                }
+                let version = u32::from_le_bytes(data[4..8].try_into().unwrap());
+                return Ok(Some(FormatHeader { version, field_modulus: data[8..].to_vec() }));
+            }
+            extra = &extra[4 + length..];
+        }
+        Ok(None)
+    }
+}
+
+pub(super) fn write<W: std::io::Write>(circuit: &Circuit, writer: W) -> std::io::Result<()> {
+    let buf = bincode::serialize(circuit).unwrap();
+    let mut encoder = GzBuilder::new()
+        .extra(FormatHeader::current().to_extra_field())
+        .write(writer, Compression::default());
+    std::io::Write::write_all(&mut encoder, &buf)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+pub(super) fn read<R: std::io::Read>(reader: R) -> Result<Circuit, CircuitDeserializationError> {
+    let mut gz_decoder = GzDecoder::new(reader);
+    let mut buf_d = Vec::new();
+    gz_decoder.read_to_end(&mut buf_d)?;
+
+    let extra = gz_decoder.header().and_then(|header| header.extra()).unwrap_or_default();
+    let version = match FormatHeader::from_extra_field(extra)? {
+        Some(header) => {
+            if header.version > CIRCUIT_FORMAT_VERSION {
+                return Err(CircuitDeserializationError::UnsupportedVersion {
+                    found: header.version,
+                    supported: CIRCUIT_FORMAT_VERSION,
+                });
+            }
+            let current = FormatHeader::current();
+            if header.field_modulus != current.field_modulus {
+                return Err(CircuitDeserializationError::FieldMismatch {
+                    expected: to_hex(&current.field_modulus),
+                    found: to_hex(&header.field_modulus),
+                });
+            }
+            header.version
+        }
+        // Circuits serialized before the header was introduced have no way of checking their
+        // field, and are decoded assuming the current field.
+        None => LEGACY_FORMAT_VERSION,
+    };
+
+    match version {
+        // The serialized types have not changed since the header was introduced, so legacy
+        // circuits share the layout of the current version.
+        LEGACY_FORMAT_VERSION | CIRCUIT_FORMAT_VERSION => bincode::deserialize(&buf_d)
+            .map_err(|source| CircuitDeserializationError::Deserialization { version, source }),
+        _ => unreachable!("versions newer than the current one are rejected above"),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{Compression, GzBuilder};
+
+    use super::{read, write, CircuitDeserializationError, FormatHeader, CIRCUIT_FORMAT_VERSION};
+    use crate::circuit::{Circuit, Opcode};
+    use crate::native_types::Witness;
+
+    fn circuit() -> Circuit {
+        Circuit {
+            current_witness_index: 2,
+            opcodes: vec![Opcode::AssertZero(Witness(1) + Witness(2))],
+            ..Circuit::default()
+        }
+    }
+
+    /// Compresses `bytes` with `extra` as the gzip extra field, if any.
+    fn compress(bytes: &[u8], extra: Option<Vec<u8>>) -> Vec<u8> {
+        let mut builder = GzBuilder::new();
+        if let Some(extra) = extra {
+            builder = builder.extra(extra);
+        }
+        let mut compressed = Vec::new();
+        let mut encoder = builder.write(&mut compressed, Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap();
+        compressed
+    }
+
+    #[test]
+    fn writes_format_header() {
+        let mut bytes = Vec::new();
+        write(&circuit(), &mut bytes).unwrap();
+
+        let decoder = flate2::read::GzDecoder::new(bytes.as_slice());
+        let extra = decoder.header().unwrap().extra().unwrap();
+        assert_eq!(FormatHeader::from_extra_field(extra).unwrap(), Some(FormatHeader::current()));
+
+        assert_eq!(read(bytes.as_slice()).unwrap(), circuit());
+    }
+
+    #[test]
+    fn reads_legacy_circuits_without_header() {
+        let bytes = compress(&bincode::serialize(&circuit()).unwrap(), None);
+        assert_eq!(read(bytes.as_slice()).unwrap(), circuit());
+
+        // Other extra subfields are ignored
+        let bytes =
+            compress(&bincode::serialize(&circuit()).unwrap(), Some(b"XY\x01\x00z".to_vec()));
+        assert_eq!(read(bytes.as_slice()).unwrap(), circuit());
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let header =
+            FormatHeader { version: CIRCUIT_FORMAT_VERSION + 1, ..FormatHeader::current() };
+        let bytes = compress(&[1, 2, 3], Some(header.to_extra_field()));
+        assert!(matches!(
+            read(bytes.as_slice()),
+            Err(CircuitDeserializationError::UnsupportedVersion { found, supported })
+                if found == CIRCUIT_FORMAT_VERSION + 1 && supported == CIRCUIT_FORMAT_VERSION
+        ));
+    }
+
+    #[test]
+    fn rejects_circuits_for_other_fields() {
+        let header = FormatHeader { field_modulus: vec![0x17], ..FormatHeader::current() };
+        let bytes =
+            compress(&bincode::serialize(&circuit()).unwrap(), Some(header.to_extra_field()));
+        let error = read(bytes.as_slice()).unwrap_err();
+        assert!(matches!(
+            &error,
+            CircuitDeserializationError::FieldMismatch { found, .. } if found == "17"
+        ));
+        assert!(error
+            .to_string()
+            .starts_with("Circuit was compiled for the field with modulus 0x17,"));
+    }
+
+    #[test]
+    fn rejects_malformed_headers() {
+        let mut extra = FormatHeader::current().to_extra_field();
+        extra[4..8].copy_from_slice(b"NOIR");
+        let bytes = compress(&bincode::serialize(&circuit()).unwrap(), Some(extra));
+        assert!(matches!(
+            read(bytes.as_slice()),
+            Err(CircuitDeserializationError::MalformedHeader)
+        ));
+    }
+
+    #[test]
+    fn reports_version_of_undecodable_circuits() {
+        let bytes = compress(&[0xff; 4], Some(FormatHeader::current().to_extra_field()));
+        assert!(matches!(
+            read(bytes.as_slice()),
+            Err(CircuitDeserializationError::Deserialization { version, .. })
+                if version == CIRCUIT_FORMAT_VERSION
+        ));
+    }
+}
@@ -0,0 +1,237 @@
+use super::ParseError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Token {
+    Ident(String),
+    /// A decimal or `0x` prefixed hexadecimal integer, as written in the source.
+    Int(String),
+    Str(String),
+    LeftParen,
+    RightParen,
+    LeftBracket,
+    RightBracket,
+    LeftBrace,
+    RightBrace,
+    Comma,
+    Colon,
+    DoubleColon,
+    Dot,
+    Equal,
+    Plus,
+    Minus,
+    Star,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Ident(ident) => write!(f, "`{ident}`"),
+            Token::Int(int) => write!(f, "`{int}`"),
+            Token::Str(string) => write!(f, "{string:?}"),
+            Token::LeftParen => write!(f, "`(`"),
+            Token::RightParen => write!(f, "`)`"),
+            Token::LeftBracket => write!(f, "`[`"),
+            Token::RightBracket => write!(f, "`]`"),
+            Token::LeftBrace => write!(f, "`{{`"),
+            Token::RightBrace => write!(f, "`}}`"),
+            Token::Comma => write!(f, "`,`"),
+            Token::Colon => write!(f, "`:`"),
+            Token::DoubleColon => write!(f, "`::`"),
+            Token::Dot => write!(f, "`.`"),
+            Token::Equal => write!(f, "`=`"),
+            Token::Plus => write!(f, "`+`"),
+            Token::Minus => write!(f, "`-`"),
+            Token::Star => write!(f, "`*`"),
+        }
+    }
+}
+
+/// The line and column (both starting from 1) at which a token starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Position {
+    pub(super) line: usize,
+    pub(super) column: usize,
+}
+
+/// Splits `source` into tokens, also returning the position of the end of the source.
+pub(super) fn tokenize(source: &str) -> Result<(Vec<(Token, Position)>, Position), ParseError> {
+    let mut lexer = Lexer { chars: source.chars().peekable(), line: 1, column: 1 };
+    let mut tokens = Vec::new();
+    while let Some(token) = lexer.next_token()? {
+        tokens.push(token);
+    }
+    Ok((tokens, lexer.position()))
+}
+
+struct Lexer<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn position(&self) -> Position {
+        Position { line: self.line, column: self.column }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let char = self.chars.next()?;
+        if char == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(char)
+    }
+
+    fn eat_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let mut result = String::new();
+        while let Some(&char) = self.chars.peek() {
+            if !predicate(char) {
+                break;
+            }
+            result.push(char);
+            self.bump();
+        }
+        result
+    }
+
+    fn next_token(&mut self) -> Result<Option<(Token, Position)>, ParseError> {
+        loop {
+            self.eat_while(char::is_whitespace);
+            let mut rest = self.chars.clone();
+            if rest.next() == Some('/') && rest.next() == Some('/') {
+                self.eat_while(|char| char != '\n');
+            } else {
+                break;
+            }
+        }
+
+        let position = self.position();
+        let Some(char) = self.bump() else {
+            return Ok(None);
+        };
+        let token = match char {
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '[' => Token::LeftBracket,
+            ']' => Token::RightBracket,
+            '{' => Token::LeftBrace,
+            '}' => Token::RightBrace,
+            ',' => Token::Comma,
+            '.' => Token::Dot,
+            '=' => Token::Equal,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            ':' => {
+                if self.chars.peek() == Some(&':') {
+                    self.bump();
+                    Token::DoubleColon
+                } else {
+                    Token::Colon
+                }
+            }
+            '"' => Token::Str(self.string(position)?),
+            '0'..='9' => {
+                let rest = self.eat_while(|char| char.is_ascii_alphanumeric());
+                Token::Int(format!("{char}{rest}"))
+            }
+            char if char.is_ascii_alphabetic() || char == '_' => {
+                let rest = self.eat_while(|char| char.is_ascii_alphanumeric() || char == '_');
+                Token::Ident(format!("{char}{rest}"))
+            }
+            char => {
+                return Err(ParseError::new(position, format!("unexpected character {char:?}")))
+            }
+        };
+        Ok(Some((token, position)))
+    }
+
+    /// Lexes the rest of a string literal, whose escape sequences are those printed by
+    /// the `Debug` implementation of `str`.
+    fn string(&mut self, start: Position) -> Result<String, ParseError> {
+        let mut result = String::new();
+        loop {
+            let position = self.position();
+            match self.bump() {
+                None => return Err(ParseError::new(start, "unterminated string".to_string())),
+                Some('"') => return Ok(result),
+                Some('\\') => {
+                    let escaped = match self.bump() {
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('0') => '\0',
+                        Some('\\') => '\\',
+                        Some('"') => '"',
+                        Some('\'') => '\'',
+                        Some('u') => self.unicode_escape(position)?,
+                        _ => {
+                            return Err(ParseError::new(
+                                position,
+                                "invalid escape sequence".to_string(),
+                            ))
+                        }
+                    };
+                    result.push(escaped);
+                }
+                Some(char) => result.push(char),
+            }
+        }
+    }
+
+    fn unicode_escape(&mut self, position: Position) -> Result<char, ParseError> {
+        let error = || ParseError::new(position, "invalid unicode escape sequence".to_string());
+        if self.bump() != Some('{') {
+            return Err(error());
+        }
+        let digits = self.eat_while(|char| char.is_ascii_hexdigit());
+        if self.bump() != Some('}') {
+            return Err(error());
+        }
+        u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32).ok_or_else(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tokenize, Token};
+
+    #[test]
+    fn tokenizes_paths_numbers_and_comments() {
+        let tokens: Vec<_> = tokenize("BLACKBOX::AND(lhs: w1:4) // a comment\n-0x1f")
+            .unwrap()
+            .0
+            .into_iter()
+            .map(|(token, _)| token)
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Ident("BLACKBOX".into()),
+                Token::DoubleColon,
+                Token::Ident("AND".into()),
+                Token::LeftParen,
+                Token::Ident("lhs".into()),
+                Token::Colon,
+                Token::Ident("w1".into()),
+                Token::Colon,
+                Token::Int("4".into()),
+                Token::RightParen,
+                Token::Minus,
+                Token::Int("0x1f".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn unescapes_strings() {
+        let message = "a \"quoted\"\tmessage\\\n\u{1b}";
+        let (tokens, _) = tokenize(&format!("{message:?}")).unwrap();
+        assert_eq!(tokens[0].0, Token::Str(message.to_string()));
+
+        assert!(tokenize("\"unterminated").is_err());
+    }
+}
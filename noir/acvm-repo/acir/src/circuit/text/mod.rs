@@ -0,0 +1,530 @@
+//! A human-readable textual format for [`Circuit`]s, which can be parsed back into the exact
+//! same circuit.
+//!
+//! Unlike the [`Display`][std::fmt::Display] implementation of [`Circuit`], which abbreviates
+//! long inputs and Brillig bytecode, this format covers every part of a circuit so that it can be
+//! used to write ACVM test fixtures by hand, to review changes to circuits as text diffs and to
+//! edit circuits before compiling or executing them.
+//!
+//! A circuit is written as a header followed by its opcodes:
+//!
+//! ```text
+//! current witness index: 4
+//! private parameters: [w1, w2]
+//! public parameters: []
+//! return values: [w4]
+//! assert message 1: "w3 must fit in 8 bits"
+//!
+//! ASSERT w1*w2 - w3 = 0
+//! BLACKBOX::RANGE(input: w3:8)
+//! BRILLIG(inputs: [w3], outputs: [w4]) {
+//!     binary_field_op(destination: r0, op: add, lhs: r0, rhs: r0)
+//!     stop
+//! }
+//! ```
+//!
+//! - Witnesses are written `w<index>`, Brillig registers `r<index>` and memory blocks `b<index>`.
+//! - Field elements are written in decimal or, when large, in `0x` prefixed hexadecimal.
+//!   Either may be negated with a leading `-`.
+//! - Expressions are sums of constants, witnesses and products of two witnesses, each of which
+//!   may be multiplied by a constant coefficient, e.g. `2*w1*w2 - w3 + 5`.
+//!   The quadratic terms, linear terms and constant of an [`Expression`][crate::native_types::Expression]
+//!   are printed in that order, and the terms are kept in the order in which they are written.
+//! - `ASSERT <lhs> = <rhs>` is an [`Opcode::AssertZero`] constraining `lhs - rhs` to be zero.
+//! - Every other opcode is written `NAME(field: value, ...)`, where the fields are named after
+//!   those of the corresponding Rust type and may be given in any order.
+//!   The opcodes are `BLACKBOX::<FUNCTION>`, named after [`BlackBoxFunc::name`][crate::BlackBoxFunc::name],
+//!   `DIR::QUOTIENT`, `DIR::TO_LE_RADIX`, `DIR::PERMUTATION_SORT`, `MEMORY_INIT`, `MEMORY_READ`,
+//!   `MEMORY_WRITE`, `MEMORY_OP` (for memory operations which are neither reads nor writes) and `BRILLIG`.
+//! - Black box function inputs are written `w<index>:<num_bits>`, and the two outputs of functions
+//!   returning a point are written as a list.
+//! - The bytecode of a `BRILLIG` opcode is written in braces, one Brillig opcode per line.
+//!   Brillig opcodes are named after the snake case form of their Rust variants, e.g.
+//!   `binary_int_op(destination: r2, op: less_than, bit_size: 32, lhs: r0, rhs: r1)`,
+//!   with black box operations written `black_box::<function>(...)`.
+//!   Heap arrays are written `array(<pointer>, <size>)` and heap vectors `vector(<pointer>, <size>)`.
+//! - Optional predicates are written as a `predicate` field, which is omitted when there is none.
+//! - `//` starts a comment which runs until the end of the line.
+
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use super::Circuit;
+
+mod lexer;
+mod parser;
+mod printer;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{line}:{column}: {message}")]
+pub struct ParseError {
+    /// The line, starting from 1, at which the error occurred.
+    pub line: usize,
+    /// The column, starting from 1, at which the error occurred.
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(position: lexer::Position, message: String) -> Self {
+        ParseError { line: position.line, column: position.column, message }
+    }
+}
+
+impl Circuit {
+    /// Returns the circuit in the [textual format][self], which can be parsed back into the same
+    /// circuit with [`Circuit::from_str`].
+    pub fn to_text(&self) -> String {
+        printer::print_circuit(self)
+    }
+}
+
+impl FromStr for Circuit {
+    type Err = ParseError;
+
+    /// Parses a circuit written in the [textual format][self].
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        parser::parse_circuit(source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::str::FromStr;
+
+    use acir_field::FieldElement;
+    use brillig::{
+        BinaryFieldOp, BinaryIntOp, BlackBoxOp, HeapArray, HeapVector, Opcode as BrilligOpcode,
+        RegisterIndex, RegisterOrMemory, Value,
+    };
+
+    use super::ParseError;
+    use crate::circuit::{
+        brillig::{Brillig, BrilligInputs, BrilligOutputs},
+        directives::{Directive, QuotientDirective},
+        opcodes::{BlackBoxFuncCall, BlockId, FunctionInput, MemOp},
+        Circuit, Opcode, OpcodeLocation, PublicInputs,
+    };
+    use crate::native_types::{Expression, Witness};
+
+    fn input(witness: u32, num_bits: u32) -> FunctionInput {
+        FunctionInput { witness: Witness(witness), num_bits }
+    }
+
+    fn inputs(witnesses: std::ops::Range<u32>, num_bits: u32) -> Vec<FunctionInput> {
+        witnesses.map(|witness| input(witness, num_bits)).collect()
+    }
+
+    fn witnesses(witnesses: std::ops::Range<u32>) -> Vec<Witness> {
+        witnesses.map(Witness).collect()
+    }
+
+    fn black_box_calls() -> Vec<BlackBoxFuncCall> {
+        vec![
+            BlackBoxFuncCall::AND { lhs: input(1, 4), rhs: input(2, 4), output: Witness(3) },
+            BlackBoxFuncCall::XOR { lhs: input(1, 4), rhs: input(2, 4), output: Witness(3) },
+            BlackBoxFuncCall::RANGE { input: input(1, 8) },
+            BlackBoxFuncCall::SHA256 { inputs: inputs(1..4, 8), outputs: witnesses(4..36) },
+            BlackBoxFuncCall::Blake2s { inputs: inputs(1..4, 8), outputs: witnesses(4..36) },
+            BlackBoxFuncCall::Blake3 { inputs: inputs(1..4, 8), outputs: witnesses(4..36) },
+            BlackBoxFuncCall::SchnorrVerify {
+                public_key_x: input(1, 254),
+                public_key_y: input(2, 254),
+                signature: inputs(3..67, 8),
+                message: inputs(67..70, 8),
+                output: Witness(70),
+            },
+            BlackBoxFuncCall::PedersenCommitment {
+                inputs: inputs(1..3, 254),
+                domain_separator: 7,
+                outputs: (Witness(3), Witness(4)),
+            },
+            BlackBoxFuncCall::PedersenHash {
+                inputs: inputs(1..3, 254),
+                domain_separator: 0,
+                output: Witness(3),
+            },
+            BlackBoxFuncCall::EcdsaSecp256k1 {
+                public_key_x: inputs(1..33, 8),
+                public_key_y: inputs(33..65, 8),
+                signature: inputs(65..129, 8),
+                hashed_message: inputs(129..161, 8),
+                output: Witness(161),
+            },
+            BlackBoxFuncCall::EcdsaSecp256r1 {
+                public_key_x: inputs(1..33, 8),
+                public_key_y: inputs(33..65, 8),
+                signature: inputs(65..129, 8),
+                hashed_message: inputs(129..161, 8),
+                output: Witness(161),
+            },
+            BlackBoxFuncCall::FixedBaseScalarMul {
+                low: input(1, 128),
+                high: input(2, 128),
+                outputs: (Witness(3), Witness(4)),
+            },
+            BlackBoxFuncCall::EmbeddedCurveAdd {
+                input1_x: input(1, 254),
+                input1_y: input(2, 254),
+                input2_x: input(3, 254),
+                input2_y: input(4, 254),
+                outputs: (Witness(5), Witness(6)),
+            },
+            BlackBoxFuncCall::EmbeddedCurveDouble {
+                input_x: input(1, 254),
+                input_y: input(2, 254),
+                outputs: (Witness(3), Witness(4)),
+            },
+            BlackBoxFuncCall::Keccak256 { inputs: inputs(1..4, 8), outputs: witnesses(4..36) },
+            BlackBoxFuncCall::Keccak256VariableLength {
+                inputs: inputs(1..4, 8),
+                var_message_size: input(4, 32),
+                outputs: witnesses(5..37),
+            },
+            BlackBoxFuncCall::Keccakf1600 { inputs: inputs(1..26, 64), outputs: witnesses(26..51) },
+            BlackBoxFuncCall::RecursiveAggregation {
+                verification_key: inputs(1..4, 254),
+                proof: inputs(4..8, 254),
+                public_inputs: inputs(8..9, 254),
+                key_hash: input(9, 254),
+            },
+        ]
+    }
+
+    fn brillig_bytecode() -> Vec<BrilligOpcode> {
+        let array = HeapArray { pointer: RegisterIndex(0), size: 2 };
+        let vector = HeapVector { pointer: RegisterIndex(1), size: RegisterIndex(2) };
+        let r = RegisterIndex;
+        vec![
+            BrilligOpcode::BinaryFieldOp {
+                destination: r(0),
+                op: BinaryFieldOp::Div,
+                lhs: r(1),
+                rhs: r(2),
+            },
+            BrilligOpcode::BinaryIntOp {
+                destination: r(0),
+                op: BinaryIntOp::LessThanEquals,
+                bit_size: 32,
+                lhs: r(1),
+                rhs: r(2),
+            },
+            BrilligOpcode::JumpIfNot { condition: r(0), location: 3 },
+            BrilligOpcode::JumpIf { condition: r(0), location: 4 },
+            BrilligOpcode::Jump { location: 5 },
+            BrilligOpcode::Call { location: 6 },
+            BrilligOpcode::Const { destination: r(0), value: Value::from(-FieldElement::one()) },
+            BrilligOpcode::Const {
+                destination: r(1),
+                value: Value::from(FieldElement::from(u128::MAX) * FieldElement::from(3u128)),
+            },
+            BrilligOpcode::Return,
+            BrilligOpcode::ForeignCall {
+                function: "print\n\"quoted\"".to_string(),
+                destinations: vec![RegisterOrMemory::RegisterIndex(r(0))],
+                inputs: vec![
+                    RegisterOrMemory::HeapArray(array),
+                    RegisterOrMemory::HeapVector(vector),
+                ],
+            },
+            BrilligOpcode::Mov { destination: r(0), source: r(1) },
+            BrilligOpcode::Load { destination: r(0), source_pointer: r(1) },
+            BrilligOpcode::Store { destination_pointer: r(0), source: r(1) },
+            BrilligOpcode::BlackBox(BlackBoxOp::Sha256 { message: vector, output: array }),
+            BrilligOpcode::BlackBox(BlackBoxOp::Blake2s { message: vector, output: array }),
+            BrilligOpcode::BlackBox(BlackBoxOp::Keccak256 { message: vector, output: array }),
+            BrilligOpcode::BlackBox(BlackBoxOp::EcdsaSecp256k1 {
+                hashed_msg: vector,
+                public_key_x: array,
+                public_key_y: array,
+                signature: array,
+                result: r(3),
+            }),
+            BrilligOpcode::BlackBox(BlackBoxOp::EcdsaSecp256r1 {
+                hashed_msg: vector,
+                public_key_x: array,
+                public_key_y: array,
+                signature: array,
+                result: r(3),
+            }),
+            BrilligOpcode::BlackBox(BlackBoxOp::SchnorrVerify {
+                public_key_x: r(3),
+                public_key_y: r(4),
+                message: vector,
+                signature: vector,
+                result: r(5),
+            }),
+            BrilligOpcode::BlackBox(BlackBoxOp::PedersenCommitment {
+                inputs: vector,
+                domain_separator: r(3),
+                output: array,
+            }),
+            BrilligOpcode::BlackBox(BlackBoxOp::PedersenHash {
+                inputs: vector,
+                domain_separator: r(3),
+                output: r(4),
+            }),
+            BrilligOpcode::BlackBox(BlackBoxOp::FixedBaseScalarMul {
+                low: r(3),
+                high: r(4),
+                result: array,
+            }),
+            BrilligOpcode::BlackBox(BlackBoxOp::EmbeddedCurveAdd {
+                input1_x: r(3),
+                input1_y: r(4),
+                input2_x: r(5),
+                input2_y: r(6),
+                result: array,
+            }),
+            BrilligOpcode::BlackBox(BlackBoxOp::EmbeddedCurveDouble {
+                input1_x: r(3),
+                input1_y: r(4),
+                result: array,
+            }),
+            BrilligOpcode::Trap,
+            BrilligOpcode::Stop,
+        ]
+    }
+
+    fn expression() -> Expression {
+        Expression {
+            mul_terms: vec![
+                (FieldElement::from(2u128), Witness(1), Witness(2)),
+                (-FieldElement::one(), Witness(3), Witness(3)),
+            ],
+            linear_combinations: vec![
+                (FieldElement::zero(), Witness(4)),
+                (-FieldElement::from(5u128), Witness(1)),
+                (FieldElement::one(), Witness(5)),
+            ],
+            q_c: -FieldElement::from(7u128),
+        }
+    }
+
+    fn circuit_with_every_opcode() -> Circuit {
+        let mut opcodes = vec![
+            Opcode::AssertZero(expression()),
+            Opcode::AssertZero(Expression::default()),
+            Opcode::AssertZero(Expression::from_field(-FieldElement::from(3u128))),
+            Opcode::AssertZero(Expression::from_field(
+                -FieldElement::from(u128::MAX) * FieldElement::from(u128::MAX),
+            )),
+            Opcode::Directive(Directive::Quotient(QuotientDirective {
+                a: expression(),
+                b: Witness(2).into(),
+                q: Witness(3),
+                r: Witness(4),
+                predicate: Some(Expression::one()),
+            })),
+            Opcode::Directive(Directive::ToLeRadix {
+                a: Witness(1).into(),
+                b: witnesses(2..10),
+                radix: 2,
+            }),
+            Opcode::Directive(Directive::PermutationSort {
+                inputs: vec![
+                    vec![Witness(1).into(), Witness(2).into()],
+                    vec![Witness(3).into(), expression()],
+                ],
+                tuple: 2,
+                bits: witnesses(5..8),
+                sort_by: vec![1, 0],
+            }),
+            Opcode::MemoryInit { block_id: BlockId(0), init: witnesses(1..4) },
+            Opcode::MemoryOp {
+                block_id: BlockId(0),
+                op: MemOp::read_at_mem_index(Expression::one(), Witness(4)),
+                predicate: None,
+            },
+            Opcode::MemoryOp {
+                block_id: BlockId(0),
+                op: MemOp::write_to_mem_index(Witness(5).into(), expression()),
+                predicate: Some(Witness(6).into()),
+            },
+            Opcode::MemoryOp {
+                block_id: BlockId(1),
+                op: MemOp {
+                    operation: Witness(7).into(),
+                    index: Expression::zero(),
+                    value: Witness(8).into(),
+                },
+                predicate: None,
+            },
+            Opcode::Brillig(Brillig {
+                inputs: vec![
+                    BrilligInputs::Single(expression()),
+                    BrilligInputs::Array(vec![Witness(1).into(), Expression::one()]),
+                    BrilligInputs::Array(vec![]),
+                ],
+                outputs: vec![
+                    BrilligOutputs::Simple(Witness(9)),
+                    BrilligOutputs::Array(witnesses(10..12)),
+                ],
+                bytecode: brillig_bytecode(),
+                predicate: Some(Witness(3).into()),
+            }),
+            Opcode::Brillig(Brillig {
+                inputs: vec![],
+                outputs: vec![],
+                bytecode: vec![],
+                predicate: None,
+            }),
+        ];
+        opcodes.extend(black_box_calls().into_iter().map(Opcode::BlackBoxFuncCall));
+
+        Circuit {
+            current_witness_index: 161,
+            opcodes,
+            private_parameters: BTreeSet::from([Witness(1), Witness(2)]),
+            public_parameters: PublicInputs(BTreeSet::from([Witness(3)])),
+            return_values: PublicInputs(BTreeSet::new()),
+            assert_messages: vec![
+                (OpcodeLocation::Acir(0), "first \"message\"\n".to_string()),
+                (OpcodeLocation::Brillig { acir_index: 11, brillig_index: 24 }, "trap".to_string()),
+            ],
+        }
+    }
+
+    #[test]
+    fn text_roundtrip() {
+        let circuit = circuit_with_every_opcode();
+        let text = circuit.to_text();
+        let parsed = Circuit::from_str(&text).unwrap_or_else(|error| panic!("{error}\n{text}"));
+        assert_eq!(parsed, circuit);
+        assert_eq!(parsed.to_text(), text);
+    }
+
+    #[test]
+    fn prints_readable_text() {
+        let circuit = Circuit {
+            current_witness_index: 3,
+            opcodes: vec![
+                Opcode::AssertZero(expression()),
+                Opcode::BlackBoxFuncCall(BlackBoxFuncCall::AND {
+                    lhs: input(1, 4),
+                    rhs: input(2, 4),
+                    output: Witness(3),
+                }),
+                Opcode::MemoryOp {
+                    block_id: BlockId(0),
+                    op: MemOp::read_at_mem_index(Expression::one(), Witness(4)),
+                    predicate: None,
+                },
+                Opcode::Brillig(Brillig {
+                    inputs: vec![BrilligInputs::Single(Witness(1).into())],
+                    outputs: vec![BrilligOutputs::Simple(Witness(2))],
+                    bytecode: vec![BrilligOpcode::Stop],
+                    predicate: None,
+                }),
+            ],
+            private_parameters: BTreeSet::from([Witness(1), Witness(2)]),
+            public_parameters: PublicInputs::default(),
+            return_values: PublicInputs(BTreeSet::from([Witness(3)])),
+            assert_messages: vec![(OpcodeLocation::Acir(1), "message".to_string())],
+        };
+
+        let expected = "current witness index: 3
+private parameters: [w1, w2]
+public parameters: []
+return values: [w3]
+assert message 1: \"message\"
+
+ASSERT 2*w1*w2 - w3*w3 + 0*w4 - 5*w1 + w5 - 7 = 0
+BLACKBOX::AND(lhs: w1:4, rhs: w2:4, output: w3)
+MEMORY_READ(block: b0, index: 1, value: w4)
+BRILLIG(inputs: [w1], outputs: [w2]) {
+    stop
+}
+";
+        assert_eq!(circuit.to_text(), expected);
+    }
+
+    #[test]
+    fn parses_hand_written_circuits() {
+        let text = "
+            current witness index: 3
+            private parameters: [w1, w2]
+            public parameters: []
+            return values: [w3]
+
+            // Comments and whitespace are ignored, and fields may be reordered
+            ASSERT w3 = w1*w2 + 0x10
+            BLACKBOX::range(input: w3:8)
+            MEMORY_WRITE(value: -w1, index: 0, block: b2, predicate: w2)
+        ";
+        let circuit = Circuit::from_str(text).unwrap();
+
+        let mut expected_expression = Expression::default();
+        expected_expression.push_addition_term(FieldElement::one(), Witness(3));
+        expected_expression.push_multiplication_term(-FieldElement::one(), Witness(1), Witness(2));
+        expected_expression.q_c = -FieldElement::from(16u128);
+
+        assert_eq!(
+            circuit.opcodes,
+            vec![
+                Opcode::AssertZero(expected_expression),
+                Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE { input: input(3, 8) }),
+                Opcode::MemoryOp {
+                    block_id: BlockId(2),
+                    op: MemOp::write_to_mem_index(
+                        Expression::zero(),
+                        Expression {
+                            linear_combinations: vec![(-FieldElement::one(), Witness(1))],
+                            ..Default::default()
+                        }
+                    ),
+                    predicate: Some(Witness(2).into()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_errors_with_their_position() {
+        let header = "current witness index: 3\nprivate parameters: []\npublic parameters: []\nreturn values: []\n";
+        let error = |opcodes: &str| Circuit::from_str(&format!("{header}{opcodes}")).unwrap_err();
+
+        assert_eq!(
+            error("BLACKBOX::AND(lhs: w1:4, output: w3)"),
+            ParseError {
+                line: 5,
+                column: 1,
+                message: "`BLACKBOX::AND` is missing field `rhs`".into()
+            }
+        );
+        assert_eq!(
+            error("BLACKBOX::RANGE(input: w1:4, output: w3)"),
+            ParseError {
+                line: 5,
+                column: 38,
+                message: "`BLACKBOX::RANGE` has no field `output`".into()
+            }
+        );
+        assert_eq!(
+            error("BLACKBOX::RANGE(input: w1)"),
+            ParseError {
+                line: 5,
+                column: 24,
+                message: "expected a function input, found an expression".into()
+            }
+        );
+        assert_eq!(
+            error("ASSERT w1 + r2 = 0"),
+            ParseError { line: 5, column: 13, message: "expected a witness, found `r2`".into() }
+        );
+        assert_eq!(
+            error("BRILLIG(inputs: [], outputs: []) {\n    jump\n"),
+            ParseError { line: 6, column: 5, message: "`jump` is missing field `location`".into() }
+        );
+        assert_eq!(
+            error("BRILLIG(inputs: [], outputs: []) {\n"),
+            ParseError { line: 6, column: 1, message: "expected `}`, found end of input".into() }
+        );
+        assert_eq!(
+            error("NOT_AN_OPCODE"),
+            ParseError { line: 5, column: 1, message: "unknown opcode `NOT_AN_OPCODE`".into() }
+        );
+    }
+}
@@ -0,0 +1,967 @@
+use std::collections::BTreeSet;
+
+use acir_field::FieldElement;
+use brillig::{
+    BinaryFieldOp, BinaryIntOp, BlackBoxOp, HeapArray, HeapVector, Opcode as BrilligOpcode,
+    RegisterIndex, RegisterOrMemory, Value as BrilligValue,
+};
+
+use super::lexer::{tokenize, Position, Token};
+use super::printer::{binary_field_op_name, binary_int_op_name};
+use super::ParseError;
+use crate::circuit::{
+    brillig::{Brillig, BrilligInputs, BrilligOutputs},
+    directives::{Directive, QuotientDirective},
+    opcodes::{BlackBoxFuncCall, BlockId, FunctionInput, MemOp},
+    Circuit, Opcode, OpcodeLocation, PublicInputs,
+};
+use crate::native_types::{Expression, Witness};
+use crate::BlackBoxFunc;
+
+pub(super) fn parse_circuit(source: &str) -> Result<Circuit, ParseError> {
+    let (tokens, end) = tokenize(source)?;
+    let mut parser = Parser { tokens, index: 0, end };
+    parser.circuit()
+}
+
+/// A value of a field of an opcode, before it is converted to the type expected by that field.
+enum Value {
+    Expression(Expression),
+    Input(FunctionInput),
+    Register(RegisterIndex),
+    Block(BlockId),
+    HeapArray(HeapArray),
+    HeapVector(HeapVector),
+    Ident(String),
+    Str(String),
+    List(Vec<Value>),
+}
+
+impl Value {
+    fn description(&self) -> &'static str {
+        match self {
+            Value::Expression(_) => "an expression",
+            Value::Input(_) => "a function input",
+            Value::Register(_) => "a register",
+            Value::Block(_) => "a block id",
+            Value::HeapArray(_) => "a heap array",
+            Value::HeapVector(_) => "a heap vector",
+            Value::Ident(_) => "an identifier",
+            Value::Str(_) => "a string",
+            Value::List(_) => "a list",
+        }
+    }
+}
+
+/// Returns the index of an identifier of the form `<prefix><index>`, such as `w3` or `r0`.
+fn indexed<T: std::str::FromStr>(ident: &str, prefix: char) -> Option<T> {
+    let digits = ident.strip_prefix(prefix)?;
+    if digits.is_empty() || !digits.chars().all(|char| char.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+fn parse_field(int: &str) -> Option<FieldElement> {
+    if int.starts_with("0x") {
+        FieldElement::from_hex(int)
+    } else if int.chars().all(|char| char.is_ascii_digit()) {
+        FieldElement::try_from_str(int)
+    } else {
+        None
+    }
+}
+
+struct Parser {
+    tokens: Vec<(Token, Position)>,
+    index: usize,
+    /// The position of the end of the source, used when reporting unexpected ends of input.
+    end: Position,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|(token, _)| token)
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.index + n).map(|(token, _)| token)
+    }
+
+    fn position(&self) -> Position {
+        self.tokens.get(self.index).map_or(self.end, |(_, position)| *position)
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, ParseError> {
+        Err(ParseError::new(self.position(), message))
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> Result<T, ParseError> {
+        match self.peek() {
+            Some(token) => self.error(format!("expected {expected}, found {token}")),
+            None => self.error(format!("expected {expected}, found end of input")),
+        }
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), ParseError> {
+        if self.eat(&token) {
+            Ok(())
+        } else {
+            self.unexpected(&token.to_string())
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, ParseError> {
+        match self.peek() {
+            Some(Token::Ident(ident)) => {
+                let ident = ident.clone();
+                self.index += 1;
+                Ok(ident)
+            }
+            _ => self.unexpected("an identifier"),
+        }
+    }
+
+    fn keywords(&mut self, keywords: &[&str]) -> Result<(), ParseError> {
+        for keyword in keywords {
+            if self.peek() != Some(&Token::Ident(keyword.to_string())) {
+                return self.unexpected(&format!("`{}`", keywords.join(" ")));
+            }
+            self.index += 1;
+        }
+        self.expect(Token::Colon)
+    }
+
+    fn int<T: std::str::FromStr>(&mut self) -> Result<T, ParseError> {
+        if let Some(Token::Int(int)) = self.peek() {
+            if let Ok(value) = int.parse() {
+                self.index += 1;
+                return Ok(value);
+            }
+        }
+        self.unexpected("an integer")
+    }
+
+    fn circuit(&mut self) -> Result<Circuit, ParseError> {
+        self.keywords(&["current", "witness", "index"])?;
+        let current_witness_index = self.int()?;
+        self.keywords(&["private", "parameters"])?;
+        let private_parameters = self.witness_set()?;
+        self.keywords(&["public", "parameters"])?;
+        let public_parameters = PublicInputs(self.witness_set()?);
+        self.keywords(&["return", "values"])?;
+        let return_values = PublicInputs(self.witness_set()?);
+
+        let mut assert_messages = Vec::new();
+        while self.peek() == Some(&Token::Ident("assert".to_string())) {
+            self.index += 1;
+            if !self.eat(&Token::Ident("message".to_string())) {
+                return self.unexpected("`message`");
+            }
+            let acir_index = self.int()?;
+            let location = if self.eat(&Token::Dot) {
+                OpcodeLocation::Brillig { acir_index, brillig_index: self.int()? }
+            } else {
+                OpcodeLocation::Acir(acir_index)
+            };
+            self.expect(Token::Colon)?;
+            match self.peek() {
+                Some(Token::Str(message)) => {
+                    assert_messages.push((location, message.clone()));
+                    self.index += 1;
+                }
+                _ => return self.unexpected("a string"),
+            }
+        }
+
+        let mut opcodes = Vec::new();
+        while self.peek().is_some() {
+            opcodes.push(self.opcode()?);
+        }
+
+        Ok(Circuit {
+            current_witness_index,
+            opcodes,
+            private_parameters,
+            public_parameters,
+            return_values,
+            assert_messages,
+        })
+    }
+
+    fn witness_set(&mut self) -> Result<BTreeSet<Witness>, ParseError> {
+        let position = self.position();
+        let value = self.value()?;
+        Ok(Fields::witnesses_from(value, position)?.into_iter().collect())
+    }
+
+    /// Parses a `::` separated path, such as `BLACKBOX::AND`.
+    fn path(&mut self) -> Result<String, ParseError> {
+        let mut path = self.ident()?;
+        while self.eat(&Token::DoubleColon) {
+            path.push_str("::");
+            path.push_str(&self.ident()?);
+        }
+        Ok(path)
+    }
+
+    /// Parses the optional `(name: value, ...)` list of fields of an opcode.
+    fn fields(&mut self, opcode: String, position: Position) -> Result<Fields, ParseError> {
+        let mut fields = Fields { opcode, position, fields: Vec::new() };
+        if !self.eat(&Token::LeftParen) {
+            return Ok(fields);
+        }
+        while !self.eat(&Token::RightParen) {
+            let position = self.position();
+            let name = self.ident()?;
+            if fields.fields.iter().any(|(other, _, _)| *other == name) {
+                return Err(ParseError::new(position, format!("duplicate field `{name}`")));
+            }
+            self.expect(Token::Colon)?;
+            let value_position = self.position();
+            fields.fields.push((name, self.value()?, value_position));
+            if !self.eat(&Token::Comma) && self.peek() != Some(&Token::RightParen) {
+                return self.unexpected("`,` or `)`");
+            }
+        }
+        Ok(fields)
+    }
+
+    fn value(&mut self) -> Result<Value, ParseError> {
+        match self.peek() {
+            Some(Token::LeftBracket) => {
+                self.index += 1;
+                let mut values = Vec::new();
+                while !self.eat(&Token::RightBracket) {
+                    values.push(self.value()?);
+                    if !self.eat(&Token::Comma) && self.peek() != Some(&Token::RightBracket) {
+                        return self.unexpected("`,` or `]`");
+                    }
+                }
+                Ok(Value::List(values))
+            }
+            Some(Token::Str(string)) => {
+                let string = string.clone();
+                self.index += 1;
+                Ok(Value::Str(string))
+            }
+            Some(Token::Int(_) | Token::Minus) => Ok(Value::Expression(self.expression()?)),
+            Some(Token::Ident(ident)) => {
+                let ident = ident.clone();
+                if indexed::<u32>(&ident, 'w').is_some() {
+                    let expression = self.expression()?;
+                    match (expression.to_witness(), self.eat(&Token::Colon)) {
+                        (Some(witness), true) => {
+                            Ok(Value::Input(FunctionInput { witness, num_bits: self.int()? }))
+                        }
+                        (None, true) => self.error("only witnesses have a bit size".to_string()),
+                        (_, false) => Ok(Value::Expression(expression)),
+                    }
+                } else if let Some(index) = indexed(&ident, 'r') {
+                    self.index += 1;
+                    Ok(Value::Register(RegisterIndex(index)))
+                } else if let Some(index) = indexed(&ident, 'b') {
+                    self.index += 1;
+                    Ok(Value::Block(BlockId(index)))
+                } else if (ident == "array" || ident == "vector")
+                    && self.peek_nth(1) == Some(&Token::LeftParen)
+                {
+                    self.index += 2;
+                    let pointer = self.register()?;
+                    self.expect(Token::Comma)?;
+                    let value = if ident == "array" {
+                        Value::HeapArray(HeapArray { pointer, size: self.int()? })
+                    } else {
+                        Value::HeapVector(HeapVector { pointer, size: self.register()? })
+                    };
+                    self.expect(Token::RightParen)?;
+                    Ok(value)
+                } else {
+                    self.index += 1;
+                    Ok(Value::Ident(ident))
+                }
+            }
+            _ => self.unexpected("a value"),
+        }
+    }
+
+    fn register(&mut self) -> Result<RegisterIndex, ParseError> {
+        if let Some(Token::Ident(ident)) = self.peek() {
+            if let Some(index) = indexed(ident, 'r') {
+                self.index += 1;
+                return Ok(RegisterIndex(index));
+            }
+        }
+        self.unexpected("a register")
+    }
+
+    fn witness(&mut self) -> Result<Witness, ParseError> {
+        if let Some(Token::Ident(ident)) = self.peek() {
+            if let Some(index) = indexed(ident, 'w') {
+                self.index += 1;
+                return Ok(Witness(index));
+            }
+        }
+        self.unexpected("a witness")
+    }
+
+    /// Parses a sum of terms, each of which is a constant, a witness or the product of two
+    /// witnesses, optionally multiplied by a constant coefficient.
+    fn expression(&mut self) -> Result<Expression, ParseError> {
+        let mut expression = Expression::default();
+        let mut is_negative = self.eat(&Token::Minus);
+        loop {
+            let mut coefficient = FieldElement::one();
+            let mut is_constant = false;
+            if let Some(Token::Int(int)) = self.peek() {
+                coefficient = match parse_field(int) {
+                    Some(coefficient) => coefficient,
+                    None => return self.error(format!("invalid field element `{int}`")),
+                };
+                self.index += 1;
+                is_constant = !self.eat(&Token::Star);
+            }
+            if is_negative {
+                coefficient = -coefficient;
+            }
+
+            if is_constant {
+                expression.q_c += coefficient;
+            } else {
+                let lhs = self.witness()?;
+                if self.eat(&Token::Star) {
+                    expression.push_multiplication_term(coefficient, lhs, self.witness()?);
+                } else {
+                    expression.push_addition_term(coefficient, lhs);
+                }
+            }
+
+            if self.eat(&Token::Plus) {
+                is_negative = false;
+            } else if self.eat(&Token::Minus) {
+                is_negative = true;
+            } else {
+                return Ok(expression);
+            }
+        }
+    }
+
+    fn opcode(&mut self) -> Result<Opcode, ParseError> {
+        let position = self.position();
+        let name = self.path()?;
+        if name == "ASSERT" {
+            let lhs = self.expression()?;
+            self.expect(Token::Equal)?;
+            let rhs = self.expression()?;
+            return Ok(Opcode::AssertZero(difference(lhs, rhs)));
+        }
+
+        let mut fields = self.fields(name.clone(), position)?;
+        let opcode = if let Some(func_name) = name.strip_prefix("BLACKBOX::") {
+            match BlackBoxFunc::lookup(&func_name.to_lowercase()) {
+                Some(func) => Opcode::BlackBoxFuncCall(black_box_func_call(func, &mut fields)?),
+                None => {
+                    return Err(ParseError::new(
+                        position,
+                        format!("unknown black box function `{func_name}`"),
+                    ))
+                }
+            }
+        } else {
+            match name.as_str() {
+                "DIR::QUOTIENT" => Opcode::Directive(Directive::Quotient(QuotientDirective {
+                    a: fields.expression("a")?,
+                    b: fields.expression("b")?,
+                    q: fields.witness("q")?,
+                    r: fields.witness("r")?,
+                    predicate: fields.predicate()?,
+                })),
+                "DIR::TO_LE_RADIX" => Opcode::Directive(Directive::ToLeRadix {
+                    a: fields.expression("a")?,
+                    b: fields.witnesses("b")?,
+                    radix: fields.int("radix")?,
+                }),
+                "DIR::PERMUTATION_SORT" => {
+                    let (inputs, position) = fields.take("inputs")?;
+                    let inputs = Fields::list_from(inputs, position)?
+                        .into_iter()
+                        .map(|tuple| {
+                            Fields::list_from(tuple, position)?
+                                .into_iter()
+                                .map(|value| Fields::expression_from(value, position))
+                                .collect()
+                        })
+                        .collect::<Result<_, _>>()?;
+                    let (sort_by, position) = fields.take("sort_by")?;
+                    let sort_by = Fields::list_from(sort_by, position)?
+                        .into_iter()
+                        .map(|value| Fields::int_from(value, position))
+                        .collect::<Result<_, _>>()?;
+                    Opcode::Directive(Directive::PermutationSort {
+                        inputs,
+                        tuple: fields.int("tuple")?,
+                        bits: fields.witnesses("bits")?,
+                        sort_by,
+                    })
+                }
+                "MEMORY_INIT" => Opcode::MemoryInit {
+                    block_id: fields.block("block")?,
+                    init: fields.witnesses("init")?,
+                },
+                "MEMORY_READ" | "MEMORY_WRITE" | "MEMORY_OP" => {
+                    let block_id = fields.block("block")?;
+                    let operation = match name.as_str() {
+                        "MEMORY_READ" => Expression::zero(),
+                        "MEMORY_WRITE" => Expression::one(),
+                        _ => fields.expression("operation")?,
+                    };
+                    let op = MemOp {
+                        operation,
+                        index: fields.expression("index")?,
+                        value: fields.expression("value")?,
+                    };
+                    Opcode::MemoryOp { block_id, op, predicate: fields.predicate()? }
+                }
+                "BRILLIG" => Opcode::Brillig(self.brillig(&mut fields)?),
+                _ => return Err(ParseError::new(position, format!("unknown opcode `{name}`"))),
+            }
+        };
+        fields.finish()?;
+        Ok(opcode)
+    }
+
+    fn brillig(&mut self, fields: &mut Fields) -> Result<Brillig, ParseError> {
+        let (inputs, position) = fields.take("inputs")?;
+        let inputs = Fields::list_from(inputs, position)?
+            .into_iter()
+            .map(|input| match input {
+                Value::List(values) => values
+                    .into_iter()
+                    .map(|value| Fields::expression_from(value, position))
+                    .collect::<Result<_, _>>()
+                    .map(BrilligInputs::Array),
+                value => Fields::expression_from(value, position).map(BrilligInputs::Single),
+            })
+            .collect::<Result<_, _>>()?;
+
+        let (outputs, position) = fields.take("outputs")?;
+        let outputs = Fields::list_from(outputs, position)?
+            .into_iter()
+            .map(|output| match output {
+                output @ Value::List(_) => {
+                    Fields::witnesses_from(output, position).map(BrilligOutputs::Array)
+                }
+                output => Fields::witness_from(output, position).map(BrilligOutputs::Simple),
+            })
+            .collect::<Result<_, _>>()?;
+
+        let predicate = fields.predicate()?;
+
+        self.expect(Token::LeftBrace)?;
+        let mut bytecode = Vec::new();
+        while !self.eat(&Token::RightBrace) {
+            if self.peek().is_none() {
+                return self.unexpected("`}`");
+            }
+            bytecode.push(self.brillig_opcode()?);
+        }
+
+        Ok(Brillig { inputs, outputs, bytecode, predicate })
+    }
+
+    fn brillig_opcode(&mut self) -> Result<BrilligOpcode, ParseError> {
+        let position = self.position();
+        let name = self.path()?;
+        let mut fields = self.fields(name.clone(), position)?;
+        let opcode = if let Some(func_name) = name.strip_prefix("black_box::") {
+            BrilligOpcode::BlackBox(black_box_op(func_name, &mut fields)?)
+        } else {
+            match name.as_str() {
+                "binary_field_op" => BrilligOpcode::BinaryFieldOp {
+                    destination: fields.register("destination")?,
+                    op: fields.binary_field_op("op")?,
+                    lhs: fields.register("lhs")?,
+                    rhs: fields.register("rhs")?,
+                },
+                "binary_int_op" => BrilligOpcode::BinaryIntOp {
+                    destination: fields.register("destination")?,
+                    op: fields.binary_int_op("op")?,
+                    bit_size: fields.int("bit_size")?,
+                    lhs: fields.register("lhs")?,
+                    rhs: fields.register("rhs")?,
+                },
+                "jump_if_not" => BrilligOpcode::JumpIfNot {
+                    condition: fields.register("condition")?,
+                    location: fields.int("location")?,
+                },
+                "jump_if" => BrilligOpcode::JumpIf {
+                    condition: fields.register("condition")?,
+                    location: fields.int("location")?,
+                },
+                "jump" => BrilligOpcode::Jump { location: fields.int("location")? },
+                "call" => BrilligOpcode::Call { location: fields.int("location")? },
+                "const" => BrilligOpcode::Const {
+                    destination: fields.register("destination")?,
+                    value: BrilligValue::from(fields.constant("value")?),
+                },
+                "return" => BrilligOpcode::Return,
+                "foreign_call" => BrilligOpcode::ForeignCall {
+                    function: fields.string("function")?,
+                    destinations: fields.registers_or_memory("destinations")?,
+                    inputs: fields.registers_or_memory("inputs")?,
+                },
+                "mov" => BrilligOpcode::Mov {
+                    destination: fields.register("destination")?,
+                    source: fields.register("source")?,
+                },
+                "load" => BrilligOpcode::Load {
+                    destination: fields.register("destination")?,
+                    source_pointer: fields.register("source_pointer")?,
+                },
+                "store" => BrilligOpcode::Store {
+                    destination_pointer: fields.register("destination_pointer")?,
+                    source: fields.register("source")?,
+                },
+                "trap" => BrilligOpcode::Trap,
+                "stop" => BrilligOpcode::Stop,
+                _ => {
+                    return Err(ParseError::new(
+                        position,
+                        format!("unknown brillig opcode `{name}`"),
+                    ))
+                }
+            }
+        };
+        fields.finish()?;
+        Ok(opcode)
+    }
+}
+
+/// Returns `lhs - rhs`, keeping the terms of both expressions in order so that printing
+/// `ASSERT <expr> = 0` and parsing it back results in the same expression.
+fn difference(mut lhs: Expression, rhs: Expression) -> Expression {
+    lhs.mul_terms.extend(rhs.mul_terms.into_iter().map(|(coefficient, a, b)| (-coefficient, a, b)));
+    lhs.linear_combinations.extend(
+        rhs.linear_combinations.into_iter().map(|(coefficient, witness)| (-coefficient, witness)),
+    );
+    lhs.q_c -= rhs.q_c;
+    lhs
+}
+
+fn black_box_func_call(
+    func: BlackBoxFunc,
+    fields: &mut Fields,
+) -> Result<BlackBoxFuncCall, ParseError> {
+    let call = match func {
+        BlackBoxFunc::AND => BlackBoxFuncCall::AND {
+            lhs: fields.input("lhs")?,
+            rhs: fields.input("rhs")?,
+            output: fields.witness("output")?,
+        },
+        BlackBoxFunc::XOR => BlackBoxFuncCall::XOR {
+            lhs: fields.input("lhs")?,
+            rhs: fields.input("rhs")?,
+            output: fields.witness("output")?,
+        },
+        BlackBoxFunc::RANGE => BlackBoxFuncCall::RANGE { input: fields.input("input")? },
+        BlackBoxFunc::SHA256 => BlackBoxFuncCall::SHA256 {
+            inputs: fields.inputs("inputs")?,
+            outputs: fields.witnesses("outputs")?,
+        },
+        BlackBoxFunc::Blake2s => BlackBoxFuncCall::Blake2s {
+            inputs: fields.inputs("inputs")?,
+            outputs: fields.witnesses("outputs")?,
+        },
+        BlackBoxFunc::Blake3 => BlackBoxFuncCall::Blake3 {
+            inputs: fields.inputs("inputs")?,
+            outputs: fields.witnesses("outputs")?,
+        },
+        BlackBoxFunc::SchnorrVerify => BlackBoxFuncCall::SchnorrVerify {
+            public_key_x: fields.input("public_key_x")?,
+            public_key_y: fields.input("public_key_y")?,
+            signature: fields.inputs("signature")?,
+            message: fields.inputs("message")?,
+            output: fields.witness("output")?,
+        },
+        BlackBoxFunc::PedersenCommitment => BlackBoxFuncCall::PedersenCommitment {
+            inputs: fields.inputs("inputs")?,
+            domain_separator: fields.int("domain_separator")?,
+            outputs: fields.witness_pair("outputs")?,
+        },
+        BlackBoxFunc::PedersenHash => BlackBoxFuncCall::PedersenHash {
+            inputs: fields.inputs("inputs")?,
+            domain_separator: fields.int("domain_separator")?,
+            output: fields.witness("output")?,
+        },
+        BlackBoxFunc::EcdsaSecp256k1 => BlackBoxFuncCall::EcdsaSecp256k1 {
+            public_key_x: fields.inputs("public_key_x")?,
+            public_key_y: fields.inputs("public_key_y")?,
+            signature: fields.inputs("signature")?,
+            hashed_message: fields.inputs("hashed_message")?,
+            output: fields.witness("output")?,
+        },
+        BlackBoxFunc::EcdsaSecp256r1 => BlackBoxFuncCall::EcdsaSecp256r1 {
+            public_key_x: fields.inputs("public_key_x")?,
+            public_key_y: fields.inputs("public_key_y")?,
+            signature: fields.inputs("signature")?,
+            hashed_message: fields.inputs("hashed_message")?,
+            output: fields.witness("output")?,
+        },
+        BlackBoxFunc::FixedBaseScalarMul => BlackBoxFuncCall::FixedBaseScalarMul {
+            low: fields.input("low")?,
+            high: fields.input("high")?,
+            outputs: fields.witness_pair("outputs")?,
+        },
+        BlackBoxFunc::EmbeddedCurveAdd => BlackBoxFuncCall::EmbeddedCurveAdd {
+            input1_x: fields.input("input1_x")?,
+            input1_y: fields.input("input1_y")?,
+            input2_x: fields.input("input2_x")?,
+            input2_y: fields.input("input2_y")?,
+            outputs: fields.witness_pair("outputs")?,
+        },
+        BlackBoxFunc::EmbeddedCurveDouble => BlackBoxFuncCall::EmbeddedCurveDouble {
+            input_x: fields.input("input_x")?,
+            input_y: fields.input("input_y")?,
+            outputs: fields.witness_pair("outputs")?,
+        },
+        // Both keccak256 calls share a name, the variable length one having a message size
+        BlackBoxFunc::Keccak256 if fields.has("var_message_size") => {
+            BlackBoxFuncCall::Keccak256VariableLength {
+                inputs: fields.inputs("inputs")?,
+                var_message_size: fields.input("var_message_size")?,
+                outputs: fields.witnesses("outputs")?,
+            }
+        }
+        BlackBoxFunc::Keccak256 => BlackBoxFuncCall::Keccak256 {
+            inputs: fields.inputs("inputs")?,
+            outputs: fields.witnesses("outputs")?,
+        },
+        BlackBoxFunc::Keccakf1600 => BlackBoxFuncCall::Keccakf1600 {
+            inputs: fields.inputs("inputs")?,
+            outputs: fields.witnesses("outputs")?,
+        },
+        BlackBoxFunc::RecursiveAggregation => BlackBoxFuncCall::RecursiveAggregation {
+            verification_key: fields.inputs("verification_key")?,
+            proof: fields.inputs("proof")?,
+            public_inputs: fields.inputs("public_inputs")?,
+            key_hash: fields.input("key_hash")?,
+        },
+    };
+    Ok(call)
+}
+
+fn black_box_op(name: &str, fields: &mut Fields) -> Result<BlackBoxOp, ParseError> {
+    let op = match name {
+        "sha256" => BlackBoxOp::Sha256 {
+            message: fields.heap_vector("message")?,
+            output: fields.heap_array("output")?,
+        },
+        "blake2s" => BlackBoxOp::Blake2s {
+            message: fields.heap_vector("message")?,
+            output: fields.heap_array("output")?,
+        },
+        "keccak256" => BlackBoxOp::Keccak256 {
+            message: fields.heap_vector("message")?,
+            output: fields.heap_array("output")?,
+        },
+        "ecdsa_secp256k1" => BlackBoxOp::EcdsaSecp256k1 {
+            hashed_msg: fields.heap_vector("hashed_msg")?,
+            public_key_x: fields.heap_array("public_key_x")?,
+            public_key_y: fields.heap_array("public_key_y")?,
+            signature: fields.heap_array("signature")?,
+            result: fields.register("result")?,
+        },
+        "ecdsa_secp256r1" => BlackBoxOp::EcdsaSecp256r1 {
+            hashed_msg: fields.heap_vector("hashed_msg")?,
+            public_key_x: fields.heap_array("public_key_x")?,
+            public_key_y: fields.heap_array("public_key_y")?,
+            signature: fields.heap_array("signature")?,
+            result: fields.register("result")?,
+        },
+        "schnorr_verify" => BlackBoxOp::SchnorrVerify {
+            public_key_x: fields.register("public_key_x")?,
+            public_key_y: fields.register("public_key_y")?,
+            message: fields.heap_vector("message")?,
+            signature: fields.heap_vector("signature")?,
+            result: fields.register("result")?,
+        },
+        "pedersen_commitment" => BlackBoxOp::PedersenCommitment {
+            inputs: fields.heap_vector("inputs")?,
+            domain_separator: fields.register("domain_separator")?,
+            output: fields.heap_array("output")?,
+        },
+        "pedersen_hash" => BlackBoxOp::PedersenHash {
+            inputs: fields.heap_vector("inputs")?,
+            domain_separator: fields.register("domain_separator")?,
+            output: fields.register("output")?,
+        },
+        "fixed_base_scalar_mul" => BlackBoxOp::FixedBaseScalarMul {
+            low: fields.register("low")?,
+            high: fields.register("high")?,
+            result: fields.heap_array("result")?,
+        },
+        "embedded_curve_add" => BlackBoxOp::EmbeddedCurveAdd {
+            input1_x: fields.register("input1_x")?,
+            input1_y: fields.register("input1_y")?,
+            input2_x: fields.register("input2_x")?,
+            input2_y: fields.register("input2_y")?,
+            result: fields.heap_array("result")?,
+        },
+        "embedded_curve_double" => BlackBoxOp::EmbeddedCurveDouble {
+            input1_x: fields.register("input1_x")?,
+            input1_y: fields.register("input1_y")?,
+            result: fields.heap_array("result")?,
+        },
+        _ => {
+            return Err(ParseError::new(
+                fields.position,
+                format!("unknown brillig black box function `{name}`"),
+            ))
+        }
+    };
+    Ok(op)
+}
+
+/// The fields of an opcode, which are removed as they are converted to the expected types
+/// so that unknown fields can be reported.
+struct Fields {
+    opcode: String,
+    position: Position,
+    fields: Vec<(String, Value, Position)>,
+}
+
+impl Fields {
+    fn has(&self, name: &str) -> bool {
+        self.fields.iter().any(|(field, _, _)| field == name)
+    }
+
+    fn take_optional(&mut self, name: &str) -> Option<(Value, Position)> {
+        let index = self.fields.iter().position(|(field, _, _)| field == name)?;
+        let (_, value, position) = self.fields.remove(index);
+        Some((value, position))
+    }
+
+    fn take(&mut self, name: &str) -> Result<(Value, Position), ParseError> {
+        self.take_optional(name).ok_or_else(|| {
+            ParseError::new(self.position, format!("`{}` is missing field `{name}`", self.opcode))
+        })
+    }
+
+    fn finish(self) -> Result<(), ParseError> {
+        match self.fields.first() {
+            Some((name, _, position)) => {
+                Err(ParseError::new(*position, format!("`{}` has no field `{name}`", self.opcode)))
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn mismatch<T>(expected: &str, value: &Value, position: Position) -> Result<T, ParseError> {
+        Err(ParseError::new(
+            position,
+            format!("expected {expected}, found {}", value.description()),
+        ))
+    }
+
+    fn expression_from(value: Value, position: Position) -> Result<Expression, ParseError> {
+        match value {
+            Value::Expression(expression) => Ok(expression),
+            value => Self::mismatch("an expression", &value, position),
+        }
+    }
+
+    fn witness_from(value: Value, position: Position) -> Result<Witness, ParseError> {
+        match value {
+            Value::Expression(expression) if expression.to_witness().is_some() => {
+                Ok(expression.to_witness().unwrap())
+            }
+            value => Self::mismatch("a witness", &value, position),
+        }
+    }
+
+    fn list_from(value: Value, position: Position) -> Result<Vec<Value>, ParseError> {
+        match value {
+            Value::List(values) => Ok(values),
+            value => Self::mismatch("a list", &value, position),
+        }
+    }
+
+    fn witnesses_from(value: Value, position: Position) -> Result<Vec<Witness>, ParseError> {
+        Self::list_from(value, position)?
+            .into_iter()
+            .map(|value| Self::witness_from(value, position))
+            .collect()
+    }
+
+    fn constant_from(value: Value, position: Position) -> Result<FieldElement, ParseError> {
+        match value {
+            Value::Expression(expression) if expression.is_const() => Ok(expression.q_c),
+            value => Self::mismatch("a constant", &value, position),
+        }
+    }
+
+    fn int_from<T: TryFrom<u64>>(value: Value, position: Position) -> Result<T, ParseError> {
+        let constant = Self::constant_from(value, position)?;
+        constant
+            .try_to_u64()
+            .and_then(|int| T::try_from(int).ok())
+            .ok_or_else(|| ParseError::new(position, format!("integer {constant} is out of range")))
+    }
+
+    fn expression(&mut self, name: &str) -> Result<Expression, ParseError> {
+        let (value, position) = self.take(name)?;
+        Self::expression_from(value, position)
+    }
+
+    fn predicate(&mut self) -> Result<Option<Expression>, ParseError> {
+        self.take_optional("predicate")
+            .map(|(value, position)| Self::expression_from(value, position))
+            .transpose()
+    }
+
+    fn witness(&mut self, name: &str) -> Result<Witness, ParseError> {
+        let (value, position) = self.take(name)?;
+        Self::witness_from(value, position)
+    }
+
+    fn witnesses(&mut self, name: &str) -> Result<Vec<Witness>, ParseError> {
+        let (value, position) = self.take(name)?;
+        Self::witnesses_from(value, position)
+    }
+
+    fn witness_pair(&mut self, name: &str) -> Result<(Witness, Witness), ParseError> {
+        let (value, position) = self.take(name)?;
+        match Self::witnesses_from(value, position)?.as_slice() {
+            [first, second] => Ok((*first, *second)),
+            _ => Err(ParseError::new(position, "expected a list of two witnesses".to_string())),
+        }
+    }
+
+    fn input(&mut self, name: &str) -> Result<FunctionInput, ParseError> {
+        match self.take(name)? {
+            (Value::Input(input), _) => Ok(input),
+            (value, position) => Self::mismatch("a function input", &value, position),
+        }
+    }
+
+    fn inputs(&mut self, name: &str) -> Result<Vec<FunctionInput>, ParseError> {
+        let (value, position) = self.take(name)?;
+        Self::list_from(value, position)?
+            .into_iter()
+            .map(|value| match value {
+                Value::Input(input) => Ok(input),
+                value => Self::mismatch("a function input", &value, position),
+            })
+            .collect()
+    }
+
+    fn int<T: TryFrom<u64>>(&mut self, name: &str) -> Result<T, ParseError> {
+        let (value, position) = self.take(name)?;
+        Self::int_from(value, position)
+    }
+
+    fn constant(&mut self, name: &str) -> Result<FieldElement, ParseError> {
+        let (value, position) = self.take(name)?;
+        Self::constant_from(value, position)
+    }
+
+    fn string(&mut self, name: &str) -> Result<String, ParseError> {
+        match self.take(name)? {
+            (Value::Str(string), _) => Ok(string),
+            (value, position) => Self::mismatch("a string", &value, position),
+        }
+    }
+
+    fn block(&mut self, name: &str) -> Result<BlockId, ParseError> {
+        match self.take(name)? {
+            (Value::Block(block_id), _) => Ok(block_id),
+            (value, position) => Self::mismatch("a block id", &value, position),
+        }
+    }
+
+    fn register(&mut self, name: &str) -> Result<RegisterIndex, ParseError> {
+        match self.take(name)? {
+            (Value::Register(register), _) => Ok(register),
+            (value, position) => Self::mismatch("a register", &value, position),
+        }
+    }
+
+    fn heap_array(&mut self, name: &str) -> Result<HeapArray, ParseError> {
+        match self.take(name)? {
+            (Value::HeapArray(array), _) => Ok(array),
+            (value, position) => Self::mismatch("a heap array", &value, position),
+        }
+    }
+
+    fn heap_vector(&mut self, name: &str) -> Result<HeapVector, ParseError> {
+        match self.take(name)? {
+            (Value::HeapVector(vector), _) => Ok(vector),
+            (value, position) => Self::mismatch("a heap vector", &value, position),
+        }
+    }
+
+    fn registers_or_memory(&mut self, name: &str) -> Result<Vec<RegisterOrMemory>, ParseError> {
+        let (value, position) = self.take(name)?;
+        Self::list_from(value, position)?
+            .into_iter()
+            .map(|value| match value {
+                Value::Register(register) => Ok(RegisterOrMemory::RegisterIndex(register)),
+                Value::HeapArray(array) => Ok(RegisterOrMemory::HeapArray(array)),
+                Value::HeapVector(vector) => Ok(RegisterOrMemory::HeapVector(vector)),
+                value => Self::mismatch("a register, heap array or heap vector", &value, position),
+            })
+            .collect()
+    }
+
+    fn ident(&mut self, name: &str) -> Result<(String, Position), ParseError> {
+        match self.take(name)? {
+            (Value::Ident(ident), position) => Ok((ident, position)),
+            (value, position) => Self::mismatch("an identifier", &value, position),
+        }
+    }
+
+    fn binary_field_op(&mut self, name: &str) -> Result<BinaryFieldOp, ParseError> {
+        let (ident, position) = self.ident(name)?;
+        [
+            BinaryFieldOp::Add,
+            BinaryFieldOp::Sub,
+            BinaryFieldOp::Mul,
+            BinaryFieldOp::Div,
+            BinaryFieldOp::Equals,
+        ]
+        .into_iter()
+        .find(|op| binary_field_op_name(op) == ident)
+        .ok_or_else(|| ParseError::new(position, format!("unknown binary field op `{ident}`")))
+    }
+
+    fn binary_int_op(&mut self, name: &str) -> Result<BinaryIntOp, ParseError> {
+        let (ident, position) = self.ident(name)?;
+        [
+            BinaryIntOp::Add,
+            BinaryIntOp::Sub,
+            BinaryIntOp::Mul,
+            BinaryIntOp::SignedDiv,
+            BinaryIntOp::UnsignedDiv,
+            BinaryIntOp::Equals,
+            BinaryIntOp::LessThan,
+            BinaryIntOp::LessThanEquals,
+            BinaryIntOp::And,
+            BinaryIntOp::Or,
+            BinaryIntOp::Xor,
+            BinaryIntOp::Shl,
+            BinaryIntOp::Shr,
+        ]
+        .into_iter()
+        .find(|op| binary_int_op_name(op) == ident)
+        .ok_or_else(|| ParseError::new(position, format!("unknown binary int op `{ident}`")))
+    }
+}
@@ -0,0 +1,524 @@
+use std::fmt::Write;
+
+use acir_field::FieldElement;
+use brillig::{
+    BinaryFieldOp, BinaryIntOp, BlackBoxOp, HeapArray, HeapVector, Opcode as BrilligOpcode,
+    RegisterIndex, RegisterOrMemory,
+};
+
+use crate::circuit::{
+    brillig::{Brillig, BrilligInputs, BrilligOutputs},
+    directives::{Directive, QuotientDirective},
+    opcodes::{BlackBoxFuncCall, BlockId, FunctionInput, MemOp},
+    Circuit, Opcode,
+};
+use crate::native_types::{Expression, Witness};
+
+pub(super) fn print_circuit(circuit: &Circuit) -> String {
+    let mut output = String::new();
+    writeln!(output, "current witness index: {}", circuit.current_witness_index).unwrap();
+    writeln!(output, "private parameters: {}", witness_list(&circuit.private_parameters)).unwrap();
+    writeln!(output, "public parameters: {}", witness_list(&circuit.public_parameters.0)).unwrap();
+    writeln!(output, "return values: {}", witness_list(&circuit.return_values.0)).unwrap();
+    for (location, message) in &circuit.assert_messages {
+        writeln!(output, "assert message {location}: {message:?}").unwrap();
+    }
+
+    for opcode in &circuit.opcodes {
+        output.push('\n');
+        output.push_str(&opcode_to_string(opcode));
+    }
+    output.push('\n');
+    output
+}
+
+/// Formats a field element in decimal, or in hexadecimal if neither it nor its negation fits
+/// in a `u128`.
+pub(super) fn field(value: FieldElement) -> String {
+    if let Some(value) = value.try_into_u128() {
+        value.to_string()
+    } else if let Some(negated) = (-value).try_into_u128() {
+        format!("-{negated}")
+    } else {
+        format!("0x{}", value.to_hex().trim_start_matches('0'))
+    }
+}
+
+fn witness(witness: &Witness) -> String {
+    format!("w{}", witness.witness_index())
+}
+
+fn witness_list<'a>(witnesses: impl IntoIterator<Item = &'a Witness>) -> String {
+    list(witnesses.into_iter().map(witness))
+}
+
+fn list(items: impl IntoIterator<Item = String>) -> String {
+    format!("[{}]", items.into_iter().collect::<Vec<_>>().join(", "))
+}
+
+/// Formats an expression as a sum of its quadratic terms, its linear terms and its constant,
+/// in the order in which they are stored.
+pub(super) fn expression(expr: &Expression) -> String {
+    let mut terms: Vec<(FieldElement, String)> = Vec::new();
+    for (coefficient, lhs, rhs) in &expr.mul_terms {
+        terms.push((*coefficient, format!("{}*{}", witness(lhs), witness(rhs))));
+    }
+    for (coefficient, variable) in &expr.linear_combinations {
+        terms.push((*coefficient, witness(variable)));
+    }
+
+    let mut output = String::new();
+    for (index, (coefficient, term)) in terms.into_iter().enumerate() {
+        let coefficient = field(coefficient);
+        let (is_negative, magnitude) = match coefficient.strip_prefix('-') {
+            Some(magnitude) => (true, magnitude),
+            None => (false, coefficient.as_str()),
+        };
+        match (index, is_negative) {
+            (0, false) => (),
+            (0, true) => output.push('-'),
+            (_, false) => output.push_str(" + "),
+            (_, true) => output.push_str(" - "),
+        }
+        if magnitude != "1" {
+            write!(output, "{magnitude}*").unwrap();
+        }
+        output.push_str(&term);
+    }
+
+    if output.is_empty() {
+        output = field(expr.q_c);
+    } else if !expr.q_c.is_zero() {
+        let constant = field(expr.q_c);
+        match constant.strip_prefix('-') {
+            Some(magnitude) => write!(output, " - {magnitude}").unwrap(),
+            None => write!(output, " + {constant}").unwrap(),
+        }
+    }
+    output
+}
+
+fn function_input(input: &FunctionInput) -> String {
+    format!("{}:{}", witness(&input.witness), input.num_bits)
+}
+
+fn function_inputs(inputs: &[FunctionInput]) -> String {
+    list(inputs.iter().map(function_input))
+}
+
+fn block_id(block_id: &BlockId) -> String {
+    format!("b{}", block_id.0)
+}
+
+fn register(register: &RegisterIndex) -> String {
+    format!("r{}", register.to_usize())
+}
+
+fn heap_array(array: &HeapArray) -> String {
+    format!("array({}, {})", register(&array.pointer), array.size)
+}
+
+fn heap_vector(vector: &HeapVector) -> String {
+    format!("vector({}, {})", register(&vector.pointer), register(&vector.size))
+}
+
+fn register_or_memory(value: &RegisterOrMemory) -> String {
+    match value {
+        RegisterOrMemory::RegisterIndex(index) => register(index),
+        RegisterOrMemory::HeapArray(array) => heap_array(array),
+        RegisterOrMemory::HeapVector(vector) => heap_vector(vector),
+    }
+}
+
+/// Formats `name(field: value, ...)`, omitting the parentheses when there are no fields.
+fn call(name: &str, fields: Vec<(&str, String)>) -> String {
+    if fields.is_empty() {
+        return name.to_string();
+    }
+    let fields: Vec<_> =
+        fields.into_iter().map(|(name, value)| format!("{name}: {value}")).collect();
+    format!("{name}({})", fields.join(", "))
+}
+
+fn with_predicate<'a>(
+    mut fields: Vec<(&'a str, String)>,
+    predicate: &Option<Expression>,
+) -> Vec<(&'a str, String)> {
+    if let Some(predicate) = predicate {
+        fields.push(("predicate", expression(predicate)));
+    }
+    fields
+}
+
+fn opcode_to_string(opcode: &Opcode) -> String {
+    match opcode {
+        Opcode::AssertZero(expr) => format!("ASSERT {} = 0", expression(expr)),
+        Opcode::BlackBoxFuncCall(call) => black_box_func_call(call),
+        Opcode::Directive(directive) => self::directive(directive),
+        Opcode::Brillig(brillig) => self::brillig(brillig),
+        Opcode::MemoryOp { block_id, op: MemOp { operation, index, value }, predicate } => {
+            let block_id = ("block", self::block_id(block_id));
+            let index = ("index", expression(index));
+            let value = ("value", expression(value));
+            if *operation == Expression::zero() {
+                call("MEMORY_READ", with_predicate(vec![block_id, index, value], predicate))
+            } else if *operation == Expression::one() {
+                call("MEMORY_WRITE", with_predicate(vec![block_id, index, value], predicate))
+            } else {
+                let operation = ("operation", expression(operation));
+                call(
+                    "MEMORY_OP",
+                    with_predicate(vec![block_id, operation, index, value], predicate),
+                )
+            }
+        }
+        Opcode::MemoryInit { block_id, init } => call(
+            "MEMORY_INIT",
+            vec![("block", self::block_id(block_id)), ("init", witness_list(init))],
+        ),
+    }
+}
+
+fn black_box_func_call(func_call: &BlackBoxFuncCall) -> String {
+    let fields = match func_call {
+        BlackBoxFuncCall::AND { lhs, rhs, output } | BlackBoxFuncCall::XOR { lhs, rhs, output } => {
+            vec![
+                ("lhs", function_input(lhs)),
+                ("rhs", function_input(rhs)),
+                ("output", witness(output)),
+            ]
+        }
+        BlackBoxFuncCall::RANGE { input } => vec![("input", function_input(input))],
+        BlackBoxFuncCall::SHA256 { inputs, outputs }
+        | BlackBoxFuncCall::Blake2s { inputs, outputs }
+        | BlackBoxFuncCall::Blake3 { inputs, outputs }
+        | BlackBoxFuncCall::Keccak256 { inputs, outputs }
+        | BlackBoxFuncCall::Keccakf1600 { inputs, outputs } => {
+            vec![("inputs", function_inputs(inputs)), ("outputs", witness_list(outputs))]
+        }
+        BlackBoxFuncCall::Keccak256VariableLength { inputs, var_message_size, outputs } => vec![
+            ("inputs", function_inputs(inputs)),
+            ("var_message_size", function_input(var_message_size)),
+            ("outputs", witness_list(outputs)),
+        ],
+        BlackBoxFuncCall::SchnorrVerify {
+            public_key_x,
+            public_key_y,
+            signature,
+            message,
+            output,
+        } => vec![
+            ("public_key_x", function_input(public_key_x)),
+            ("public_key_y", function_input(public_key_y)),
+            ("signature", function_inputs(signature)),
+            ("message", function_inputs(message)),
+            ("output", witness(output)),
+        ],
+        BlackBoxFuncCall::PedersenCommitment { inputs, domain_separator, outputs } => vec![
+            ("inputs", function_inputs(inputs)),
+            ("domain_separator", domain_separator.to_string()),
+            ("outputs", witness_list([&outputs.0, &outputs.1])),
+        ],
+        BlackBoxFuncCall::PedersenHash { inputs, domain_separator, output } => vec![
+            ("inputs", function_inputs(inputs)),
+            ("domain_separator", domain_separator.to_string()),
+            ("output", witness(output)),
+        ],
+        BlackBoxFuncCall::EcdsaSecp256k1 {
+            public_key_x,
+            public_key_y,
+            signature,
+            hashed_message,
+            output,
+        }
+        | BlackBoxFuncCall::EcdsaSecp256r1 {
+            public_key_x,
+            public_key_y,
+            signature,
+            hashed_message,
+            output,
+        } => vec![
+            ("public_key_x", function_inputs(public_key_x)),
+            ("public_key_y", function_inputs(public_key_y)),
+            ("signature", function_inputs(signature)),
+            ("hashed_message", function_inputs(hashed_message)),
+            ("output", witness(output)),
+        ],
+        BlackBoxFuncCall::FixedBaseScalarMul { low, high, outputs } => vec![
+            ("low", function_input(low)),
+            ("high", function_input(high)),
+            ("outputs", witness_list([&outputs.0, &outputs.1])),
+        ],
+        BlackBoxFuncCall::EmbeddedCurveAdd { input1_x, input1_y, input2_x, input2_y, outputs } => {
+            vec![
+                ("input1_x", function_input(input1_x)),
+                ("input1_y", function_input(input1_y)),
+                ("input2_x", function_input(input2_x)),
+                ("input2_y", function_input(input2_y)),
+                ("outputs", witness_list([&outputs.0, &outputs.1])),
+            ]
+        }
+        BlackBoxFuncCall::EmbeddedCurveDouble { input_x, input_y, outputs } => vec![
+            ("input_x", function_input(input_x)),
+            ("input_y", function_input(input_y)),
+            ("outputs", witness_list([&outputs.0, &outputs.1])),
+        ],
+        BlackBoxFuncCall::RecursiveAggregation {
+            verification_key,
+            proof,
+            public_inputs,
+            key_hash,
+        } => vec![
+            ("verification_key", function_inputs(verification_key)),
+            ("proof", function_inputs(proof)),
+            ("public_inputs", function_inputs(public_inputs)),
+            ("key_hash", function_input(key_hash)),
+        ],
+    };
+    call(&format!("BLACKBOX::{}", func_call.name().to_uppercase()), fields)
+}
+
+fn directive(directive: &Directive) -> String {
+    match directive {
+        Directive::Quotient(QuotientDirective { a, b, q, r, predicate }) => call(
+            "DIR::QUOTIENT",
+            with_predicate(
+                vec![
+                    ("a", expression(a)),
+                    ("b", expression(b)),
+                    ("q", witness(q)),
+                    ("r", witness(r)),
+                ],
+                predicate,
+            ),
+        ),
+        Directive::ToLeRadix { a, b, radix } => call(
+            "DIR::TO_LE_RADIX",
+            vec![("a", expression(a)), ("b", witness_list(b)), ("radix", radix.to_string())],
+        ),
+        Directive::PermutationSort { inputs, tuple, bits, sort_by } => call(
+            "DIR::PERMUTATION_SORT",
+            vec![
+                ("inputs", list(inputs.iter().map(|tuple| list(tuple.iter().map(expression))))),
+                ("tuple", tuple.to_string()),
+                ("bits", witness_list(bits)),
+                ("sort_by", list(sort_by.iter().map(u32::to_string))),
+            ],
+        ),
+    }
+}
+
+fn brillig(brillig: &Brillig) -> String {
+    let inputs = brillig.inputs.iter().map(|input| match input {
+        BrilligInputs::Single(expr) => expression(expr),
+        BrilligInputs::Array(exprs) => list(exprs.iter().map(expression)),
+    });
+    let outputs = brillig.outputs.iter().map(|output| match output {
+        BrilligOutputs::Simple(output) => witness(output),
+        BrilligOutputs::Array(outputs) => witness_list(outputs),
+    });
+    let header = call(
+        "BRILLIG",
+        with_predicate(
+            vec![("inputs", list(inputs)), ("outputs", list(outputs))],
+            &brillig.predicate,
+        ),
+    );
+
+    let mut output = format!("{header} {{\n");
+    for opcode in &brillig.bytecode {
+        writeln!(output, "    {}", brillig_opcode(opcode)).unwrap();
+    }
+    output.push('}');
+    output
+}
+
+pub(super) fn binary_field_op_name(op: &BinaryFieldOp) -> &'static str {
+    match op {
+        BinaryFieldOp::Add => "add",
+        BinaryFieldOp::Sub => "sub",
+        BinaryFieldOp::Mul => "mul",
+        BinaryFieldOp::Div => "div",
+        BinaryFieldOp::Equals => "equals",
+    }
+}
+
+pub(super) fn binary_int_op_name(op: &BinaryIntOp) -> &'static str {
+    match op {
+        BinaryIntOp::Add => "add",
+        BinaryIntOp::Sub => "sub",
+        BinaryIntOp::Mul => "mul",
+        BinaryIntOp::SignedDiv => "signed_div",
+        BinaryIntOp::UnsignedDiv => "unsigned_div",
+        BinaryIntOp::Equals => "equals",
+        BinaryIntOp::LessThan => "less_than",
+        BinaryIntOp::LessThanEquals => "less_than_equals",
+        BinaryIntOp::And => "and",
+        BinaryIntOp::Or => "or",
+        BinaryIntOp::Xor => "xor",
+        BinaryIntOp::Shl => "shl",
+        BinaryIntOp::Shr => "shr",
+    }
+}
+
+fn brillig_opcode(opcode: &BrilligOpcode) -> String {
+    match opcode {
+        BrilligOpcode::BinaryFieldOp { destination, op, lhs, rhs } => call(
+            "binary_field_op",
+            vec![
+                ("destination", register(destination)),
+                ("op", binary_field_op_name(op).to_string()),
+                ("lhs", register(lhs)),
+                ("rhs", register(rhs)),
+            ],
+        ),
+        BrilligOpcode::BinaryIntOp { destination, op, bit_size, lhs, rhs } => call(
+            "binary_int_op",
+            vec![
+                ("destination", register(destination)),
+                ("op", binary_int_op_name(op).to_string()),
+                ("bit_size", bit_size.to_string()),
+                ("lhs", register(lhs)),
+                ("rhs", register(rhs)),
+            ],
+        ),
+        BrilligOpcode::JumpIfNot { condition, location } => call(
+            "jump_if_not",
+            vec![("condition", register(condition)), ("location", location.to_string())],
+        ),
+        BrilligOpcode::JumpIf { condition, location } => call(
+            "jump_if",
+            vec![("condition", register(condition)), ("location", location.to_string())],
+        ),
+        BrilligOpcode::Jump { location } => call("jump", vec![("location", location.to_string())]),
+        BrilligOpcode::Call { location } => call("call", vec![("location", location.to_string())]),
+        BrilligOpcode::Const { destination, value } => call(
+            "const",
+            vec![("destination", register(destination)), ("value", field(value.to_field()))],
+        ),
+        BrilligOpcode::Return => call("return", vec![]),
+        BrilligOpcode::ForeignCall { function, destinations, inputs } => call(
+            "foreign_call",
+            vec![
+                ("function", format!("{function:?}")),
+                ("destinations", list(destinations.iter().map(register_or_memory))),
+                ("inputs", list(inputs.iter().map(register_or_memory))),
+            ],
+        ),
+        BrilligOpcode::Mov { destination, source } => {
+            call("mov", vec![("destination", register(destination)), ("source", register(source))])
+        }
+        BrilligOpcode::Load { destination, source_pointer } => call(
+            "load",
+            vec![
+                ("destination", register(destination)),
+                ("source_pointer", register(source_pointer)),
+            ],
+        ),
+        BrilligOpcode::Store { destination_pointer, source } => call(
+            "store",
+            vec![
+                ("destination_pointer", register(destination_pointer)),
+                ("source", register(source)),
+            ],
+        ),
+        BrilligOpcode::BlackBox(op) => black_box_op(op),
+        BrilligOpcode::Trap => call("trap", vec![]),
+        BrilligOpcode::Stop => call("stop", vec![]),
+    }
+}
+
+fn black_box_op(op: &BlackBoxOp) -> String {
+    let (name, fields) = match op {
+        BlackBoxOp::Sha256 { message, output } => {
+            ("sha256", vec![("message", heap_vector(message)), ("output", heap_array(output))])
+        }
+        BlackBoxOp::Blake2s { message, output } => {
+            ("blake2s", vec![("message", heap_vector(message)), ("output", heap_array(output))])
+        }
+        BlackBoxOp::Keccak256 { message, output } => {
+            ("keccak256", vec![("message", heap_vector(message)), ("output", heap_array(output))])
+        }
+        BlackBoxOp::EcdsaSecp256k1 {
+            hashed_msg,
+            public_key_x,
+            public_key_y,
+            signature,
+            result,
+        } => (
+            "ecdsa_secp256k1",
+            vec![
+                ("hashed_msg", heap_vector(hashed_msg)),
+                ("public_key_x", heap_array(public_key_x)),
+                ("public_key_y", heap_array(public_key_y)),
+                ("signature", heap_array(signature)),
+                ("result", register(result)),
+            ],
+        ),
+        BlackBoxOp::EcdsaSecp256r1 {
+            hashed_msg,
+            public_key_x,
+            public_key_y,
+            signature,
+            result,
+        } => (
+            "ecdsa_secp256r1",
+            vec![
+                ("hashed_msg", heap_vector(hashed_msg)),
+                ("public_key_x", heap_array(public_key_x)),
+                ("public_key_y", heap_array(public_key_y)),
+                ("signature", heap_array(signature)),
+                ("result", register(result)),
+            ],
+        ),
+        BlackBoxOp::SchnorrVerify { public_key_x, public_key_y, message, signature, result } => (
+            "schnorr_verify",
+            vec![
+                ("public_key_x", register(public_key_x)),
+                ("public_key_y", register(public_key_y)),
+                ("message", heap_vector(message)),
+                ("signature", heap_vector(signature)),
+                ("result", register(result)),
+            ],
+        ),
+        BlackBoxOp::PedersenCommitment { inputs, domain_separator, output } => (
+            "pedersen_commitment",
+            vec![
+                ("inputs", heap_vector(inputs)),
+                ("domain_separator", register(domain_separator)),
+                ("output", heap_array(output)),
+            ],
+        ),
+        BlackBoxOp::PedersenHash { inputs, domain_separator, output } => (
+            "pedersen_hash",
+            vec![
+                ("inputs", heap_vector(inputs)),
+                ("domain_separator", register(domain_separator)),
+                ("output", register(output)),
+            ],
+        ),
+        BlackBoxOp::FixedBaseScalarMul { low, high, result } => (
+            "fixed_base_scalar_mul",
+            vec![("low", register(low)), ("high", register(high)), ("result", heap_array(result))],
+        ),
+        BlackBoxOp::EmbeddedCurveAdd { input1_x, input1_y, input2_x, input2_y, result } => (
+            "embedded_curve_add",
+            vec![
+                ("input1_x", register(input1_x)),
+                ("input1_y", register(input1_y)),
+                ("input2_x", register(input2_x)),
+                ("input2_y", register(input2_y)),
+                ("result", heap_array(result)),
+            ],
+        ),
+        BlackBoxOp::EmbeddedCurveDouble { input1_x, input1_y, result } => (
+            "embedded_curve_double",
+            vec![
+                ("input1_x", register(input1_x)),
+                ("input1_y", register(input1_y)),
+                ("result", heap_array(result)),
+            ],
+        ),
+    };
+    call(&format!("black_box::{name}"), fields)
+}
@@ -9,7 +9,7 @@ mod optimizers;
 mod transformers;
 
 pub use optimizers::optimize;
-use optimizers::optimize_internal;
+use optimizers::{full_optimize_internal, optimize_internal};
 pub use transformers::transform;
 use transformers::transform_internal;
 
@@ -33,6 +33,24 @@ impl AcirTransformationMap {
         AcirTransformationMap { old_indices_to_new_indices }
     }
 
+    /// Maps the old acir indices of opcodes which were merged into other opcodes to the new indices
+    /// of the opcodes they were merged into.
+    /// Each pair holds the old index of the merged opcode and the old index of the opcode it was merged into.
+    fn with_merged_opcodes(mut self, merged_opcode_positions: Vec<(usize, usize)>) -> Self {
+        // An opcode may have been merged into an opcode which was itself merged later on,
+        // so later merges must be resolved first.
+        for (merged_index, surviving_index) in merged_opcode_positions.into_iter().rev() {
+            if let Some(new_indices) = self.old_indices_to_new_indices.get(&surviving_index) {
+                let new_indices = new_indices.clone();
+                self.old_indices_to_new_indices
+                    .entry(merged_index)
+                    .or_default()
+                    .extend(new_indices);
+            }
+        }
+        self
+    }
+
     pub fn new_locations(
         &self,
         old_location: OpcodeLocation,
@@ -68,17 +86,56 @@ fn transform_assert_messages(
         .collect()
 }
 
+/// Specifies which optimization passes are applied to a [`Circuit`] when compiling it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OptimizationLevel {
+    /// Simplifies expressions and removes redundant range constraints and unused memory blocks.
+    #[default]
+    Basic,
+    /// Additionally removes duplicate opcodes, inlines witnesses defined by linear expressions
+    /// and removes directives whose outputs are unused.
+    Full,
+}
+
+impl std::str::FromStr for OptimizationLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "basic" => Ok(OptimizationLevel::Basic),
+            "full" => Ok(OptimizationLevel::Full),
+            _ => Err(format!("Invalid optimization level `{s}`, expected `basic` or `full`")),
+        }
+    }
+}
+
+impl std::fmt::Display for OptimizationLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptimizationLevel::Basic => write!(f, "basic"),
+            OptimizationLevel::Full => write!(f, "full"),
+        }
+    }
+}
+
 /// Applies [`ProofSystemCompiler`][crate::ProofSystemCompiler] specific optimizations to a [`Circuit`].
 pub fn compile(
     acir: Circuit,
     expression_width: ExpressionWidth,
+    optimization_level: OptimizationLevel,
 ) -> (Circuit, AcirTransformationMap) {
     let (acir, acir_opcode_positions) = optimize_internal(acir);
 
+    let (acir, acir_opcode_positions, merged_opcode_positions) = match optimization_level {
+        OptimizationLevel::Basic => (acir, acir_opcode_positions, Vec::new()),
+        OptimizationLevel::Full => full_optimize_internal(acir, acir_opcode_positions),
+    };
+
     let (mut acir, acir_opcode_positions) =
         transform_internal(acir, expression_width, acir_opcode_positions);
 
-    let transformation_map = AcirTransformationMap::new(acir_opcode_positions);
+    let transformation_map = AcirTransformationMap::new(acir_opcode_positions)
+        .with_merged_opcodes(merged_opcode_positions);
 
     acir.assert_messages = transform_assert_messages(acir.assert_messages, &transformation_map);
 
@@ -0,0 +1,197 @@
+use acir::{
+    circuit::{opcodes::BlackBoxFuncCall, Circuit, Opcode},
+    native_types::{Expression, Witness},
+    BlackBoxFunc, FieldElement,
+};
+use std::collections::{hash_map::Entry, HashMap};
+
+/// `DuplicateOpcodeOptimizer` will remove opcodes which are implied by an earlier opcode.
+///
+/// - An [`Opcode::AssertZero`] which constrains the same expression as an earlier one is removed.
+/// - An [`Opcode::BlackBoxFuncCall`] which is applied to the same inputs as an earlier one is replaced
+///   by constraints equating its outputs to the outputs of the earlier call.
+pub(crate) struct DuplicateOpcodeOptimizer {
+    circuit: Circuit,
+}
+
+/// Identifies the inputs of a black box function call, ignoring its outputs.
+#[derive(PartialEq, Eq, Hash)]
+struct BlackBoxCallKey {
+    func: BlackBoxFunc,
+    inputs: Vec<(Witness, u32)>,
+    domain_separator: Option<u32>,
+}
+
+impl BlackBoxCallKey {
+    /// Returns `None` for calls which can't be deduplicated by this pass.
+    fn new(call: &BlackBoxFuncCall) -> Option<Self> {
+        let domain_separator = match call {
+            // Duplicate range constraints are already removed by the `RangeOptimizer`.
+            BlackBoxFuncCall::RANGE { .. }
+            // The inputs of a recursive aggregation are split into several variable length arrays,
+            // which can't be told apart once they have been flattened.
+            | BlackBoxFuncCall::RecursiveAggregation { .. } => return None,
+            BlackBoxFuncCall::PedersenCommitment { domain_separator, .. }
+            | BlackBoxFuncCall::PedersenHash { domain_separator, .. } => Some(*domain_separator),
+            _ => None,
+        };
+        let inputs =
+            call.get_inputs_vec().iter().map(|input| (input.witness, input.num_bits)).collect();
+        Some(BlackBoxCallKey { func: call.get_black_box_func(), inputs, domain_separator })
+    }
+}
+
+impl DuplicateOpcodeOptimizer {
+    pub(crate) fn new(circuit: Circuit) -> Self {
+        Self { circuit }
+    }
+
+    /// Returns a `Circuit` where duplicate opcodes are removed, along with the pairs of old opcode positions
+    /// of the removed opcodes and of the opcodes they duplicate.
+    pub(crate) fn remove_duplicate_opcodes(
+        self,
+        order_list: Vec<usize>,
+    ) -> (Circuit, Vec<usize>, Vec<(usize, usize)>) {
+        let mut seen_expressions: HashMap<Expression, usize> = HashMap::new();
+        let mut seen_calls: HashMap<BlackBoxCallKey, (usize, Vec<Witness>)> = HashMap::new();
+
+        let mut new_order_list = Vec::with_capacity(order_list.len());
+        let mut optimized_opcodes = Vec::with_capacity(self.circuit.opcodes.len());
+        let mut merged_opcodes = Vec::new();
+        for (idx, opcode) in self.circuit.opcodes.into_iter().enumerate() {
+            match &opcode {
+                Opcode::AssertZero(expr) => {
+                    let mut expr = expr.clone();
+                    expr.sort();
+                    match seen_expressions.entry(expr) {
+                        Entry::Occupied(entry) => {
+                            merged_opcodes.push((order_list[idx], *entry.get()));
+                            continue;
+                        }
+                        Entry::Vacant(entry) => {
+                            entry.insert(order_list[idx]);
+                        }
+                    }
+                }
+                Opcode::BlackBoxFuncCall(call) => {
+                    if let Some(key) = BlackBoxCallKey::new(call) {
+                        let outputs = call.get_outputs_vec();
+                        match seen_calls.entry(key) {
+                            Entry::Occupied(entry) => {
+                                let (original_position, original_outputs) = entry.get();
+                                if outputs.is_empty() {
+                                    merged_opcodes.push((order_list[idx], *original_position));
+                                }
+                                // The outputs of the duplicate call are constrained to be equal to the outputs
+                                // of the original call instead.
+                                for (output, original_output) in
+                                    outputs.iter().zip(original_outputs)
+                                {
+                                    if output != original_output {
+                                        let mut expr = Expression::default();
+                                        expr.push_addition_term(
+                                            FieldElement::one(),
+                                            *original_output,
+                                        );
+                                        expr.push_addition_term(-FieldElement::one(), *output);
+                                        expr.sort();
+                                        new_order_list.push(order_list[idx]);
+                                        optimized_opcodes.push(Opcode::AssertZero(expr));
+                                    }
+                                }
+                                continue;
+                            }
+                            Entry::Vacant(entry) => {
+                                entry.insert((order_list[idx], outputs));
+                            }
+                        }
+                    }
+                }
+                _ => (),
+            }
+            new_order_list.push(order_list[idx]);
+            optimized_opcodes.push(opcode);
+        }
+
+        (Circuit { opcodes: optimized_opcodes, ..self.circuit }, new_order_list, merged_opcodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::compiler::optimizers::duplicate_opcodes::DuplicateOpcodeOptimizer;
+    use acir::{
+        circuit::{
+            opcodes::{BlackBoxFuncCall, FunctionInput},
+            Circuit, Opcode, PublicInputs,
+        },
+        native_types::{Expression, Witness},
+        FieldElement,
+    };
+
+    fn test_circuit(opcodes: Vec<Opcode>) -> Circuit {
+        Circuit {
+            current_witness_index: 10,
+            opcodes,
+            private_parameters: BTreeSet::new(),
+            public_parameters: PublicInputs::default(),
+            return_values: PublicInputs::default(),
+            assert_messages: Default::default(),
+        }
+    }
+
+    fn sha256(input: Witness, outputs: Vec<Witness>) -> Opcode {
+        Opcode::BlackBoxFuncCall(BlackBoxFuncCall::SHA256 {
+            inputs: vec![FunctionInput { witness: input, num_bits: 8 }],
+            outputs,
+        })
+    }
+
+    #[test]
+    fn removes_duplicate_assert_zero() {
+        let circuit = test_circuit(vec![
+            Opcode::AssertZero(Witness(1) + Witness(2)),
+            Opcode::AssertZero(&Expression::from(Witness(1)) - Witness(2)),
+            Opcode::AssertZero(Witness(2) + Witness(1)),
+        ]);
+        let acir_opcode_positions = circuit.opcodes.iter().enumerate().map(|(i, _)| i).collect();
+
+        let optimizer = DuplicateOpcodeOptimizer::new(circuit);
+        let (optimized_circuit, new_positions, merged_opcodes) =
+            optimizer.remove_duplicate_opcodes(acir_opcode_positions);
+
+        assert_eq!(optimized_circuit.opcodes.len(), 2);
+        assert_eq!(new_positions, vec![0, 1]);
+        assert_eq!(merged_opcodes, vec![(2, 0)]);
+    }
+
+    #[test]
+    fn replaces_duplicate_black_box_calls_with_equalities() {
+        let circuit = test_circuit(vec![
+            sha256(Witness(1), vec![Witness(2), Witness(3)]),
+            sha256(Witness(4), vec![Witness(5), Witness(6)]),
+            sha256(Witness(1), vec![Witness(7), Witness(8)]),
+        ]);
+        let acir_opcode_positions = circuit.opcodes.iter().enumerate().map(|(i, _)| i).collect();
+
+        let optimizer = DuplicateOpcodeOptimizer::new(circuit);
+        let (optimized_circuit, new_positions, merged_opcodes) =
+            optimizer.remove_duplicate_opcodes(acir_opcode_positions);
+
+        let equality = |lhs: Witness, rhs: Witness| {
+            let mut expr = Expression::default();
+            expr.push_addition_term(FieldElement::one(), lhs);
+            expr.push_addition_term(-FieldElement::one(), rhs);
+            expr.sort();
+            Opcode::AssertZero(expr)
+        };
+        assert_eq!(
+            optimized_circuit.opcodes[2..],
+            [equality(Witness(2), Witness(7)), equality(Witness(3), Witness(8))]
+        );
+        assert_eq!(new_positions, vec![0, 1, 2, 2]);
+        assert!(merged_opcodes.is_empty());
+    }
+}
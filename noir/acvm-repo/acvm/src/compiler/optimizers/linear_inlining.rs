@@ -0,0 +1,391 @@
+use acir::{
+    circuit::{Circuit, Opcode},
+    native_types::{Expression, Witness},
+    FieldElement,
+};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use super::{circuit_io_witnesses, opcode_witnesses, GeneralOptimizer};
+
+/// `LinearInliningOptimizer` will inline witnesses which are defined by a linear [`Opcode::AssertZero`]
+/// and used in a single other [`Opcode::AssertZero`].
+///
+/// # Example
+///
+/// The opcodes
+///
+/// ```text
+/// w3 - 2*w1 - 1 = 0
+/// w3*w2 - w4 = 0
+/// ```
+/// only use `w3` to compute `2*w1 + 1`, so they can be merged into the single opcode
+///
+/// ```text
+/// 2*w1*w2 + w2 - w4 = 0
+/// ```
+///
+/// The merged opcode takes the place of the latter of the two opcodes, where all of the witnesses
+/// which were known when solving either opcode are known.
+///
+/// Opcodes are only merged if the ACVM can still solve the merged opcode, that is if it has at most one
+/// multiplication term and at most one unknown witness when it is reached. For example, inlining `w3` into
+/// `w3*w4 - 5 = 0` instead would result in the opcode `w1*w4 + 2*w2*w4 - 5 = 0`, so it is kept as is.
+pub(crate) struct LinearInliningOptimizer {
+    circuit: Circuit,
+}
+
+impl LinearInliningOptimizer {
+    pub(crate) fn new(circuit: Circuit) -> Self {
+        Self { circuit }
+    }
+
+    /// Returns a `Circuit` where witnesses defined by linear expressions are inlined into the opcode using them,
+    /// along with the pairs of old opcode positions of the removed definitions and of the opcodes they were merged into.
+    pub(crate) fn inline_linear_witnesses(
+        self,
+        mut order_list: Vec<usize>,
+    ) -> (Circuit, Vec<usize>, Vec<(usize, usize)>) {
+        let mut circuit = self.circuit;
+        let mut merged_opcodes = Vec::new();
+        // Each round merges disjoint pairs of opcodes, so chains of definitions are inlined over several rounds.
+        loop {
+            let (new_circuit, new_order_list, merged_in_round) = inline_round(circuit, order_list);
+            circuit = new_circuit;
+            order_list = new_order_list;
+            if merged_in_round.is_empty() {
+                break;
+            }
+            merged_opcodes.extend(merged_in_round);
+        }
+        (circuit, order_list, merged_opcodes)
+    }
+}
+
+/// Inlines the definitions of witnesses into the opcodes using them, such that each opcode is merged at most once.
+fn inline_round(
+    circuit: Circuit,
+    order_list: Vec<usize>,
+) -> (Circuit, Vec<usize>, Vec<(usize, usize)>) {
+    let io_witnesses = circuit_io_witnesses(&circuit);
+    let parameters = circuit.circuit_arguments();
+    let solving_opcodes = solving_opcodes(&circuit, &parameters);
+
+    // Maps each witness to the indices of the opcodes using it.
+    let mut witness_uses: BTreeMap<Witness, Vec<usize>> = BTreeMap::new();
+    for (idx, opcode) in circuit.opcodes.iter().enumerate() {
+        for witness in opcode_witnesses(opcode) {
+            witness_uses.entry(witness).or_default().push(idx);
+        }
+    }
+
+    // Maps the index of an opcode which is replaced by a merged opcode to this merged opcode.
+    let mut replacements: BTreeMap<usize, Expression> = BTreeMap::new();
+    // Indices of the opcodes which are removed, and of the opcode they were merged into.
+    let mut removed: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut touched: HashSet<usize> = HashSet::new();
+
+    for (definition_idx, opcode) in circuit.opcodes.iter().enumerate() {
+        let Opcode::AssertZero(definition) = opcode else { continue };
+        if !definition.is_linear() || touched.contains(&definition_idx) {
+            continue;
+        }
+        for (coefficient, witness) in &definition.linear_combinations {
+            if coefficient.is_zero() || io_witnesses.contains(witness) {
+                continue;
+            }
+            let Some(user_idx) = single_other_use(&witness_uses, *witness, definition_idx) else {
+                continue;
+            };
+            if touched.contains(&user_idx) {
+                continue;
+            }
+            let Opcode::AssertZero(user) = &circuit.opcodes[user_idx] else { continue };
+            let Some(merged) = inline_witness(definition, *coefficient, *witness, user) else {
+                continue;
+            };
+
+            let (removed_idx, kept_idx) = if definition_idx < user_idx {
+                (definition_idx, user_idx)
+            } else {
+                (user_idx, definition_idx)
+            };
+            let is_known = |witness: Witness| {
+                parameters.contains(&witness)
+                    || solving_opcodes.get(&witness).is_some_and(|idx| *idx < kept_idx)
+            };
+            if !is_solvable(&merged, is_known) {
+                continue;
+            }
+            replacements.insert(kept_idx, merged);
+            removed.insert(removed_idx, kept_idx);
+            touched.insert(definition_idx);
+            touched.insert(user_idx);
+            break;
+        }
+    }
+
+    let mut new_order_list = Vec::with_capacity(order_list.len());
+    let mut optimized_opcodes = Vec::with_capacity(circuit.opcodes.len());
+    for (idx, opcode) in circuit.opcodes.into_iter().enumerate() {
+        if removed.contains_key(&idx) {
+            continue;
+        }
+        let opcode = match replacements.remove(&idx) {
+            Some(merged) => Opcode::AssertZero(merged),
+            None => opcode,
+        };
+        new_order_list.push(order_list[idx]);
+        optimized_opcodes.push(opcode);
+    }
+    let merged_opcodes = removed
+        .into_iter()
+        .map(|(removed_idx, kept_idx)| (order_list[removed_idx], order_list[kept_idx]))
+        .collect();
+
+    (Circuit { opcodes: optimized_opcodes, ..circuit }, new_order_list, merged_opcodes)
+}
+
+/// Maps the witnesses which aren't `parameters` to the index of the opcode solving them, assuming that an
+/// [`Opcode::AssertZero`] solves its only unknown witness and that other opcodes solve all of their unknown witnesses.
+fn solving_opcodes(circuit: &Circuit, parameters: &BTreeSet<Witness>) -> BTreeMap<Witness, usize> {
+    let mut solving_opcodes = BTreeMap::new();
+    for (idx, opcode) in circuit.opcodes.iter().enumerate() {
+        let unknowns: Vec<Witness> = opcode_witnesses(opcode)
+            .into_iter()
+            .filter(|witness| {
+                !parameters.contains(witness) && !solving_opcodes.contains_key(witness)
+            })
+            .collect();
+        if matches!(opcode, Opcode::AssertZero(_)) && unknowns.len() > 1 {
+            continue;
+        }
+        for witness in unknowns {
+            solving_opcodes.insert(witness, idx);
+        }
+    }
+    solving_opcodes
+}
+
+/// Returns whether the ACVM can solve `expr` when only the witnesses for which `is_known` holds are known.
+///
+/// This mirrors the ACVM's `ExpressionSolver`, which requires at most one multiplication term and at most
+/// one unknown witness once the known witnesses are substituted.
+fn is_solvable(expr: &Expression, is_known: impl Fn(Witness) -> bool) -> bool {
+    if expr.mul_terms.len() > 1 {
+        return false;
+    }
+    let mut unknowns = BTreeSet::new();
+    for (_, lhs, rhs) in &expr.mul_terms {
+        match (is_known(*lhs), is_known(*rhs)) {
+            (true, true) => {}
+            (false, false) => return false,
+            (false, true) => {
+                unknowns.insert(*lhs);
+            }
+            (true, false) => {
+                unknowns.insert(*rhs);
+            }
+        }
+    }
+    unknowns.extend(
+        expr.linear_combinations.iter().map(|(_, witness)| *witness).filter(|w| !is_known(*w)),
+    );
+    unknowns.len() <= 1
+}
+
+/// Returns the index of the only opcode other than `opcode_idx` which uses `witness`, if any.
+fn single_other_use(
+    witness_uses: &BTreeMap<Witness, Vec<usize>>,
+    witness: Witness,
+    opcode_idx: usize,
+) -> Option<usize> {
+    match witness_uses.get(&witness).map(Vec::as_slice) {
+        Some(&[first, second]) if first == opcode_idx => Some(second),
+        Some(&[first, second]) if second == opcode_idx => Some(first),
+        _ => None,
+    }
+}
+
+/// Substitutes `witness` in `user` by its value according to the linear `definition`,
+/// where `witness` has the coefficient `coefficient`.
+///
+/// Returns `None` if `witness` can't be substituted, such as when it is squared in `user`.
+fn inline_witness(
+    definition: &Expression,
+    coefficient: FieldElement,
+    witness: Witness,
+    user: &Expression,
+) -> Option<Expression> {
+    // The definition must be `coefficient * witness + rest = 0`, so that `witness = -rest / coefficient`.
+    if definition.linear_combinations.iter().filter(|(_, w)| *w == witness).count() != 1 {
+        return None;
+    }
+    let mut rest = definition.clone();
+    rest.linear_combinations.retain(|(_, w)| *w != witness);
+    rest.sort();
+    let value = &rest * -coefficient.inverse();
+
+    // Split `user` into `factor * witness + remainder`, where neither `factor` nor `remainder` use `witness`.
+    let mut factor = Expression::default();
+    let mut remainder = Expression { q_c: user.q_c, ..Expression::default() };
+    for (q_m, lhs, rhs) in &user.mul_terms {
+        match (*lhs == witness, *rhs == witness) {
+            (true, true) => return None,
+            (true, false) => factor.push_addition_term(*q_m, *rhs),
+            (false, true) => factor.push_addition_term(*q_m, *lhs),
+            (false, false) => remainder.push_multiplication_term(*q_m, *lhs, *rhs),
+        }
+    }
+    for (q_l, w) in &user.linear_combinations {
+        if *w == witness {
+            factor.q_c += *q_l;
+        } else {
+            remainder.push_addition_term(*q_l, *w);
+        }
+    }
+    factor.sort();
+    remainder.sort();
+
+    let mut product = (&factor * &value)?;
+    product.sort();
+    let mut merged = GeneralOptimizer::optimize(&remainder + &product);
+    merged.sort();
+    Some(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::compiler::optimizers::{linear_inlining::LinearInliningOptimizer, opcode_witnesses};
+    use acir::{
+        circuit::{Circuit, Opcode, PublicInputs},
+        native_types::{Expression, Witness},
+        FieldElement,
+    };
+
+    fn used_witnesses(circuit: &Circuit) -> BTreeSet<Witness> {
+        circuit.opcodes.iter().flat_map(opcode_witnesses).collect()
+    }
+
+    fn test_circuit(opcodes: Vec<Opcode>, return_values: Vec<Witness>) -> Circuit {
+        Circuit {
+            current_witness_index: 10,
+            opcodes,
+            private_parameters: BTreeSet::from([Witness(1), Witness(2)]),
+            public_parameters: PublicInputs::default(),
+            return_values: PublicInputs(return_values.into_iter().collect()),
+            assert_messages: Default::default(),
+        }
+    }
+
+    #[test]
+    fn inlines_linear_definitions() {
+        // w3 = 2*w1 + 1
+        let mut definition = Expression::from_field(-FieldElement::one());
+        definition.push_addition_term(FieldElement::one(), Witness(3));
+        definition.push_addition_term(-FieldElement::from(2_u128), Witness(1));
+        // w3*w2 - w4 = 0
+        let mut user = Expression::default();
+        user.push_multiplication_term(FieldElement::one(), Witness(3), Witness(2));
+        user.push_addition_term(-FieldElement::one(), Witness(4));
+
+        let circuit = test_circuit(
+            vec![Opcode::AssertZero(definition), Opcode::AssertZero(user)],
+            vec![Witness(4)],
+        );
+        let acir_opcode_positions = circuit.opcodes.iter().enumerate().map(|(i, _)| i).collect();
+
+        let optimizer = LinearInliningOptimizer::new(circuit);
+        let (optimized_circuit, new_positions, merged_opcodes) =
+            optimizer.inline_linear_witnesses(acir_opcode_positions);
+
+        // 2*w1*w2 + w2 - w4 = 0
+        let mut expected = Expression::default();
+        expected.push_multiplication_term(FieldElement::from(2_u128), Witness(1), Witness(2));
+        expected.push_addition_term(FieldElement::one(), Witness(2));
+        expected.push_addition_term(-FieldElement::one(), Witness(4));
+        expected.sort();
+        assert_eq!(optimized_circuit.opcodes, vec![Opcode::AssertZero(expected)]);
+        assert_eq!(new_positions, vec![1]);
+        assert_eq!(merged_opcodes, vec![(0, 1)]);
+    }
+
+    #[test]
+    fn keeps_definitions_which_would_merge_into_multiple_mul_terms() {
+        // w3 = w1 + 2*w2
+        let mut definition = Expression::default();
+        definition.push_addition_term(FieldElement::one(), Witness(3));
+        definition.push_addition_term(-FieldElement::one(), Witness(1));
+        definition.push_addition_term(-FieldElement::from(2_u128), Witness(2));
+        // w3*w4 - 5 = 0, which would become w1*w4 + 2*w2*w4 - 5 = 0
+        let mut user = Expression::from_field(-FieldElement::from(5_u128));
+        user.push_multiplication_term(FieldElement::one(), Witness(3), Witness(4));
+
+        let circuit = test_circuit(
+            vec![Opcode::AssertZero(definition), Opcode::AssertZero(user)],
+            vec![Witness(4)],
+        );
+        let acir_opcode_positions = circuit.opcodes.iter().enumerate().map(|(i, _)| i).collect();
+
+        let optimizer = LinearInliningOptimizer::new(circuit.clone());
+        let (optimized_circuit, _, merged_opcodes) =
+            optimizer.inline_linear_witnesses(acir_opcode_positions);
+
+        assert_eq!(optimized_circuit, circuit);
+        assert!(merged_opcodes.is_empty());
+    }
+
+    #[test]
+    fn inlines_chains_of_definitions() {
+        // w3 = w1 + 1, w4 = w3 + 1, w5 = w4 + 1
+        let increment = |input: Witness, output: Witness| {
+            let mut expr = Expression::from_field(-FieldElement::one());
+            expr.push_addition_term(FieldElement::one(), output);
+            expr.push_addition_term(-FieldElement::one(), input);
+            Opcode::AssertZero(expr)
+        };
+        let circuit = test_circuit(
+            vec![
+                increment(Witness(1), Witness(3)),
+                increment(Witness(3), Witness(4)),
+                increment(Witness(4), Witness(5)),
+            ],
+            vec![Witness(5)],
+        );
+        let acir_opcode_positions = circuit.opcodes.iter().enumerate().map(|(i, _)| i).collect();
+
+        let optimizer = LinearInliningOptimizer::new(circuit);
+        let (optimized_circuit, new_positions, merged_opcodes) =
+            optimizer.inline_linear_witnesses(acir_opcode_positions);
+
+        // w5 = w1 + 3
+        assert_eq!(optimized_circuit.opcodes.len(), 1);
+        assert_eq!(used_witnesses(&optimized_circuit), BTreeSet::from([Witness(1), Witness(5)]));
+        assert_eq!(new_positions, vec![2]);
+        assert_eq!(merged_opcodes, vec![(0, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn keeps_circuit_inputs_and_outputs() {
+        // w3 = w1 + w2, where w3 is returned
+        let mut definition = Expression::default();
+        definition.push_addition_term(FieldElement::one(), Witness(3));
+        definition.push_addition_term(-FieldElement::one(), Witness(1));
+        definition.push_addition_term(-FieldElement::one(), Witness(2));
+        let user = &Expression::from(Witness(3)) - Witness(4);
+
+        let circuit = test_circuit(
+            vec![Opcode::AssertZero(definition), Opcode::AssertZero(user)],
+            vec![Witness(3), Witness(4)],
+        );
+        let acir_opcode_positions = circuit.opcodes.iter().enumerate().map(|(i, _)| i).collect();
+
+        let optimizer = LinearInliningOptimizer::new(circuit.clone());
+        let (optimized_circuit, _, merged_opcodes) =
+            optimizer.inline_linear_witnesses(acir_opcode_positions);
+
+        assert_eq!(optimized_circuit, circuit);
+        assert!(merged_opcodes.is_empty());
+    }
+}
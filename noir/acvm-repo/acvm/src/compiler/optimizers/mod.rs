@@ -1,14 +1,29 @@
-use acir::circuit::{Circuit, Opcode};
+use std::collections::BTreeSet;
 
+use acir::{
+    circuit::{
+        brillig::{BrilligInputs, BrilligOutputs},
+        directives::{Directive, QuotientDirective},
+        Circuit, Opcode,
+    },
+    native_types::{Expression, Witness},
+};
+
+mod duplicate_opcodes;
 mod general;
+mod linear_inlining;
 mod redundant_range;
+mod unused_directives;
 mod unused_memory;
 
 pub(crate) use general::GeneralOptimizer;
 pub(crate) use redundant_range::RangeOptimizer;
 use tracing::info;
 
-use self::unused_memory::UnusedMemoryOptimizer;
+use self::{
+    duplicate_opcodes::DuplicateOpcodeOptimizer, linear_inlining::LinearInliningOptimizer,
+    unused_directives::UnusedDirectiveOptimizer, unused_memory::UnusedMemoryOptimizer,
+};
 
 use super::{transform_assert_messages, AcirTransformationMap};
 
@@ -60,3 +75,116 @@ pub(super) fn optimize_internal(acir: Circuit) -> (Circuit, Vec<usize>) {
 
     (acir, acir_opcode_positions)
 }
+
+/// Applies the optimizations of [`OptimizationLevel::Full`][super::OptimizationLevel::Full] to a [`Circuit`]
+/// which has already been optimized by [`optimize_internal`].
+///
+/// Alongside the new opcode positions, returns the pairs of old opcode positions of opcodes which were merged
+/// into other opcodes and of the opcodes they were merged into.
+#[tracing::instrument(level = "trace", name = "full_optimize_acir" skip(acir, acir_opcode_positions))]
+pub(super) fn full_optimize_internal(
+    acir: Circuit,
+    acir_opcode_positions: Vec<usize>,
+) -> (Circuit, Vec<usize>, Vec<(usize, usize)>) {
+    info!("Number of opcodes before full optimization: {}", acir.opcodes.len());
+
+    // Duplicate opcode optimization pass
+    let duplicate_optimizer = DuplicateOpcodeOptimizer::new(acir);
+    let (acir, acir_opcode_positions, mut merged_opcode_positions) =
+        duplicate_optimizer.remove_duplicate_opcodes(acir_opcode_positions);
+
+    // Linear witness inlining pass
+    let inlining_optimizer = LinearInliningOptimizer::new(acir);
+    let (acir, acir_opcode_positions, inlined_opcode_positions) =
+        inlining_optimizer.inline_linear_witnesses(acir_opcode_positions);
+    merged_opcode_positions.extend(inlined_opcode_positions);
+
+    // Unused directive optimization pass
+    let directive_optimizer = UnusedDirectiveOptimizer::new(acir);
+    let (acir, acir_opcode_positions) =
+        directive_optimizer.remove_unused_directives(acir_opcode_positions);
+
+    info!("Number of opcodes after full optimization: {}", acir.opcodes.len());
+
+    (acir, acir_opcode_positions, merged_opcode_positions)
+}
+
+/// Returns the witnesses which are read or written by `opcode`.
+pub(super) fn opcode_witnesses(opcode: &Opcode) -> BTreeSet<Witness> {
+    fn expression_witnesses(expr: &Expression, witnesses: &mut BTreeSet<Witness>) {
+        for (_, lhs, rhs) in &expr.mul_terms {
+            witnesses.insert(*lhs);
+            witnesses.insert(*rhs);
+        }
+        witnesses.extend(expr.linear_combinations.iter().map(|(_, witness)| *witness));
+    }
+
+    let mut witnesses = BTreeSet::new();
+    match opcode {
+        Opcode::AssertZero(expr) => expression_witnesses(expr, &mut witnesses),
+        Opcode::BlackBoxFuncCall(call) => {
+            witnesses.extend(call.get_inputs_vec().iter().map(|input| input.witness));
+            witnesses.extend(call.get_outputs_vec());
+        }
+        Opcode::Directive(Directive::Quotient(QuotientDirective { a, b, q, r, predicate })) => {
+            expression_witnesses(a, &mut witnesses);
+            expression_witnesses(b, &mut witnesses);
+            if let Some(predicate) = predicate {
+                expression_witnesses(predicate, &mut witnesses);
+            }
+            witnesses.insert(*q);
+            witnesses.insert(*r);
+        }
+        Opcode::Directive(Directive::ToLeRadix { a, b, .. }) => {
+            expression_witnesses(a, &mut witnesses);
+            witnesses.extend(b);
+        }
+        Opcode::Directive(Directive::PermutationSort { inputs, bits, .. }) => {
+            for expr in inputs.iter().flatten() {
+                expression_witnesses(expr, &mut witnesses);
+            }
+            witnesses.extend(bits);
+        }
+        Opcode::Brillig(brillig) => {
+            for input in &brillig.inputs {
+                match input {
+                    BrilligInputs::Single(expr) => expression_witnesses(expr, &mut witnesses),
+                    BrilligInputs::Array(exprs) => {
+                        for expr in exprs {
+                            expression_witnesses(expr, &mut witnesses);
+                        }
+                    }
+                }
+            }
+            for output in &brillig.outputs {
+                match output {
+                    BrilligOutputs::Simple(witness) => {
+                        witnesses.insert(*witness);
+                    }
+                    BrilligOutputs::Array(outputs) => witnesses.extend(outputs),
+                }
+            }
+            if let Some(predicate) = &brillig.predicate {
+                expression_witnesses(predicate, &mut witnesses);
+            }
+        }
+        Opcode::MemoryOp { op, predicate, .. } => {
+            expression_witnesses(&op.operation, &mut witnesses);
+            expression_witnesses(&op.index, &mut witnesses);
+            expression_witnesses(&op.value, &mut witnesses);
+            if let Some(predicate) = predicate {
+                expression_witnesses(predicate, &mut witnesses);
+            }
+        }
+        Opcode::MemoryInit { init, .. } => witnesses.extend(init),
+    }
+    witnesses
+}
+
+/// Returns the witnesses which are visible outside of the circuit, and so must be kept by all optimizations.
+pub(super) fn circuit_io_witnesses(circuit: &Circuit) -> BTreeSet<Witness> {
+    let mut witnesses = circuit.private_parameters.clone();
+    witnesses.extend(&circuit.public_parameters.0);
+    witnesses.extend(&circuit.return_values.0);
+    witnesses
+}
@@ -0,0 +1,127 @@
+use acir::{
+    circuit::{
+        directives::{Directive, QuotientDirective},
+        Circuit, Opcode,
+    },
+    native_types::Witness,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{circuit_io_witnesses, opcode_witnesses};
+
+/// `UnusedDirectiveOptimizer` will remove directives whose outputs are not used by any other opcode.
+///
+/// Directives don't apply any constraints, so the witnesses they compute are only relevant to the circuit
+/// if they are used elsewhere.
+pub(crate) struct UnusedDirectiveOptimizer {
+    circuit: Circuit,
+}
+
+impl UnusedDirectiveOptimizer {
+    pub(crate) fn new(circuit: Circuit) -> Self {
+        Self { circuit }
+    }
+
+    /// Returns a `Circuit` where [`Opcode::Directive`]s with unused outputs are dropped.
+    pub(crate) fn remove_unused_directives(self, order_list: Vec<usize>) -> (Circuit, Vec<usize>) {
+        let io_witnesses = circuit_io_witnesses(&self.circuit);
+        let opcode_witnesses: Vec<_> = self.circuit.opcodes.iter().map(opcode_witnesses).collect();
+
+        // Counts the opcodes using each witness.
+        let mut witness_uses: BTreeMap<Witness, usize> = BTreeMap::new();
+        for witness in opcode_witnesses.iter().flatten() {
+            *witness_uses.entry(*witness).or_default() += 1;
+        }
+
+        // Removing a directive may leave the outputs of the directives computing its inputs unused,
+        // so directives are removed until none of the remaining ones are unused.
+        let mut removed: BTreeSet<usize> = BTreeSet::new();
+        loop {
+            let mut removed_in_round = false;
+            for (idx, opcode) in self.circuit.opcodes.iter().enumerate() {
+                let Opcode::Directive(directive) = opcode else { continue };
+                if removed.contains(&idx) {
+                    continue;
+                }
+                let is_unused = directive_outputs(directive).iter().all(|output| {
+                    !io_witnesses.contains(output) && witness_uses.get(output) == Some(&1)
+                });
+                if is_unused {
+                    for witness in &opcode_witnesses[idx] {
+                        *witness_uses.get_mut(witness).expect("witness uses are counted above") -=
+                            1;
+                    }
+                    removed.insert(idx);
+                    removed_in_round = true;
+                }
+            }
+            if !removed_in_round {
+                break;
+            }
+        }
+
+        let mut new_order_list = Vec::with_capacity(order_list.len());
+        let mut optimized_opcodes = Vec::with_capacity(self.circuit.opcodes.len());
+        for (idx, opcode) in self.circuit.opcodes.into_iter().enumerate() {
+            if !removed.contains(&idx) {
+                new_order_list.push(order_list[idx]);
+                optimized_opcodes.push(opcode);
+            }
+        }
+
+        (Circuit { opcodes: optimized_opcodes, ..self.circuit }, new_order_list)
+    }
+}
+
+/// Returns the witnesses computed by `directive`.
+fn directive_outputs(directive: &Directive) -> Vec<Witness> {
+    match directive {
+        Directive::Quotient(QuotientDirective { q, r, .. }) => vec![*q, *r],
+        Directive::ToLeRadix { b, .. } => b.clone(),
+        Directive::PermutationSort { bits, .. } => bits.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::compiler::optimizers::unused_directives::UnusedDirectiveOptimizer;
+    use acir::{
+        circuit::{directives::Directive, Circuit, Opcode, PublicInputs},
+        native_types::{Expression, Witness},
+    };
+
+    fn to_le_radix(input: Witness, outputs: Vec<Witness>) -> Opcode {
+        Opcode::Directive(Directive::ToLeRadix { a: input.into(), b: outputs, radix: 2 })
+    }
+
+    #[test]
+    fn removes_directives_with_unused_outputs() {
+        let circuit = Circuit {
+            current_witness_index: 6,
+            opcodes: vec![
+                // Only used by the unused directive below
+                to_le_radix(Witness(1), vec![Witness(2)]),
+                to_le_radix(Witness(2), vec![Witness(3), Witness(4)]),
+                // Output constrained below
+                to_le_radix(Witness(1), vec![Witness(5)]),
+                Opcode::AssertZero(&Expression::from(Witness(5)) - Witness(1)),
+                // Output returned from the circuit
+                to_le_radix(Witness(1), vec![Witness(6)]),
+            ],
+            private_parameters: BTreeSet::from([Witness(1)]),
+            public_parameters: PublicInputs::default(),
+            return_values: PublicInputs(BTreeSet::from([Witness(6)])),
+            assert_messages: Default::default(),
+        };
+        let acir_opcode_positions = circuit.opcodes.iter().enumerate().map(|(i, _)| i).collect();
+
+        let optimizer = UnusedDirectiveOptimizer::new(circuit);
+        let (optimized_circuit, new_positions) =
+            optimizer.remove_unused_directives(acir_opcode_positions);
+
+        assert_eq!(optimized_circuit.opcodes.len(), 3);
+        assert_eq!(new_positions, vec![2, 3, 4]);
+    }
+}
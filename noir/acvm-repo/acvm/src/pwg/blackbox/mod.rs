@@ -10,6 +10,7 @@ use self::{hash::keccakf1600, pedersen::pedersen_hash};
 use super::{insert_value, OpcodeNotSolvable, OpcodeResolutionError};
 use crate::{pwg::witness_to_value, BlackBoxFunctionSolver};
 
+mod embedded_curve_ops;
 mod fixed_base_scalar_mul;
 mod hash;
 mod logic;
@@ -17,6 +18,7 @@ mod pedersen;
 mod range;
 mod signature;
 
+use embedded_curve_ops::{embedded_curve_add, embedded_curve_double};
 use fixed_base_scalar_mul::fixed_base_scalar_mul;
 // Hash functions should eventually be exposed for external consumers.
 use hash::solve_generic_256_hash_opcode;
@@ -177,11 +179,19 @@ pub(crate) fn solve(
         BlackBoxFuncCall::FixedBaseScalarMul { low, high, outputs } => {
             fixed_base_scalar_mul(backend, initial_witness, *low, *high, *outputs)
         }
-        BlackBoxFuncCall::EmbeddedCurveAdd { .. } => {
-            todo!();
+        BlackBoxFuncCall::EmbeddedCurveAdd { input1_x, input1_y, input2_x, input2_y, outputs } => {
+            embedded_curve_add(
+                backend,
+                initial_witness,
+                *input1_x,
+                *input1_y,
+                *input2_x,
+                *input2_y,
+                *outputs,
+            )
         }
-        BlackBoxFuncCall::EmbeddedCurveDouble { .. } => {
-            todo!();
+        BlackBoxFuncCall::EmbeddedCurveDouble { input_x, input_y, outputs } => {
+            embedded_curve_double(backend, initial_witness, *input_x, *input_y, *outputs)
         }
         // Recursive aggregation will be entirely handled by the backend and is not solved by the ACVM
         BlackBoxFuncCall::RecursiveAggregation { .. } => Ok(()),
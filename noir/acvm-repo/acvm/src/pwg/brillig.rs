@@ -8,7 +8,7 @@ use acir::{
     FieldElement,
 };
 use acvm_blackbox_solver::BlackBoxFunctionSolver;
-use brillig_vm::{Registers, VMStatus, VM};
+use brillig_vm::{ExecutionLimits, Registers, VMStatus, VM};
 
 use crate::{pwg::OpcodeNotSolvable, OpcodeResolutionError};
 
@@ -26,6 +26,13 @@ pub struct BrilligSolver<'b, B: BlackBoxFunctionSolver> {
     acir_index: usize,
 }
 
+// Implemented by hand as deriving `Clone` would require the borrowed black box solver to implement it.
+impl<'b, B: BlackBoxFunctionSolver> Clone for BrilligSolver<'b, B> {
+    fn clone(&self) -> Self {
+        Self { vm: self.vm.clone(), acir_index: self.acir_index }
+    }
+}
+
 impl<'b, B: BlackBoxFunctionSolver> BrilligSolver<'b, B> {
     /// Evaluates if the Brillig block should be skipped entirely
     pub(super) fn should_skip(
@@ -67,6 +74,7 @@ impl<'b, B: BlackBoxFunctionSolver> BrilligSolver<'b, B> {
         brillig: &'b Brillig,
         bb_solver: &'b B,
         acir_index: usize,
+        limits: ExecutionLimits,
     ) -> Result<Self, OpcodeResolutionError> {
         // Set input values
         let mut input_register_values: Vec<Value> = Vec::new();
@@ -108,7 +116,8 @@ impl<'b, B: BlackBoxFunctionSolver> BrilligSolver<'b, B> {
         // Instantiate a Brillig VM given the solved input registers and memory
         // along with the Brillig bytecode.
         let input_registers = Registers::load(input_register_values);
-        let vm = VM::new(input_registers, input_memory, &brillig.bytecode, vec![], bb_solver);
+        let vm = VM::new(input_registers, input_memory, &brillig.bytecode, vec![], bb_solver)
+            .with_limits(limits);
         Ok(Self { vm, acir_index })
     }
 
@@ -142,6 +151,11 @@ impl<'b, B: BlackBoxFunctionSolver> BrilligSolver<'b, B> {
         self.vm.program_counter()
     }
 
+    /// Returns the number of Brillig opcodes which have been executed by this solver.
+    pub fn steps(&self) -> usize {
+        self.vm.steps()
+    }
+
     fn handle_vm_status(
         &self,
         vm_status: VMStatus,
@@ -156,13 +170,13 @@ impl<'b, B: BlackBoxFunctionSolver> BrilligSolver<'b, B> {
             VMStatus::Failure { message, call_stack } => {
                 Err(OpcodeResolutionError::BrilligFunctionFailed {
                     message,
-                    call_stack: call_stack
-                        .iter()
-                        .map(|brillig_index| OpcodeLocation::Brillig {
-                            acir_index: self.acir_index,
-                            brillig_index: *brillig_index,
-                        })
-                        .collect(),
+                    call_stack: self.opcode_locations(&call_stack),
+                })
+            }
+            VMStatus::LimitExceeded { limit, call_stack } => {
+                Err(OpcodeResolutionError::BrilligLimitExceeded {
+                    limit,
+                    call_stack: self.opcode_locations(&call_stack),
                 })
             }
             VMStatus::ForeignCallWait { function, inputs } => {
@@ -171,6 +185,16 @@ impl<'b, B: BlackBoxFunctionSolver> BrilligSolver<'b, B> {
         }
     }
 
+    fn opcode_locations(&self, call_stack: &[usize]) -> Vec<OpcodeLocation> {
+        call_stack
+            .iter()
+            .map(|brillig_index| OpcodeLocation::Brillig {
+                acir_index: self.acir_index,
+                brillig_index: *brillig_index,
+            })
+            .collect()
+    }
+
     pub(super) fn finalize(
         self,
         witness: &mut WitnessMap,
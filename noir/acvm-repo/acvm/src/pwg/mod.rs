@@ -9,6 +9,7 @@ use acir::{
     BlackBoxFunc, FieldElement,
 };
 use acvm_blackbox_solver::BlackBoxResolutionError;
+use brillig_vm::{ExceededLimit, ExecutionLimits};
 
 use self::{arithmetic::ExpressionSolver, directives::solve_directives, memory_op::MemoryOpSolver};
 use crate::BlackBoxFunctionSolver;
@@ -112,6 +113,8 @@ pub enum OpcodeResolutionError {
     BlackBoxFunctionFailed(BlackBoxFunc, String),
     #[error("Failed to solve brillig function, reason: {message}")]
     BrilligFunctionFailed { message: String, call_stack: Vec<OpcodeLocation> },
+    #[error("Brillig function exceeded its {limit}")]
+    BrilligLimitExceeded { limit: ExceededLimit, call_stack: Vec<OpcodeLocation> },
 }
 
 impl From<BlackBoxResolutionError> for OpcodeResolutionError {
@@ -140,6 +143,29 @@ pub struct ACVM<'a, B: BlackBoxFunctionSolver> {
     witness_map: WitnessMap,
 
     brillig_solver: Option<BrilligSolver<'a, B>>,
+
+    /// Total number of Brillig opcodes executed by all completed Brillig calls.
+    brillig_steps: usize,
+
+    /// Limits applied to each Brillig call.
+    brillig_limits: ExecutionLimits,
+}
+
+// A clone borrows the same backend and opcodes as the original, which need not be `Clone` themselves.
+impl<'a, B: BlackBoxFunctionSolver> Clone for ACVM<'a, B> {
+    fn clone(&self) -> Self {
+        Self {
+            status: self.status.clone(),
+            backend: self.backend,
+            block_solvers: self.block_solvers.clone(),
+            opcodes: self.opcodes,
+            instruction_pointer: self.instruction_pointer,
+            witness_map: self.witness_map.clone(),
+            brillig_solver: self.brillig_solver.clone(),
+            brillig_steps: self.brillig_steps,
+            brillig_limits: self.brillig_limits,
+        }
+    }
 }
 
 impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
@@ -153,9 +179,18 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
             instruction_pointer: 0,
             witness_map: initial_witness,
             brillig_solver: None,
+            brillig_steps: 0,
+            brillig_limits: ExecutionLimits::default(),
         }
     }
 
+    /// Halts execution with [`OpcodeResolutionError::BrilligLimitExceeded`] once any Brillig call
+    /// exceeds the given `limits`.
+    pub fn with_brillig_limits(mut self, limits: ExecutionLimits) -> Self {
+        self.brillig_limits = limits;
+        self
+    }
+
     /// Returns a reference to the current state of the ACVM's [`WitnessMap`].
     ///
     /// Once execution has completed, the witness map can be extracted using [`ACVM::finalize`]
@@ -181,6 +216,13 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
         self.instruction_pointer
     }
 
+    /// Returns the total number of Brillig opcodes executed so far.
+    ///
+    /// Steps are counted once a Brillig call has finished or failed.
+    pub fn brillig_steps(&self) -> usize {
+        self.brillig_steps
+    }
+
     /// Finalize the ACVM execution, returning the resulting [`WitnessMap`].
     pub fn finalize(self) -> WitnessMap {
         if self.status != ACVMStatus::Solved {
@@ -327,9 +369,19 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
         // there will be a cached `BrilligSolver` to avoid recomputation.
         let mut solver: BrilligSolver<'_, B> = match self.brillig_solver.take() {
             Some(solver) => solver,
-            None => BrilligSolver::new(witness, brillig, self.backend, self.instruction_pointer)?,
+            None => BrilligSolver::new(
+                witness,
+                brillig,
+                self.backend,
+                self.instruction_pointer,
+                self.brillig_limits,
+            )?,
         };
-        match solver.solve()? {
+        let status = solver.solve();
+        if !matches!(status, Ok(BrilligSolverStatus::ForeignCallWait(_))) {
+            self.brillig_steps += solver.steps();
+        }
+        match status? {
             BrilligSolverStatus::ForeignCallWait(foreign_call) => {
                 // Cache the current state of the solver
                 self.brillig_solver = Some(solver);
@@ -362,7 +414,13 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
             return StepResult::Status(self.handle_opcode_resolution(resolution));
         }
 
-        let solver = BrilligSolver::new(witness, brillig, self.backend, self.instruction_pointer);
+        let solver = BrilligSolver::new(
+            witness,
+            brillig,
+            self.backend,
+            self.instruction_pointer,
+            self.brillig_limits,
+        );
         match solver {
             Ok(solver) => StepResult::IntoBrillig(solver),
             Err(..) => StepResult::Status(self.handle_opcode_resolution(solver.map(|_| ()))),
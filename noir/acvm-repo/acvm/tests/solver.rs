@@ -1,19 +1,21 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, str::FromStr, time::Instant};
 
 use acir::{
     brillig::{BinaryFieldOp, Opcode as BrilligOpcode, RegisterIndex, RegisterOrMemory, Value},
     circuit::{
         brillig::{Brillig, BrilligInputs, BrilligOutputs},
         opcodes::{BlockId, MemOp},
-        Opcode, OpcodeLocation,
+        Circuit, Opcode, OpcodeLocation,
     },
     native_types::{Expression, Witness, WitnessMap},
     FieldElement,
 };
 
 use acvm::{
+    brillig_vm::{ExceededLimit, ExecutionLimits},
+    compiler::{compile, OptimizationLevel},
     pwg::{ACVMStatus, ErrorLocation, ForeignCallWaitInfo, OpcodeResolutionError, ACVM},
-    BlackBoxFunctionSolver,
+    BlackBoxFunctionSolver, ExpressionWidth,
 };
 use acvm_blackbox_solver::BlackBoxResolutionError;
 
@@ -50,6 +52,22 @@ impl BlackBoxFunctionSolver for StubbedBackend {
     ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
         panic!("Path not trodden by this test")
     }
+    fn ec_add(
+        &self,
+        _input1_x: &FieldElement,
+        _input1_y: &FieldElement,
+        _input2_x: &FieldElement,
+        _input2_y: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        panic!("Path not trodden by this test")
+    }
+    fn ec_double(
+        &self,
+        _input_x: &FieldElement,
+        _input_y: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        panic!("Path not trodden by this test")
+    }
 }
 
 // Reenable these test cases once we move the brillig implementation of inversion down into the acvm stdlib.
@@ -609,6 +627,43 @@ fn unsatisfied_opcode_resolved_brillig() {
     );
 }
 
+#[test]
+fn brillig_solving_halts_at_limits() {
+    let w_result = Witness(1);
+    // A Brillig call which loops forever without reaching its `Stop` opcode.
+    let brillig_opcode = Opcode::Brillig(Brillig {
+        inputs: vec![],
+        outputs: vec![BrilligOutputs::Simple(w_result)],
+        bytecode: vec![BrilligOpcode::Jump { location: 0 }, BrilligOpcode::Stop],
+        predicate: None,
+    });
+    let opcodes = vec![brillig_opcode];
+
+    let limits = ExecutionLimits { max_steps: Some(1000), ..ExecutionLimits::default() };
+    let mut acvm =
+        ACVM::new(&StubbedBackend, &opcodes, WitnessMap::new()).with_brillig_limits(limits);
+    assert_eq!(
+        acvm.solve(),
+        ACVMStatus::Failure(OpcodeResolutionError::BrilligLimitExceeded {
+            limit: ExceededLimit::Steps(1000),
+            call_stack: vec![OpcodeLocation::Brillig { acir_index: 0, brillig_index: 0 }]
+        })
+    );
+    assert_eq!(acvm.brillig_steps(), 1000);
+
+    // A deadline which has already passed halts the call before it executes any opcodes.
+    let limits = ExecutionLimits { deadline: Some(Instant::now()), ..ExecutionLimits::default() };
+    let mut acvm =
+        ACVM::new(&StubbedBackend, &opcodes, WitnessMap::new()).with_brillig_limits(limits);
+    assert_eq!(
+        acvm.solve(),
+        ACVMStatus::Failure(OpcodeResolutionError::BrilligLimitExceeded {
+            limit: ExceededLimit::Deadline,
+            call_stack: vec![OpcodeLocation::Brillig { acir_index: 0, brillig_index: 0 }]
+        })
+    );
+}
+
 #[test]
 fn memory_operations() {
     let initial_witness = WitnessMap::from(BTreeMap::from_iter([
@@ -648,3 +703,330 @@ fn memory_operations() {
 
     assert_eq!(witness_map[&Witness(8)], FieldElement::from(6u128));
 }
+
+#[test]
+fn text_circuit_compiles_and_executes() {
+    // Computes w4 = 1 / (w1 * w2 + 3) and reads w5 = [w1, w2, w4][w3]
+    let circuit: Circuit = "
+        current witness index: 6
+        private parameters: [w1, w2, w3]
+        public parameters: []
+        return values: [w5]
+
+        ASSERT w6 = w1*w2 + 3
+        BRILLIG(inputs: [w6], outputs: [w4]) {
+            const(destination: r1, value: 1)
+            binary_field_op(destination: r0, op: div, lhs: r1, rhs: r0)
+            stop
+        }
+        ASSERT w4*w6 = 1
+        MEMORY_INIT(block: b0, init: [w1, w2, w4])
+        MEMORY_READ(block: b0, index: w3, value: w5)
+    "
+    .parse()
+    .unwrap();
+    assert_eq!(Circuit::from_str(&circuit.to_text()).unwrap(), circuit);
+
+    let (circuit, _) =
+        compile(circuit, ExpressionWidth::Bounded { width: 3 }, OptimizationLevel::Basic);
+
+    let initial_witness = WitnessMap::from(BTreeMap::from_iter([
+        (Witness(1), FieldElement::from(2u128)),
+        (Witness(2), FieldElement::from(5u128)),
+        (Witness(3), FieldElement::from(2u128)),
+    ]));
+    let mut acvm = ACVM::new(&StubbedBackend, &circuit.opcodes, initial_witness);
+    assert_eq!(acvm.solve(), ACVMStatus::Solved);
+    let witness_map = acvm.finalize();
+
+    let expected = FieldElement::from(13u128).inverse();
+    assert_eq!(witness_map[&Witness(4)], expected);
+    assert_eq!(witness_map[&Witness(5)], expected);
+}
+
+#[test]
+fn fully_optimized_circuit_keeps_assert_messages() {
+    let circuit: Circuit = r#"
+        current witness index: 7
+        private parameters: [w1, w2]
+        public parameters: []
+        return values: [w5]
+        assert message 0: "w3 is the sum"
+        assert message 3: "w5 is the product"
+
+        ASSERT w3 = w1 + w2
+        DIR::TO_LE_RADIX(a: w1, b: [w6, w7], radix: 2)
+        ASSERT w5 = w3*w2
+        ASSERT w5 = w3*w2
+    "#
+    .parse()
+    .unwrap();
+
+    let width = ExpressionWidth::Bounded { width: 3 };
+    let (basic_circuit, _) = compile(circuit.clone(), width, OptimizationLevel::Basic);
+    let (circuit, transformation_map) = compile(circuit, width, OptimizationLevel::Full);
+    assert!(circuit.opcodes.len() < basic_circuit.opcodes.len());
+
+    // Both opcodes with assert messages were merged into the opcodes computing `w5`
+    for old_index in [0, 3] {
+        let new_locations: Vec<_> =
+            transformation_map.new_locations(OpcodeLocation::Acir(old_index)).collect();
+        assert!(!new_locations.is_empty());
+        for location in new_locations {
+            assert!(circuit
+                .assert_messages
+                .iter()
+                .any(|(message_location, _)| *message_location == location));
+        }
+    }
+    let messages: Vec<_> =
+        circuit.assert_messages.iter().map(|(_, message)| message.as_str()).collect();
+    assert!(messages.contains(&"w3 is the sum") && messages.contains(&"w5 is the product"));
+
+    let initial_witness = WitnessMap::from(BTreeMap::from_iter([
+        (Witness(1), FieldElement::from(2u128)),
+        (Witness(2), FieldElement::from(5u128)),
+    ]));
+    let mut acvm = ACVM::new(&StubbedBackend, &circuit.opcodes, initial_witness);
+    assert_eq!(acvm.solve(), ACVMStatus::Solved);
+    let witness_map = acvm.finalize();
+    assert_eq!(witness_map[&Witness(5)], FieldElement::from(35u128));
+}
+
+#[test]
+fn fully_optimized_circuit_inlines_only_solvable_definitions() {
+    // `w3` can't be inlined as `w3*w4` would become two multiplication terms, while `w5` can be
+    let circuit: Circuit = "
+        current witness index: 6
+        private parameters: [w1, w2]
+        public parameters: []
+        return values: [w4, w6]
+
+        ASSERT w3 = w1 + 2*w2
+        ASSERT w3*w4 = 5
+        ASSERT w5 = 2*w1 + 1
+        ASSERT w6 = w5*w2
+    "
+    .parse()
+    .unwrap();
+
+    for width in [ExpressionWidth::Unbounded, ExpressionWidth::Bounded { width: 3 }] {
+        let (full_circuit, _) = compile(circuit.clone(), width, OptimizationLevel::Full);
+        if matches!(width, ExpressionWidth::Unbounded) {
+            assert_eq!(full_circuit.opcodes.len(), circuit.opcodes.len() - 1);
+        }
+
+        let initial_witness = WitnessMap::from(BTreeMap::from_iter([
+            (Witness(1), FieldElement::from(1u128)),
+            (Witness(2), FieldElement::from(2u128)),
+        ]));
+        let mut acvm = ACVM::new(&StubbedBackend, &full_circuit.opcodes, initial_witness);
+        assert_eq!(acvm.solve(), ACVMStatus::Solved);
+        let witness_map = acvm.finalize();
+        assert_eq!(witness_map[&Witness(4)], FieldElement::one());
+        assert_eq!(witness_map[&Witness(6)], FieldElement::from(6u128));
+    }
+}
+
+/// Executes circuits calling the black box functions specific to the embedded curve
+/// with the solver for bls12_381, whose embedded curve is Jubjub.
+#[cfg(feature = "bls12_381")]
+mod bls12_381 {
+    use acir::circuit::opcodes::{BlackBoxFuncCall, FunctionInput};
+    use acvm_blackbox_solver::blake2s;
+    use bls12_381_blackbox_solver::Bls12381BlackBoxSolver;
+    use num_bigint::BigUint;
+
+    use super::*;
+
+    /// The order of the prime order subgroup of Jubjub
+    const JUBJUB_SUBGROUP_ORDER: &str =
+        "6554484396890773809930967563523245729705921265872317281365359162392183254199";
+
+    fn input(witness: u32) -> FunctionInput {
+        FunctionInput { witness: Witness(witness), num_bits: FieldElement::max_num_bits() }
+    }
+
+    fn byte_inputs(witnesses: std::ops::Range<u32>) -> Vec<FunctionInput> {
+        witnesses.map(|witness| FunctionInput { witness: Witness(witness), num_bits: 8 }).collect()
+    }
+
+    fn assert_equal(lhs: u32, rhs: u32) -> Opcode {
+        Opcode::AssertZero(Expression {
+            mul_terms: Vec::new(),
+            linear_combinations: vec![
+                (FieldElement::one(), Witness(lhs)),
+                (-FieldElement::one(), Witness(rhs)),
+            ],
+            q_c: FieldElement::zero(),
+        })
+    }
+
+    fn solve(opcodes: &[Opcode], initial_witness: WitnessMap) -> WitnessMap {
+        let solver = Bls12381BlackBoxSolver::new();
+        let mut acvm = ACVM::new(&solver, opcodes, initial_witness);
+        assert_eq!(acvm.solve(), ACVMStatus::Solved);
+        acvm.finalize()
+    }
+
+    #[test]
+    fn embedded_curve_operations() {
+        // 3 * G = 2 * G + G, where 2 * G is computed by doubling G
+        let opcodes = vec![
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::FixedBaseScalarMul {
+                low: input(1),
+                high: input(2),
+                outputs: (Witness(4), Witness(5)),
+            }),
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::EmbeddedCurveDouble {
+                input_x: input(4),
+                input_y: input(5),
+                outputs: (Witness(6), Witness(7)),
+            }),
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::EmbeddedCurveAdd {
+                input1_x: input(4),
+                input1_y: input(5),
+                input2_x: input(6),
+                input2_y: input(7),
+                outputs: (Witness(8), Witness(9)),
+            }),
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::FixedBaseScalarMul {
+                low: input(3),
+                high: input(2),
+                outputs: (Witness(10), Witness(11)),
+            }),
+            assert_equal(8, 10),
+            assert_equal(9, 11),
+        ];
+        let initial_witness = WitnessMap::from(BTreeMap::from_iter([
+            (Witness(1), FieldElement::one()),
+            (Witness(2), FieldElement::zero()),
+            (Witness(3), FieldElement::from(3u128)),
+        ]));
+
+        let witness_map = solve(&opcodes, initial_witness);
+
+        let (g_x, g_y) = bls12_381_blackbox_solver::fixed_base_scalar_mul(
+            &FieldElement::one(),
+            &FieldElement::zero(),
+        )
+        .unwrap();
+        assert_eq!((witness_map[&Witness(4)], witness_map[&Witness(5)]), (g_x, g_y));
+        assert_ne!(witness_map[&Witness(8)], g_x);
+    }
+
+    #[test]
+    fn embedded_curve_points_must_be_on_curve() {
+        let opcodes = vec![Opcode::BlackBoxFuncCall(BlackBoxFuncCall::EmbeddedCurveDouble {
+            input_x: input(1),
+            input_y: input(2),
+            outputs: (Witness(3), Witness(4)),
+        })];
+        let initial_witness = WitnessMap::from(BTreeMap::from_iter([
+            (Witness(1), FieldElement::one()),
+            (Witness(2), FieldElement::one()),
+        ]));
+
+        let solver = Bls12381BlackBoxSolver::new();
+        let mut acvm = ACVM::new(&solver, &opcodes, initial_witness);
+        assert!(matches!(
+            acvm.solve(),
+            ACVMStatus::Failure(OpcodeResolutionError::BlackBoxFunctionFailed(..))
+        ));
+    }
+
+    #[test]
+    fn pedersen_commitment_and_hash() {
+        let opcodes = vec![
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::PedersenCommitment {
+                inputs: vec![input(1), input(2)],
+                domain_separator: 0,
+                outputs: (Witness(3), Witness(4)),
+            }),
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::PedersenHash {
+                inputs: vec![input(1), input(2)],
+                domain_separator: 0,
+                output: Witness(5),
+            }),
+        ];
+        let inputs = [FieldElement::from(1u128), FieldElement::from(2u128)];
+        let initial_witness = WitnessMap::from(BTreeMap::from_iter([
+            (Witness(1), inputs[0]),
+            (Witness(2), inputs[1]),
+        ]));
+
+        let witness_map = solve(&opcodes, initial_witness);
+
+        // The commitment is G_0 + 2 * G_1 for the default generators G_i
+        let solver = Bls12381BlackBoxSolver::new();
+        let (g0_x, g0_y) = solver.pedersen_commitment(&inputs[..1], 0).unwrap();
+        let (g1_x, g1_y) = solver.pedersen_commitment(&inputs[..1], 1).unwrap();
+        let (g1_double_x, g1_double_y) = solver.ec_double(&g1_x, &g1_y).unwrap();
+        let expected_commitment = solver.ec_add(&g0_x, &g0_y, &g1_double_x, &g1_double_y).unwrap();
+        assert_eq!((witness_map[&Witness(3)], witness_map[&Witness(4)]), expected_commitment);
+
+        assert_ne!(witness_map[&Witness(5)], expected_commitment.0);
+        assert_eq!(witness_map[&Witness(5)], solver.pedersen_hash(&inputs, 0).unwrap());
+    }
+
+    #[test]
+    fn schnorr_verify() {
+        let solver = Bls12381BlackBoxSolver::new();
+        let message = b"hello world".to_vec();
+        let (public_key, signature) = sign(&solver, 0xdead_beef, 0xc0ffee, &message);
+
+        let signature_start = 3;
+        let message_start = signature_start + signature.len() as u32;
+        let message_end = message_start + message.len() as u32;
+        let opcodes = vec![Opcode::BlackBoxFuncCall(BlackBoxFuncCall::SchnorrVerify {
+            public_key_x: input(1),
+            public_key_y: input(2),
+            signature: byte_inputs(signature_start..message_start),
+            message: byte_inputs(message_start..message_end),
+            output: Witness(message_end),
+        })];
+
+        let bytes = signature.iter().chain(&message).map(|byte| FieldElement::from(*byte as u128));
+        let mut initial_witness = WitnessMap::from(BTreeMap::from_iter(
+            (signature_start..message_end).map(Witness).zip(bytes),
+        ));
+        initial_witness.insert(Witness(1), public_key.0);
+        initial_witness.insert(Witness(2), public_key.1);
+
+        let witness_map = solve(&opcodes, initial_witness.clone());
+        assert_eq!(witness_map[&Witness(message_end)], FieldElement::one());
+
+        // Tampering with the message invalidates the signature
+        initial_witness.insert(Witness(message_start), FieldElement::from(b'j' as u128));
+        let witness_map = solve(&opcodes, initial_witness);
+        assert_eq!(witness_map[&Witness(message_end)], FieldElement::zero());
+    }
+
+    /// Signs `message` with the private key `secret`, using `nonce` as the signature nonce.
+    fn sign(
+        solver: &Bls12381BlackBoxSolver,
+        secret: u128,
+        nonce: u128,
+        message: &[u8],
+    ) -> ((FieldElement, FieldElement), Vec<u8>) {
+        let public_key =
+            solver.fixed_base_scalar_mul(&secret.into(), &FieldElement::zero()).unwrap();
+        let (nonce_x, _) =
+            solver.fixed_base_scalar_mul(&nonce.into(), &FieldElement::zero()).unwrap();
+
+        let compressed_keys =
+            solver.pedersen_hash(&[nonce_x, public_key.0, public_key.1], 0).unwrap();
+        let challenge =
+            blake2s(&[compressed_keys.to_be_bytes(), message.to_vec()].concat()).unwrap();
+
+        // The nonce point is G * s + public_key * e, so s = nonce - secret * e
+        let order: BigUint = JUBJUB_SUBGROUP_ORDER.parse().unwrap();
+        let e = BigUint::from_bytes_be(&challenge) % &order;
+        let s = (BigUint::from(nonce) + &order - BigUint::from(secret) * e % &order) % &order;
+
+        let mut signature = vec![0; 32 - s.to_bytes_be().len()];
+        signature.extend(s.to_bytes_be());
+        signature.extend(challenge);
+        (public_key, signature)
+    }
+}
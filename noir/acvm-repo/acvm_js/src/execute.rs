@@ -62,7 +62,7 @@ pub async fn execute_circuit_with_black_box_solver(
 ) -> Result<JsWitnessMap, Error> {
     console_error_panic_hook::set_once();
     let circuit: Circuit = Circuit::deserialize_circuit(&circuit)
-        .map_err(|_| JsExecutionError::new("Failed to deserialize circuit. This is likely due to differing serialization formats between ACVM_JS and your compiler".to_string(), None))?;
+        .map_err(|err| JsExecutionError::new(err.to_string(), None))?;
 
     let mut acvm = ACVM::new(&solver.0, &circuit.opcodes, initial_witness.into());
 
@@ -90,6 +90,9 @@ pub async fn execute_circuit_with_black_box_solver(
                             call_stack.last().expect("Brillig error call stacks cannot be empty");
                         (circuit.get_assert_message(*failing_opcode), Some(call_stack.clone()))
                     }
+                    OpcodeResolutionError::BrilligLimitExceeded { call_stack, .. } => {
+                        (None, Some(call_stack.clone()))
+                    }
                     _ => (None, None),
                 };
 
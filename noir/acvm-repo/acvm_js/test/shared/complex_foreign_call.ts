@@ -2,11 +2,13 @@ import { WitnessMap } from '@noir-lang/acvm_js';
 
 // See `complex_brillig_foreign_call` integration test in `acir/tests/test_program_serialization.rs`.
 export const bytecode = Uint8Array.from([
-  31, 139, 8, 0, 0, 0, 0, 0, 0, 255, 213, 83, 219, 10, 128, 48, 8, 117, 174, 139, 159, 179, 254, 160, 127, 137, 222,
-  138, 122, 236, 243, 19, 114, 32, 22, 244, 144, 131, 118, 64, 156, 178, 29, 14, 59, 74, 0, 16, 224, 66, 228, 64, 57, 7,
-  169, 53, 242, 189, 81, 114, 250, 134, 33, 248, 113, 165, 82, 26, 177, 2, 141, 177, 128, 198, 60, 15, 63, 245, 219,
-  211, 23, 215, 255, 139, 15, 251, 211, 112, 180, 28, 157, 212, 189, 100, 82, 179, 64, 170, 63, 109, 235, 190, 204, 135,
-  166, 178, 150, 216, 62, 154, 252, 250, 70, 147, 35, 220, 119, 93, 227, 4, 182, 131, 81, 25, 36, 4, 0, 0,
+  31, 139, 8, 4, 0, 0, 0, 0, 0, 255, 44, 0, 65, 67, 40, 0, 65, 67, 73, 82, 1, 0, 0, 0, 48, 100, 78, 114, 225, 49, 160,
+  41, 184, 80, 69, 182, 129, 129, 88, 93, 40, 51, 232, 72, 121, 185, 112, 145, 67, 225, 245, 147, 240, 0, 0, 1, 213, 83,
+  219, 10, 128, 48, 8, 117, 174, 139, 159, 179, 254, 160, 127, 137, 222, 138, 122, 236, 243, 19, 114, 32, 22, 244, 144,
+  131, 118, 64, 156, 178, 29, 14, 59, 74, 0, 16, 224, 66, 228, 64, 57, 7, 169, 53, 242, 189, 81, 114, 250, 134, 33, 248,
+  113, 165, 82, 26, 177, 2, 141, 177, 128, 198, 60, 15, 63, 245, 219, 211, 23, 215, 255, 139, 15, 251, 211, 112, 180,
+  28, 157, 212, 189, 100, 82, 179, 64, 170, 63, 109, 235, 190, 204, 135, 166, 178, 150, 216, 62, 154, 252, 250, 70, 147,
+  35, 220, 119, 93, 227, 4, 182, 131, 81, 25, 36, 4, 0, 0,
 ]);
 export const initialWitnessMap: WitnessMap = new Map([
   [1, '0x0000000000000000000000000000000000000000000000000000000000000001'],
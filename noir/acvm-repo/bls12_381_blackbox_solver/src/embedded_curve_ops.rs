@@ -0,0 +1,181 @@
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::MontConfig;
+use num_bigint::BigUint;
+
+use acir::{BlackBoxFunc, FieldElement};
+
+use crate::jubjub::{EdwardsAffine, FrConfig};
+use crate::BlackBoxResolutionError;
+
+pub fn fixed_base_scalar_mul(
+    low: &FieldElement,
+    high: &FieldElement,
+) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+    let low: u128 = low.try_into_u128().ok_or_else(|| {
+        BlackBoxResolutionError::Failed(
+            BlackBoxFunc::FixedBaseScalarMul,
+            format!("Limb {} is not less than 2^128", low.to_hex()),
+        )
+    })?;
+
+    let high: u128 = high.try_into_u128().ok_or_else(|| {
+        BlackBoxResolutionError::Failed(
+            BlackBoxFunc::FixedBaseScalarMul,
+            format!("Limb {} is not less than 2^128", high.to_hex()),
+        )
+    })?;
+
+    let mut bytes = high.to_be_bytes().to_vec();
+    bytes.extend_from_slice(&low.to_be_bytes());
+
+    // Check if this is smaller than the order of Jubjub's prime order subgroup
+    let jubjub_integer = BigUint::from_bytes_be(&bytes);
+
+    if jubjub_integer >= FrConfig::MODULUS.into() {
+        return Err(BlackBoxResolutionError::Failed(
+            BlackBoxFunc::FixedBaseScalarMul,
+            format!("{} is not a valid jubjub scalar", jubjub_integer.to_str_radix(16)),
+        ));
+    }
+
+    let result =
+        EdwardsAffine::generator().mul_bigint(jubjub_integer.to_u64_digits()).into_affine();
+    Ok(to_field_elements(result))
+}
+
+pub fn embedded_curve_add(
+    input1_x: &FieldElement,
+    input1_y: &FieldElement,
+    input2_x: &FieldElement,
+    input2_y: &FieldElement,
+) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+    let point1 = to_point(BlackBoxFunc::EmbeddedCurveAdd, input1_x, input1_y)?;
+    let point2 = to_point(BlackBoxFunc::EmbeddedCurveAdd, input2_x, input2_y)?;
+    Ok(to_field_elements((point1 + point2).into_affine()))
+}
+
+pub fn embedded_curve_double(
+    input_x: &FieldElement,
+    input_y: &FieldElement,
+) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+    let point = to_point(BlackBoxFunc::EmbeddedCurveDouble, input_x, input_y)?;
+    Ok(to_field_elements((point + point).into_affine()))
+}
+
+/// Returns the point of Jubjub with the given coordinates, if it is on the curve.
+fn to_point(
+    func: BlackBoxFunc,
+    x: &FieldElement,
+    y: &FieldElement,
+) -> Result<EdwardsAffine, BlackBoxResolutionError> {
+    let point = EdwardsAffine::new_unchecked(x.into_repr(), y.into_repr());
+    if point.is_on_curve() {
+        Ok(point)
+    } else {
+        Err(BlackBoxResolutionError::Failed(
+            func,
+            format!("Point ({}, {}) is not on the jubjub curve", x.to_hex(), y.to_hex()),
+        ))
+    }
+}
+
+fn to_field_elements(point: EdwardsAffine) -> (FieldElement, FieldElement) {
+    // The identity of a twisted Edwards curve is (0, 1) rather than a point at infinity
+    (FieldElement::from_repr(point.x), FieldElement::from_repr(point.y))
+}
+
+#[cfg(test)]
+mod jubjub_embedded_curve_ops {
+    use ark_ff::BigInteger;
+
+    use super::*;
+
+    #[test]
+    fn smoke_test() -> Result<(), BlackBoxResolutionError> {
+        let input = FieldElement::one();
+
+        let res = fixed_base_scalar_mul(&input, &FieldElement::zero())?;
+        let x = "11dafe5d23e1218086a365b99fbf3d3be72f6afd7d1f72623e6b071492d1122b";
+        let y = "1d523cf1ddab1a1793132e78c866c0c33e26ba5cc220fed7cc3f870e59d292aa";
+
+        assert_eq!(x, res.0.to_hex());
+        assert_eq!(y, res.1.to_hex());
+        Ok(())
+    }
+
+    #[test]
+    fn low_high_smoke_test() -> Result<(), BlackBoxResolutionError> {
+        let low = FieldElement::one();
+        let high = FieldElement::from(2u128);
+
+        let res = fixed_base_scalar_mul(&low, &high)?;
+        let x = "1fa23ce8548db0de7643e1991258b767755bd7be6abd1573ebf4daa741f3c0b5";
+        let y = "1cf1cd369649868591ce8885fa348ee7b12bd18f974b2017e8ffb73751d46424";
+
+        assert_eq!(x, res.0.to_hex());
+        assert_eq!(y, res.1.to_hex());
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_invalid_limbs() {
+        let invalid_limb = FieldElement::from(u128::MAX) + FieldElement::one();
+
+        let expected_error = Err(BlackBoxResolutionError::Failed(
+            BlackBoxFunc::FixedBaseScalarMul,
+            "Limb 0000000000000000000000000000000100000000000000000000000000000000 is not less than 2^128".into(),
+        ));
+
+        let res = fixed_base_scalar_mul(&invalid_limb, &FieldElement::zero());
+        assert_eq!(res, expected_error);
+
+        let res = fixed_base_scalar_mul(&FieldElement::zero(), &invalid_limb);
+        assert_eq!(res, expected_error);
+    }
+
+    #[test]
+    fn rejects_jubjub_modulus() {
+        let x = FrConfig::MODULUS.to_bytes_be();
+
+        let high = FieldElement::from_be_bytes_reduce(&x[0..16]);
+        let low = FieldElement::from_be_bytes_reduce(&x[16..32]);
+
+        let res = fixed_base_scalar_mul(&low, &high);
+
+        assert_eq!(
+            res,
+            Err(BlackBoxResolutionError::Failed(
+                BlackBoxFunc::FixedBaseScalarMul,
+                "e7db4ea6533afa906673b0101343b00a6682093ccc81082d0970e5ed6f72cb7 is not a valid jubjub scalar".into(),
+            ))
+        );
+    }
+
+    #[test]
+    fn add_and_double_agree_with_scalar_mul() -> Result<(), BlackBoxResolutionError> {
+        let (g_x, g_y) = fixed_base_scalar_mul(&FieldElement::one(), &FieldElement::zero())?;
+        let two_g = fixed_base_scalar_mul(&FieldElement::from(2u128), &FieldElement::zero())?;
+        let three_g = fixed_base_scalar_mul(&FieldElement::from(3u128), &FieldElement::zero())?;
+
+        assert_eq!(embedded_curve_double(&g_x, &g_y)?, two_g);
+        assert_eq!(embedded_curve_add(&g_x, &g_y, &two_g.0, &two_g.1)?, three_g);
+
+        // The identity is (0, 1)
+        let identity = (FieldElement::zero(), FieldElement::one());
+        assert_eq!(embedded_curve_add(&g_x, &g_y, &identity.0, &identity.1)?, (g_x, g_y));
+        assert_eq!(embedded_curve_double(&identity.0, &identity.1)?, identity);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_points_not_on_curve() {
+        let res = embedded_curve_double(&FieldElement::one(), &FieldElement::one());
+        assert_eq!(
+            res,
+            Err(BlackBoxResolutionError::Failed(
+                BlackBoxFunc::EmbeddedCurveDouble,
+                "Point (0000000000000000000000000000000000000000000000000000000000000001, 0000000000000000000000000000000000000000000000000000000000000001) is not on the jubjub curve".into(),
+            ))
+        );
+    }
+}
@@ -0,0 +1,113 @@
+// Generators are derived in the same way as for grumpkin in `bn254_blackbox_solver`, except that
+// points are multiplied by Jubjub's cofactor so that they lie in its prime order subgroup.
+
+use ark_ec::{twisted_edwards::TECurveConfig, AffineRepr};
+use ark_ff::{BigInteger, Field, One, PrimeField};
+
+use crate::jubjub::{EdwardsAffine, Fq, JubjubConfig};
+
+/// The domain separator of the generators used by default for Pedersen commitments and hashes.
+pub(crate) const DEFAULT_DOMAIN_SEPARATOR: &[u8] = b"DEFAULT_DOMAIN_SEPARATOR";
+
+/// Derives `num_generators` points of the prime order subgroup of Jubjub, starting from the one
+/// at `starting_index`, whose discrete logarithms with respect to each other are unknown.
+pub(crate) fn derive_generators(
+    domain_separator_bytes: &[u8],
+    num_generators: u32,
+    starting_index: u32,
+) -> Vec<EdwardsAffine> {
+    // The preimage of each generator is the hash of the domain separator followed by the
+    // generator's index, zero-padded to 64 bytes
+    let mut generator_preimage = [0; 64];
+    generator_preimage[..32].copy_from_slice(blake3::hash(domain_separator_bytes).as_bytes());
+
+    (starting_index..starting_index + num_generators)
+        .map(|generator_index| {
+            generator_preimage[32..36].copy_from_slice(&generator_index.to_be_bytes());
+            hash_to_curve(&generator_preimage, 0)
+        })
+        .collect()
+}
+
+/// Hashes `seed` to a point of the prime order subgroup of Jubjub, trying successive
+/// `attempt_count`s until the hash gives the x coordinate of such a point.
+fn hash_to_curve(seed: &[u8], attempt_count: u8) -> EdwardsAffine {
+    let mut target_seed = seed.to_vec();
+    target_seed.extend_from_slice(&[attempt_count, 0]);
+    let hash_hi = blake3::hash(&target_seed);
+    *target_seed.last_mut().expect("seed is not empty") = 1;
+    let hash_lo = blake3::hash(&target_seed);
+
+    // The 512 bits of the two hashes ensure the reduced x coordinate is not biased
+    let x_bytes = [hash_hi.as_bytes().as_slice(), hash_lo.as_bytes()].concat();
+    let x = Fq::from_be_bytes_mod_order(&x_bytes);
+    let sign_bit = hash_hi.as_bytes()[0] > 127;
+
+    // Points of small order are cleared to the identity along with the cofactor
+    match derive_from_x_coordinate(x, sign_bit).map(|point| point.clear_cofactor()) {
+        Some(point) if !point.is_zero() => point,
+        _ => hash_to_curve(seed, attempt_count + 1),
+    }
+}
+
+/// Returns the point of Jubjub with the given x coordinate and whose
+/// y coordinate has the given parity, if there is one.
+fn derive_from_x_coordinate(x: Fq, sign_bit: bool) -> Option<EdwardsAffine> {
+    // a x^2 + y^2 = 1 + d x^2 y^2, so y^2 = (1 - a x^2) / (1 - d x^2)
+    let x_squared = x.square();
+    let numerator = Fq::one() - JubjubConfig::mul_by_a(x_squared);
+    let denominator = Fq::one() - JubjubConfig::COEFF_D * x_squared;
+    let y = (numerator * denominator.inverse()?).sqrt()?;
+    let y = if y.into_bigint().is_odd() == sign_bit { y } else { -y };
+    Some(EdwardsAffine::new_unchecked(x, y))
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{MontConfig, MontFp, Zero};
+
+    use super::*;
+    use crate::jubjub::FrConfig;
+
+    #[test]
+    fn derived_generators_are_in_prime_order_subgroup() {
+        let generators = derive_generators(DEFAULT_DOMAIN_SEPARATOR, 4, 0);
+        assert_eq!(generators.len(), 4);
+        for generator in &generators {
+            assert!(generator.is_on_curve());
+            assert!(generator.is_in_correct_subgroup_assuming_on_curve());
+            assert!(!generator.is_zero());
+        }
+
+        // The generators starting at an offset are the trailing ones of the full sequence
+        assert_eq!(derive_generators(DEFAULT_DOMAIN_SEPARATOR, 2, 2), generators[2..]);
+        assert_ne!(derive_generators(b"pedersen_hash_length", 1, 0)[0], generators[0]);
+    }
+
+    #[test]
+    fn jubjub_generator_has_prime_order() {
+        let generator = EdwardsAffine::generator();
+        assert!(generator.is_on_curve());
+        assert!(generator.mul_bigint(FrConfig::MODULUS).is_zero());
+        assert!(!generator.mul_bigint([8]).is_zero());
+    }
+
+    #[test]
+    fn derive_from_x_coordinate_respects_sign_bit() {
+        let x: Fq = MontFp!("3");
+        let even = derive_from_x_coordinate(x, false).unwrap();
+        let odd = derive_from_x_coordinate(x, true).unwrap();
+        assert!(even.is_on_curve());
+        assert!(even.y.into_bigint().is_even());
+        assert!(odd.y.into_bigint().is_odd());
+        assert_eq!(even.y, -odd.y);
+        assert_eq!(even.x, odd.x);
+
+        // (1 + x^2) / (1 - d x^2) is not a square for x = 1
+        assert!(derive_from_x_coordinate(MontFp!("1"), false).is_none());
+
+        // The identity and its negation, of order 2, have x = 0
+        let origin = derive_from_x_coordinate(Fq::zero(), false).unwrap();
+        assert!(origin.clear_cofactor().is_zero());
+    }
+}
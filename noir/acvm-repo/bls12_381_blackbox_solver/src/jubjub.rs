@@ -0,0 +1,62 @@
+// Jubjub is the twisted Edwards curve `-x^2 + y^2 = 1 + d x^2 y^2` with `d = -(10240/10241)`,
+// defined over the scalar field of bls12_381 so that its points can be represented by ACIR witnesses.
+// https://zips.z.cash/protocol/protocol.pdf#jubjub
+
+use ark_ec::{
+    models::CurveConfig,
+    twisted_edwards::{self as te, TECurveConfig},
+};
+use ark_ff::{Fp256, MontBackend, MontConfig, MontFp};
+
+/// The base field of Jubjub, which is the scalar field of bls12_381.
+pub(crate) type Fq = ark_bls12_381::Fr;
+
+/// The scalar field of the prime order subgroup of Jubjub.
+pub(crate) type Fr = Fp256<MontBackend<FrConfig, 4>>;
+
+#[derive(MontConfig)]
+#[modulus = "6554484396890773809930967563523245729705921265872317281365359162392183254199"]
+#[generator = "6"]
+pub(crate) struct FrConfig;
+
+pub(crate) type EdwardsAffine = te::Affine<JubjubConfig>;
+pub(crate) type EdwardsProjective = te::Projective<JubjubConfig>;
+
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+pub(crate) struct JubjubConfig;
+
+impl CurveConfig for JubjubConfig {
+    type BaseField = Fq;
+    type ScalarField = Fr;
+
+    const COFACTOR: &'static [u64] = &[8];
+    const COFACTOR_INV: Fr =
+        MontFp!("819310549611346726241370945440405716213240158234039660170669895299022906775");
+}
+
+impl TECurveConfig for JubjubConfig {
+    const COEFF_A: Fq = MontFp!("-1");
+    const COEFF_D: Fq =
+        MontFp!("19257038036680949359750312669786877991949435402254120286184196891950884077233");
+    const GENERATOR: EdwardsAffine = EdwardsAffine::new_unchecked(GENERATOR_X, GENERATOR_Y);
+
+    type MontCurveConfig = Self;
+
+    #[inline(always)]
+    fn mul_by_a(elem: Fq) -> Fq {
+        -elem
+    }
+}
+
+impl ark_ec::models::twisted_edwards::MontCurveConfig for JubjubConfig {
+    const COEFF_A: Fq = MontFp!("40962");
+    const COEFF_B: Fq =
+        MontFp!("52435875175126190479447740508185965837690552500527637822603658699938581143549");
+
+    type TECurveConfig = Self;
+}
+
+const GENERATOR_X: Fq =
+    MontFp!("8076246640662884909881801758704306714034609987455869804520522091855516602923");
+const GENERATOR_Y: Fq =
+    MontFp!("13262374693698910701929044844600465831413122818447359594527400194675274060458");
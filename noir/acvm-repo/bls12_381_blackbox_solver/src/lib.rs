@@ -0,0 +1,101 @@
+#![warn(unreachable_pub)]
+#![warn(clippy::semicolon_if_nothing_returned)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies, unused_extern_crates))]
+// The solver works over the Jubjub curve, whose base field is the scalar field of bls12_381,
+// so it is only available when ACIR is compiled over that field.
+#![cfg(feature = "bls12_381")]
+
+use acir::FieldElement;
+use acvm_blackbox_solver::{BlackBoxFunctionSolver, BlackBoxResolutionError};
+
+mod embedded_curve_ops;
+mod generator;
+mod jubjub;
+mod pedersen;
+mod schnorr;
+
+#[cfg(target_arch = "wasm32")]
+use getrandom as _;
+
+pub use embedded_curve_ops::{embedded_curve_add, embedded_curve_double, fixed_base_scalar_mul};
+
+#[derive(Default)]
+pub struct Bls12381BlackBoxSolver;
+
+impl Bls12381BlackBoxSolver {
+    #[cfg(target_arch = "wasm32")]
+    pub async fn initialize() -> Bls12381BlackBoxSolver {
+        Bls12381BlackBoxSolver
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new() -> Bls12381BlackBoxSolver {
+        Bls12381BlackBoxSolver
+    }
+}
+
+impl BlackBoxFunctionSolver for Bls12381BlackBoxSolver {
+    fn schnorr_verify(
+        &self,
+        public_key_x: &FieldElement,
+        public_key_y: &FieldElement,
+        signature: &[u8],
+        message: &[u8],
+    ) -> Result<bool, BlackBoxResolutionError> {
+        let sig_s: [u8; 32] = signature[0..32].try_into().unwrap();
+        let sig_e: [u8; 32] = signature[32..64].try_into().unwrap();
+
+        Ok(schnorr::verify_signature(
+            public_key_x.into_repr(),
+            public_key_y.into_repr(),
+            sig_s,
+            sig_e,
+            message,
+        ))
+    }
+
+    fn pedersen_commitment(
+        &self,
+        inputs: &[FieldElement],
+        domain_separator: u32,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        let inputs: Vec<_> = inputs.iter().map(|input| input.into_repr()).collect();
+        let commitment = pedersen::commitment(&inputs, domain_separator);
+        Ok((FieldElement::from_repr(commitment.x), FieldElement::from_repr(commitment.y)))
+    }
+
+    fn pedersen_hash(
+        &self,
+        inputs: &[FieldElement],
+        domain_separator: u32,
+    ) -> Result<FieldElement, BlackBoxResolutionError> {
+        let inputs: Vec<_> = inputs.iter().map(|input| input.into_repr()).collect();
+        Ok(FieldElement::from_repr(pedersen::hash(&inputs, domain_separator)))
+    }
+
+    fn fixed_base_scalar_mul(
+        &self,
+        low: &FieldElement,
+        high: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        fixed_base_scalar_mul(low, high)
+    }
+
+    fn ec_add(
+        &self,
+        input1_x: &FieldElement,
+        input1_y: &FieldElement,
+        input2_x: &FieldElement,
+        input2_y: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        embedded_curve_add(input1_x, input1_y, input2_x, input2_y)
+    }
+
+    fn ec_double(
+        &self,
+        input_x: &FieldElement,
+        input_y: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        embedded_curve_double(input_x, input_y)
+    }
+}
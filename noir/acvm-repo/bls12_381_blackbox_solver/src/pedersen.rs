@@ -0,0 +1,77 @@
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::PrimeField;
+
+use crate::generator::{derive_generators, DEFAULT_DOMAIN_SEPARATOR};
+use crate::jubjub::{EdwardsAffine, EdwardsProjective, Fq};
+
+/// Commits to `inputs` using the default generators, starting from the one at `starting_index`.
+pub(crate) fn commitment(inputs: &[Fq], starting_index: u32) -> EdwardsAffine {
+    let generators =
+        derive_generators(DEFAULT_DOMAIN_SEPARATOR, inputs.len() as u32, starting_index);
+    commit(inputs, &generators).into_affine()
+}
+
+/// Hashes `inputs` to a field element by committing to them along with their number.
+pub(crate) fn hash(inputs: &[Fq], starting_index: u32) -> Fq {
+    let length_generator = derive_generators(b"pedersen_hash_length", 1, 0)[0];
+    let generators =
+        derive_generators(DEFAULT_DOMAIN_SEPARATOR, inputs.len() as u32, starting_index);
+
+    let length = Fq::from(inputs.len() as u64);
+    let result = commit(&[length], &[length_generator]) + commit(inputs, &generators);
+    result.into_affine().x
+}
+
+fn commit(inputs: &[Fq], generators: &[EdwardsAffine]) -> EdwardsProjective {
+    // Inputs may be larger than the Jubjub scalar field modulus, in which case they are
+    // reduced by the multiplication as the generators are in the prime order subgroup
+    inputs
+        .iter()
+        .zip(generators)
+        .map(|(input, generator)| generator.mul_bigint(input.into_bigint()))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{MontFp, Zero};
+
+    use super::*;
+
+    #[test]
+    fn commitment_is_linear_combination_of_generators() {
+        let one = Fq::from(1u64);
+        let generators = derive_generators(DEFAULT_DOMAIN_SEPARATOR, 3, 0);
+
+        let result = commitment(&[one, one], 1);
+        assert_eq!(result, (generators[1] + generators[2]).into_affine());
+
+        let result = commitment(&[Fq::zero(), Fq::from(3u64)], 0);
+        assert_eq!(result, (generators[1] * crate::jubjub::Fr::from(3u64)).into_affine());
+
+        assert!(commitment(&[Fq::zero()], 0).is_zero());
+    }
+
+    #[test]
+    fn hash_depends_on_inputs_and_their_number() {
+        let zero = Fq::zero();
+        let one = Fq::from(1u64);
+
+        let hash_zero_one = hash(&[zero, one], 0);
+        assert_eq!(hash_zero_one, hash(&[zero, one], 0));
+        assert_ne!(hash_zero_one, hash(&[one, zero], 0));
+        assert_ne!(hash_zero_one, hash(&[zero, one], 1));
+        // Trailing zeroes do not change the commitment but do change the hash
+        assert_eq!(commitment(&[one], 0), commitment(&[one, zero], 0));
+        assert_ne!(hash(&[one], 0), hash(&[one, zero], 0));
+    }
+
+    #[test]
+    fn inputs_are_reduced_modulo_subgroup_order() {
+        // The order of the prime order subgroup of Jubjub, as an element of its base field
+        let subgroup_order: Fq =
+            MontFp!("6554484396890773809930967563523245729705921265872317281365359162392183254199");
+        let one = Fq::from(1u64);
+        assert_eq!(commitment(&[subgroup_order + one], 0), commitment(&[one], 0));
+    }
+}
@@ -0,0 +1,96 @@
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use blake2::{Blake2s256, Digest};
+
+use crate::jubjub::{EdwardsAffine, Fq, Fr};
+use crate::pedersen;
+
+/// Verifies a Schnorr signature over Jubjub, where the challenge `e` is the blake2s hash of the
+/// Pedersen hash of the signature nonce and the public key, followed by the message.
+pub(crate) fn verify_signature(
+    pub_key_x: Fq,
+    pub_key_y: Fq,
+    sig_s: [u8; 32],
+    sig_e: [u8; 32],
+    message: &[u8],
+) -> bool {
+    let pub_key = EdwardsAffine::new_unchecked(pub_key_x, pub_key_y);
+    if !pub_key.is_on_curve()
+        || !pub_key.is_in_correct_subgroup_assuming_on_curve()
+        || pub_key.is_zero()
+    {
+        return false;
+    }
+
+    let s = Fr::from_be_bytes_mod_order(&sig_s);
+    if s.is_zero() || s.into_bigint().to_bytes_be() != sig_s {
+        // `s` must be a canonical non-zero scalar
+        return false;
+    }
+    let e = Fr::from_be_bytes_mod_order(&sig_e);
+
+    let nonce = (pub_key * e + EdwardsAffine::generator() * s).into_affine();
+    if nonce.is_zero() {
+        return false;
+    }
+
+    challenge(nonce.x, pub_key_x, pub_key_y, message) == sig_e
+}
+
+fn challenge(nonce_x: Fq, pub_key_x: Fq, pub_key_y: Fq, message: &[u8]) -> [u8; 32] {
+    let compressed_keys = pedersen::hash(&[nonce_x, pub_key_x, pub_key_y], 0);
+    let mut hasher = Blake2s256::new();
+    hasher.update(compressed_keys.into_bigint().to_bytes_be());
+    hasher.update(message);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Signs `message` with the private key `secret`, using `nonce` as the signature nonce.
+    fn sign(secret: Fr, nonce: Fr, message: &[u8]) -> (Fq, Fq, [u8; 32], [u8; 32]) {
+        let pub_key = (EdwardsAffine::generator() * secret).into_affine();
+        let nonce_point = (EdwardsAffine::generator() * nonce).into_affine();
+
+        let sig_e = challenge(nonce_point.x, pub_key.x, pub_key.y, message);
+        // R = G * k = G * s + P * e, so s = k - secret * e
+        let s = nonce - secret * Fr::from_be_bytes_mod_order(&sig_e);
+        let sig_s = s.into_bigint().to_bytes_be().try_into().unwrap();
+
+        (pub_key.x, pub_key.y, sig_s, sig_e)
+    }
+
+    #[test]
+    fn verifies_valid_signature() {
+        let message = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let (pub_key_x, pub_key_y, sig_s, sig_e) =
+            sign(Fr::from(123456789u64), Fr::from(987654321u64), &message);
+        assert!(verify_signature(pub_key_x, pub_key_y, sig_s, sig_e, &message));
+    }
+
+    #[test]
+    fn rejects_invalid_signatures() {
+        let message = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let (pub_key_x, pub_key_y, sig_s, sig_e) =
+            sign(Fr::from(123456789u64), Fr::from(987654321u64), &message);
+
+        let mut other_message = message;
+        other_message[0] = 1;
+        assert!(!verify_signature(pub_key_x, pub_key_y, sig_s, sig_e, &other_message));
+
+        let mut other_sig_s = sig_s;
+        other_sig_s[31] ^= 1;
+        assert!(!verify_signature(pub_key_x, pub_key_y, other_sig_s, sig_e, &message));
+
+        // Public keys which are not on the curve are rejected
+        let other_pub_key_y = pub_key_y + Fq::from(1u64);
+        assert!(!verify_signature(pub_key_x, other_pub_key_y, sig_s, sig_e, &message));
+
+        // As are public keys outside of the prime order subgroup, such as the point of order 2
+        assert!(!verify_signature(Fq::zero(), -Fq::from(1u64), sig_s, sig_e, &message));
+
+        assert!(!verify_signature(pub_key_x, pub_key_y, [0; 32], sig_e, &message));
+    }
+}
@@ -3,6 +3,11 @@ use std::path::PathBuf;
 const BARRETENBERG_BIN_DIR: &str = "BARRETENBERG_BIN_DIR";
 
 fn main() -> Result<(), String> {
+    // The Barretenberg wasm is only embedded when cross-checking against it
+    if std::env::var_os("CARGO_FEATURE_BARRETENBERG").is_none() {
+        return Ok(());
+    }
+
     let out_dir = std::env::var("OUT_DIR").unwrap();
 
     let dest_path = PathBuf::from(out_dir.clone()).join("acvm_backend.wasm");
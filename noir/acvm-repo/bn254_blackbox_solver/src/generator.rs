@@ -0,0 +1,149 @@
+// Generators are derived in the same way as Barretenberg's `derive_generators`,
+// so that commitments and hashes match those computed by the proving backend.
+// https://github.com/AztecProtocol/barretenberg/blob/master/cpp/src/barretenberg/ecc/groups/group.hpp
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+
+use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ff::{BigInteger, Field, PrimeField};
+use grumpkin::{GrumpkinParameters, SWAffine};
+
+/// The domain separator of the generators used by default for Pedersen commitments and hashes.
+pub(crate) const DEFAULT_DOMAIN_SEPARATOR: &[u8] = b"DEFAULT_DOMAIN_SEPARATOR";
+
+/// The domain separator of the generator which Pedersen hashes commit to their number of inputs with.
+const LENGTH_DOMAIN_SEPARATOR: &[u8] = b"pedersen_hash_length";
+
+/// The default generators derived so far, keyed by their index.
+static DEFAULT_GENERATORS: Mutex<BTreeMap<u32, SWAffine>> = Mutex::new(BTreeMap::new());
+
+static LENGTH_GENERATOR: OnceLock<SWAffine> = OnceLock::new();
+
+/// Returns `num_generators` of the default generators, starting from the one at `starting_index`.
+///
+/// Deriving generators is expensive, so they are cached across calls.
+pub(crate) fn default_generators(num_generators: u32, starting_index: u32) -> Vec<SWAffine> {
+    let mut generators = DEFAULT_GENERATORS.lock().expect("Failed to lock default generators");
+    (starting_index..starting_index + num_generators)
+        .map(|index| {
+            *generators
+                .entry(index)
+                .or_insert_with(|| derive_generators(DEFAULT_DOMAIN_SEPARATOR, 1, index)[0])
+        })
+        .collect()
+}
+
+/// Returns the generator which Pedersen hashes commit to their number of inputs with.
+pub(crate) fn length_generator() -> SWAffine {
+    *LENGTH_GENERATOR.get_or_init(|| derive_generators(LENGTH_DOMAIN_SEPARATOR, 1, 0)[0])
+}
+
+/// Derives `num_generators` points of the grumpkin curve, starting from the one at `starting_index`,
+/// whose discrete logarithms with respect to each other are unknown.
+pub(crate) fn derive_generators(
+    domain_separator_bytes: &[u8],
+    num_generators: u32,
+    starting_index: u32,
+) -> Vec<SWAffine> {
+    // The preimage of each generator is the hash of the domain separator followed by the
+    // generator's index, zero-padded to 64 bytes
+    let mut generator_preimage = [0; 64];
+    generator_preimage[..32].copy_from_slice(blake3::hash(domain_separator_bytes).as_bytes());
+
+    (starting_index..starting_index + num_generators)
+        .map(|generator_index| {
+            generator_preimage[32..36].copy_from_slice(&generator_index.to_be_bytes());
+            hash_to_curve(&generator_preimage, 0)
+        })
+        .collect()
+}
+
+/// Hashes `seed` to a point of the grumpkin curve, trying successive `attempt_count`s
+/// until the hash gives the x coordinate of a point.
+fn hash_to_curve(seed: &[u8], attempt_count: u8) -> SWAffine {
+    let mut target_seed = seed.to_vec();
+    target_seed.extend_from_slice(&[attempt_count, 0]);
+    let hash_hi = blake3::hash(&target_seed);
+    *target_seed.last_mut().expect("seed is not empty") = 1;
+    let hash_lo = blake3::hash(&target_seed);
+
+    // The 512 bits of the two hashes ensure the reduced x coordinate is not biased
+    let x_bytes = [hash_hi.as_bytes().as_slice(), hash_lo.as_bytes()].concat();
+    let x = grumpkin::Fq::from_be_bytes_mod_order(&x_bytes);
+    let sign_bit = hash_hi.as_bytes()[0] > 127;
+
+    match derive_from_x_coordinate(x, sign_bit) {
+        Some(point) => point,
+        None => hash_to_curve(seed, attempt_count + 1),
+    }
+}
+
+/// Returns the point of the grumpkin curve with the given x coordinate and whose
+/// y coordinate has the given parity, if there is one.
+fn derive_from_x_coordinate(x: grumpkin::Fq, sign_bit: bool) -> Option<SWAffine> {
+    let y_squared = x * x * x + GrumpkinParameters::COEFF_B;
+    let y = y_squared.sqrt()?;
+    let y = if y.into_bigint().is_odd() == sign_bit { y } else { -y };
+    Some(SWAffine::new_unchecked(x, y))
+}
+
+#[cfg(test)]
+mod tests {
+    use acir::FieldElement;
+    use ark_ec::AffineRepr;
+    use ark_ff::MontFp;
+
+    use super::*;
+
+    #[test]
+    fn derived_generators_are_on_curve() {
+        let generators = derive_generators(DEFAULT_DOMAIN_SEPARATOR, 4, 0);
+        assert_eq!(generators.len(), 4);
+        for generator in &generators {
+            assert!(generator.is_on_curve());
+            assert!(!generator.is_zero());
+        }
+
+        // The generators starting at an offset are the trailing ones of the full sequence
+        assert_eq!(derive_generators(DEFAULT_DOMAIN_SEPARATOR, 2, 2), generators[2..]);
+        assert_ne!(derive_generators(LENGTH_DOMAIN_SEPARATOR, 1, 0)[0], generators[0]);
+    }
+
+    #[test]
+    fn cached_generators_match_derived_ones() {
+        let generators = derive_generators(DEFAULT_DOMAIN_SEPARATOR, 6, 0);
+
+        // Later requests reuse the generators cached by earlier ones, deriving any others
+        assert_eq!(default_generators(2, 1), generators[1..3]);
+        assert_eq!(default_generators(3, 3), generators[3..6]);
+        assert_eq!(default_generators(6, 0), generators);
+        assert!(default_generators(0, 2).is_empty());
+
+        assert_eq!(length_generator(), derive_generators(LENGTH_DOMAIN_SEPARATOR, 1, 0)[0]);
+    }
+
+    #[test]
+    fn derives_default_generators() {
+        let field_from_hex = |hex| FieldElement::from_hex(hex).unwrap().into_repr();
+        let generator = derive_generators(DEFAULT_DOMAIN_SEPARATOR, 1, 0)[0];
+        let expected_x =
+            field_from_hex("0x083e7911d835097629f0067531fc15cafd79a89beecb39903f69572c636f4a5a");
+        let expected_y =
+            field_from_hex("0x1a7f5efaad7f315c25a918f30cc8d7333fccab7ad7c90f14de81bcc528f9935d");
+        assert_eq!(generator.xy(), Some((&expected_x, &expected_y)));
+    }
+
+    #[test]
+    fn derive_from_x_coordinate_respects_sign_bit() {
+        let x: grumpkin::Fq = MontFp!("1");
+        let even = derive_from_x_coordinate(x, false).unwrap();
+        let odd = derive_from_x_coordinate(x, true).unwrap();
+        assert!(even.y.into_bigint().is_even());
+        assert!(odd.y.into_bigint().is_odd());
+        assert_eq!(even, -odd);
+
+        // x^3 - 17 is not a square for x = 0
+        assert!(derive_from_x_coordinate(grumpkin::Fq::from(0u64), false).is_none());
+    }
+}
@@ -1,40 +1,39 @@
 #![warn(unreachable_pub)]
 #![warn(clippy::semicolon_if_nothing_returned)]
-#![cfg_attr(not(test), warn(unused_crate_dependencies, unused_extern_crates))]
+#![cfg_attr(
+    not(any(test, feature = "barretenberg")),
+    warn(unused_crate_dependencies, unused_extern_crates)
+)]
 
-use acir::{BlackBoxFunc, FieldElement};
+use acir::FieldElement;
 use acvm_blackbox_solver::{BlackBoxFunctionSolver, BlackBoxResolutionError};
+use ark_ec::AffineRepr;
 
 mod fixed_base_scalar_mul;
+mod generator;
+mod pedersen;
+mod schnorr;
+// The Barretenberg wasm is only used to check the native implementations against it
+#[cfg(all(test, feature = "barretenberg"))]
 mod wasm;
 
-pub use fixed_base_scalar_mul::fixed_base_scalar_mul;
-use wasm::Barretenberg;
+#[cfg(target_arch = "wasm32")]
+use getrandom as _;
 
-use self::wasm::{Pedersen, SchnorrSig};
+pub use fixed_base_scalar_mul::fixed_base_scalar_mul;
 
-pub struct Bn254BlackBoxSolver {
-    blackbox_vendor: Barretenberg,
-}
+#[derive(Default)]
+pub struct Bn254BlackBoxSolver;
 
 impl Bn254BlackBoxSolver {
     #[cfg(target_arch = "wasm32")]
     pub async fn initialize() -> Bn254BlackBoxSolver {
-        let blackbox_vendor = Barretenberg::initialize().await;
-        Bn254BlackBoxSolver { blackbox_vendor }
+        Bn254BlackBoxSolver
     }
 
     #[cfg(not(target_arch = "wasm32"))]
     pub fn new() -> Bn254BlackBoxSolver {
-        let blackbox_vendor = Barretenberg::new();
-        Bn254BlackBoxSolver { blackbox_vendor }
-    }
-}
-
-#[cfg(not(target_arch = "wasm32"))]
-impl Default for Bn254BlackBoxSolver {
-    fn default() -> Self {
-        Self::new()
+        Bn254BlackBoxSolver
     }
 }
 
@@ -46,16 +45,16 @@ impl BlackBoxFunctionSolver for Bn254BlackBoxSolver {
         signature: &[u8],
         message: &[u8],
     ) -> Result<bool, BlackBoxResolutionError> {
-        let pub_key_bytes: Vec<u8> =
-            public_key_x.to_be_bytes().iter().copied().chain(public_key_y.to_be_bytes()).collect();
-
-        let pub_key: [u8; 64] = pub_key_bytes.try_into().unwrap();
         let sig_s: [u8; 32] = signature[0..32].try_into().unwrap();
         let sig_e: [u8; 32] = signature[32..64].try_into().unwrap();
 
-        self.blackbox_vendor.verify_signature(pub_key, sig_s, sig_e, message).map_err(|err| {
-            BlackBoxResolutionError::Failed(BlackBoxFunc::SchnorrVerify, err.to_string())
-        })
+        Ok(schnorr::verify_signature(
+            public_key_x.into_repr(),
+            public_key_y.into_repr(),
+            sig_s,
+            sig_e,
+            message,
+        ))
     }
 
     fn pedersen_commitment(
@@ -63,10 +62,12 @@ impl BlackBoxFunctionSolver for Bn254BlackBoxSolver {
         inputs: &[FieldElement],
         domain_separator: u32,
     ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
-        #[allow(deprecated)]
-        self.blackbox_vendor.encrypt(inputs.to_vec(), domain_separator).map_err(|err| {
-            BlackBoxResolutionError::Failed(BlackBoxFunc::PedersenCommitment, err.to_string())
-        })
+        let inputs: Vec<_> = inputs.iter().map(|input| input.into_repr()).collect();
+        let commitment = pedersen::commitment(&inputs, domain_separator);
+        match commitment.xy() {
+            Some((x, y)) => Ok((FieldElement::from_repr(*x), FieldElement::from_repr(*y))),
+            None => Ok((FieldElement::zero(), FieldElement::zero())),
+        }
     }
 
     fn pedersen_hash(
@@ -74,10 +75,8 @@ impl BlackBoxFunctionSolver for Bn254BlackBoxSolver {
         inputs: &[FieldElement],
         domain_separator: u32,
     ) -> Result<FieldElement, BlackBoxResolutionError> {
-        #[allow(deprecated)]
-        self.blackbox_vendor.hash(inputs.to_vec(), domain_separator).map_err(|err| {
-            BlackBoxResolutionError::Failed(BlackBoxFunc::PedersenCommitment, err.to_string())
-        })
+        let inputs: Vec<_> = inputs.iter().map(|input| input.into_repr()).collect();
+        Ok(FieldElement::from_repr(pedersen::hash(&inputs, domain_separator)))
     }
 
     fn fixed_base_scalar_mul(
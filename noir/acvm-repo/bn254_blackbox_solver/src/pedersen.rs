@@ -0,0 +1,70 @@
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::PrimeField;
+use grumpkin::{SWAffine, SWProjective};
+
+use crate::generator::{default_generators, length_generator};
+
+/// Commits to `inputs` using the default generators, starting from the one at `starting_index`.
+pub(crate) fn commitment(inputs: &[grumpkin::Fq], starting_index: u32) -> SWAffine {
+    let generators = default_generators(inputs.len() as u32, starting_index);
+    commit(inputs, &generators).into_affine()
+}
+
+/// Hashes `inputs` to a field element by committing to them along with their number.
+pub(crate) fn hash(inputs: &[grumpkin::Fq], starting_index: u32) -> grumpkin::Fq {
+    let generators = default_generators(inputs.len() as u32, starting_index);
+
+    let length = grumpkin::Fq::from(inputs.len() as u64);
+    let result = commit(&[length], &[length_generator()]) + commit(inputs, &generators);
+    // The hash of no inputs can't be the point at infinity as the length generator is not
+    let result = result.into_affine();
+    let (x, _) = result.xy().expect("hash should not be the point at infinity");
+    *x
+}
+
+fn commit(inputs: &[grumpkin::Fq], generators: &[SWAffine]) -> SWProjective {
+    // Inputs are smaller than the grumpkin scalar field modulus so are used as scalars as is
+    inputs
+        .iter()
+        .zip(generators)
+        .map(|(input, generator)| generator.mul_bigint(input.into_bigint()))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use acir::FieldElement;
+
+    use super::*;
+
+    #[test]
+    fn commitment_matches_barretenberg() {
+        let one = grumpkin::Fq::from(1u64);
+
+        let result = commitment(&[one, one], 1);
+        let expected_x =
+            field_from_hex("0x12afb43195f5c621d1d2cabb5f629707095c5307fd4185a663d4e80bb083e878");
+        let expected_y =
+            field_from_hex("0x25793f5b5e62beb92fd18a66050293a9fd554a2ff13bceba0339cae1a038d7c1");
+        assert_eq!(result.xy(), Some((&expected_x, &expected_y)));
+
+        let result = commitment(&[grumpkin::Fq::from(0u64), one], 0);
+        let expected_x =
+            field_from_hex("0x054aa86a73cb8a34525e5bbed6e43ba1198e860f5f3950268f71df4591bde402");
+        let expected_y =
+            field_from_hex("0x209dcfbf2cfb57f9f6046f44d71ac6faf87254afc7407c04eb621a6287cac126");
+        assert_eq!(result.xy(), Some((&expected_x, &expected_y)));
+    }
+
+    #[test]
+    fn hash_matches_barretenberg() {
+        let hash = hash(&[grumpkin::Fq::from(0u64), grumpkin::Fq::from(1u64)], 0);
+        let expected =
+            field_from_hex("0x0d98561fb02ca04d00801dfdc118b2a24cea0351963587712a28d368041370e1");
+        assert_eq!(hash, expected);
+    }
+
+    fn field_from_hex(hex: &str) -> grumpkin::Fq {
+        FieldElement::from_hex(hex).unwrap().into_repr()
+    }
+}
@@ -0,0 +1,100 @@
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use blake2::{Blake2s256, Digest};
+use grumpkin::SWAffine;
+
+use crate::pedersen;
+
+/// Verifies a Schnorr signature over the grumpkin curve, where the challenge `e` is the blake2s hash
+/// of the Pedersen hash of the signature nonce and the public key, followed by the message.
+pub(crate) fn verify_signature(
+    pub_key_x: grumpkin::Fq,
+    pub_key_y: grumpkin::Fq,
+    sig_s: [u8; 32],
+    sig_e: [u8; 32],
+    message: &[u8],
+) -> bool {
+    let pub_key = SWAffine::new_unchecked(pub_key_x, pub_key_y);
+    if !pub_key.is_on_curve() || pub_key.is_zero() {
+        return false;
+    }
+
+    let s = grumpkin::Fr::from_be_bytes_mod_order(&sig_s);
+    if s.is_zero() || s.into_bigint().to_bytes_be() != sig_s {
+        // `s` must be a canonical non-zero scalar
+        return false;
+    }
+    let e = grumpkin::Fr::from_be_bytes_mod_order(&sig_e);
+
+    let nonce = (pub_key * e + SWAffine::generator() * s).into_affine();
+    let Some((nonce_x, _)) = nonce.xy() else {
+        return false;
+    };
+
+    let compressed_keys = pedersen::hash(&[*nonce_x, pub_key_x, pub_key_y], 0);
+    let mut hasher = Blake2s256::new();
+    hasher.update(compressed_keys.into_bigint().to_bytes_be());
+    hasher.update(message);
+    let challenge: [u8; 32] = hasher.finalize().into();
+
+    challenge == sig_e
+}
+
+#[cfg(test)]
+mod tests {
+    use acir::FieldElement;
+
+    use super::*;
+
+    fn signed_message() -> (grumpkin::Fq, grumpkin::Fq, [u8; 32], [u8; 32], Vec<u8>) {
+        // Taken from the `schnorr` test program
+        let pub_key_x = FieldElement::from_hex(
+            "0x04b260954662e97f00cab9adb773a259097f7a274b83b113532bce27fa3fb96a",
+        )
+        .unwrap();
+        let pub_key_y = FieldElement::from_hex(
+            "0x2fd51571db6c08666b0edfbfbc57d432068bccd0110a39b166ab243da0037197",
+        )
+        .unwrap();
+        let signature: [u8; 64] = [
+            1, 13, 119, 112, 212, 39, 233, 41, 84, 235, 255, 93, 245, 172, 186, 83, 157, 253, 76,
+            77, 33, 128, 178, 15, 214, 67, 105, 107, 177, 234, 77, 48, 27, 237, 155, 84, 39, 84,
+            247, 27, 22, 8, 176, 230, 24, 115, 145, 220, 254, 122, 135, 179, 171, 4, 214, 202, 64,
+            199, 19, 84, 239, 138, 124, 12,
+        ];
+        let message = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+        (
+            pub_key_x.into_repr(),
+            pub_key_y.into_repr(),
+            signature[0..32].try_into().unwrap(),
+            signature[32..64].try_into().unwrap(),
+            message,
+        )
+    }
+
+    #[test]
+    fn verifies_valid_signature() {
+        let (pub_key_x, pub_key_y, sig_s, sig_e, message) = signed_message();
+        assert!(verify_signature(pub_key_x, pub_key_y, sig_s, sig_e, &message));
+    }
+
+    #[test]
+    fn rejects_invalid_signatures() {
+        let (pub_key_x, pub_key_y, sig_s, sig_e, message) = signed_message();
+
+        let mut other_message = message.clone();
+        other_message[0] = 1;
+        assert!(!verify_signature(pub_key_x, pub_key_y, sig_s, sig_e, &other_message));
+
+        let mut other_sig_s = sig_s;
+        other_sig_s[31] ^= 1;
+        assert!(!verify_signature(pub_key_x, pub_key_y, other_sig_s, sig_e, &message));
+
+        // Public keys which are not on the curve are rejected
+        let other_pub_key_y = pub_key_y + grumpkin::Fq::from(1u64);
+        assert!(!verify_signature(pub_key_x, other_pub_key_y, sig_s, sig_e, &message));
+
+        assert!(!verify_signature(pub_key_x, pub_key_y, [0; 32], sig_e, &message));
+    }
+}
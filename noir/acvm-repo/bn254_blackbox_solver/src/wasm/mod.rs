@@ -1,8 +1,7 @@
 //! ACVM execution is independent of the proving backend against which the ACIR code is being proven.
-//! However there are currently a few opcodes for which there is currently no rust implementation so we must
-//! use the C++ implementations included in Aztec Lab's Barretenberg library.
-//!
-//! As [`acvm`] includes rust implementations for these opcodes, this module can be removed.
+//! However the results of the Pedersen and Schnorr opcodes must match those of the C++ implementations
+//! included in Aztec Lab's Barretenberg library, so this module runs them to check the native implementations
+//! against in tests when the `barretenberg` feature is enabled.
 
 mod barretenberg_structures;
 mod pedersen;
@@ -71,3 +71,28 @@ fn pedersen_hash_to_point() -> Result<(), Error> {
     assert_eq!(expected_y.to_hex(), y.to_hex());
     Ok(())
 }
+
+#[test]
+fn pedersen_matches_native_implementation() -> Result<(), Error> {
+    use crate::Bn254BlackBoxSolver;
+    use acvm_blackbox_solver::BlackBoxFunctionSolver;
+
+    let barretenberg = Barretenberg::new();
+    let solver = Bn254BlackBoxSolver;
+    let inputs_list = [
+        vec![FieldElement::zero()],
+        vec![FieldElement::one(), FieldElement::one()],
+        vec![-FieldElement::one(), FieldElement::from(2u128), FieldElement::from(u128::MAX)],
+        (0..20u128).map(|i| FieldElement::from(i * i + 7)).collect(),
+    ];
+    for inputs in inputs_list {
+        for hash_index in [0, 1, 5] {
+            let expected = barretenberg.encrypt(inputs.clone(), hash_index)?;
+            assert_eq!(solver.pedersen_commitment(&inputs, hash_index).unwrap(), expected);
+
+            let expected = barretenberg.hash(inputs.clone(), hash_index)?;
+            assert_eq!(solver.pedersen_hash(&inputs, hash_index).unwrap(), expected);
+        }
+    }
+    Ok(())
+}
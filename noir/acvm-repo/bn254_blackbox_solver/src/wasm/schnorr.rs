@@ -50,7 +50,6 @@ impl SchnorrSig for Barretenberg {
         Ok((sig_s, sig_e))
     }
 
-    #[allow(dead_code)]
     fn construct_public_key(&self, private_key: [u8; 32]) -> Result<[u8; 64], Error> {
         let private_key_ptr: usize = 0;
         let result_ptr: usize = private_key_ptr + FIELD_BYTES;
@@ -102,3 +101,34 @@ impl SchnorrSig for Barretenberg {
         Ok(verified.try_into()?)
     }
 }
+
+#[test]
+fn schnorr_matches_native_implementation() -> Result<(), Error> {
+    use crate::Bn254BlackBoxSolver;
+    use acir::FieldElement;
+    use acvm_blackbox_solver::BlackBoxFunctionSolver;
+
+    let barretenberg = Barretenberg::new();
+    let solver = Bn254BlackBoxSolver;
+    for seed in 1..=5u8 {
+        let private_key = [seed; 32];
+        let message: Vec<u8> = (0..seed * 10).collect();
+
+        let pub_key = barretenberg.construct_public_key(private_key)?;
+        let (sig_s, sig_e) = barretenberg.construct_signature(&message, private_key)?;
+        let pub_key_x = FieldElement::from_be_bytes_reduce(&pub_key[0..32]);
+        let pub_key_y = FieldElement::from_be_bytes_reduce(&pub_key[32..64]);
+        let signature = [sig_s, sig_e].concat();
+
+        assert!(barretenberg.verify_signature(pub_key, sig_s, sig_e, &message)?);
+        assert!(solver.schnorr_verify(&pub_key_x, &pub_key_y, &signature, &message).unwrap());
+
+        let mut other_message = message.clone();
+        other_message.push(0);
+        assert!(!barretenberg.verify_signature(pub_key, sig_s, sig_e, &other_message)?);
+        assert!(!solver
+            .schnorr_verify(&pub_key_x, &pub_key_y, &signature, &other_message)
+            .unwrap());
+    }
+    Ok(())
+}
@@ -16,6 +16,7 @@ use acir::brillig::{
     RegisterIndex, RegisterOrMemory, Value,
 };
 use acir::FieldElement;
+use std::time::Instant;
 // Re-export `brillig`.
 pub use acir::brillig;
 
@@ -35,6 +36,43 @@ pub use registers::Registers;
 /// The error call stack contains the opcode indexes of the call stack at the time of failure, plus the index of the opcode that failed.
 pub type ErrorCallStack = Vec<usize>;
 
+/// Limits on the resources which a Brillig process may use before it is halted.
+///
+/// Without limits, a process which never reaches a `Stop` opcode runs forever.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionLimits {
+    /// The maximum number of opcodes which may be processed.
+    pub max_steps: Option<usize>,
+    /// The maximum number of values which may be held in memory.
+    pub max_memory: Option<usize>,
+    /// The time by which the process must have completed.
+    pub deadline: Option<Instant>,
+}
+
+/// The number of steps between checks of the [deadline][ExecutionLimits::deadline], as reading the clock
+/// costs far more than processing an opcode.
+const DEADLINE_CHECK_INTERVAL: usize = 1024;
+
+/// A limit set by [`ExecutionLimits`] which a Brillig process has exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExceededLimit {
+    Steps(usize),
+    Memory(usize),
+    Deadline,
+}
+
+impl std::fmt::Display for ExceededLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExceededLimit::Steps(max_steps) => write!(f, "step limit of {max_steps} opcodes"),
+            ExceededLimit::Memory(max_memory) => {
+                write!(f, "memory limit of {max_memory} values")
+            }
+            ExceededLimit::Deadline => write!(f, "time limit"),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum VMStatus {
     Finished,
@@ -43,6 +81,11 @@ pub enum VMStatus {
         message: String,
         call_stack: ErrorCallStack,
     },
+    /// The VM process was halted as it exceeded one of its [`ExecutionLimits`].
+    LimitExceeded {
+        limit: ExceededLimit,
+        call_stack: ErrorCallStack,
+    },
     /// The VM process is not solvable as a [foreign call][Opcode::ForeignCall] has been
     /// reached where the outputs are yet to be resolved.
     ///
@@ -58,7 +101,7 @@ pub enum VMStatus {
     },
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq)]
 /// VM encapsulates the state of the Brillig VM during execution.
 pub struct VM<'a, B: BlackBoxFunctionSolver> {
     /// Register storage
@@ -81,6 +124,29 @@ pub struct VM<'a, B: BlackBoxFunctionSolver> {
     call_stack: Vec<Value>,
     /// The solver for blackbox functions
     black_box_solver: &'a B,
+    /// Number of opcodes which have been processed to completion
+    steps: usize,
+    /// Limits on the resources which the VM may use
+    limits: ExecutionLimits,
+}
+
+// The black box solver is only borrowed, so the VM can be cloned even if the solver can't.
+impl<'a, B: BlackBoxFunctionSolver> Clone for VM<'a, B> {
+    fn clone(&self) -> Self {
+        Self {
+            registers: self.registers.clone(),
+            program_counter: self.program_counter,
+            foreign_call_counter: self.foreign_call_counter,
+            foreign_call_results: self.foreign_call_results.clone(),
+            bytecode: self.bytecode,
+            status: self.status.clone(),
+            memory: self.memory.clone(),
+            call_stack: self.call_stack.clone(),
+            black_box_solver: self.black_box_solver,
+            steps: self.steps,
+            limits: self.limits,
+        }
+    }
 }
 
 impl<'a, B: BlackBoxFunctionSolver> VM<'a, B> {
@@ -102,9 +168,17 @@ impl<'a, B: BlackBoxFunctionSolver> VM<'a, B> {
             memory: memory.into(),
             call_stack: Vec::new(),
             black_box_solver,
+            steps: 0,
+            limits: ExecutionLimits::default(),
         }
     }
 
+    /// Halts the VM once it exceeds any of the given `limits`.
+    pub fn with_limits(mut self, limits: ExecutionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
     /// Updates the current status of the VM.
     /// Returns the given status.
     fn status(&mut self, status: VMStatus) -> VMStatus {
@@ -143,18 +217,53 @@ impl<'a, B: BlackBoxFunctionSolver> VM<'a, B> {
     /// Indicating that the VM encountered a `Trap` Opcode
     /// or an invalid state.
     fn fail(&mut self, message: String) -> VMStatus {
+        let call_stack = self.error_call_stack(self.program_counter);
+        self.status(VMStatus::Failure { call_stack, message })
+    }
+
+    /// Sets the current status of the VM to `LimitExceeded`, with the opcode at `program_counter`
+    /// being the one which exceeded the `limit`.
+    fn exceed_limit(&mut self, limit: ExceededLimit, program_counter: usize) -> VMStatus {
+        let call_stack = self.error_call_stack(program_counter);
+        self.status(VMStatus::LimitExceeded { limit, call_stack })
+    }
+
+    fn error_call_stack(&self, program_counter: usize) -> ErrorCallStack {
         let mut error_stack: Vec<_> =
             self.call_stack.iter().map(|value| value.to_usize()).collect();
-        error_stack.push(self.program_counter);
-        self.status(VMStatus::Failure { call_stack: error_stack, message });
-        self.status.clone()
+        error_stack.push(program_counter);
+        error_stack
+    }
+
+    /// Returns the limit which processing another opcode would exceed, if any.
+    fn exceeded_limit(&self) -> Option<ExceededLimit> {
+        if let Some(max_steps) = self.limits.max_steps.filter(|max_steps| self.steps >= *max_steps)
+        {
+            return Some(ExceededLimit::Steps(max_steps));
+        }
+
+        let check_deadline = self.steps % DEADLINE_CHECK_INTERVAL == 0;
+        match self.limits.deadline {
+            Some(deadline) if check_deadline && Instant::now() >= deadline => {
+                Some(ExceededLimit::Deadline)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the memory limit which holding `size` values in memory would exceed, if any.
+    fn exceeds_memory_limit(&self, size: usize) -> Option<ExceededLimit> {
+        self.limits.max_memory.filter(|max_memory| size > *max_memory).map(ExceededLimit::Memory)
     }
 
     /// Loop over the bytecode and update the program counter
     pub fn process_opcodes(&mut self) -> VMStatus {
         while !matches!(
             self.process_opcode(),
-            VMStatus::Finished | VMStatus::Failure { .. } | VMStatus::ForeignCallWait { .. }
+            VMStatus::Finished
+                | VMStatus::Failure { .. }
+                | VMStatus::LimitExceeded { .. }
+                | VMStatus::ForeignCallWait { .. }
         ) {}
         self.status.clone()
     }
@@ -178,8 +287,13 @@ impl<'a, B: BlackBoxFunctionSolver> VM<'a, B> {
 
     /// Process a single opcode and modify the program counter.
     pub fn process_opcode(&mut self) -> VMStatus {
+        let program_counter = self.program_counter;
+        if let Some(limit) = self.exceeded_limit() {
+            return self.exceed_limit(limit, program_counter);
+        }
+
         let opcode = &self.bytecode[self.program_counter];
-        match opcode {
+        let status = match opcode {
             Opcode::BinaryFieldOp { op, lhs, rhs, destination: result } => {
                 self.process_binary_field_op(*op, *lhs, *rhs, *result);
                 self.increment_program_counter()
@@ -198,16 +312,18 @@ impl<'a, B: BlackBoxFunctionSolver> VM<'a, B> {
                 // We use 0 to mean false and any other value to mean true
                 let condition_value = self.registers.get(*condition);
                 if !condition_value.is_zero() {
-                    return self.set_program_counter(*destination);
+                    self.set_program_counter(*destination)
+                } else {
+                    self.increment_program_counter()
                 }
-                self.increment_program_counter()
             }
             Opcode::JumpIfNot { condition, location: destination } => {
                 let condition_value = self.registers.get(*condition);
                 if condition_value.is_zero() {
-                    return self.set_program_counter(*destination);
+                    self.set_program_counter(*destination)
+                } else {
+                    self.increment_program_counter()
                 }
-                self.increment_program_counter()
             }
             Opcode::Return => {
                 if let Some(register) = self.call_stack.pop() {
@@ -309,6 +425,10 @@ impl<'a, B: BlackBoxFunctionSolver> VM<'a, B> {
             Opcode::Store { destination_pointer, source: source_register } => {
                 // Convert our destination_pointer to a usize
                 let destination = self.registers.get(*destination_pointer).to_usize();
+                // Check the limit before writing, as memory grows to fit the destination.
+                if let Some(limit) = self.exceeds_memory_limit(destination.saturating_add(1)) {
+                    return self.exceed_limit(limit, program_counter);
+                }
                 // Use our usize destination index to set the value in memory
                 self.memory.write(destination, self.registers.get(*source_register));
                 self.increment_program_counter()
@@ -333,7 +453,18 @@ impl<'a, B: BlackBoxFunctionSolver> VM<'a, B> {
                     Err(e) => self.fail(e.to_string()),
                 }
             }
+        };
+        // A foreign call which is waiting on its result will be processed again once it is resolved.
+        if !matches!(status, VMStatus::ForeignCallWait { .. }) {
+            self.steps += 1;
+        }
+        // Other opcodes only write small amounts of memory, so it's enough to check them once written.
+        if matches!(status, VMStatus::InProgress | VMStatus::Finished) {
+            if let Some(limit) = self.exceeds_memory_limit(self.memory.values().len()) {
+                return self.exceed_limit(limit, program_counter);
+            }
         }
+        status
     }
 
     /// Returns the current value of the program counter.
@@ -341,6 +472,11 @@ impl<'a, B: BlackBoxFunctionSolver> VM<'a, B> {
         self.program_counter
     }
 
+    /// Returns the number of opcodes which have been processed so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
     /// Increments the program counter by 1.
     fn increment_program_counter(&mut self) -> VMStatus {
         self.set_program_counter(self.program_counter + 1)
@@ -990,6 +1126,9 @@ mod tests {
 
         // Ensure the foreign call counter has been incremented
         assert_eq!(vm.foreign_call_counter, 1);
+
+        // The foreign call is only counted as a step once its result has been processed
+        assert_eq!(vm.steps(), 2);
     }
     #[test]
     fn foreign_call_opcode_memory_result() {
@@ -1270,4 +1409,69 @@ mod tests {
         // Ensure the foreign call counter has been incremented
         assert_eq!(vm.foreign_call_counter, 1);
     }
+
+    #[test]
+    fn halts_infinite_loops_at_the_step_limit() {
+        // A function calling into a loop which never exits.
+        let opcodes = [Opcode::Call { location: 2 }, Opcode::Stop, Opcode::Jump { location: 2 }];
+        let limits = ExecutionLimits { max_steps: Some(100), ..ExecutionLimits::default() };
+        let mut vm =
+            VM::new(Registers::load(vec![]), vec![], &opcodes, vec![], &DummyBlackBoxSolver)
+                .with_limits(limits);
+
+        let status = vm.process_opcodes();
+        assert_eq!(
+            status,
+            VMStatus::LimitExceeded { limit: ExceededLimit::Steps(100), call_stack: vec![0, 2] }
+        );
+        assert_eq!(vm.steps(), 100);
+    }
+
+    #[test]
+    fn halts_at_the_memory_limit() {
+        let limits = ExecutionLimits { max_memory: Some(8), ..ExecutionLimits::default() };
+
+        // Stores to ever increasing addresses until the memory limit is reached.
+        let r_pointer = RegisterIndex::from(0);
+        let r_one = RegisterIndex::from(1);
+        let opcodes = [
+            Opcode::Const { destination: r_one, value: Value::from(1u128) },
+            Opcode::Store { destination_pointer: r_pointer, source: r_one },
+            Opcode::BinaryIntOp {
+                op: BinaryIntOp::Add,
+                bit_size: 32,
+                lhs: r_pointer,
+                rhs: r_one,
+                destination: r_pointer,
+            },
+            Opcode::Jump { location: 1 },
+        ];
+        let mut vm = VM::new(
+            Registers::load(vec![Value::from(0u128)]),
+            vec![],
+            &opcodes,
+            vec![],
+            &DummyBlackBoxSolver,
+        )
+        .with_limits(limits);
+        let status = vm.process_opcodes();
+        assert_eq!(
+            status,
+            VMStatus::LimitExceeded { limit: ExceededLimit::Memory(8), call_stack: vec![1] }
+        );
+        assert_eq!(vm.get_memory().len(), 8);
+
+        // A store to a distant address is stopped before memory is grown to reach it.
+        let opcodes = [Opcode::Store { destination_pointer: r_pointer, source: r_pointer }];
+        let mut vm = VM::new(
+            Registers::load(vec![Value::from(u64::MAX as u128)]),
+            vec![],
+            &opcodes,
+            vec![],
+            &DummyBlackBoxSolver,
+        )
+        .with_limits(limits);
+        assert!(matches!(vm.process_opcodes(), VMStatus::LimitExceeded { .. }));
+        assert!(vm.get_memory().is_empty());
+    }
 }
@@ -99,6 +99,11 @@ impl FileManager {
         self.id_to_path.get(&file_id).unwrap().as_path()
     }
 
+    /// Returns an iterator over the ids and paths of every file in the [`FileManager`].
+    pub fn paths(&self) -> impl Iterator<Item = (FileId, &Path)> + '_ {
+        self.id_to_path.iter().map(|(file_id, path)| (*file_id, path.as_path()))
+    }
+
     // TODO: This should accept a &Path instead of a PathBuf
     pub fn name_to_id(&self, file_name: PathBuf) -> Option<FileId> {
         self.file_map.get_file_id(&PathString::from_path(file_name))
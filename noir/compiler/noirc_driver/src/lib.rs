@@ -3,6 +3,7 @@
 #![warn(unreachable_pub)]
 #![warn(clippy::semicolon_if_nothing_returned)]
 
+use acvm::compiler::OptimizationLevel;
 use clap::Args;
 use fm::{FileId, FileManager};
 use iter_extended::vecmap;
@@ -14,7 +15,7 @@ use noirc_frontend::graph::{CrateId, CrateName};
 use noirc_frontend::hir::def_map::{Contract, CrateDefMap};
 use noirc_frontend::hir::Context;
 use noirc_frontend::macros_api::MacroProcessor;
-use noirc_frontend::monomorphization::monomorphize;
+use noirc_frontend::monomorphization::{monomorphize, monomorphize_debug};
 use noirc_frontend::node_interner::FuncId;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -71,6 +72,19 @@ pub struct CompileOptions {
     /// Disables the builtin macros being used in the compiler
     #[arg(long, hide = true)]
     pub disable_macros: bool,
+
+    /// Track the values of variables so that the debugger can display them
+    #[arg(long, hide = true)]
+    pub instrument_debug: bool,
+
+    /// Force Brillig output, compiling `main` and all functions it calls as unconstrained functions
+    #[arg(long, hide = true)]
+    pub force_brillig: bool,
+
+    /// Level of the ACIR optimizations applied to the circuit: `basic` or `full`
+    #[arg(long, default_value_t = OptimizationLevel::Basic)]
+    #[serde(skip)]
+    pub optimization_level: OptimizationLevel,
 }
 
 /// Helper type used to signify where only warnings are expected in file diagnostics
@@ -383,15 +397,25 @@ pub fn compile_no_check(
     cached_program: Option<CompiledProgram>,
     force_compile: bool,
 ) -> Result<CompiledProgram, RuntimeError> {
-    let program = monomorphize(main_function, &context.def_interner);
+    let program = if options.instrument_debug {
+        monomorphize_debug(main_function, &context.def_interner)
+    } else {
+        monomorphize(main_function, &context.def_interner)
+    };
 
-    let hash = fxhash::hash64(&program);
+    // The optimization level isn't applied here and forcing Brillig doesn't change the monomorphized
+    // program, but both change the circuit saved to the program's artifact, so the artifact is only
+    // reused if it was compiled with the same options.
+    let hash = fxhash::hash64(&(&program, options.optimization_level, options.force_brillig));
     let hashes_match = cached_program.as_ref().map_or(false, |program| program.hash == hash);
 
     // If user has specified that they want to see intermediate steps printed then we should
     // force compilation even if the program hasn't changed.
-    let force_compile =
-        force_compile || options.print_acir || options.show_brillig || options.show_ssa;
+    let force_compile = force_compile
+        || options.print_acir
+        || options.show_brillig
+        || options.show_ssa
+        || options.force_brillig;
 
     if !force_compile && hashes_match {
         info!("Program matches existing artifact, returning early");
@@ -399,7 +423,7 @@ pub fn compile_no_check(
     }
     let visibility = program.return_visibility;
     let (circuit, debug, input_witnesses, return_witnesses, warnings) =
-        create_circuit(program, options.show_ssa, options.show_brillig)?;
+        create_circuit(program, options.show_ssa, options.show_brillig, options.force_brillig)?;
 
     let abi =
         abi_gen::gen_abi(context, &main_function, input_witnesses, return_witnesses, visibility);
@@ -415,3 +439,58 @@ pub fn compile_no_check(
         warnings,
     })
 }
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use acvm::compiler::OptimizationLevel;
+    use noirc_frontend::hir::Context;
+
+    use super::{compile_main, file_manager_with_stdlib, prepare_crate, CompileOptions};
+
+    const MAIN_SOURCE: &str = "fn main(x: Field, y: pub Field) { assert(x != y); }";
+
+    fn compile(
+        options: &CompileOptions,
+        cached_program: Option<super::CompiledProgram>,
+    ) -> super::CompiledProgram {
+        let root = Path::new("/");
+        let main_path = root.join("main.nr");
+        let mut file_manager = file_manager_with_stdlib(root);
+        file_manager.add_file_with_source(&main_path, MAIN_SOURCE.to_string());
+
+        let mut context = Context::new(file_manager);
+        let crate_id = prepare_crate(&mut context, &main_path);
+        let (program, _) = compile_main(&mut context, crate_id, options, cached_program, false)
+            .expect("program should compile");
+        program
+    }
+
+    #[test]
+    fn cached_program_is_only_reused_at_the_same_optimization_level() {
+        let basic_options = CompileOptions::default();
+        let full_options =
+            CompileOptions { optimization_level: OptimizationLevel::Full, ..Default::default() };
+
+        let program = compile(&basic_options, None);
+        let basic_hash = program.hash;
+        assert_eq!(compile(&basic_options, Some(program.clone())).hash, basic_hash);
+        // The program is recompiled rather than reusing the one optimized at another level
+        assert_ne!(compile(&full_options, Some(program)).hash, basic_hash);
+    }
+
+    #[test]
+    fn cached_program_is_not_reused_for_brillig_compilation() {
+        let acir_options = CompileOptions::default();
+        let brillig_options = CompileOptions { force_brillig: true, ..Default::default() };
+
+        let acir_program = compile(&acir_options, None);
+        let brillig_program = compile(&brillig_options, Some(acir_program.clone()));
+        assert_ne!(brillig_program.hash, acir_program.hash);
+        // A program compiled to Brillig isn't reused when compiling to ACIR
+        let recompiled_program = compile(&acir_options, Some(brillig_program));
+        assert_eq!(recompiled_program.hash, acir_program.hash);
+        assert_eq!(recompiled_program.circuit, acir_program.circuit);
+    }
+}
@@ -1,6 +1,10 @@
 use acvm::acir::circuit::OpcodeLocation;
+use acvm::acir::native_types::Witness;
+use acvm::brillig_vm::brillig::RegisterOrMemory;
 use acvm::compiler::AcirTransformationMap;
 
+use noirc_printable_type::PrintableType;
+
 use serde_with::serde_as;
 use serde_with::DisplayFromStr;
 use std::collections::BTreeMap;
@@ -18,6 +22,41 @@ pub struct DebugInfo {
     /// that they should be serialized to/from strings.
     #[serde_as(as = "BTreeMap<DisplayFromStr, _>")]
     pub locations: BTreeMap<OpcodeLocation, Vec<Location>>,
+    /// The Noir variables whose values can be displayed by the debugger, keyed by their identifier.
+    /// Only programs compiled for the debugger track variables.
+    #[serde(default)]
+    pub variables: BTreeMap<DebugVarId, DebugVariable>,
+    /// Map opcode index of an ACIR circuit into the variables which hold a new value
+    /// once execution reaches that opcode, along with where that value is stored.
+    #[serde_as(as = "BTreeMap<DisplayFromStr, _>")]
+    #[serde(default)]
+    pub variable_assignments: BTreeMap<OpcodeLocation, Vec<(DebugVarId, DebugVariableValue)>>,
+}
+
+/// Identifies a [`DebugVariable`] within a program.
+pub type DebugVarId = u32;
+
+/// A named Noir variable along with the type its value is decoded as.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DebugVariable {
+    /// The name of the function declaring the variable
+    pub function: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub typ: PrintableType,
+}
+
+/// Where the value of a [`DebugVariable`] can be read from.
+///
+/// In both cases the value is laid out as its fields flattened in declaration order,
+/// which is the encoding expected to decode a value of the variable's [`PrintableType`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum DebugVariableValue {
+    /// The value is held in these witnesses, one per field element
+    Witnesses(Vec<Witness>),
+    /// The value is held in the Brillig VM. Arrays are given as a register pointing to their
+    /// elements in memory, where any nested array is stored as a pointer to a reference to it.
+    Brillig(Vec<RegisterOrMemory>),
 }
 
 /// Holds OpCodes Counts for Acir and Brillig Opcodes
@@ -30,10 +69,10 @@ pub struct OpCodesCount {
 
 impl DebugInfo {
     pub fn new(locations: BTreeMap<OpcodeLocation, Vec<Location>>) -> Self {
-        DebugInfo { locations }
+        DebugInfo { locations, ..Default::default() }
     }
 
-    /// Updates the locations map when the [`Circuit`][acvm::acir::circuit::Circuit] is modified.
+    /// Updates the locations and variable assignments maps when the [`Circuit`][acvm::acir::circuit::Circuit] is modified.
     ///
     /// The [`OpcodeLocation`]s are generated with the ACIR, but passing the ACIR through a transformation step
     /// renders the old `OpcodeLocation`s invalid. The AcirTransformationMap is able to map the old `OpcodeLocation` to the new ones.
@@ -41,12 +80,45 @@ impl DebugInfo {
     #[tracing::instrument(level = "trace", skip(self, update_map))]
     pub fn update_acir(&mut self, update_map: AcirTransformationMap) {
         let old_locations = mem::take(&mut self.locations);
+        let old_assignments = mem::take(&mut self.variable_assignments);
 
         for (old_opcode_location, source_locations) in old_locations {
             update_map.new_locations(old_opcode_location).for_each(|new_opcode_location| {
                 self.locations.insert(new_opcode_location, source_locations.clone());
             });
         }
+
+        let last_old_acir_index = old_assignments.keys().map(acir_index).max().unwrap_or_default();
+        let new_acir_index = |old_index| update_map.new_locations(OpcodeLocation::Acir(old_index));
+        let new_opcodes_count = (0..=last_old_acir_index)
+            .flat_map(new_acir_index)
+            .map(|location| acir_index(&location) + 1)
+            .max()
+            .unwrap_or_default();
+
+        for (old_opcode_location, assignments) in old_assignments {
+            match old_opcode_location {
+                OpcodeLocation::Acir(old_acir_index) => {
+                    // Variables hold their value once execution reaches their opcode, so if that
+                    // opcode was removed they are moved to the first opcode which follows it.
+                    let new_opcode_location = (old_acir_index..=last_old_acir_index)
+                        .find_map(|old_index| new_acir_index(old_index).min())
+                        .unwrap_or(OpcodeLocation::Acir(new_opcodes_count));
+                    self.variable_assignments
+                        .entry(new_opcode_location)
+                        .or_default()
+                        .extend(assignments);
+                }
+                OpcodeLocation::Brillig { .. } => {
+                    update_map.new_locations(old_opcode_location).for_each(|new_opcode_location| {
+                        self.variable_assignments
+                            .entry(new_opcode_location)
+                            .or_default()
+                            .extend(assignments.iter().cloned());
+                    });
+                }
+            }
+        }
     }
 
     pub fn opcode_location(&self, loc: &OpcodeLocation) -> Option<Vec<Location>> {
@@ -87,3 +159,79 @@ impl DebugInfo {
         counted_opcodes
     }
 }
+
+fn acir_index(location: &OpcodeLocation) -> usize {
+    match location {
+        OpcodeLocation::Acir(index) => *index,
+        OpcodeLocation::Brillig { acir_index, .. } => *acir_index,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use acvm::acir::circuit::opcodes::{BlackBoxFuncCall, FunctionInput};
+    use acvm::acir::circuit::{Circuit, Opcode, OpcodeLocation};
+    use acvm::acir::native_types::{Expression, Witness};
+    use acvm::brillig_vm::brillig::{RegisterIndex, RegisterOrMemory};
+    use acvm::compiler::{compile, OptimizationLevel};
+    use acvm::{ExpressionWidth, FieldElement};
+
+    use super::{DebugInfo, DebugVariableValue};
+
+    fn range(witness: u32, num_bits: u32) -> Opcode {
+        Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+            input: FunctionInput { witness: Witness(witness), num_bits },
+        })
+    }
+
+    #[test]
+    fn update_acir_remaps_variable_assignments() {
+        // The second range constraint is redundant and is removed when compiling the circuit.
+        let circuit = Circuit {
+            current_witness_index: 3,
+            opcodes: vec![
+                range(1, 8),
+                range(1, 16),
+                Opcode::AssertZero(Expression {
+                    mul_terms: vec![(FieldElement::one(), Witness(1), Witness(2))],
+                    linear_combinations: vec![(-FieldElement::one(), Witness(3))],
+                    q_c: FieldElement::zero(),
+                }),
+            ],
+            ..Circuit::default()
+        };
+        let (optimized_circuit, transformation_map) =
+            compile(circuit, ExpressionWidth::Unbounded, OptimizationLevel::Basic);
+        assert_eq!(optimized_circuit.opcodes.len(), 2);
+
+        let witness_value = |id| (id, DebugVariableValue::Witnesses(vec![Witness(1)]));
+        let brillig_value = (
+            3,
+            DebugVariableValue::Brillig(vec![RegisterOrMemory::RegisterIndex(RegisterIndex(0))]),
+        );
+        let variable_assignments = BTreeMap::from([
+            (OpcodeLocation::Acir(0), vec![witness_value(0)]),
+            (OpcodeLocation::Acir(1), vec![witness_value(1)]),
+            (
+                OpcodeLocation::Brillig { acir_index: 2, brillig_index: 5 },
+                vec![brillig_value.clone()],
+            ),
+            // Assignments made after the last opcode are displayed once execution finishes.
+            (OpcodeLocation::Acir(3), vec![witness_value(2)]),
+        ]);
+        let mut debug_info = DebugInfo { variable_assignments, ..DebugInfo::default() };
+
+        debug_info.update_acir(transformation_map);
+
+        let expected_assignments = BTreeMap::from([
+            (OpcodeLocation::Acir(0), vec![witness_value(0)]),
+            // The assignment to the removed opcode is moved to the one following it.
+            (OpcodeLocation::Acir(1), vec![witness_value(1)]),
+            (OpcodeLocation::Brillig { acir_index: 1, brillig_index: 5 }, vec![brillig_value]),
+            (OpcodeLocation::Acir(2), vec![witness_value(2)]),
+        ]);
+        assert_eq!(debug_info.variable_assignments, expected_assignments);
+    }
+}
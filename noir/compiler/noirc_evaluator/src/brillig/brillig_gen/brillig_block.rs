@@ -18,6 +18,7 @@ use acvm::brillig_vm::brillig::HeapVector;
 use acvm::FieldElement;
 use fxhash::{FxHashMap as HashMap, FxHashSet as HashSet};
 use iter_extended::vecmap;
+use noirc_errors::debug_info::DebugVarId;
 use num_bigint::BigUint;
 
 use super::brillig_black_box::convert_black_box_call;
@@ -517,6 +518,18 @@ impl<'block> BrilligBlock<'block> {
 
                     self.brillig_context.deallocate_register(radix);
                 }
+                Value::Intrinsic(Intrinsic::DebugVar) => {
+                    let (id, values) =
+                        arguments.split_first().expect("ICE: debug_var expects a variable id");
+                    let id = dfg
+                        .get_numeric_constant(*id)
+                        .and_then(|id| id.try_to_u64())
+                        .expect("ICE: debug_var expects a constant variable id");
+                    let values = vecmap(values, |value| {
+                        self.convert_ssa_value(*value, dfg).to_register_or_memory()
+                    });
+                    self.brillig_context.add_debug_variable(id as DebugVarId, values);
+                }
                 _ => {
                     unreachable!("unsupported function call type {:?}", dfg[*func])
                 }
@@ -26,6 +26,7 @@ use acvm::{
     FieldElement,
 };
 use debug_show::DebugShow;
+use noirc_errors::debug_info::DebugVarId;
 
 /// Integer arithmetic in Brillig is limited to 127 bit
 /// integers.
@@ -1036,6 +1037,12 @@ impl BrilligContext {
     pub(crate) fn set_call_stack(&mut self, call_stack: CallStack) {
         self.obj.set_call_stack(call_stack);
     }
+
+    /// Records that the given variable holds a new value, read from `value`,
+    /// once execution reaches the next opcode.
+    pub(crate) fn add_debug_variable(&mut self, id: DebugVarId, value: Vec<RegisterOrMemory>) {
+        self.obj.add_debug_variable(id, value);
+    }
 }
 
 /// Type to encapsulate the binary operation types in Brillig
@@ -1,4 +1,5 @@
-use acvm::acir::brillig::Opcode as BrilligOpcode;
+use acvm::acir::brillig::{Opcode as BrilligOpcode, RegisterOrMemory};
+use noirc_errors::debug_info::DebugVarId;
 use std::collections::{BTreeMap, HashMap};
 
 use crate::ssa::ir::dfg::CallStack;
@@ -18,6 +19,7 @@ pub(crate) struct GeneratedBrillig {
     pub(crate) byte_code: Vec<BrilligOpcode>,
     pub(crate) locations: BTreeMap<OpcodeLocation, CallStack>,
     pub(crate) assert_messages: BTreeMap<OpcodeLocation, String>,
+    pub(crate) debug_variables: BTreeMap<OpcodeLocation, Vec<(DebugVarId, Vec<RegisterOrMemory>)>>,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -43,6 +45,9 @@ pub(crate) struct BrilligArtifact {
     locations: BTreeMap<OpcodeLocation, CallStack>,
     /// The current call stack. All opcodes that are pushed will be associated with this call stack.
     call_stack: CallStack,
+    /// Maps bytecode positions to the variables which hold a new value once execution reaches them,
+    /// along with the registers and memory that value is read from.
+    debug_variables: BTreeMap<OpcodeLocation, Vec<(DebugVarId, Vec<RegisterOrMemory>)>>,
 }
 
 /// A pointer to a location in the opcode.
@@ -75,6 +80,7 @@ impl BrilligArtifact {
             byte_code: self.byte_code,
             locations: self.locations,
             assert_messages: self.assert_messages,
+            debug_variables: self.debug_variables,
         }
     }
 
@@ -145,6 +151,10 @@ impl BrilligArtifact {
         for (position_in_bytecode, call_stack) in obj.locations.iter() {
             self.locations.insert(position_in_bytecode + offset, call_stack.clone());
         }
+
+        for (position_in_bytecode, variables) in &obj.debug_variables {
+            self.debug_variables.insert(position_in_bytecode + offset, variables.clone());
+        }
     }
 
     /// Adds a brillig instruction to the brillig byte code
@@ -254,6 +264,12 @@ impl BrilligArtifact {
         self.call_stack = call_stack;
     }
 
+    /// Records that the given variable holds a new value, read from `value`,
+    /// once execution reaches the next opcode.
+    pub(crate) fn add_debug_variable(&mut self, id: DebugVarId, value: Vec<RegisterOrMemory>) {
+        self.debug_variables.entry(self.index_of_next_opcode()).or_default().push((id, value));
+    }
+
     pub(crate) fn add_assert_message_to_last_opcode(&mut self, message: String) {
         let position = self.index_of_next_opcode() - 1;
         self.assert_messages.insert(position, message);
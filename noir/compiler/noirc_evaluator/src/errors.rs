@@ -154,6 +154,19 @@ impl RuntimeError {
                     noirc_errors::Span::inclusive(0, 0)
                 )
             }
+            RuntimeError::UnknownLoopBound { .. } => {
+                let message = self.to_string();
+                let location =
+                    self.call_stack().back().expect("Expected RuntimeError to have a location");
+
+                let secondary =
+                    "Loops in constrained code are unrolled, so must loop a known number of times";
+                let mut diagnostic =
+                    Diagnostic::simple_error(message, secondary.to_owned(), location.span);
+                diagnostic
+                    .add_note("Consider moving this loop into an unconstrained function".to_owned());
+                diagnostic
+            }
             _ => {
                 let message = self.to_string();
                 let location =
@@ -40,12 +40,13 @@ pub(crate) fn optimize_into_acir(
     program: Program,
     print_ssa_passes: bool,
     print_brillig_trace: bool,
+    force_brillig_output: bool,
 ) -> Result<GeneratedAcir, RuntimeError> {
     let abi_distinctness = program.return_distinctness;
 
     let ssa_gen_span = span!(Level::TRACE, "ssa_generation");
     let ssa_gen_span_guard = ssa_gen_span.enter();
-    let ssa_builder = SsaBuilder::new(program, print_ssa_passes)?
+    let ssa_builder = SsaBuilder::new(program, print_ssa_passes, force_brillig_output)?
         .run_pass(Ssa::defunctionalize, "After Defunctionalization:")
         .run_pass(Ssa::inline_functions, "After Inlining:")
         // Run mem2reg with the CFG separated into blocks
@@ -88,10 +89,15 @@ pub fn create_circuit(
     program: Program,
     enable_ssa_logging: bool,
     enable_brillig_logging: bool,
+    force_brillig_output: bool,
 ) -> Result<(Circuit, DebugInfo, Vec<Witness>, Vec<Witness>, Vec<SsaReport>), RuntimeError> {
     let func_sig = program.main_function_signature.clone();
-    let mut generated_acir =
-        optimize_into_acir(program, enable_ssa_logging, enable_brillig_logging)?;
+    let mut generated_acir = optimize_into_acir(
+        program,
+        enable_ssa_logging,
+        enable_brillig_logging,
+        force_brillig_output,
+    )?;
     let opcodes = generated_acir.take_opcodes();
     let GeneratedAcir {
         current_witness_index,
@@ -99,6 +105,8 @@ pub fn create_circuit(
         locations,
         input_witnesses,
         assert_messages,
+        debug_variables,
+        debug_variable_assignments,
         warnings,
         ..
     } = generated_acir;
@@ -124,7 +132,11 @@ pub fn create_circuit(
         .map(|(index, locations)| (index, locations.into_iter().collect()))
         .collect();
 
-    let mut debug_info = DebugInfo::new(locations);
+    let mut debug_info = DebugInfo {
+        locations,
+        variables: debug_variables,
+        variable_assignments: debug_variable_assignments,
+    };
 
     // Perform any ACIR-level optimizations
     let (optimized_circuit, transformation_map) = acvm::compiler::optimize(circuit);
@@ -174,8 +186,12 @@ struct SsaBuilder {
 }
 
 impl SsaBuilder {
-    fn new(program: Program, print_ssa_passes: bool) -> Result<SsaBuilder, RuntimeError> {
-        let ssa = ssa_gen::generate_ssa(program)?;
+    fn new(
+        program: Program,
+        print_ssa_passes: bool,
+        force_brillig_runtime: bool,
+    ) -> Result<SsaBuilder, RuntimeError> {
+        let ssa = ssa_gen::generate_ssa(program, force_brillig_runtime)?;
         Ok(SsaBuilder { print_ssa_passes, ssa }.print("Initial SSA:"))
     }
 
@@ -210,3 +226,130 @@ impl SsaBuilder {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use acvm::{acir::circuit::OpcodeLocation, FieldElement};
+    use noirc_errors::{debug_info::DebugVariableValue, Location};
+    use noirc_frontend::{
+        monomorphization::ast::{
+            Binary, DebugVariable, Definition, Expression, FuncId, Function, Ident, Let, Literal,
+            LocalId, Program, Type,
+        },
+        BinaryOpKind, Distinctness, Type as HirType, Visibility,
+    };
+
+    use super::{optimize_into_acir, ssa_gen::generate_ssa};
+    use crate::ssa::ir::{instruction::Instruction, instruction::Intrinsic, value::Value};
+
+    /// Builds the program `fn main(x: Field) { let y = x + 1; }`, tracking the values of
+    /// `x` and `y` for the debugger if `instrument_debug` is set.
+    fn program(instrument_debug: bool) -> Program {
+        let x = Expression::Ident(Ident {
+            location: None,
+            definition: Definition::Local(LocalId(0)),
+            mutable: false,
+            name: "x".to_string(),
+            typ: Type::Field,
+        });
+        let one = Expression::Literal(Literal::Integer(
+            FieldElement::one(),
+            Type::Field,
+            Location::dummy(),
+        ));
+        let let_y = Expression::Let(Let {
+            id: LocalId(1),
+            mutable: false,
+            name: "y".to_string(),
+            expression: Box::new(Expression::Binary(Binary {
+                lhs: Box::new(x),
+                operator: BinaryOpKind::Add,
+                rhs: Box::new(one),
+                location: Location::dummy(),
+            })),
+        });
+        let main = Function {
+            id: FuncId(0),
+            name: "main".to_string(),
+            parameters: vec![(LocalId(0), false, "x".to_string(), Type::Field)],
+            body: Expression::Block(vec![let_y]),
+            return_type: Type::Unit,
+            unconstrained: false,
+        };
+
+        let debug_variable = |name: &str| DebugVariable {
+            name: name.to_string(),
+            typ: HirType::FieldElement.into(),
+        };
+        let debug_variables = if instrument_debug {
+            BTreeMap::from([(LocalId(0), debug_variable("x")), (LocalId(1), debug_variable("y"))])
+        } else {
+            BTreeMap::new()
+        };
+        Program::new(
+            vec![main],
+            (Vec::new(), None),
+            Distinctness::DuplicationAllowed,
+            None,
+            Visibility::Private,
+            debug_variables,
+        )
+    }
+
+    /// Returns the variable ids passed to each call to [`Intrinsic::DebugVar`] in `main`.
+    fn debug_var_calls(program: Program) -> Vec<u128> {
+        let ssa = generate_ssa(program, false).unwrap();
+        let main = ssa.main();
+        main.reachable_blocks()
+            .into_iter()
+            .flat_map(|block| main.dfg[block].instructions())
+            .filter_map(|instruction| match &main.dfg[*instruction] {
+                Instruction::Call { func, arguments }
+                    if main.dfg[*func] == Value::Intrinsic(Intrinsic::DebugVar) =>
+                {
+                    main.dfg.get_numeric_constant(arguments[0]).map(|id| id.to_u128())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn emits_debug_var_calls_for_tracked_variables() {
+        assert_eq!(debug_var_calls(program(true)), vec![0, 1]);
+        assert!(debug_var_calls(program(false)).is_empty());
+    }
+
+    #[test]
+    fn instrumented_acir_records_variable_assignments() {
+        let mut generated_acir = optimize_into_acir(program(true), false, false, false).unwrap();
+
+        let variable_names: Vec<_> = generated_acir
+            .debug_variables
+            .values()
+            .map(|variable| variable.name.as_str())
+            .collect();
+        assert_eq!(variable_names, vec!["x", "y"]);
+
+        // `y` isn't otherwise used, so it is only assigned a witness to be displayed
+        assert_eq!(generated_acir.take_opcodes().len(), 1);
+        let assignments: Vec<_> =
+            generated_acir.debug_variable_assignments.values().flatten().collect();
+        assert_eq!(assignments.len(), 2);
+        assert!(assignments
+            .iter()
+            .all(|(_, value)| matches!(value, DebugVariableValue::Witnesses(witnesses) if witnesses.len() == 1)));
+        assert!(generated_acir.debug_variable_assignments.contains_key(&OpcodeLocation::Acir(1)));
+    }
+
+    #[test]
+    fn uninstrumented_acir_is_unchanged() {
+        let mut generated_acir = optimize_into_acir(program(false), false, false, false).unwrap();
+
+        assert!(generated_acir.take_opcodes().is_empty());
+        assert!(generated_acir.debug_variables.is_empty());
+        assert!(generated_acir.debug_variable_assignments.is_empty());
+    }
+}
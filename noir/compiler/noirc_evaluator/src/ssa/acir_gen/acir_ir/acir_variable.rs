@@ -9,7 +9,7 @@ use crate::ssa::ir::{instruction::Endian, types::NumericType};
 use acvm::acir::circuit::brillig::{BrilligInputs, BrilligOutputs};
 use acvm::acir::circuit::opcodes::{BlockId, MemOp};
 use acvm::acir::circuit::Opcode;
-use acvm::brillig_vm::{brillig::Value, Registers, VMStatus, VM};
+use acvm::brillig_vm::{brillig::Value, ExecutionLimits, Registers, VMStatus, VM};
 use acvm::{
     acir::{
         brillig::Opcode as BrilligOpcode,
@@ -22,9 +22,13 @@ use acvm::{
 use acvm::{BlackBoxFunctionSolver, BlackBoxResolutionError};
 use fxhash::FxHashMap as HashMap;
 use iter_extended::{try_vecmap, vecmap};
+use noirc_errors::debug_info::DebugVarId;
 use num_bigint::BigUint;
 use std::{borrow::Cow, hash::Hash};
 
+/// The maximum number of opcodes executed when evaluating a Brillig call with constant inputs at compile time.
+const MAX_CONSTANT_BRILLIG_STEPS: usize = 1_000_000;
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 /// High level Type descriptor for Variables.
 ///
@@ -1284,6 +1288,18 @@ impl AcirContext {
         }
     }
 
+    /// Records that the given variable holds a new value, made of `values`,
+    /// once execution reaches the next opcode.
+    pub(crate) fn push_debug_variable(
+        &mut self,
+        id: DebugVarId,
+        values: Vec<AcirVar>,
+    ) -> Result<(), InternalError> {
+        let witnesses = try_vecmap(values, |value| self.var_to_witness(value))?;
+        self.acir_ir.push_debug_variable(id, witnesses);
+        Ok(())
+    }
+
     /// Terminates the context and takes the resulting `GeneratedAcir`
     pub(crate) fn finish(
         mut self,
@@ -1798,7 +1814,14 @@ fn execute_brillig(
 
     // Instantiate a Brillig VM given the solved input registers and memory, along with the Brillig bytecode.
     let input_registers = Registers::load(input_register_values);
-    let mut vm = VM::new(input_registers, input_memory, code, Vec::new(), &NullBbSolver);
+    // Calls which don't finish within the step limit are left to be executed at runtime, as
+    // they may never finish.
+    let limits = ExecutionLimits {
+        max_steps: Some(MAX_CONSTANT_BRILLIG_STEPS),
+        ..ExecutionLimits::default()
+    };
+    let mut vm =
+        VM::new(input_registers, input_memory, code, Vec::new(), &NullBbSolver).with_limits(limits);
 
     // Run the Brillig VM on these inputs, bytecode, etc!
     let vm_status = vm.process_opcodes();
@@ -1813,6 +1836,7 @@ fn execute_brillig(
             // TODO: Return an error stating that the brillig function failed.
             None
         }
+        VMStatus::LimitExceeded { .. } => None,
         VMStatus::ForeignCallWait { .. } => {
             // If execution can't complete then keep the opcode
 
@@ -22,6 +22,7 @@ use acvm::{
     FieldElement,
 };
 use iter_extended::vecmap;
+use noirc_errors::debug_info::{DebugVarId, DebugVariable, DebugVariableValue};
 use num_bigint::BigUint;
 
 #[derive(Debug, Default)]
@@ -54,6 +55,13 @@ pub(crate) struct GeneratedAcir {
     /// Correspondence between an opcode index and the error message associated with it.
     pub(crate) assert_messages: BTreeMap<OpcodeLocation, String>,
 
+    /// The variables whose values can be displayed by the debugger, keyed by their identifier.
+    pub(crate) debug_variables: BTreeMap<DebugVarId, DebugVariable>,
+
+    /// Correspondence between an opcode index and the variables which hold a new value once it is reached.
+    pub(crate) debug_variable_assignments:
+        BTreeMap<OpcodeLocation, Vec<(DebugVarId, DebugVariableValue)>>,
+
     pub(crate) warnings: Vec<SsaReport>,
 }
 
@@ -71,6 +79,15 @@ impl GeneratedAcir {
         }
     }
 
+    /// Records that the given variable holds a new value, held in `witnesses`,
+    /// once execution reaches the next opcode.
+    pub(crate) fn push_debug_variable(&mut self, id: DebugVarId, witnesses: Vec<Witness>) {
+        self.debug_variable_assignments
+            .entry(OpcodeLocation::Acir(self.opcodes.len()))
+            .or_default()
+            .push((id, DebugVariableValue::Witnesses(witnesses)));
+    }
+
     pub(crate) fn take_opcodes(&mut self) -> Vec<AcirOpcode> {
         std::mem::take(&mut self.opcodes)
     }
@@ -519,6 +536,16 @@ impl GeneratedAcir {
                 message,
             );
         }
+        for (brillig_index, variables) in generated_brillig.debug_variables {
+            let assignments = variables
+                .into_iter()
+                .map(|(id, value)| (id, DebugVariableValue::Brillig(value)))
+                .collect();
+            self.debug_variable_assignments.insert(
+                OpcodeLocation::Brillig { acir_index: self.opcodes.len() - 1, brillig_index },
+                assignments,
+            );
+        }
     }
 
     /// Generate gates and control bits witnesses which ensure that out_expr is a permutation of in_expr
@@ -35,6 +35,7 @@ use acvm::{
 use fxhash::FxHashMap as HashMap;
 use im::Vector;
 use iter_extended::{try_vecmap, vecmap};
+use noirc_errors::debug_info::DebugVarId;
 use noirc_frontend::Distinctness;
 
 /// Context struct for the acir generation pass.
@@ -158,13 +159,15 @@ impl AcirValue {
 impl Ssa {
     #[tracing::instrument(level = "trace", skip_all)]
     pub(crate) fn into_acir(
-        self,
+        mut self,
         brillig: Brillig,
         abi_distinctness: Distinctness,
         last_array_uses: &HashMap<ValueId, InstructionId>,
     ) -> Result<GeneratedAcir, RuntimeError> {
+        let debug_variables = std::mem::take(&mut self.debug_variables);
         let context = Context::new();
         let mut generated_acir = context.convert_ssa(self, brillig, last_array_uses)?;
+        generated_acir.debug_variables = debug_variables;
 
         match abi_distinctness {
             Distinctness::Distinct => {
@@ -509,6 +512,9 @@ impl Context {
                             }
                         }
                     }
+                    Value::Intrinsic(Intrinsic::DebugVar) => {
+                        self.convert_debug_var(arguments, dfg)?;
+                    }
                     Value::Intrinsic(intrinsic) => {
                         if matches!(
                             intrinsic,
@@ -2328,6 +2334,28 @@ impl Context {
         }
     }
 
+    /// Records the witnesses holding the value of the variable given to a call to [`Intrinsic::DebugVar`].
+    fn convert_debug_var(
+        &mut self,
+        arguments: &[ValueId],
+        dfg: &DataFlowGraph,
+    ) -> Result<(), RuntimeError> {
+        let (id, values) = arguments.split_first().expect("ICE: debug_var expects a variable id");
+        let id = dfg
+            .get_numeric_constant(*id)
+            .and_then(|id| id.try_to_u64())
+            .expect("ICE: debug_var expects a constant variable id");
+
+        let mut flattened_values = Vector::new();
+        for value in values {
+            let value = self.convert_value(*value, dfg);
+            self.slice_intrinsic_input(&mut flattened_values, value)?;
+        }
+        let vars = try_vecmap(flattened_values, |value| value.into_var())?;
+        self.acir_context.push_debug_variable(id as DebugVarId, vars)?;
+        Ok(())
+    }
+
     fn slice_intrinsic_input(
         &mut self,
         old_slice: &mut Vector<AcirValue>,
@@ -2,7 +2,7 @@ use std::collections::BTreeSet;
 
 use super::{
     basic_block::{BasicBlock, BasicBlockId},
-    function::Function,
+    function::{Function, RuntimeType},
 };
 use fxhash::FxHashMap as HashMap;
 
@@ -24,6 +24,10 @@ struct CfgNode {
 /// basic blocks.
 pub(crate) struct ControlFlowGraph {
     data: HashMap<BasicBlockId, CfgNode>,
+
+    /// Whether a block may have more than two predecessors. This is only the case in unconstrained
+    /// functions, where a loop may be jumped to by each `break` or `continue` within it.
+    allow_many_predecessors: bool,
 }
 
 impl ControlFlowGraph {
@@ -37,7 +41,8 @@ impl ControlFlowGraph {
         let mut data = HashMap::default();
         data.insert(entry_block, empty_node);
 
-        let mut cfg = ControlFlowGraph { data };
+        let allow_many_predecessors = func.runtime() == RuntimeType::Brillig;
+        let mut cfg = ControlFlowGraph { data, allow_many_predecessors };
         cfg.compute(func);
         cfg
     }
@@ -96,7 +101,7 @@ impl ControlFlowGraph {
         predecessor_node.successors.insert(to);
         let successor_node = self.data.entry(to).or_default();
         assert!(
-            successor_node.predecessors.len() < 2,
+            self.allow_many_predecessors || successor_node.predecessors.len() < 2,
             "ICE: A cfg node cannot have more than two predecessors"
         );
         successor_node.predecessors.insert(from);
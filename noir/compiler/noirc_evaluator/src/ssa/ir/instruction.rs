@@ -46,6 +46,9 @@ pub(crate) enum Intrinsic {
     BlackBox(BlackBoxFunc),
     FromField,
     AsField,
+    /// Records the value of a Noir variable for the debugger.
+    /// The first argument identifies the variable and the remaining ones hold its flattened value.
+    DebugVar,
 }
 
 impl std::fmt::Display for Intrinsic {
@@ -68,6 +71,7 @@ impl std::fmt::Display for Intrinsic {
             Intrinsic::BlackBox(function) => write!(f, "{function}"),
             Intrinsic::FromField => write!(f, "from_field"),
             Intrinsic::AsField => write!(f, "as_field"),
+            Intrinsic::DebugVar => write!(f, "debug_var"),
         }
     }
 }
@@ -78,7 +82,7 @@ impl Intrinsic {
     /// If there are no side effects then the `Intrinsic` can be removed if the result is unused.
     pub(crate) fn has_side_effects(&self) -> bool {
         match self {
-            Intrinsic::AssertConstant => true,
+            Intrinsic::AssertConstant | Intrinsic::DebugVar => true,
 
             Intrinsic::Sort
             | Intrinsic::ArrayLen
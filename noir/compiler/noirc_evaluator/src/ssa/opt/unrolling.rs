@@ -10,9 +10,15 @@
 //!       blocks. If unsuccessfuly either error if the abort_on_error flag is set,
 //!       or otherwise remember that the loop failed to unroll and leave it unmodified.
 //!
+//! The condition of a loop is usually a comparison against an induction variable passed
+//! as a parameter to the loop header. The condition of a `while` loop however generally depends
+//! on values loaded from mutable variables, which mem2reg is unable to resolve within the loop
+//! header. If such a condition is not known, the loop is unrolled again while forwarding the
+//! values known to be stored to each variable to any later loads (see `KnownStores`).
+//!
 //! Note that this pass also often creates superfluous jmp instructions in the
 //! program that will need to be removed by a later simplify cfg pass.
-use std::collections::HashSet;
+use std::{collections::HashSet, rc::Rc};
 
 use crate::{
     errors::RuntimeError,
@@ -24,15 +30,20 @@ use crate::{
             dom::DominatorTree,
             function::{Function, RuntimeType},
             function_inserter::FunctionInserter,
-            instruction::TerminatorInstruction,
+            instruction::{Instruction, InstructionId, TerminatorInstruction},
             post_order::PostOrder,
-            value::ValueId,
+            types::Type,
+            value::{Value, ValueId},
         },
         ssa_gen::Ssa,
     },
 };
 use fxhash::FxHashMap as HashMap;
 
+/// The maximum number of iterations unrolled of a loop whose condition is only known by forwarding
+/// stores. Unlike a `for` loop over a known range, such a loop (usually a `while` loop) may never end.
+const MAX_FORWARDED_ITERATIONS: usize = 10_000;
+
 impl Ssa {
     /// Unroll all loops in each SSA function.
     /// If any loop cannot be unrolled, it is left as-is or in a partially unrolled state.
@@ -184,13 +195,32 @@ fn unroll_loop(
     cfg: &ControlFlowGraph,
     loop_: &Loop,
 ) -> Result<(), CallStack> {
-    let mut unroll_into = get_pre_header(cfg, loop_);
-    let mut jump_value = get_induction_variable(function, unroll_into)?;
+    let pre_header = get_pre_header(cfg, loop_);
+    let induction_value = get_induction_variable(function, pre_header)?;
+
+    let mut next_iteration = unroll_loop_header(function, loop_, pre_header, induction_value, None);
+
+    // If the loop condition is unknown we may still be able to resolve it by forwarding stores.
+    // This is safe to retry since the loop is left unmodified if its first header fails to unroll.
+    if next_iteration.is_err() {
+        let block_order = Rc::new(LoopBlockOrder::new(loop_, cfg));
+        let stores = KnownStores::at_end_of_block(function, pre_header);
+        let forwarding = StoreForwarding::new(block_order, stores);
+        next_iteration =
+            unroll_loop_header(function, loop_, pre_header, induction_value, Some(forwarding));
+    }
 
-    while let Some(context) = unroll_loop_header(function, loop_, unroll_into, jump_value)? {
-        let (last_block, last_value) = context.unroll_loop_iteration();
-        unroll_into = last_block;
-        jump_value = last_value;
+    let mut forwarded_iterations = 0;
+    while let Some(context) = next_iteration? {
+        if context.forwarding.is_some() {
+            forwarded_iterations += 1;
+            if forwarded_iterations > MAX_FORWARDED_ITERATIONS {
+                return Err(context.loop_condition_call_stack());
+            }
+        }
+
+        let (last_block, last_value, forwarding) = context.unroll_loop_iteration();
+        next_iteration = unroll_loop_header(function, loop_, last_block, last_value, forwarding);
     }
 
     Ok(())
@@ -240,13 +270,14 @@ fn unroll_loop_header<'a>(
     loop_: &'a Loop,
     unroll_into: BasicBlockId,
     induction_value: ValueId,
+    forwarding: Option<StoreForwarding>,
 ) -> Result<Option<LoopIteration<'a>>, CallStack> {
     // We insert into a fresh block first and move instructions into the unroll_into block later
     // only once we verify the jmpif instruction has a constant condition. If it does not, we can
     // just discard this fresh block and leave the loop unmodified.
     let fresh_block = function.dfg.make_block();
 
-    let mut context = LoopIteration::new(function, loop_, fresh_block, loop_.header);
+    let mut context = LoopIteration::new(function, loop_, fresh_block, loop_.header, forwarding);
     let source_block = &context.dfg()[context.source_block];
     assert_eq!(source_block.parameters().len(), 1, "Expected only 1 argument in loop header");
 
@@ -263,6 +294,7 @@ fn unroll_loop_header<'a>(
             // If there is only 1 next block the jmpif evaluated to a single known block.
             // This is the expected case and lets us know if we should loop again or not.
             if next_blocks.len() == 1 {
+                context.forward_stores_to(&next_blocks);
                 context.dfg_mut().inline_block(fresh_block, unroll_into);
 
                 // The fresh block is gone now so we're committing to insert into the original
@@ -302,6 +334,9 @@ struct LoopIteration<'f> {
     /// This is None until we visit the block which jumps back to the start of the
     /// loop, at which point we record its value and the block it was found in.
     induction_value: Option<(BasicBlockId, ValueId)>,
+
+    /// Only set if the loop condition could not be determined without forwarding stores.
+    forwarding: Option<StoreForwarding>,
 }
 
 impl<'f> LoopIteration<'f> {
@@ -310,6 +345,7 @@ impl<'f> LoopIteration<'f> {
         loop_: &'f Loop,
         insert_block: BasicBlockId,
         source_block: BasicBlockId,
+        forwarding: Option<StoreForwarding>,
     ) -> Self {
         Self {
             inserter: FunctionInserter::new(function),
@@ -320,6 +356,17 @@ impl<'f> LoopIteration<'f> {
             original_blocks: HashMap::default(),
             visited_blocks: HashSet::default(),
             induction_value: None,
+            forwarding,
+        }
+    }
+
+    /// Returns the call stack of the loop's condition, used to report a loop which can't be unrolled.
+    fn loop_condition_call_stack(&self) -> CallStack {
+        match self.dfg()[self.loop_.header].unwrap_terminator() {
+            TerminatorInstruction::JmpIf { condition, .. } => {
+                self.dfg().get_value_call_stack(*condition)
+            }
+            _ => CallStack::new(),
         }
     }
 
@@ -329,10 +376,13 @@ impl<'f> LoopIteration<'f> {
     /// It is expected the terminator instructions are set up to branch into an empty block
     /// for further unrolling. When the loop is finished this will need to be mutated to
     /// jump to the end of the loop instead.
-    fn unroll_loop_iteration(mut self) -> (BasicBlockId, ValueId) {
+    ///
+    /// If stores are being forwarded, this also returns the stores known at the start
+    /// of the next iteration.
+    fn unroll_loop_iteration(mut self) -> (BasicBlockId, ValueId, Option<StoreForwarding>) {
         let mut next_blocks = self.unroll_loop_block();
 
-        while let Some(block) = next_blocks.pop() {
+        while let Some(block) = self.next_block(&mut next_blocks) {
             self.insert_block = block;
             self.source_block = self.get_original_block(block);
 
@@ -342,22 +392,50 @@ impl<'f> LoopIteration<'f> {
             }
         }
 
-        self.induction_value
-            .expect("Expected to find the induction variable by end of loop iteration")
+        let (last_block, last_value) = self
+            .induction_value
+            .expect("Expected to find the induction variable by end of loop iteration");
+
+        let forwarding = self.forwarding.map(StoreForwarding::into_next_iteration);
+        (last_block, last_value, forwarding)
+    }
+
+    /// Pops the next block to unroll. When forwarding stores, blocks are unrolled in reverse
+    /// post order so that the stores of each of a block's predecessors are known beforehand.
+    fn next_block(&self, next_blocks: &mut Vec<BasicBlockId>) -> Option<BasicBlockId> {
+        match &self.forwarding {
+            Some(forwarding) => {
+                let order = |block: &BasicBlockId| {
+                    forwarding.block_order.index(self.get_original_block(*block))
+                };
+                let (index, _) =
+                    next_blocks.iter().enumerate().min_by_key(|(_, block)| order(block))?;
+                Some(next_blocks.swap_remove(index))
+            }
+            None => next_blocks.pop(),
+        }
     }
 
     /// Unroll a single block in the current iteration of the loop
     fn unroll_loop_block(&mut self) -> Vec<BasicBlockId> {
         let mut next_blocks = self.unroll_loop_block_helper();
+        self.forward_stores_to(&next_blocks);
+
+        // When forwarding stores, the next iteration's header is unrolled separately with
+        // the stores found along the back edge, so we don't need to unroll it here.
+        let skip_header = self.forwarding.is_some();
         next_blocks.retain(|block| {
             let b = self.get_original_block(*block);
-            self.loop_.blocks.contains(&b)
+            self.loop_.blocks.contains(&b) && !(skip_header && b == self.loop_.header)
         });
         next_blocks
     }
 
     /// Unroll a single block in the current iteration of the loop
     fn unroll_loop_block_helper(&mut self) -> Vec<BasicBlockId> {
+        if let Some(forwarding) = &mut self.forwarding {
+            forwarding.start_block(self.source_block, &mut self.inserter);
+        }
         self.inline_instructions_from_block();
         self.visited_blocks.insert(self.source_block);
 
@@ -439,7 +517,14 @@ impl<'f> LoopIteration<'f> {
         // instances of the induction variable or any values that were changed as a result
         // of the new induction variable value.
         for instruction in instructions {
-            self.inserter.push_instruction(instruction, self.insert_block);
+            match &mut self.forwarding {
+                Some(forwarding) => {
+                    forwarding.push_instruction(&mut self.inserter, instruction, self.insert_block);
+                }
+                None => {
+                    self.inserter.push_instruction(instruction, self.insert_block);
+                }
+            }
         }
 
         let mut terminator = self.dfg()[self.source_block]
@@ -451,6 +536,21 @@ impl<'f> LoopIteration<'f> {
         self.inserter.function.dfg.set_block_terminator(self.insert_block, terminator);
     }
 
+    /// Pass the stores known at the end of the current block on to each of its successors.
+    fn forward_stores_to(&mut self, successors: &[BasicBlockId]) {
+        if let Some(forwarding) = &mut self.forwarding {
+            for successor in successors {
+                let successor = self.original_blocks.get(successor).copied().unwrap_or(*successor);
+                if successor == self.loop_.header {
+                    KnownStores::merge_into(&mut forwarding.next_iteration, &forwarding.current);
+                } else if self.loop_.blocks.contains(&successor) {
+                    let pending = forwarding.pending.entry(successor).or_insert(None);
+                    KnownStores::merge_into(pending, &forwarding.current);
+                }
+            }
+        }
+    }
+
     fn dfg(&self) -> &DataFlowGraph {
         &self.inserter.function.dfg
     }
@@ -460,11 +560,256 @@ impl<'f> LoopIteration<'f> {
     }
 }
 
+/// The order in which the blocks of a loop are unrolled when forwarding stores,
+/// along with the blocks of each loop nested within it.
+struct LoopBlockOrder {
+    /// The reverse post order index of each block in the loop, ignoring the loop's back edge.
+    /// Unrolling blocks in this order ensures each block is unrolled after its predecessors,
+    /// other than any predecessors along the back edge of a nested loop.
+    indices: HashMap<BasicBlockId, usize>,
+
+    /// Maps the header of each nested loop to all the blocks in that loop
+    nested_loops: HashMap<BasicBlockId, HashSet<BasicBlockId>>,
+}
+
+impl LoopBlockOrder {
+    fn new(loop_: &Loop, cfg: &ControlFlowGraph) -> Self {
+        let successors = |block| {
+            cfg.successors(block)
+                .filter(|successor| *successor != loop_.header && loop_.blocks.contains(successor))
+        };
+
+        let mut post_order = Vec::with_capacity(loop_.blocks.len());
+        let mut visited = HashSet::new();
+        let mut stack = vec![(loop_.header, false)];
+
+        while let Some((block, finished)) = stack.pop() {
+            if finished {
+                post_order.push(block);
+            } else if visited.insert(block) {
+                stack.push((block, true));
+                stack.extend(successors(block).map(|successor| (successor, false)));
+            }
+        }
+
+        let indices: HashMap<_, _> =
+            post_order.into_iter().rev().enumerate().map(|(index, block)| (block, index)).collect();
+
+        // Any edge to a block which does not come later in the order is the back edge of a nested loop
+        let mut nested_loops: HashMap<_, HashSet<_>> = HashMap::default();
+        for (block, index) in &indices {
+            for successor in successors(*block) {
+                if indices[&successor] <= *index {
+                    let nested_loop = find_blocks_in_loop(successor, *block, cfg);
+                    nested_loops.entry(successor).or_default().extend(nested_loop.blocks);
+                }
+            }
+        }
+
+        Self { indices, nested_loops }
+    }
+
+    fn index(&self, block: BasicBlockId) -> usize {
+        self.indices.get(&block).copied().unwrap_or(usize::MAX)
+    }
+}
+
+/// The value most recently stored to each allocation along every path to some point in the program.
+///
+/// To keep this simple and conservative, only stores to the direct results of `allocate`
+/// instructions are tracked. Any store to another address, or call given a reference,
+/// may modify any allocation so all known stores are forgotten.
+#[derive(Clone, Default)]
+struct KnownStores(HashMap<ValueId, ValueId>);
+
+impl KnownStores {
+    /// Returns the stores known at the end of the given block, before any loop unrolling
+    /// forwarded stores within it.
+    fn at_end_of_block(function: &Function, target: BasicBlockId) -> Self {
+        let cfg = ControlFlowGraph::with_function(function);
+        let post_order = PostOrder::with_function(function);
+        let mut block_ends: HashMap<BasicBlockId, KnownStores> = HashMap::default();
+
+        for block in post_order.as_slice().iter().rev() {
+            // Blocks are visited in reverse post order, so a predecessor is only missing if
+            // it is along the back edge of a loop. In that case nothing is known.
+            let mut predecessors = cfg.predecessors(*block).map(|block| block_ends.get(&block));
+            let mut stores = match predecessors.next() {
+                Some(Some(first)) => {
+                    let mut stores = first.clone();
+                    for predecessor in predecessors {
+                        match predecessor {
+                            Some(predecessor) => stores.merge(predecessor),
+                            None => stores.0.clear(),
+                        }
+                    }
+                    stores
+                }
+                _ => KnownStores::default(),
+            };
+
+            for instruction in function.dfg[*block].instructions() {
+                stores.record(&function.dfg[*instruction], &function.dfg);
+            }
+
+            if *block == target {
+                return stores;
+            }
+            block_ends.insert(*block, stores);
+        }
+
+        KnownStores::default()
+    }
+
+    /// Update the known stores after the given instruction
+    fn record(&mut self, instruction: &Instruction, dfg: &DataFlowGraph) {
+        match instruction {
+            Instruction::Store { address, value } => {
+                let address = dfg.resolve(*address);
+                if is_allocation(dfg, address) {
+                    self.0.insert(address, dfg.resolve(*value));
+                } else {
+                    self.0.clear();
+                }
+            }
+            Instruction::Call { arguments, .. } if passes_reference(dfg, arguments) => {
+                self.0.clear();
+            }
+            _ => (),
+        }
+    }
+
+    /// Forget any stores which may be modified by the given instruction
+    fn forget(&mut self, instruction: &Instruction, dfg: &DataFlowGraph) {
+        match instruction {
+            Instruction::Store { address, .. } if is_allocation(dfg, *address) => {
+                self.0.remove(address);
+            }
+            Instruction::Store { .. } => self.0.clear(),
+            Instruction::Call { arguments, .. } if passes_reference(dfg, arguments) => {
+                self.0.clear();
+            }
+            _ => (),
+        }
+    }
+
+    fn get(&self, dfg: &DataFlowGraph, address: ValueId) -> Option<ValueId> {
+        self.0.get(&dfg.resolve(address)).copied()
+    }
+
+    /// Only keep the stores which are also known in `other`
+    fn merge(&mut self, other: &KnownStores) {
+        self.0.retain(|address, value| other.0.get(address) == Some(value));
+    }
+
+    /// Merge the stores along another edge into a block, where `target` is None
+    /// if no edges into the block have been seen yet.
+    fn merge_into(target: &mut Option<KnownStores>, stores: &KnownStores) {
+        match target {
+            Some(target) => target.merge(stores),
+            None => *target = Some(stores.clone()),
+        }
+    }
+}
+
+fn is_allocation(dfg: &DataFlowGraph, address: ValueId) -> bool {
+    match &dfg[address] {
+        Value::Instruction { instruction, .. } => {
+            matches!(dfg[*instruction], Instruction::Allocate)
+        }
+        _ => false,
+    }
+}
+
+fn passes_reference(dfg: &DataFlowGraph, arguments: &[ValueId]) -> bool {
+    arguments.iter().any(|argument| matches!(dfg.type_of_value(*argument), Type::Reference(_)))
+}
+
+/// The state needed to forward stores within a loop while unrolling it.
+struct StoreForwarding {
+    block_order: Rc<LoopBlockOrder>,
+
+    /// The stores known at the current point of the block being unrolled
+    current: KnownStores,
+
+    /// The stores known at the start of each block in the current iteration yet to be unrolled
+    pending: HashMap<BasicBlockId, Option<KnownStores>>,
+
+    /// The stores known along the back edge of the loop at the end of the current iteration
+    next_iteration: Option<KnownStores>,
+}
+
+impl StoreForwarding {
+    fn new(block_order: Rc<LoopBlockOrder>, current: KnownStores) -> Self {
+        Self { block_order, current, pending: HashMap::default(), next_iteration: None }
+    }
+
+    fn into_next_iteration(self) -> Self {
+        let current = self.next_iteration.unwrap_or_default();
+        Self::new(self.block_order, current)
+    }
+
+    /// Set the known stores to those at the start of the given block. If the block is the header of
+    /// a nested loop, any stores within that loop could also be reached along its back edge.
+    fn start_block(&mut self, block: BasicBlockId, inserter: &mut FunctionInserter) {
+        self.current = self.pending.remove(&block).flatten().unwrap_or_default();
+
+        if let Some(nested_loop) = self.block_order.nested_loops.get(&block) {
+            for nested_block in nested_loop {
+                for instruction in inserter.function.dfg[*nested_block].instructions().to_vec() {
+                    let instruction = Self::map_address(inserter, instruction);
+                    self.current.forget(&instruction, &inserter.function.dfg);
+                }
+            }
+        }
+    }
+
+    /// Returns the given instruction, resolving only the address if it is a store. This avoids
+    /// inserting new values for any arrays in the instruction since it is not itself inserted.
+    fn map_address(inserter: &mut FunctionInserter, instruction: InstructionId) -> Instruction {
+        match inserter.function.dfg[instruction].clone() {
+            Instruction::Store { address, value } => {
+                Instruction::Store { address: inserter.resolve(address), value }
+            }
+            other => other,
+        }
+    }
+
+    /// Push an instruction into the given block, replacing it with the known stored value
+    /// if it is a load.
+    fn push_instruction(
+        &mut self,
+        inserter: &mut FunctionInserter,
+        id: InstructionId,
+        block: BasicBlockId,
+    ) {
+        let (instruction, call_stack) = inserter.map_instruction(id);
+
+        if let Instruction::Load { address } = &instruction {
+            if let Some(value) = self.current.get(&inserter.function.dfg, *address) {
+                let result = inserter.function.dfg.instruction_results(id)[0];
+                inserter.map_value(result, value);
+                return;
+            }
+        }
+
+        self.current.record(&instruction, &inserter.function.dfg);
+        inserter.push_instruction_value(instruction, id, block, call_stack);
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::rc::Rc;
+
     use crate::ssa::{
         function_builder::FunctionBuilder,
-        ir::{function::RuntimeType, instruction::BinaryOp, map::Id, types::Type},
+        ir::{
+            function::RuntimeType,
+            instruction::{BinaryOp, TerminatorInstruction},
+            map::Id,
+            types::Type,
+        },
     };
 
     #[test]
@@ -636,4 +981,192 @@ mod tests {
         // Expected that we failed to unroll the loop
         assert!(ssa.unroll_loops().is_err());
     }
+
+    #[test]
+    fn unroll_loop_with_stored_condition() {
+        // fn main() {
+        //     let mut i = 0;
+        //     while i < 3 {
+        //         i += 1;
+        //     }
+        // }
+        //
+        // fn main f0 {
+        //   b0():
+        //     v0 = allocate
+        //     store Field 0 at v0
+        //     jmp b1(Field 0)
+        //   b1(v1: Field):
+        //     v2 = load v0
+        //     v3 = lt v2, Field 3
+        //     jmpif v3, then: b2, else: b3
+        //   b2():
+        //     v4 = load v0
+        //     v5 = add v4, Field 1
+        //     store v5 at v0
+        //     v6 = add v1, Field 1
+        //     jmp b1(v6)
+        //   b3():
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+
+        let zero = builder.field_constant(0u128);
+        let one = builder.field_constant(1u128);
+        let three = builder.field_constant(3u128);
+
+        let v0 = builder.insert_allocate(Type::field());
+        builder.insert_store(v0, zero);
+        builder.terminate_with_jmp(b1, vec![zero]);
+
+        builder.switch_to_block(b1);
+        let v1 = builder.add_block_parameter(b1, Type::field());
+        let v2 = builder.insert_load(v0, Type::field());
+        let v3 = builder.insert_binary(v2, BinaryOp::Lt, three);
+        builder.terminate_with_jmpif(v3, b2, b3);
+
+        builder.switch_to_block(b2);
+        let v4 = builder.insert_load(v0, Type::field());
+        let v5 = builder.insert_binary(v4, BinaryOp::Add, one);
+        builder.insert_store(v0, v5);
+        let v6 = builder.insert_binary(v1, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b1, vec![v6]);
+
+        builder.switch_to_block(b3);
+        builder.terminate_with_return(vec![]);
+
+        let ssa = builder.finish();
+        assert_eq!(ssa.main().reachable_blocks().len(), 4);
+
+        // The loop condition is only known after forwarding the value stored to v0 on each
+        // iteration, so each iteration should be unrolled with the loop removed entirely.
+        let ssa = ssa.unroll_loops().expect("The loop should be unrolled");
+        let main = ssa.main();
+        let has_loop = main.reachable_blocks().into_iter().any(|block| {
+            matches!(main.dfg[block].terminator(), Some(TerminatorInstruction::JmpIf { .. }))
+        });
+        assert!(!has_loop);
+    }
+
+    #[test]
+    fn fail_to_unroll_loop_which_never_ends() {
+        // fn main() {
+        //     let mut i = 0;
+        //     while i < 3 {}
+        // }
+        //
+        // fn main f0 {
+        //   b0():
+        //     v0 = allocate
+        //     store Field 0 at v0
+        //     jmp b1(Field 0)
+        //   b1(v1: Field):
+        //     v2 = load v0
+        //     v3 = lt v2, Field 3
+        //     jmpif v3, then: b2, else: b3
+        //   b2():
+        //     v4 = add v1, Field 1
+        //     jmp b1(v4)
+        //   b3():
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+
+        let zero = builder.field_constant(0u128);
+        let one = builder.field_constant(1u128);
+        let three = builder.field_constant(3u128);
+
+        let v0 = builder.insert_allocate(Type::field());
+        builder.insert_store(v0, zero);
+        builder.terminate_with_jmp(b1, vec![zero]);
+
+        builder.switch_to_block(b1);
+        let v1 = builder.add_block_parameter(b1, Type::field());
+        let v2 = builder.insert_load(v0, Type::field());
+        let v3 = builder.insert_binary(v2, BinaryOp::Lt, three);
+        builder.terminate_with_jmpif(v3, b2, b3);
+
+        builder.switch_to_block(b2);
+        let v4 = builder.insert_binary(v1, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b1, vec![v4]);
+
+        builder.switch_to_block(b3);
+        builder.terminate_with_return(vec![]);
+
+        // The loop condition is known on every iteration, but is never false so unrolling must
+        // give up instead of running forever.
+        let ssa = builder.finish();
+        assert!(ssa.unroll_loops().is_err());
+    }
+
+    // Stores through a reference which may alias any allocation cannot be forwarded
+    #[test]
+    fn fail_to_unroll_loop_with_aliased_store() {
+        // fn main f0 {
+        //   b0(v0: &mut Field):
+        //     v1 = allocate
+        //     store Field 0 at v1
+        //     jmp b1(Field 0)
+        //   b1(v2: Field):
+        //     v3 = load v1
+        //     v4 = lt v3, Field 3
+        //     jmpif v4, then: b2, else: b3
+        //   b2():
+        //     v5 = load v1
+        //     v6 = add v5, Field 1
+        //     store v6 at v1
+        //     store Field 5 at v0
+        //     v7 = add v2, Field 1
+        //     jmp b1(v7)
+        //   b3():
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+
+        let v0 = builder.add_parameter(Type::Reference(Rc::new(Type::field())));
+
+        let zero = builder.field_constant(0u128);
+        let one = builder.field_constant(1u128);
+        let three = builder.field_constant(3u128);
+        let five = builder.field_constant(5u128);
+
+        let v1 = builder.insert_allocate(Type::field());
+        builder.insert_store(v1, zero);
+        builder.terminate_with_jmp(b1, vec![zero]);
+
+        builder.switch_to_block(b1);
+        let v2 = builder.add_block_parameter(b1, Type::field());
+        let v3 = builder.insert_load(v1, Type::field());
+        let v4 = builder.insert_binary(v3, BinaryOp::Lt, three);
+        builder.terminate_with_jmpif(v4, b2, b3);
+
+        builder.switch_to_block(b2);
+        let v5 = builder.insert_load(v1, Type::field());
+        let v6 = builder.insert_binary(v5, BinaryOp::Add, one);
+        builder.insert_store(v1, v6);
+        builder.insert_store(v0, five);
+        let v7 = builder.insert_binary(v2, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b1, vec![v7]);
+
+        builder.switch_to_block(b3);
+        builder.terminate_with_return(vec![]);
+
+        let ssa = builder.finish();
+        assert!(ssa.unroll_loops().is_err());
+    }
 }
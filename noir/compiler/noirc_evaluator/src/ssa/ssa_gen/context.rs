@@ -1,8 +1,10 @@
+use std::collections::BTreeMap;
 use std::rc::Rc;
 use std::sync::{Mutex, RwLock};
 
 use acvm::FieldElement;
 use iter_extended::vecmap;
+use noirc_errors::debug_info::{DebugVarId, DebugVariable};
 use noirc_errors::Location;
 use noirc_frontend::monomorphization::ast::{self, LocalId, Parameters};
 use noirc_frontend::monomorphization::ast::{FuncId, Program};
@@ -10,11 +12,13 @@ use noirc_frontend::{BinaryOpKind, Signedness};
 
 use crate::errors::RuntimeError;
 use crate::ssa::function_builder::FunctionBuilder;
+use crate::ssa::ir::basic_block::BasicBlockId;
 use crate::ssa::ir::dfg::DataFlowGraph;
 use crate::ssa::ir::function::FunctionId as IrFunctionId;
 use crate::ssa::ir::function::{Function, RuntimeType};
 use crate::ssa::ir::instruction::BinaryOp;
 use crate::ssa::ir::instruction::Instruction;
+use crate::ssa::ir::instruction::Intrinsic;
 use crate::ssa::ir::map::AtomicCounter;
 use crate::ssa::ir::types::{NumericType, Type};
 use crate::ssa::ir::value::ValueId;
@@ -38,6 +42,23 @@ pub(super) struct FunctionContext<'a> {
 
     pub(super) builder: FunctionBuilder,
     shared_context: &'a SharedContext,
+
+    /// The loops enclosing the expression currently being compiled, innermost last.
+    /// This is used to find the target block of any `break` or `continue`.
+    loops: Vec<Loop>,
+}
+
+/// The blocks of a loop which a `break` or `continue` may jump to.
+#[derive(Copy, Clone)]
+pub(super) struct Loop {
+    /// The block which checks whether to start another iteration of the loop
+    pub(super) loop_entry: BasicBlockId,
+
+    /// The parameter of `loop_entry`, if any. This is incremented on each iteration.
+    pub(super) loop_index: Option<ValueId>,
+
+    /// The block after the loop
+    pub(super) loop_end: BasicBlockId,
 }
 
 /// Shared context for all functions during ssa codegen. This is the only
@@ -69,6 +90,9 @@ pub(super) struct SharedContext {
 
     /// The entire monomorphized source program
     pub(super) program: Program,
+
+    /// The variables recorded by calls to [`Intrinsic::DebugVar`] so far, keyed by their identifier.
+    debug_variables: Mutex<BTreeMap<DebugVarId, DebugVariable>>,
 }
 
 /// The queue of functions remaining to compile
@@ -96,7 +120,8 @@ impl<'a> FunctionContext<'a> {
             .1;
 
         let builder = FunctionBuilder::new(function_name, function_id, runtime);
-        let mut this = Self { definitions: HashMap::default(), builder, shared_context };
+        let mut this =
+            Self { definitions: HashMap::default(), builder, shared_context, loops: Vec::new() };
         this.add_parameters_to_scope(parameters);
         this
     }
@@ -106,9 +131,15 @@ impl<'a> FunctionContext<'a> {
     ///
     /// Note that the previous function cannot be resumed after calling this. Developers should
     /// avoid calling new_function until the previous function is completely finished with ssa-gen.
-    pub(super) fn new_function(&mut self, id: IrFunctionId, func: &ast::Function) {
+    pub(super) fn new_function(
+        &mut self,
+        id: IrFunctionId,
+        func: &ast::Function,
+        force_brillig_runtime: bool,
+    ) {
         self.definitions.clear();
-        if func.unconstrained {
+        self.loops.clear();
+        if func.unconstrained || force_brillig_runtime {
             self.builder.new_brillig_function(func.name.clone(), id);
         } else {
             self.builder.new_function(func.name.clone(), id);
@@ -147,6 +178,7 @@ impl<'a> FunctionContext<'a> {
         });
 
         self.definitions.insert(parameter_id, parameter_value);
+        self.emit_debug_var(parameter_id);
     }
 
     /// Allocate a single slot of memory and store into it the given initial value of the variable.
@@ -707,6 +739,64 @@ impl<'a> FunctionContext<'a> {
         assert!(existing.is_none(), "Variable {id:?} was defined twice in ssa-gen pass");
     }
 
+    /// Records the current value of the given local variable with a call to [`Intrinsic::DebugVar`]
+    /// if the program is being compiled for the debugger and the variable can be displayed.
+    pub(super) fn emit_debug_var(&mut self, id: LocalId) {
+        let Some(variable) = self.shared_context.program.debug_variables.get(&id) else {
+            return;
+        };
+
+        let function = self.builder.current_function.name().to_owned();
+        self.shared_context
+            .debug_variables
+            .lock()
+            .expect("Failed to lock debug_variables")
+            .entry(id.0)
+            .or_insert_with(|| DebugVariable {
+                function,
+                name: variable.name.clone(),
+                typ: variable.typ.clone(),
+            });
+
+        let mut arguments = vec![self.builder.field_constant(id.0 as u128)];
+        arguments.extend(self.lookup(id).into_value_list(self));
+        let debug_var = self.builder.import_intrinsic_id(Intrinsic::DebugVar);
+        self.builder.insert_call(debug_var, arguments, Vec::new());
+    }
+
+    /// Returns the local variable an assignment to the given lvalue modifies, if any.
+    pub(super) fn lvalue_local_id(lvalue: &ast::LValue) -> Option<LocalId> {
+        match lvalue {
+            ast::LValue::Ident(ident) => match ident.definition {
+                ast::Definition::Local(id) => Some(id),
+                _ => None,
+            },
+            ast::LValue::Index { array: lvalue, .. }
+            | ast::LValue::MemberAccess { object: lvalue, .. }
+            | ast::LValue::Dereference { reference: lvalue, .. } => Self::lvalue_local_id(lvalue),
+        }
+    }
+
+    /// Enter a loop, making it the target of any `break` or `continue` until `exit_loop` is called.
+    pub(super) fn enter_loop(
+        &mut self,
+        loop_entry: BasicBlockId,
+        loop_index: Option<ValueId>,
+        loop_end: BasicBlockId,
+    ) {
+        self.loops.push(Loop { loop_entry, loop_index, loop_end });
+    }
+
+    pub(super) fn exit_loop(&mut self) {
+        self.loops.pop();
+    }
+
+    /// Returns the innermost loop currently being compiled.
+    /// Panics if we are not currently within a loop.
+    pub(super) fn current_loop(&self) -> Loop {
+        *self.loops.last().expect("Expected to be in a loop")
+    }
+
     /// Looks up the value of a given local variable. Expects the variable to have
     /// been previously defined or panics otherwise.
     pub(super) fn lookup(&self, id: LocalId) -> Values {
@@ -1102,9 +1192,15 @@ impl SharedContext {
             function_queue: Default::default(),
             function_counter: Default::default(),
             program,
+            debug_variables: Default::default(),
         }
     }
 
+    /// Takes the variables recorded by calls to [`Intrinsic::DebugVar`], leaving none behind.
+    pub(super) fn take_debug_variables(&self) -> BTreeMap<DebugVarId, DebugVariable> {
+        std::mem::take(&mut *self.debug_variables.lock().expect("Failed to lock debug_variables"))
+    }
+
     /// Pops the next function from the shared function queue, returning None if the queue is empty.
     pub(super) fn pop_next_function_in_queue(&self) -> Option<(ast::FuncId, IrFunctionId)> {
         self.function_queue.lock().expect("Failed to lock function_queue").pop()
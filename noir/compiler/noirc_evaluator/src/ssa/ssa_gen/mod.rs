@@ -28,6 +28,7 @@ use self::{
 use super::{
     function_builder::data_bus::DataBus,
     ir::{
+        basic_block::BasicBlockId,
         function::RuntimeType,
         instruction::{BinaryOp, TerminatorInstruction},
         types::Type,
@@ -38,7 +39,12 @@ use super::{
 /// Generates SSA for the given monomorphized program.
 ///
 /// This function will generate the SSA but does not perform any optimizations on it.
-pub(crate) fn generate_ssa(program: Program) -> Result<Ssa, RuntimeError> {
+///
+/// If `force_brillig_runtime` is set, all functions are generated as unconstrained Brillig functions.
+pub(crate) fn generate_ssa(
+    program: Program,
+    force_brillig_runtime: bool,
+) -> Result<Ssa, RuntimeError> {
     // see which parameter has call_data/return_data attribute
     let is_databus = DataBusBuilder::is_databus(&program.main_function_signature);
 
@@ -56,7 +62,11 @@ pub(crate) fn generate_ssa(program: Program) -> Result<Ssa, RuntimeError> {
     let mut function_context = FunctionContext::new(
         main.name.clone(),
         &main.parameters,
-        if main.unconstrained { RuntimeType::Brillig } else { RuntimeType::Acir },
+        if force_brillig_runtime || main.unconstrained {
+            RuntimeType::Brillig
+        } else {
+            RuntimeType::Acir
+        },
         &context,
     );
 
@@ -103,14 +113,16 @@ pub(crate) fn generate_ssa(program: Program) -> Result<Ssa, RuntimeError> {
     // to generate SSA for each function used within the program.
     while let Some((src_function_id, dest_id)) = context.pop_next_function_in_queue() {
         let function = &context.program[src_function_id];
-        function_context.new_function(dest_id, function);
+        function_context.new_function(dest_id, function, force_brillig_runtime);
         function_context.codegen_function_body(&function.body)?;
     }
     // we save the data bus inside the dfg
     function_context.builder.current_function.dfg.data_bus =
         DataBus::get_data_bus(call_data, return_data);
 
-    Ok(function_context.builder.finish())
+    let mut ssa = function_context.builder.finish();
+    ssa.debug_variables = context.take_debug_variables();
+    Ok(ssa)
 }
 
 impl<'a> FunctionContext<'a> {
@@ -133,6 +145,7 @@ impl<'a> FunctionContext<'a> {
             Expression::Index(index) => self.codegen_index(index),
             Expression::Cast(cast) => self.codegen_cast(cast),
             Expression::For(for_expr) => self.codegen_for(for_expr),
+            Expression::While(while_expr) => self.codegen_while(while_expr),
             Expression::If(if_expr) => self.codegen_if(if_expr),
             Expression::Tuple(tuple) => self.codegen_tuple(tuple),
             Expression::ExtractTupleField(tuple, index) => {
@@ -145,6 +158,8 @@ impl<'a> FunctionContext<'a> {
             }
             Expression::Assign(assign) => self.codegen_assign(assign),
             Expression::Semi(semi) => self.codegen_semi(semi),
+            Expression::Break => Ok(self.codegen_break()),
+            Expression::Continue => Ok(self.codegen_continue()),
         }
     }
 
@@ -488,7 +503,10 @@ impl<'a> FunctionContext<'a> {
         // Compile the loop body
         self.builder.switch_to_block(loop_body);
         self.define(for_expr.index_variable, loop_index.into());
+        self.emit_debug_var(for_expr.index_variable);
+        self.enter_loop(loop_entry, Some(loop_index), loop_end);
         self.codegen_expression(&for_expr.block)?;
+        self.exit_loop();
         let new_loop_index = self.make_offset(loop_index, 1);
         self.builder.terminate_with_jmp(loop_entry, vec![new_loop_index]);
 
@@ -497,6 +515,96 @@ impl<'a> FunctionContext<'a> {
         Ok(Self::unit_value())
     }
 
+    /// Codegens a while loop, creating three new blocks in the process.
+    /// The return value of a while loop is always a unit literal.
+    ///
+    /// For example, the loop `while cond { body }` is codegen'd as:
+    ///
+    ///   br loop_entry(0)
+    /// loop_entry(i: Field):
+    ///   v0 = ... codegen cond ...
+    ///   brif v0, then: loop_body, else: loop_end
+    /// loop_body():
+    ///   v1 = ... codegen body ...
+    ///   v2 = add 1, i
+    ///   br loop_entry(v2)
+    /// loop_end():
+    ///   ... This is the current insert point after codegen_while finishes ...
+    ///
+    /// The `i` parameter only counts the iterations of the loop. It is needed in constrained code
+    /// since the loop unrolling pass expects each loop header to have an induction variable.
+    /// Unconstrained loops are never unrolled so `i` is omitted from them.
+    fn codegen_while(&mut self, while_expr: &ast::While) -> Result<Values, RuntimeError> {
+        let loop_entry = self.builder.insert_block();
+        let loop_body = self.builder.insert_block();
+        let loop_end = self.builder.insert_block();
+
+        let loop_index = match self.builder.current_function.runtime() {
+            RuntimeType::Acir => Some(self.builder.add_block_parameter(loop_entry, Type::field())),
+            RuntimeType::Brillig => None,
+        };
+
+        let start_index = loop_index.map(|_| self.builder.field_constant(0u128));
+        self.builder.terminate_with_jmp(loop_entry, start_index.into_iter().collect());
+
+        // Compile the loop entry block. The location of the condition is used to issue an error
+        // if the number of iterations of a constrained loop cannot be determined at compile-time.
+        self.builder.switch_to_block(loop_entry);
+        self.builder.set_location(while_expr.condition_location);
+        let jump_condition = self.codegen_non_tuple_expression(&while_expr.condition)?;
+        self.builder.terminate_with_jmpif(jump_condition, loop_body, loop_end);
+
+        // Compile the loop body
+        self.builder.switch_to_block(loop_body);
+        self.enter_loop(loop_entry, loop_index, loop_end);
+        self.codegen_expression(&while_expr.body)?;
+        self.exit_loop();
+        self.codegen_jump_to_loop_entry(loop_entry, loop_index);
+
+        // Finish by switching back to the end of the loop
+        self.builder.switch_to_block(loop_end);
+        Ok(Self::unit_value())
+    }
+
+    /// Codegens a `break`, jumping to the end of the innermost loop.
+    /// These are only codegen'd in unconstrained code, constrained code uses flags instead.
+    fn codegen_break(&mut self) -> Values {
+        let loop_end = self.current_loop().loop_end;
+        self.builder.terminate_with_jmp(loop_end, Vec::new());
+
+        self.switch_to_unreachable_block();
+        Self::unit_value()
+    }
+
+    /// Codegens a `continue`, jumping to the start of the next iteration of the innermost loop.
+    /// These are only codegen'd in unconstrained code, constrained code uses flags instead.
+    fn codegen_continue(&mut self) -> Values {
+        let loop_ = self.current_loop();
+        self.codegen_jump_to_loop_entry(loop_.loop_entry, loop_.loop_index);
+
+        self.switch_to_unreachable_block();
+        Self::unit_value()
+    }
+
+    fn codegen_jump_to_loop_entry(
+        &mut self,
+        loop_entry: BasicBlockId,
+        loop_index: Option<ValueId>,
+    ) {
+        let arguments = match loop_index {
+            Some(loop_index) => vec![self.make_offset(loop_index, 1)],
+            None => Vec::new(),
+        };
+        self.builder.terminate_with_jmp(loop_entry, arguments);
+    }
+
+    /// Any code following a `break` or `continue` is unreachable but still needs a block to be
+    /// codegen'd into. The new block has no predecessors so it will be removed later on.
+    fn switch_to_unreachable_block(&mut self) {
+        let unreachable_block = self.builder.insert_block();
+        self.builder.switch_to_block(unreachable_block);
+    }
+
     /// Codegens an if expression, handling the case of what to do if there is no 'else'.
     ///
     /// For example, the expression `if cond { a } else { b }` is codegen'd as:
@@ -644,6 +752,7 @@ impl<'a> FunctionContext<'a> {
         });
 
         self.define(let_expr.id, values);
+        self.emit_debug_var(let_expr.id);
         Ok(Self::unit_value())
     }
 
@@ -665,6 +774,9 @@ impl<'a> FunctionContext<'a> {
         let rhs = self.codegen_expression(&assign.expression)?;
 
         self.assign_new_value(lhs, rhs);
+        if let Some(id) = Self::lvalue_local_id(&assign.lvalue) {
+            self.emit_debug_var(id);
+        }
         Ok(Self::unit_value())
     }
 
@@ -1,6 +1,7 @@
 use std::{collections::BTreeMap, fmt::Display};
 
 use iter_extended::btree_map;
+use noirc_errors::debug_info::{DebugVarId, DebugVariable};
 
 use crate::ssa::ir::{
     function::{Function, FunctionId},
@@ -12,6 +13,9 @@ pub(crate) struct Ssa {
     pub(crate) functions: BTreeMap<FunctionId, Function>,
     pub(crate) main_id: FunctionId,
     pub(crate) next_id: AtomicCounter<Function>,
+    /// The variables whose values are recorded by calls to [`Intrinsic::DebugVar`][crate::ssa::ir::instruction::Intrinsic::DebugVar],
+    /// keyed by the identifier passed as the first argument of those calls.
+    pub(crate) debug_variables: BTreeMap<DebugVarId, DebugVariable>,
 }
 
 impl Ssa {
@@ -26,7 +30,12 @@ impl Ssa {
             (f.id(), f)
         });
 
-        Self { functions, main_id, next_id: AtomicCounter::starting_after(max_id) }
+        Self {
+            functions,
+            main_id,
+            next_id: AtomicCounter::starting_after(max_id),
+            debug_variables: BTreeMap::new(),
+        }
     }
 
     /// Returns the entry-point function of the program
@@ -0,0 +1,61 @@
+use std::fmt::Display;
+
+use crate::{token::SecondaryAttribute, Ident, UnresolvedGenerics, UnresolvedType};
+use iter_extended::vecmap;
+use noirc_errors::Span;
+
+/// Ast node for an enum
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NoirEnum {
+    pub name: Ident,
+    pub attributes: Vec<SecondaryAttribute>,
+    pub generics: UnresolvedGenerics,
+    pub variants: Vec<EnumVariant>,
+    pub span: Span,
+}
+
+/// A single variant of an enum, e.g. `Some(T)` or `None`.
+/// Variants without parentheses have no arguments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnumVariant {
+    pub name: Ident,
+    pub arguments: Vec<UnresolvedType>,
+}
+
+impl NoirEnum {
+    pub fn new(
+        name: Ident,
+        attributes: Vec<SecondaryAttribute>,
+        generics: Vec<Ident>,
+        variants: Vec<EnumVariant>,
+        span: Span,
+    ) -> NoirEnum {
+        NoirEnum { name, attributes, generics, variants, span }
+    }
+}
+
+impl Display for NoirEnum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let generics = vecmap(&self.generics, |generic| generic.to_string());
+        let generics = if generics.is_empty() { "".into() } else { generics.join(", ") };
+
+        writeln!(f, "enum {}{} {{", self.name, generics)?;
+
+        for variant in self.variants.iter() {
+            writeln!(f, "    {variant},")?;
+        }
+
+        write!(f, "}}")
+    }
+}
+
+impl Display for EnumVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.arguments.is_empty() {
+            write!(f, "{}", self.name)
+        } else {
+            let arguments = vecmap(&self.arguments, ToString::to_string);
+            write!(f, "{}({})", self.name, arguments.join(", "))
+        }
+    }
+}
@@ -23,6 +23,7 @@ pub enum ExpressionKind {
     Cast(Box<CastExpression>),
     Infix(Box<InfixExpression>),
     If(Box<IfExpression>),
+    Match(Box<MatchExpression>),
     Variable(Path),
     Tuple(Vec<Expression>),
     Lambda(Box<Lambda>),
@@ -42,6 +43,13 @@ impl ExpressionKind {
         }
     }
 
+    pub fn as_path(&self) -> Option<&Path> {
+        match self {
+            ExpressionKind::Variable(path) => Some(path),
+            _ => None,
+        }
+    }
+
     pub fn into_infix(self) -> Option<InfixExpression> {
         match self {
             ExpressionKind::Infix(infix) => Some(*infix),
@@ -176,10 +184,10 @@ impl Expression {
     }
 
     pub fn call(lhs: Expression, arguments: Vec<Expression>, span: Span) -> Expression {
-        // Need to check if lhs is an if expression since users can sequence if expressions
+        // Need to check if lhs is an if or match expression since users can sequence if expressions
         // with tuples without calling them. E.g. `if c { t } else { e }(a, b)` is interpreted
         // as a sequence of { if, tuple } rather than a function call. This behavior matches rust.
-        let kind = if matches!(&lhs.kind, ExpressionKind::If(..)) {
+        let kind = if matches!(&lhs.kind, ExpressionKind::If(..) | ExpressionKind::Match(..)) {
             ExpressionKind::Block(BlockExpression(vec![
                 Statement { kind: StatementKind::Expression(lhs), span },
                 Statement {
@@ -354,6 +362,33 @@ pub struct IfExpression {
     pub alternative: Option<Expression>,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MatchExpression {
+    pub expression: Expression,
+    pub arms: Vec<(MatchPattern, Expression)>,
+}
+
+/// The pattern of a single `match` arm.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum MatchPattern {
+    /// `_`, matching any value without binding it
+    Wildcard(Span),
+    /// `x`, matching any value and binding it to the given name
+    Binding(Ident),
+    /// `Enum::Variant(a, b)` or `Enum::Variant`, matching a single variant of an enum
+    /// and binding each of its arguments to the given (irrefutable) patterns
+    Variant(Path, Vec<Pattern>, Span),
+}
+
+impl MatchPattern {
+    pub fn span(&self) -> Span {
+        match self {
+            MatchPattern::Wildcard(span) | MatchPattern::Variant(_, _, span) => *span,
+            MatchPattern::Binding(ident) => ident.span(),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Lambda {
     pub parameters: Vec<(Pattern, UnresolvedType)>,
@@ -491,6 +526,7 @@ impl Display for ExpressionKind {
             Cast(cast) => cast.fmt(f),
             Infix(infix) => infix.fmt(f),
             If(if_expr) => if_expr.fmt(f),
+            Match(match_expr) => match_expr.fmt(f),
             Variable(path) => path.fmt(f),
             Constructor(constructor) => constructor.fmt(f),
             MemberAccess(access) => access.fmt(f),
@@ -645,6 +681,30 @@ impl Display for IfExpression {
     }
 }
 
+impl Display for MatchExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "match {} {{", self.expression)?;
+        for (pattern, branch) in &self.arms {
+            writeln!(f, "    {pattern} => {branch},")?;
+        }
+        write!(f, "}}")
+    }
+}
+
+impl Display for MatchPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchPattern::Wildcard(_) => write!(f, "_"),
+            MatchPattern::Binding(name) => name.fmt(f),
+            MatchPattern::Variant(path, arguments, _) if arguments.is_empty() => path.fmt(f),
+            MatchPattern::Variant(path, arguments, _) => {
+                let arguments = vecmap(arguments, ToString::to_string);
+                write!(f, "{}({})", path, arguments.join(", "))
+            }
+        }
+    }
+}
+
 impl Display for Lambda {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let parameters = vecmap(&self.parameters, |(name, r#type)| format!("{name}: {type}"));
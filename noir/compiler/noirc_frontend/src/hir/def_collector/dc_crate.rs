@@ -7,9 +7,9 @@ use crate::hir::resolution::errors::ResolverError;
 use crate::hir::resolution::import::{resolve_import, ImportDirective};
 use crate::hir::resolution::resolver::Resolver;
 use crate::hir::resolution::{
-    collect_impls, collect_trait_impls, path_resolver, resolve_free_functions, resolve_globals,
-    resolve_impls, resolve_structs, resolve_trait_by_path, resolve_trait_impls, resolve_traits,
-    resolve_type_aliases,
+    collect_impls, collect_trait_impls, path_resolver, resolve_enums, resolve_free_functions,
+    resolve_globals, resolve_impls, resolve_structs, resolve_trait_by_path, resolve_trait_impls,
+    resolve_traits, resolve_type_aliases,
 };
 use crate::hir::type_check::{type_check_func, TypeCheckError, TypeChecker};
 use crate::hir::Context;
@@ -19,7 +19,7 @@ use crate::node_interner::{FuncId, NodeInterner, StmtId, StructId, TraitId, Type
 
 use crate::parser::{ParserError, SortedModule};
 use crate::{
-    ExpressionKind, Ident, LetStatement, Literal, NoirFunction, NoirStruct, NoirTrait,
+    ExpressionKind, Ident, LetStatement, Literal, NoirEnum, NoirFunction, NoirStruct, NoirTrait,
     NoirTypeAlias, Path, PathKind, Type, UnresolvedGenerics, UnresolvedTraitConstraint,
     UnresolvedType,
 };
@@ -76,6 +76,12 @@ pub struct UnresolvedStruct {
     pub struct_def: NoirStruct,
 }
 
+pub struct UnresolvedEnum {
+    pub file_id: FileId,
+    pub module_id: LocalModuleId,
+    pub enum_def: NoirEnum,
+}
+
 #[derive(Clone)]
 pub struct UnresolvedTrait {
     pub file_id: FileId,
@@ -118,6 +124,7 @@ pub struct DefCollector {
     pub(crate) collected_imports: Vec<ImportDirective>,
     pub(crate) collected_functions: Vec<UnresolvedFunctions>,
     pub(crate) collected_types: BTreeMap<StructId, UnresolvedStruct>,
+    pub(crate) collected_enums: BTreeMap<StructId, UnresolvedEnum>,
     pub(crate) collected_type_aliases: BTreeMap<TypeAliasId, UnresolvedTypeAlias>,
     pub(crate) collected_traits: BTreeMap<TraitId, UnresolvedTrait>,
     pub(crate) collected_globals: Vec<UnresolvedGlobal>,
@@ -184,6 +191,7 @@ impl DefCollector {
             collected_imports: vec![],
             collected_functions: vec![],
             collected_types: BTreeMap::new(),
+            collected_enums: BTreeMap::new(),
             collected_type_aliases: BTreeMap::new(),
             collected_traits: BTreeMap::new(),
             collected_impls: HashMap::new(),
@@ -307,8 +315,9 @@ impl DefCollector {
         ));
 
         errors.extend(resolve_traits(context, def_collector.collected_traits, crate_id));
-        // Must resolve structs before we resolve globals.
+        // Must resolve structs and enums before we resolve globals.
         errors.extend(resolve_structs(context, def_collector.collected_types, crate_id));
+        errors.extend(resolve_enums(context, def_collector.collected_enums, crate_id));
 
         // We must wait to resolve non-integer globals until after we resolve structs since structs
         // globals will need to reference the struct type they're initialized to to ensure they are valid.
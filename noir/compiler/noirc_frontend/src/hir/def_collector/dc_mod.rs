@@ -6,11 +6,11 @@ use noirc_errors::Location;
 
 use crate::{
     graph::CrateId,
-    hir::def_collector::dc_crate::{UnresolvedStruct, UnresolvedTrait},
+    hir::def_collector::dc_crate::{UnresolvedEnum, UnresolvedStruct, UnresolvedTrait},
     node_interner::{FunctionModifiers, TraitId, TypeAliasId},
     parser::{SortedModule, SortedSubModule},
-    FunctionDefinition, Ident, LetStatement, NoirFunction, NoirStruct, NoirTrait, NoirTraitImpl,
-    NoirTypeAlias, TraitImplItem, TraitItem, TypeImpl,
+    FunctionDefinition, Ident, LetStatement, NoirEnum, NoirFunction, NoirStruct, NoirTrait,
+    NoirTraitImpl, NoirTypeAlias, TraitImplItem, TraitItem, TypeImpl,
 };
 
 use super::{
@@ -68,6 +68,8 @@ pub fn collect_defs(
 
     errors.extend(collector.collect_structs(context, ast.types, crate_id));
 
+    errors.extend(collector.collect_enums(context, ast.enums, crate_id));
+
     errors.extend(collector.collect_type_aliases(context, ast.type_aliases));
 
     errors.extend(collector.collect_functions(context, ast.functions, crate_id));
@@ -300,6 +302,67 @@ impl<'a> ModCollector<'a> {
         definition_errors
     }
 
+    /// Collect any enum definitions declared within the ast.
+    /// Returns a vector of errors if any enums or their variants were already defined.
+    fn collect_enums(
+        &mut self,
+        context: &mut Context,
+        enums: Vec<NoirEnum>,
+        krate: CrateId,
+    ) -> Vec<(CompilationError, FileId)> {
+        let mut definition_errors = vec![];
+        for enum_definition in enums {
+            let name = enum_definition.name.clone();
+
+            let mut variant_names: HashMap<&str, &Ident> = HashMap::new();
+            for variant in &enum_definition.variants {
+                let variant_name = &variant.name;
+                if let Some(first_def) =
+                    variant_names.insert(&variant_name.0.contents, variant_name)
+                {
+                    let error = DefCollectorErrorKind::Duplicate {
+                        typ: DuplicateType::EnumVariant,
+                        first_def: first_def.clone(),
+                        second_def: variant_name.clone(),
+                    };
+                    definition_errors.push((error.into(), self.file_id));
+                }
+            }
+
+            let unresolved = UnresolvedEnum {
+                file_id: self.file_id,
+                module_id: self.module_id,
+                enum_def: enum_definition,
+            };
+
+            // Like structs, each enum gets its own module for its namespace
+            let id = match self.push_child_module(&name, self.file_id, false, false) {
+                Ok(local_id) => {
+                    context.def_interner.new_enum(&unresolved, krate, local_id, self.file_id)
+                }
+                Err(error) => {
+                    definition_errors.push((error.into(), self.file_id));
+                    continue;
+                }
+            };
+
+            let result =
+                self.def_collector.def_map.modules[self.module_id.0].declare_struct(name, id);
+
+            if let Err((first_def, second_def)) = result {
+                let error = DefCollectorErrorKind::Duplicate {
+                    typ: DuplicateType::TypeDefinition,
+                    first_def,
+                    second_def,
+                };
+                definition_errors.push((error.into(), self.file_id));
+            }
+
+            self.def_collector.collected_enums.insert(id, unresolved);
+        }
+        definition_errors
+    }
+
     /// Collect any type aliases definitions declared within the ast.
     /// Returns a vector of errors if any type aliases were already defined.
     fn collect_type_aliases(
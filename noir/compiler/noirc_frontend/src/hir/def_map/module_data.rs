@@ -121,6 +121,16 @@ impl ModuleData {
         self.scope.find_name(name)
     }
 
+    /// Return an iterator over the names of all items visible within this module, including
+    /// imported items, along with the definitions they refer to.
+    pub fn scope_definitions(&self) -> impl Iterator<Item = (&Ident, ModuleDefId)> + '_ {
+        let types = self.scope.types().iter();
+        let values = self.scope.values().iter();
+        types
+            .chain(values)
+            .flat_map(|(name, scope)| scope.values().map(move |(id, _, _)| (name, *id)))
+    }
+
     pub fn type_definitions(&self) -> impl Iterator<Item = ModuleDefId> + '_ {
         self.definitions.types().values().flat_map(|a| a.values().map(|(id, _, _)| *id))
     }
@@ -68,6 +68,11 @@ impl Context<'_> {
         self.def_maps.get(crate_id)
     }
 
+    /// Returns the CrateDefMaps of every crate which has been collected.
+    pub fn def_maps(&self) -> &BTreeMap<CrateId, CrateDefMap> {
+        &self.def_maps
+    }
+
     /// Return the CrateId for each crate that has been compiled
     /// successfully
     pub fn crates(&self) -> impl Iterator<Item = CrateId> + '_ {
@@ -82,6 +82,10 @@ pub enum ResolverError {
     NonCrateFunctionCalled { name: String, span: Span },
     #[error("Only sized types may be used in the entry point to a program")]
     InvalidTypeForEntryPoint { span: Span },
+    #[error("Could not find an enum variant with the given path")]
+    NoSuchEnumVariant { path: crate::Path },
+    #[error("{} is only allowed within loops", if *is_break { "break" } else { "continue" })]
+    JumpOutsideLoop { is_break: bool, span: Span },
 }
 
 impl ResolverError {
@@ -304,6 +308,19 @@ impl From<ResolverError> for Diagnostic {
             ResolverError::InvalidTypeForEntryPoint { span } => Diagnostic::simple_error(
                 "Only sized types may be used in the entry point to a program".to_string(),
                 "Slices, references, or any type containing them may not be used in main or a contract function".to_string(), span),
+            ResolverError::NoSuchEnumVariant { path } => Diagnostic::simple_error(
+                format!("cannot find an enum variant named `{path}`"),
+                "Match patterns other than `_` or a variable name must refer to an enum variant".to_string(),
+                path.span(),
+            ),
+            ResolverError::JumpOutsideLoop { is_break, span } => {
+                let item = if is_break { "break" } else { "continue" };
+                Diagnostic::simple_error(
+                    format!("{item} is only allowed within loops"),
+                    String::new(),
+                    span,
+                )
+            }
         }
     }
 }
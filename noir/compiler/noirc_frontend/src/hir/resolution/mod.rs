@@ -20,7 +20,7 @@ mod type_aliases;
 pub(crate) use functions::resolve_free_functions;
 pub(crate) use globals::resolve_globals;
 pub(crate) use impls::{collect_impls, resolve_impls};
-pub(crate) use structs::resolve_structs;
+pub(crate) use structs::{resolve_enums, resolve_structs};
 pub(crate) use traits::{
     collect_trait_impls, resolve_trait_by_path, resolve_trait_impls, resolve_traits,
 };
@@ -13,9 +13,10 @@
 // XXX: Resolver does not check for unused functions
 use crate::hir_def::expr::{
     HirArrayLiteral, HirBinaryOp, HirBlockExpression, HirCallExpression, HirCapturedVar,
-    HirCastExpression, HirConstructorExpression, HirExpression, HirIdent, HirIfExpression,
-    HirIndexExpression, HirInfixExpression, HirLambda, HirLiteral, HirMemberAccess,
-    HirMethodCallExpression, HirPrefixExpression,
+    HirCastExpression, HirConstructorExpression, HirEnumConstructorExpression, HirExpression,
+    HirIdent, HirIfExpression, HirIndexExpression, HirInfixExpression, HirLambda, HirLiteral,
+    HirMatchArm, HirMatchExpression, HirMatchPattern, HirMemberAccess, HirMethodCallExpression,
+    HirPrefixExpression,
 };
 
 use crate::hir_def::traits::{Trait, TraitConstraint};
@@ -26,22 +27,24 @@ use std::rc::Rc;
 
 use crate::graph::CrateId;
 use crate::hir::def_map::{LocalModuleId, ModuleDefId, TryFromModuleDefId, MAIN_FUNCTION};
-use crate::hir_def::stmt::{HirAssignStatement, HirForStatement, HirLValue, HirPattern};
+use crate::hir_def::stmt::{
+    HirAssignStatement, HirForStatement, HirLValue, HirPattern, HirWhileStatement,
+};
 use crate::node_interner::{
-    DefinitionId, DefinitionKind, ExprId, FuncId, NodeInterner, StmtId, StructId, TraitId,
-    TraitImplId, TraitImplKind,
+    DefinitionId, DefinitionKind, ExprId, FuncId, NodeInterner, ReferenceId, StmtId, StructId,
+    TraitId, TraitImplId, TraitImplKind,
 };
 use crate::{
     hir::{def_map::CrateDefMap, resolution::path_resolver::PathResolver},
     BlockExpression, Expression, ExpressionKind, FunctionKind, Ident, Literal, NoirFunction,
-    StatementKind,
+    Statement, StatementKind,
 };
 use crate::{
-    ArrayLiteral, ContractFunctionType, Distinctness, ForRange, FunctionDefinition,
-    FunctionReturnType, FunctionVisibility, Generics, LValue, NoirStruct, NoirTypeAlias, Param,
-    Path, PathKind, Pattern, Shared, StructType, Type, TypeAliasType, TypeBinding, TypeVariable,
-    UnaryOp, UnresolvedGenerics, UnresolvedTraitConstraint, UnresolvedType, UnresolvedTypeData,
-    UnresolvedTypeExpression, Visibility, ERROR_IDENT,
+    ArrayLiteral, ContractFunctionType, Distinctness, EnumVariants, ForRange, FunctionDefinition,
+    FunctionReturnType, FunctionVisibility, Generics, LValue, MatchPattern, NoirEnum, NoirStruct,
+    NoirTypeAlias, Param, Path, PathKind, Pattern, Shared, StructType, Type, TypeAliasType,
+    TypeBinding, TypeVariable, UnaryOp, UnresolvedGenerics, UnresolvedTraitConstraint,
+    UnresolvedType, UnresolvedTypeData, UnresolvedTypeExpression, Visibility, ERROR_IDENT,
 };
 use fm::FileId;
 use iter_extended::vecmap;
@@ -111,6 +114,10 @@ pub struct Resolver<'a> {
     /// that are captured. We do this in order to create the hidden environment
     /// parameter for the lambda function.
     lambda_stack: Vec<LambdaContext>,
+
+    /// The number of loops we are currently nested within. Used to ensure
+    /// `break` and `continue` are only used inside of a loop.
+    nested_loops: usize,
 }
 
 /// ResolverMetas are tagged onto each definition to track how many times they are used
@@ -147,6 +154,7 @@ impl<'a> Resolver<'a> {
             generics: Vec::new(),
             errors: Vec::new(),
             lambda_stack: Vec::new(),
+            nested_loops: 0,
             current_trait_impl: None,
             file,
             in_contract,
@@ -389,6 +397,7 @@ impl<'a> Resolver<'a> {
         if let Some((variable_found, scope)) = variable {
             variable_found.num_times_used += 1;
             let id = variable_found.ident.id;
+            self.interner.add_reference(ReferenceId::Definition(id), location);
             Ok((HirIdent { location, id }, scope))
         } else {
             Err(ResolverError::VariableNotDeclared {
@@ -662,16 +671,23 @@ impl<'a> Resolver<'a> {
 
     fn get_ident_from_path(&mut self, path: Path) -> (HirIdent, usize) {
         let location = Location::new(path.span(), self.file);
+        let name_span = path.last_segment().span();
 
         let error = match path.as_ident().map(|ident| self.find_variable(ident)) {
             Some(Ok(found)) => return found,
             // Try to look it up as a global, but still issue the first error if we fail
             Some(Err(error)) => match self.lookup_global(path) {
-                Ok(id) => return (HirIdent { location, id }, 0),
+                Ok(id) => {
+                    self.add_reference(ReferenceId::Definition(id), name_span);
+                    return (HirIdent { location, id }, 0);
+                }
                 Err(_) => error,
             },
             None => match self.lookup_global(path) {
-                Ok(id) => return (HirIdent { location, id }, 0),
+                Ok(id) => {
+                    self.add_reference(ReferenceId::Definition(id), name_span);
+                    return (HirIdent { location, id }, 0);
+                }
                 Err(error) => error,
             },
         };
@@ -764,6 +780,22 @@ impl<'a> Resolver<'a> {
         (generics, fields, self.errors)
     }
 
+    pub fn resolve_enum_variants(
+        mut self,
+        unresolved: NoirEnum,
+    ) -> (Generics, EnumVariants, Vec<ResolverError>) {
+        let generics = self.add_generics(&unresolved.generics);
+
+        // Check whether the enum definition has globals in the local module and add them to the scope
+        self.resolve_local_globals();
+
+        let variants = vecmap(unresolved.variants, |variant| {
+            (variant.name, vecmap(variant.arguments, |typ| self.resolve_type(typ)))
+        });
+
+        (generics, variants, self.errors)
+    }
+
     fn resolve_local_globals(&mut self) {
         for (stmt_id, global_info) in self.interner.get_all_globals() {
             if global_info.local_id == self.path_resolver.local_module_id() {
@@ -780,9 +812,17 @@ impl<'a> Resolver<'a> {
         &mut self,
         where_clause: &Vec<UnresolvedTraitConstraint>,
     ) -> Vec<TraitConstraint> {
-        vecmap(where_clause, |constraint| TraitConstraint {
-            typ: self.resolve_type(constraint.typ.clone()),
-            trait_id: constraint.trait_bound.trait_id.unwrap_or_else(TraitId::dummy_id),
+        vecmap(where_clause, |constraint| {
+            let trait_bound = &constraint.trait_bound;
+            if let Some(trait_id) = trait_bound.trait_id {
+                let name_span = trait_bound.trait_path.last_segment().span();
+                self.add_reference(ReferenceId::Trait(trait_id), name_span);
+            }
+
+            TraitConstraint {
+                typ: self.resolve_type(constraint.typ.clone()),
+                trait_id: trait_bound.trait_id.unwrap_or_else(TraitId::dummy_id),
+            }
         })
     }
 
@@ -1043,7 +1083,7 @@ impl<'a> Resolver<'a> {
         })
     }
 
-    pub fn resolve_stmt(&mut self, stmt: StatementKind) -> HirStatement {
+    pub fn resolve_stmt(&mut self, stmt: StatementKind, span: Span) -> HirStatement {
         match stmt {
             StatementKind::Let(let_stmt) => {
                 let expression = self.resolve_expression(let_stmt.expression);
@@ -1078,6 +1118,7 @@ impl<'a> Resolver<'a> {
 
                         // TODO: For loop variables are currently mutable by default since we haven't
                         //       yet implemented syntax for them to be optionally mutable.
+                        self.nested_loops += 1;
                         let (identifier, block) = self.in_new_scope(|this| {
                             let decl = this.add_variable_decl(
                                 identifier,
@@ -1087,6 +1128,7 @@ impl<'a> Resolver<'a> {
                             );
                             (decl, this.resolve_expression(block))
                         });
+                        self.nested_loops -= 1;
 
                         HirStatement::For(HirForStatement {
                             start_range,
@@ -1098,19 +1140,42 @@ impl<'a> Resolver<'a> {
                     range @ ForRange::Array(_) => {
                         let for_stmt =
                             range.into_for(for_loop.identifier, for_loop.block, for_loop.span);
-                        self.resolve_stmt(for_stmt)
+                        self.resolve_stmt(for_stmt, span)
                     }
                 }
             }
+            StatementKind::While(while_loop) => {
+                let condition = self.resolve_expression(while_loop.condition);
+
+                self.nested_loops += 1;
+                let block = self.resolve_expression(while_loop.block);
+                self.nested_loops -= 1;
+
+                HirStatement::While(HirWhileStatement { condition, block })
+            }
+            StatementKind::Break => {
+                self.check_jump_is_within_loop(true, span);
+                HirStatement::Break
+            }
+            StatementKind::Continue => {
+                self.check_jump_is_within_loop(false, span);
+                HirStatement::Continue
+            }
             StatementKind::Error => HirStatement::Error,
         }
     }
 
-    pub fn intern_stmt(&mut self, stmt: StatementKind) -> StmtId {
-        let hir_stmt = self.resolve_stmt(stmt);
+    pub fn intern_stmt(&mut self, stmt: Statement) -> StmtId {
+        let hir_stmt = self.resolve_stmt(stmt.kind, stmt.span);
         self.interner.push_stmt(hir_stmt)
     }
 
+    fn check_jump_is_within_loop(&mut self, is_break: bool, span: Span) {
+        if self.nested_loops == 0 {
+            self.push_err(ResolverError::JumpOutsideLoop { is_break, span });
+        }
+    }
+
     fn resolve_lvalue(&mut self, lvalue: LValue) -> HirLValue {
         match lvalue {
             LValue::Ident(ident) => {
@@ -1250,7 +1315,19 @@ impl<'a> Resolver<'a> {
                 Literal::Unit => HirLiteral::Unit,
             }),
             ExpressionKind::Variable(path) => {
-                if let Some((hir_expr, object_type)) = self.resolve_trait_generic_path(&path) {
+                if let Some((r#type, enum_generics, variant_index)) =
+                    self.lookup_enum_variant(&path)
+                {
+                    // A variant without arguments is referred to by its path alone
+                    let arguments = Vec::new();
+                    HirExpression::EnumConstructor(HirEnumConstructorExpression {
+                        r#type,
+                        enum_generics,
+                        variant_index,
+                        arguments,
+                    })
+                } else if let Some((hir_expr, object_type)) = self.resolve_trait_generic_path(&path)
+                {
                     let expr_id = self.interner.push_expr(hir_expr);
                     self.interner.push_expr_location(expr_id, expr.span, self.file);
                     self.interner.select_impl_for_expression(
@@ -1325,6 +1402,21 @@ impl<'a> Resolver<'a> {
                 })
             }
             ExpressionKind::Call(call_expr) => {
+                if let Some((r#type, enum_generics, variant_index)) =
+                    call_expr.func.kind.as_path().and_then(|path| self.lookup_enum_variant(path))
+                {
+                    let arguments = vecmap(call_expr.arguments, |arg| self.resolve_expression(arg));
+                    let hir_expr = HirExpression::EnumConstructor(HirEnumConstructorExpression {
+                        r#type,
+                        enum_generics,
+                        variant_index,
+                        arguments,
+                    });
+                    let expr_id = self.interner.push_expr(hir_expr);
+                    self.interner.push_expr_location(expr_id, expr.span, self.file);
+                    return expr_id;
+                }
+
                 // Get the span and name of path for error reporting
                 let func = self.resolve_expression(*call_expr.func);
 
@@ -1353,6 +1445,18 @@ impl<'a> Resolver<'a> {
                 consequence: self.resolve_expression(if_expr.consequence),
                 alternative: if_expr.alternative.map(|e| self.resolve_expression(e)),
             }),
+            ExpressionKind::Match(match_expr) => {
+                let expression = self.resolve_expression(match_expr.expression);
+                let arms = vecmap(match_expr.arms, |(pattern, branch)| {
+                    // Each arm gets its own scope for the variables bound by its pattern
+                    self.in_new_scope(|this| {
+                        let pattern = this.resolve_match_pattern(pattern);
+                        let branch = this.resolve_expression(branch);
+                        HirMatchArm { pattern, branch }
+                    })
+                });
+                HirExpression::Match(HirMatchExpression { expression, arms })
+            }
             ExpressionKind::Index(indexed_expr) => HirExpression::Index(HirIndexExpression {
                 collection: self.resolve_expression(indexed_expr.collection),
                 index: self.resolve_expression(indexed_expr.index),
@@ -1362,6 +1466,10 @@ impl<'a> Resolver<'a> {
                 let span = constructor.type_name.span();
 
                 match self.lookup_type_or_error(constructor.type_name) {
+                    Some(typ @ Type::Struct(..)) if typ.is_enum() => {
+                        self.push_err(ResolverError::NonStructUsedInConstructor { typ, span });
+                        HirExpression::Error
+                    }
                     Some(Type::Struct(r#type, struct_generics)) => {
                         let typ = r#type.clone();
                         let fields = constructor.fields;
@@ -1401,6 +1509,9 @@ impl<'a> Resolver<'a> {
 
                 this.lambda_stack.push(LambdaContext { captures: Vec::new(), scope_index });
 
+                // `break` and `continue` cannot jump out of a lambda into an enclosing loop
+                let nested_loops = std::mem::take(&mut this.nested_loops);
+
                 let parameters = vecmap(lambda.parameters, |(pattern, typ)| {
                     let parameter = DefinitionKind::Local(None);
                     (this.resolve_pattern(pattern, parameter), this.resolve_inferred_type(typ))
@@ -1409,6 +1520,7 @@ impl<'a> Resolver<'a> {
                 let return_type = this.resolve_inferred_type(lambda.return_type);
                 let body = this.resolve_expression(lambda.body);
 
+                this.nested_loops = nested_loops;
                 let lambda_context = this.lambda_stack.pop().unwrap();
 
                 HirExpression::Lambda(HirLambda {
@@ -1474,6 +1586,10 @@ impl<'a> Resolver<'a> {
                 };
 
                 let (struct_type, generics) = match self.lookup_type_or_error(name) {
+                    Some(typ @ Type::Struct(..)) if typ.is_enum() => {
+                        self.push_err(ResolverError::NonStructUsedInConstructor { typ, span });
+                        return error_identifier(self);
+                    }
                     Some(Type::Struct(struct_type, generics)) => (struct_type, generics),
                     None => return error_identifier(self),
                     Some(typ) => {
@@ -1495,6 +1611,74 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    fn resolve_match_pattern(&mut self, pattern: MatchPattern) -> HirMatchPattern {
+        match pattern {
+            MatchPattern::Wildcard(span) => {
+                HirMatchPattern::Wildcard(Location::new(span, self.file))
+            }
+            MatchPattern::Binding(name) => {
+                let definition = DefinitionKind::Local(None);
+                HirMatchPattern::Binding(self.add_variable_decl(name, false, true, definition))
+            }
+            MatchPattern::Variant(path, arguments, span) => {
+                let location = Location::new(span, self.file);
+                let variant = self.lookup_enum_variant(&path);
+
+                // Resolve the arguments even if the variant is unknown so that any
+                // variables they bind are still defined within the arm.
+                let arguments = vecmap(arguments, |argument| {
+                    self.resolve_pattern(argument, DefinitionKind::Local(None))
+                });
+
+                match variant {
+                    Some((r#type, _, variant_index)) => {
+                        HirMatchPattern::Variant { r#type, variant_index, arguments, location }
+                    }
+                    None => {
+                        self.push_err(ResolverError::NoSuchEnumVariant { path });
+                        HirMatchPattern::Wildcard(location)
+                    }
+                }
+            }
+        }
+    }
+
+    /// If the given path refers to a variant of an enum type, e.g. `Option::Some`,
+    /// returns the enum type, its generic arguments, and the index of the variant.
+    ///
+    /// The generics are freshly instantiated unless the path is relative to `Self`,
+    /// in which case the generics of the current self type are used.
+    fn lookup_enum_variant(
+        &mut self,
+        path: &Path,
+    ) -> Option<(Shared<StructType>, Vec<Type>, usize)> {
+        if path.segments.len() < 2 {
+            return None;
+        }
+
+        let mut type_path = path.clone();
+        let variant_name = type_path.pop();
+
+        let (enum_type, generics) = match type_path.as_ident() {
+            Some(ident) if ident == SELF_TYPE_NAME => match &self.self_type {
+                Some(Type::Struct(enum_type, generics)) => (enum_type.clone(), generics.clone()),
+                _ => return None,
+            },
+            _ => {
+                // The type is referred to even if this is a path to one of its methods
+                let name_span = type_path.last_segment().span();
+                let enum_id: StructId = self.lookup(type_path).ok()?;
+                self.add_reference(ReferenceId::Struct(enum_id), name_span);
+                let enum_type = self.get_struct(enum_id);
+                let generics = enum_type.borrow().instantiate(self.interner);
+                (enum_type, generics)
+            }
+        };
+
+        let variant_index = enum_type.borrow().get_variant(&variant_name.0.contents, &generics)?.0;
+        Some((enum_type, generics, variant_index))
+    }
+
     /// Resolve all the fields of a struct constructor expression.
     /// Ensures all fields are present, none are repeated, and all
     /// are part of the struct.
@@ -1515,6 +1699,11 @@ impl<'a> Resolver<'a> {
         for (field, expr) in fields {
             let resolved = resolve_function(self, expr);
 
+            if let Some(index) = struct_type.borrow().field_index(&field.0.contents) {
+                let struct_id = struct_type.borrow().id;
+                self.add_reference(ReferenceId::StructMember(struct_id, index), field.span());
+            }
+
             if unseen_fields.contains(&field) {
                 unseen_fields.remove(&field);
                 seen_fields.insert(field.clone());
@@ -1579,10 +1768,19 @@ impl<'a> Resolver<'a> {
         Err(ResolverError::Expected { span, expected, got })
     }
 
+    /// Records that the name with the given span refers to the given item.
+    fn add_reference(&mut self, id: ReferenceId, span: Span) {
+        self.interner.add_reference(id, Location::new(span, self.file));
+    }
+
     /// Lookup a given struct type by name.
     fn lookup_struct_or_error(&mut self, path: Path) -> Option<Shared<StructType>> {
+        let name_span = path.last_segment().span();
         match self.lookup(path) {
-            Ok(struct_id) => Some(self.get_struct(struct_id)),
+            Ok(struct_id) => {
+                self.add_reference(ReferenceId::Struct(struct_id), name_span);
+                Some(self.get_struct(struct_id))
+            }
             Err(error) => {
                 self.push_err(error);
                 None
@@ -1592,8 +1790,12 @@ impl<'a> Resolver<'a> {
 
     /// Lookup a given trait by name/path.
     fn lookup_trait_or_error(&mut self, path: Path) -> Option<&mut Trait> {
+        let name_span = path.last_segment().span();
         match self.lookup(path) {
-            Ok(trait_id) => Some(self.get_trait_mut(trait_id)),
+            Ok(trait_id) => {
+                self.add_reference(ReferenceId::Trait(trait_id), name_span);
+                Some(self.get_trait_mut(trait_id))
+            }
             Err(error) => {
                 self.push_err(error);
                 None
@@ -1611,8 +1813,10 @@ impl<'a> Resolver<'a> {
             }
         }
 
+        let name_span = path.last_segment().span();
         match self.lookup(path) {
             Ok(struct_id) => {
+                self.add_reference(ReferenceId::Struct(struct_id), name_span);
                 let struct_type = self.get_struct(struct_id);
                 let generics = struct_type.borrow().instantiate(self.interner);
                 Some(Type::Struct(struct_type, generics))
@@ -1712,7 +1916,7 @@ impl<'a> Resolver<'a> {
 
     fn resolve_block(&mut self, block_expr: BlockExpression) -> HirExpression {
         let statements =
-            self.in_new_scope(|this| vecmap(block_expr.0, |stmt| this.intern_stmt(stmt.kind)));
+            self.in_new_scope(|this| vecmap(block_expr.0, |stmt| this.intern_stmt(stmt)));
         HirExpression::Block(HirBlockExpression(statements))
     }
 
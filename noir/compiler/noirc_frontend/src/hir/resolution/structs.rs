@@ -2,16 +2,17 @@ use std::collections::BTreeMap;
 
 use fm::FileId;
 use iter_extended::vecmap;
+use noirc_errors::Location;
 
 use crate::{
     graph::CrateId,
     hir::{
-        def_collector::dc_crate::{CompilationError, UnresolvedStruct},
+        def_collector::dc_crate::{CompilationError, UnresolvedEnum, UnresolvedStruct},
         def_map::ModuleId,
         Context,
     },
-    node_interner::StructId,
-    Generics, Ident, Type,
+    node_interner::{ReferenceId, StructId},
+    EnumVariants, Generics, Ident, Type,
 };
 
 use super::{errors::ResolverError, path_resolver::StandardPathResolver, resolver::Resolver};
@@ -30,6 +31,12 @@ pub(crate) fn resolve_structs(
         let file_id = typ.file_id;
         let (generics, fields, resolver_errors) = resolve_struct_fields(context, crate_id, typ);
         errors.extend(vecmap(resolver_errors, |err| (err.into(), file_id)));
+        for (index, (name, _)) in fields.iter().enumerate() {
+            let location = Location::new(name.span(), file_id);
+            context
+                .def_interner
+                .add_declaration(ReferenceId::StructMember(type_id, index), location);
+        }
         context.def_interner.update_struct(type_id, |struct_def| {
             struct_def.set_fields(fields);
             struct_def.generics = generics;
@@ -38,6 +45,27 @@ pub(crate) fn resolve_structs(
     errors
 }
 
+/// Create the mappings from TypeId -> StructType for each enum
+/// so that expressions can construct and match on their variants
+pub(crate) fn resolve_enums(
+    context: &mut Context,
+    enums: BTreeMap<StructId, UnresolvedEnum>,
+    crate_id: CrateId,
+) -> Vec<(CompilationError, FileId)> {
+    let mut errors: Vec<(CompilationError, FileId)> = vec![];
+    // Each enum should already be present in the NodeInterner after def collection.
+    for (type_id, typ) in enums {
+        let file_id = typ.file_id;
+        let (generics, variants, resolver_errors) = resolve_enum_variants(context, crate_id, typ);
+        errors.extend(vecmap(resolver_errors, |err| (err.into(), file_id)));
+        context.def_interner.update_struct(type_id, |enum_def| {
+            enum_def.set_variants(variants);
+            enum_def.generics = generics;
+        });
+    }
+    errors
+}
+
 fn resolve_struct_fields(
     context: &mut Context,
     krate: CrateId,
@@ -51,3 +79,15 @@ fn resolve_struct_fields(
             .resolve_struct_fields(unresolved.struct_def);
     (generics, fields, errors)
 }
+
+fn resolve_enum_variants(
+    context: &mut Context,
+    krate: CrateId,
+    unresolved: UnresolvedEnum,
+) -> (Generics, EnumVariants, Vec<ResolverError>) {
+    let path_resolver =
+        StandardPathResolver::new(ModuleId { local_id: unresolved.module_id, krate });
+    let file_id = unresolved.file_id;
+    Resolver::new(&mut context.def_interner, &path_resolver, &context.def_maps, file_id)
+        .resolve_enum_variants(unresolved.enum_def)
+}
@@ -17,7 +17,7 @@ use crate::{
         Context,
     },
     hir_def::traits::{TraitConstant, TraitFunction, TraitImpl, TraitType},
-    node_interner::{FuncId, NodeInterner, TraitId},
+    node_interner::{FuncId, NodeInterner, ReferenceId, TraitId},
     Path, Shared, TraitItem, Type, TypeBinding, TypeVariableKind,
 };
 
@@ -273,7 +273,12 @@ fn collect_trait_impl(
     let module = ModuleId { local_id: trait_impl.module_id, krate: crate_id };
     trait_impl.trait_id =
         match resolve_trait_by_path(def_maps, module, trait_impl.trait_path.clone()) {
-            Ok(trait_id) => Some(trait_id),
+            Ok(trait_id) => {
+                let name_span = trait_impl.trait_path.last_segment().span();
+                let location = Location::new(name_span, trait_impl.file_id);
+                interner.add_reference(ReferenceId::Trait(trait_id), location);
+                Some(trait_id)
+            }
             Err(error) => {
                 errors.push((error.into(), trait_impl.file_id));
                 None
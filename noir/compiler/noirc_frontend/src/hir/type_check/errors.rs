@@ -1,4 +1,5 @@
 use acvm::FieldElement;
+use iter_extended::vecmap;
 use noirc_errors::CustomDiagnostic as Diagnostic;
 use noirc_errors::Span;
 use thiserror::Error;
@@ -115,6 +116,10 @@ pub enum TypeCheckError {
     NoMatchingImplFound { constraints: Vec<(Type, String)>, span: Span },
     #[error("Constraint for `{typ}: {trait_name}` is not needed, another matching impl is already in scope")]
     UnneededTraitConstraint { trait_name: String, typ: Type, span: Span },
+    #[error("Non-exhaustive match on type {typ}")]
+    NonExhaustiveMatch { typ: Type, missing_variants: Vec<String>, span: Span },
+    #[error("Unreachable match arm")]
+    UnreachableMatchArm { span: Span },
 }
 
 impl TypeCheckError {
@@ -275,6 +280,21 @@ impl From<TypeCheckError> for Diagnostic {
                 let msg = format!("Constraint for `{typ}: {trait_name}` is not needed, another matching impl is already in scope");
                 Diagnostic::simple_warning(msg, "Unnecessary trait constraint in where clause".into(), span)
             }
+            TypeCheckError::NonExhaustiveMatch { typ, missing_variants, span } => {
+                // Missing variants are already qualified by their enum's name
+                let missing = vecmap(&missing_variants, |variant| format!("`{variant}`"));
+                let plural = if missing_variants.len() == 1 { "" } else { "s" };
+                Diagnostic::simple_error(
+                    format!("Non-exhaustive match on type {typ}"),
+                    format!("Missing case{plural} {}", missing.join(", ")),
+                    span,
+                )
+            }
+            TypeCheckError::UnreachableMatchArm { span } => Diagnostic::simple_warning(
+                "Unreachable match arm".to_string(),
+                "This pattern is already covered by a previous arm".to_string(),
+                span,
+            ),
         }
     }
 }
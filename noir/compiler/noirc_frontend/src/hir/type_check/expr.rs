@@ -1,5 +1,7 @@
+use std::collections::BTreeSet;
+
 use iter_extended::vecmap;
-use noirc_errors::Span;
+use noirc_errors::{Location, Span};
 
 use crate::{
     hir::{resolution::resolver::verify_mutable_reference, type_check::errors::Source},
@@ -8,9 +10,12 @@ use crate::{
             self, HirArrayLiteral, HirBinaryOp, HirExpression, HirLiteral, HirMethodCallExpression,
             HirMethodReference, HirPrefixExpression,
         },
+        stmt::HirPattern,
         types::Type,
     },
-    node_interner::{DefinitionKind, ExprId, FuncId, TraitId, TraitImplKind, TraitMethodId},
+    node_interner::{
+        DefinitionKind, ExprId, FuncId, ReferenceId, TraitId, TraitImplKind, TraitMethodId,
+    },
     BinaryOpKind, TypeBinding, TypeBindings, TypeVariableKind, UnaryOp,
 };
 
@@ -190,6 +195,12 @@ impl<'interner> TypeChecker<'interner> {
                                 // Automatically add `&mut` if the method expects a mutable reference and
                                 // the object is not already one.
                                 if *func_id != FuncId::dummy_id() {
+                                    let id = self.interner.function_definition_id(*func_id);
+                                    let name_span = method_call.method.span();
+                                    let name_location = Location::new(name_span, location.file);
+                                    self.interner
+                                        .add_reference(ReferenceId::Definition(id), name_location);
+
                                     let function_type =
                                         self.interner.function_meta(func_id).typ.clone();
                                     self.try_add_mutable_reference_to_object(
@@ -269,7 +280,11 @@ impl<'interner> TypeChecker<'interner> {
                 self.type_check_prefix_operand(&prefix_expr.operator, &rhs_type, span)
             }
             HirExpression::If(if_expr) => self.check_if_expr(&if_expr, expr_id),
+            HirExpression::Match(match_expr) => self.check_match(match_expr, expr_id),
             HirExpression::Constructor(constructor) => self.check_constructor(constructor, expr_id),
+            HirExpression::EnumConstructor(constructor) => {
+                self.check_enum_constructor(constructor, expr_id)
+            }
             HirExpression::MemberAccess(access) => self.check_member_access(access, *expr_id),
             HirExpression::Error => Type::Error,
             HirExpression::Tuple(elements) => {
@@ -629,6 +644,141 @@ impl<'interner> TypeChecker<'interner> {
         }
     }
 
+    fn check_match(&mut self, match_expr: expr::HirMatchExpression, expr_id: &ExprId) -> Type {
+        let expr_type = self.check_expression(&match_expr.expression);
+        let mut result_type: Option<Type> = None;
+
+        // Exhaustiveness: since the arguments of a variant pattern are always irrefutable,
+        // a match is exhaustive if it has an irrefutable arm or it covers every variant.
+        let mut covered_variants = BTreeSet::new();
+        let mut has_irrefutable_arm = false;
+
+        for arm in &match_expr.arms {
+            let pattern_span = arm.pattern.location().span;
+            if has_irrefutable_arm {
+                self.errors.push(TypeCheckError::UnreachableMatchArm { span: pattern_span });
+            }
+
+            match &arm.pattern {
+                expr::HirMatchPattern::Wildcard(_) => has_irrefutable_arm = true,
+                expr::HirMatchPattern::Binding(ident) => {
+                    self.bind_pattern(&HirPattern::Identifier(*ident), expr_type.clone());
+                    has_irrefutable_arm = true;
+                }
+                expr::HirMatchPattern::Variant { r#type, variant_index, arguments, .. } => {
+                    let generics = r#type.borrow().instantiate(self.interner);
+                    let pattern_type = Type::Struct(r#type.clone(), generics.clone());
+
+                    self.unify(&pattern_type, &expr_type, || TypeCheckError::TypeMismatch {
+                        expected_typ: expr_type.to_string(),
+                        expr_typ: pattern_type.to_string(),
+                        expr_span: pattern_span,
+                    });
+
+                    let (_, parameters) =
+                        r#type.borrow().get_variants(&generics).swap_remove(*variant_index);
+
+                    if parameters.len() != arguments.len() {
+                        self.errors.push(TypeCheckError::ArityMisMatch {
+                            expected: parameters.len() as u16,
+                            found: arguments.len() as u16,
+                            span: pattern_span,
+                        });
+                    }
+
+                    let mut parameters = parameters.into_iter();
+                    for argument in arguments {
+                        let parameter = parameters.next().unwrap_or(Type::Error);
+                        self.bind_pattern(argument, parameter);
+                    }
+
+                    if !covered_variants.insert(*variant_index) && !has_irrefutable_arm {
+                        self.errors
+                            .push(TypeCheckError::UnreachableMatchArm { span: pattern_span });
+                    }
+                }
+            }
+
+            let branch_type = self.check_expression(&arm.branch);
+            match &result_type {
+                None => result_type = Some(branch_type),
+                Some(first_type) => {
+                    let expr_span = self.interner.expr_span(&arm.branch);
+                    self.unify(&branch_type, first_type, || {
+                        TypeCheckError::TypeMismatch {
+                            expected_typ: first_type.to_string(),
+                            expr_typ: branch_type.to_string(),
+                            expr_span,
+                        }
+                        .add_context("Expected the types of all match arms to be equal")
+                    });
+                }
+            }
+        }
+
+        if !has_irrefutable_arm {
+            let missing_variants = match expr_type.follow_bindings() {
+                Type::Struct(definition, generics) if definition.borrow().is_enum() => {
+                    let definition = definition.borrow();
+                    let variants = definition.get_variants(&generics).into_iter().enumerate();
+                    variants
+                        .filter(|(index, _)| !covered_variants.contains(index))
+                        .map(|(_, (name, _))| format!("{}::{name}", definition.name))
+                        .collect()
+                }
+                // A value of any other type can only be matched exhaustively by a catch-all arm
+                _ => vec!["_".to_string()],
+            };
+
+            if !missing_variants.is_empty() && expr_type != Type::Error {
+                let span = self.interner.expr_span(expr_id);
+                self.errors.push(TypeCheckError::NonExhaustiveMatch {
+                    typ: expr_type,
+                    missing_variants,
+                    span,
+                });
+            }
+        }
+
+        result_type.unwrap_or(Type::Unit)
+    }
+
+    fn check_enum_constructor(
+        &mut self,
+        constructor: expr::HirEnumConstructorExpression,
+        expr_id: &ExprId,
+    ) -> Type {
+        let typ = constructor.r#type;
+        let generics = constructor.enum_generics;
+        let (_, parameters) =
+            typ.borrow().get_variants(&generics).swap_remove(constructor.variant_index);
+
+        if parameters.len() != constructor.arguments.len() {
+            self.errors.push(TypeCheckError::ArityMisMatch {
+                expected: parameters.len() as u16,
+                found: constructor.arguments.len() as u16,
+                span: self.interner.expr_span(expr_id),
+            });
+        }
+
+        for (i, arg) in constructor.arguments.iter().enumerate() {
+            let arg_type = self.check_expression(arg);
+
+            if let Some(param_type) = parameters.get(i) {
+                let span = self.interner.expr_span(arg);
+                self.unify_with_coercions(&arg_type, param_type, *arg, || {
+                    TypeCheckError::TypeMismatch {
+                        expected_typ: param_type.to_string(),
+                        expr_typ: arg_type.to_string(),
+                        expr_span: span,
+                    }
+                });
+            }
+        }
+
+        Type::Struct(typ, generics)
+    }
+
     fn check_constructor(
         &mut self,
         constructor: expr::HirConstructorExpression,
@@ -689,6 +839,9 @@ impl<'interner> TypeChecker<'interner> {
         match self.check_field_access(&lhs_type, &access.rhs.0.contents, span, dereference_lhs) {
             Some((element_type, index)) => {
                 self.interner.set_field_index(expr_id, index);
+                let file = self.interner.expr_location(&expr_id).file;
+                let name_location = Location::new(access.rhs.span(), file);
+                self.add_field_reference(&lhs_type, index, name_location);
                 // We must update `access` in case we added any dereferences to it
                 self.interner.replace_expr(&expr_id, HirExpression::MemberAccess(access));
                 element_type
@@ -710,6 +863,26 @@ impl<'interner> TypeChecker<'interner> {
     /// expression. The second parameter of this function represents the lhs_type (which should
     /// always be a Type::MutableReference if `dereference_lhs` is called) and the third
     /// represents the element type.
+    /// Records that the name at the given [Location] refers to a field of the given struct
+    /// type, which may be behind references that are dereferenced to access the field.
+    pub(super) fn add_field_reference(
+        &mut self,
+        lhs_type: &Type,
+        index: usize,
+        location: Location,
+    ) {
+        match lhs_type.follow_bindings() {
+            Type::Struct(struct_type, _) => {
+                let struct_id = struct_type.borrow().id;
+                self.interner.add_reference(ReferenceId::StructMember(struct_id, index), location);
+            }
+            Type::MutableReference(element) => {
+                self.add_field_reference(&element, index, location);
+            }
+            _ => (),
+        }
+    }
+
     pub(super) fn check_field_access(
         &mut self,
         lhs_type: &Type,
@@ -4,7 +4,7 @@ use noirc_errors::{Location, Span};
 use crate::hir_def::expr::{HirExpression, HirIdent, HirLiteral};
 use crate::hir_def::stmt::{
     HirAssignStatement, HirConstrainStatement, HirForStatement, HirLValue, HirLetStatement,
-    HirPattern, HirStatement,
+    HirPattern, HirStatement, HirWhileStatement,
 };
 use crate::hir_def::types::Type;
 use crate::node_interner::{DefinitionId, ExprId, StmtId};
@@ -50,7 +50,8 @@ impl<'interner> TypeChecker<'interner> {
             HirStatement::Constrain(constrain_stmt) => self.check_constrain_stmt(constrain_stmt),
             HirStatement::Assign(assign_stmt) => self.check_assign_stmt(assign_stmt, stmt_id),
             HirStatement::For(for_loop) => self.check_for_loop(for_loop),
-            HirStatement::Error => (),
+            HirStatement::While(while_loop) => self.check_while_loop(while_loop),
+            HirStatement::Break | HirStatement::Continue | HirStatement::Error => (),
         }
         Type::Unit
     }
@@ -86,6 +87,19 @@ impl<'interner> TypeChecker<'interner> {
         self.check_expression(&for_loop.block);
     }
 
+    fn check_while_loop(&mut self, while_loop: HirWhileStatement) {
+        let condition_type = self.check_expression(&while_loop.condition);
+        let expr_span = self.interner.expr_span(&while_loop.condition);
+
+        self.unify(&condition_type, &Type::Bool, || TypeCheckError::TypeMismatch {
+            expected_typ: Type::Bool.to_string(),
+            expr_typ: condition_type.to_string(),
+            expr_span,
+        });
+
+        self.check_expression(&while_loop.block);
+    }
+
     /// Associate a given HirPattern with the given Type, and remember
     /// this association in the NodeInterner.
     pub(crate) fn bind_pattern(&mut self, pattern: &HirPattern, typ: Type) {
@@ -226,6 +240,11 @@ impl<'interner> TypeChecker<'interner> {
                     )
                     .unwrap_or((Type::Error, 0));
 
+                if object_type != Type::Error {
+                    let name_location = Location::new(span, lvalue.ident().location.file);
+                    self.add_field_reference(&lhs_type, field_index, name_location);
+                }
+
                 let field_index = Some(field_index);
                 let typ = object_type.clone();
                 let lvalue = HirLValue::MemberAccess { object, field_name, field_index, typ };
@@ -22,11 +22,13 @@ pub enum HirExpression {
     Infix(HirInfixExpression),
     Index(HirIndexExpression),
     Constructor(HirConstructorExpression),
+    EnumConstructor(HirEnumConstructorExpression),
     MemberAccess(HirMemberAccess),
     Call(HirCallExpression),
     MethodCall(HirMethodCallExpression),
     Cast(HirCastExpression),
     If(HirIfExpression),
+    Match(HirMatchExpression),
     Tuple(Vec<ExprId>),
     Lambda(HirLambda),
     TraitMethodReference(TraitMethodId),
@@ -126,6 +128,50 @@ pub struct HirIfExpression {
     pub alternative: Option<ExprId>,
 }
 
+#[derive(Debug, Clone)]
+pub struct HirMatchExpression {
+    pub expression: ExprId,
+    pub arms: Vec<HirMatchArm>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HirMatchArm {
+    pub pattern: HirMatchPattern,
+    pub branch: ExprId,
+}
+
+#[derive(Debug, Clone)]
+pub enum HirMatchPattern {
+    /// `_`, matches anything
+    Wildcard(Location),
+    /// A variable name, matches anything and binds it to the variable
+    Binding(HirIdent),
+    /// `Enum::Variant(args)`, matches a single variant of an enum. Each argument pattern
+    /// is irrefutable, so whether a value matches depends only on the variant.
+    Variant {
+        r#type: Shared<StructType>,
+        variant_index: usize,
+        arguments: Vec<HirPattern>,
+        location: Location,
+    },
+}
+
+impl HirMatchPattern {
+    /// True if this pattern matches every value of its type
+    pub fn is_irrefutable(&self) -> bool {
+        !matches!(self, HirMatchPattern::Variant { .. })
+    }
+
+    pub fn location(&self) -> Location {
+        match self {
+            HirMatchPattern::Wildcard(location) | HirMatchPattern::Variant { location, .. } => {
+                *location
+            }
+            HirMatchPattern::Binding(ident) => ident.location,
+        }
+    }
+}
+
 // `lhs as type` in the source code
 #[derive(Debug, Clone)]
 pub struct HirCastExpression {
@@ -202,6 +248,15 @@ pub struct HirConstructorExpression {
     pub fields: Vec<(Ident, ExprId)>,
 }
 
+/// An enum variant constructor, as in `Option::Some(x)` or `Option::None`
+#[derive(Debug, Clone)]
+pub struct HirEnumConstructorExpression {
+    pub r#type: Shared<StructType>,
+    pub enum_generics: Vec<Type>,
+    pub variant_index: usize,
+    pub arguments: Vec<ExprId>,
+}
+
 /// Indexing, as in `array[index]`
 #[derive(Debug, Clone)]
 pub struct HirIndexExpression {
@@ -14,6 +14,9 @@ pub enum HirStatement {
     Constrain(HirConstrainStatement),
     Assign(HirAssignStatement),
     For(HirForStatement),
+    While(HirWhileStatement),
+    Break,
+    Continue,
     Expression(ExprId),
     Semi(ExprId),
     Error,
@@ -43,6 +46,12 @@ pub struct HirForStatement {
     pub block: ExprId,
 }
 
+#[derive(Debug, Clone)]
+pub struct HirWhileStatement {
+    pub condition: ExprId,
+    pub block: ExprId,
+}
+
 /// Corresponds to `lvalue = expression;` in the source code
 #[derive(Debug, Clone)]
 pub struct HirAssignStatement {
@@ -120,3 +129,15 @@ pub enum HirLValue {
         element_type: Type,
     },
 }
+
+impl HirLValue {
+    /// Returns the identifier of the variable being assigned to, e.g. `a` in `a.b[c] = d`.
+    pub fn ident(&self) -> &HirIdent {
+        match self {
+            HirLValue::Ident(ident, _) => ident,
+            HirLValue::MemberAccess { object: lvalue, .. }
+            | HirLValue::Index { array: lvalue, .. }
+            | HirLValue::Dereference { lvalue, .. } => lvalue.ident(),
+        }
+    }
+}
@@ -166,6 +166,11 @@ pub struct StructType {
     /// since these will handle applying generic arguments to fields as well.
     fields: Vec<(Ident, Type)>,
 
+    /// The variants of this type if it was declared as an enum rather than a struct.
+    /// Like fields, these are private and should be accessed through get_variant()
+    /// or get_variants() which handle applying generic arguments.
+    variants: Option<EnumVariants>,
+
     pub generics: Generics,
     pub location: Location,
 }
@@ -176,6 +181,9 @@ pub struct StructType {
 /// the actual part that can be mutated to bind it to another type.
 pub type Generics = Vec<(TypeVariableId, TypeVariable)>;
 
+/// The name and argument types of each variant of an enum, in declaration order.
+pub type EnumVariants = Vec<(Ident, Vec<Type>)>;
+
 impl std::hash::Hash for StructType {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.id.hash(state);
@@ -197,7 +205,7 @@ impl StructType {
         fields: Vec<(Ident, Type)>,
         generics: Generics,
     ) -> StructType {
-        StructType { id, fields, name, location, generics }
+        StructType { id, fields, name, location, generics, variants: None }
     }
 
     /// To account for cyclic references between structs, a struct's
@@ -209,10 +217,60 @@ impl StructType {
         self.fields = fields;
     }
 
+    /// Enum variants are resolved after the enum itself is created, for the
+    /// same reason as struct fields. Setting the variants also marks this type as an enum.
+    pub fn set_variants(&mut self, variants: EnumVariants) {
+        assert!(self.variants.is_none());
+        self.variants = Some(variants);
+    }
+
+    /// True if this type was declared with `enum` rather than `struct`.
+    /// Note that this will be false for enums whose variants are not yet resolved.
+    pub fn is_enum(&self) -> bool {
+        self.variants.is_some()
+    }
+
     pub fn num_fields(&self) -> usize {
         self.fields.len()
     }
 
+    pub fn num_variants(&self) -> usize {
+        self.variants.as_ref().map_or(0, |variants| variants.len())
+    }
+
+    /// Returns the index of the variant matching the given variant name, as well as the types
+    /// of its arguments after being applied to the given generic arguments.
+    pub fn get_variant(
+        &self,
+        variant_name: &str,
+        generic_args: &[Type],
+    ) -> Option<(usize, Vec<Type>)> {
+        let index =
+            self.variants.as_ref()?.iter().position(|(name, _)| name.0.contents == variant_name)?;
+
+        let (_, arguments) = self.get_variants(generic_args).swap_remove(index);
+        Some((index, arguments))
+    }
+
+    /// Returns all the variants of this type in declaration order, after being applied to
+    /// the given generic arguments. This is empty if this type is not an enum.
+    pub fn get_variants(&self, generic_args: &[Type]) -> Vec<(String, Vec<Type>)> {
+        assert_eq!(self.generics.len(), generic_args.len());
+
+        let substitutions = self
+            .generics
+            .iter()
+            .zip(generic_args)
+            .map(|((old_id, old_var), new)| (*old_id, (old_var.clone(), new.clone())))
+            .collect();
+
+        let variants = self.variants.iter().flatten();
+        vecmap(variants, |(name, arguments)| {
+            let name = name.0.contents.clone();
+            (name, vecmap(arguments, |typ| typ.substitute(&substitutions)))
+        })
+    }
+
     /// Returns the field matching the given field name, as well as its field index.
     pub fn get_field(&self, field_name: &str, generic_args: &[Type]) -> Option<(Type, usize)> {
         assert_eq!(self.generics.len(), generic_args.len());
@@ -248,16 +306,29 @@ impl StructType {
         })
     }
 
+    /// Returns the index of the field matching the given field name.
+    pub fn field_index(&self, field_name: &str) -> Option<usize> {
+        self.fields.iter().position(|(name, _)| name.0.contents == field_name)
+    }
+
     pub fn field_names(&self) -> BTreeSet<Ident> {
         self.fields.iter().map(|(name, _)| name.clone()).collect()
     }
 
+    /// Returns the names of all the variants of this type in declaration order.
+    /// This is empty if this type is not an enum.
+    pub fn variant_names(&self) -> Vec<Ident> {
+        self.variants.iter().flatten().map(|(name, _)| name.clone()).collect()
+    }
+
     /// True if the given index is the same index as a generic type of this struct
     /// which is expected to be a numeric generic.
     /// This is needed because we infer type kinds in Noir and don't have extensive kind checking.
     pub fn generic_is_numeric(&self, index_of_generic: usize) -> bool {
         let target_id = self.generics[index_of_generic].0;
+        let mut variant_arguments = self.variants.iter().flatten().flat_map(|(_, args)| args);
         self.fields.iter().any(|(_, field)| field.contains_numeric_typevar(target_id))
+            || variant_arguments.any(|argument| argument.contains_numeric_typevar(target_id))
     }
 
     /// Instantiate this struct type, returning a Vec of the new generic args (in
@@ -550,6 +621,14 @@ impl Type {
         matches!(self.follow_bindings(), Type::Integer(Signedness::Unsigned, _))
     }
 
+    /// True if this is a user-defined type declared with `enum`
+    pub fn is_enum(&self) -> bool {
+        match self.follow_bindings() {
+            Type::Struct(definition, _) => definition.borrow().is_enum(),
+            _ => false,
+        }
+    }
+
     fn contains_numeric_typevar(&self, target_id: TypeVariableId) -> bool {
         // True if the given type is a NamedGeneric with the target_id
         let named_generic_id_matches_target = |typ: &Type| {
@@ -640,6 +719,8 @@ impl Type {
             }
             Type::String(length) => length.is_valid_for_program_input(),
             Type::Tuple(elements) => elements.iter().all(|elem| elem.is_valid_for_program_input()),
+            // Enums have no representation in the ABI yet
+            Type::Struct(definition, _) if definition.borrow().is_enum() => false,
             Type::Struct(definition, generics) => definition
                 .borrow()
                 .get_fields(generics)
@@ -1646,6 +1727,19 @@ impl From<&Type> for PrintableType {
             Type::Error => unreachable!(),
             Type::Unit => unreachable!(),
             Type::Constant(_) => unreachable!(),
+            Type::Struct(def, ref args) if def.borrow().is_enum() => {
+                // Enums are printed following their monomorphized representation: the index
+                // of the active variant followed by the arguments of every variant.
+                let enum_type = def.borrow();
+                let tag = ("tag".to_string(), PrintableType::Field);
+                let variants = vecmap(enum_type.get_variants(args), |(name, arguments)| {
+                    let arguments = arguments.iter().enumerate();
+                    let fields = vecmap(arguments, |(i, typ)| (i.to_string(), typ.into()));
+                    (name.clone(), PrintableType::Struct { fields, name })
+                });
+                let fields = std::iter::once(tag).chain(variants).collect();
+                PrintableType::Struct { fields, name: enum_type.name.to_string() }
+            }
             Type::Struct(def, ref args) => {
                 let struct_type = def.borrow();
                 let fields = struct_type.get_fields(args);
@@ -70,6 +70,8 @@ pub enum Token {
     RightBracket,
     /// ->
     Arrow,
+    /// =>
+    FatArrow,
     /// |
     Pipe,
     /// #
@@ -191,6 +193,7 @@ impl fmt::Display for Token {
             Token::LeftBracket => write!(f, "["),
             Token::RightBracket => write!(f, "]"),
             Token::Arrow => write!(f, "->"),
+            Token::FatArrow => write!(f, "=>"),
             Token::Pipe => write!(f, "|"),
             Token::Pound => write!(f, "#"),
             Token::Comma => write!(f, ","),
@@ -642,15 +645,18 @@ pub enum Keyword {
     Assert,
     AssertEq,
     Bool,
+    Break,
     CallData,
     Char,
     CompTime,
     Constrain,
+    Continue,
     Contract,
     Crate,
     Dep,
     Distinct,
     Else,
+    Enum,
     Field,
     Fn,
     For,
@@ -661,6 +667,7 @@ pub enum Keyword {
     In,
     Internal,
     Let,
+    Match,
     Mod,
     Mut,
     Open,
@@ -684,15 +691,18 @@ impl fmt::Display for Keyword {
             Keyword::Assert => write!(f, "assert"),
             Keyword::AssertEq => write!(f, "assert_eq"),
             Keyword::Bool => write!(f, "bool"),
+            Keyword::Break => write!(f, "break"),
             Keyword::Char => write!(f, "char"),
             Keyword::CallData => write!(f, "call_data"),
             Keyword::CompTime => write!(f, "comptime"),
             Keyword::Constrain => write!(f, "constrain"),
+            Keyword::Continue => write!(f, "continue"),
             Keyword::Contract => write!(f, "contract"),
             Keyword::Crate => write!(f, "crate"),
             Keyword::Dep => write!(f, "dep"),
             Keyword::Distinct => write!(f, "distinct"),
             Keyword::Else => write!(f, "else"),
+            Keyword::Enum => write!(f, "enum"),
             Keyword::Field => write!(f, "Field"),
             Keyword::Fn => write!(f, "fn"),
             Keyword::For => write!(f, "for"),
@@ -703,6 +713,7 @@ impl fmt::Display for Keyword {
             Keyword::In => write!(f, "in"),
             Keyword::Internal => write!(f, "internal"),
             Keyword::Let => write!(f, "let"),
+            Keyword::Match => write!(f, "match"),
             Keyword::Mod => write!(f, "mod"),
             Keyword::Mut => write!(f, "mut"),
             Keyword::Open => write!(f, "open"),
@@ -729,15 +740,18 @@ impl Keyword {
             "assert" => Keyword::Assert,
             "assert_eq" => Keyword::AssertEq,
             "bool" => Keyword::Bool,
+            "break" => Keyword::Break,
             "call_data" => Keyword::CallData,
             "char" => Keyword::Char,
             "comptime" => Keyword::CompTime,
             "constrain" => Keyword::Constrain,
+            "continue" => Keyword::Continue,
             "contract" => Keyword::Contract,
             "crate" => Keyword::Crate,
             "dep" => Keyword::Dep,
             "distinct" => Keyword::Distinct,
             "else" => Keyword::Else,
+            "enum" => Keyword::Enum,
             "Field" => Keyword::Field,
             "fn" => Keyword::Fn,
             "for" => Keyword::For,
@@ -748,6 +762,7 @@ impl Keyword {
             "in" => Keyword::In,
             "internal" => Keyword::Internal,
             "let" => Keyword::Let,
+            "match" => Keyword::Match,
             "mod" => Keyword::Mod,
             "mut" => Keyword::Mut,
             "open" => Keyword::Open,
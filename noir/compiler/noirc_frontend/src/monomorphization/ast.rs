@@ -1,6 +1,9 @@
+use std::collections::BTreeMap;
+
 use acvm::FieldElement;
 use iter_extended::vecmap;
 use noirc_errors::Location;
+use noirc_printable_type::PrintableType;
 
 use crate::{
     hir_def::function::FunctionSignature, BinaryOpKind, Distinctness, Signedness, Visibility,
@@ -26,6 +29,7 @@ pub enum Expression {
     Index(Index),
     Cast(Cast),
     For(For),
+    While(While),
     If(If),
     Tuple(Vec<Expression>),
     ExtractTupleField(Box<Expression>, usize),
@@ -34,6 +38,8 @@ pub enum Expression {
     Constrain(Box<Expression>, Location, Option<String>),
     Assign(Assign),
     Semi(Box<Expression>),
+    Break,
+    Continue,
 }
 
 /// A definition is either a local (variable), function, or is a built-in
@@ -50,7 +56,7 @@ pub enum Definition {
 
 /// ID of a local definition, e.g. from a let binding or
 /// function parameter that should be compiled before it is referenced.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct LocalId(pub u32);
 
 /// A function ID corresponds directly to an index of `Program::functions`
@@ -80,6 +86,14 @@ pub struct For {
     pub end_range_location: Location,
 }
 
+#[derive(Debug, Clone, Hash)]
+pub struct While {
+    pub condition: Box<Expression>,
+    pub body: Box<Expression>,
+
+    pub condition_location: Location,
+}
+
 #[derive(Debug, Clone, Hash)]
 pub enum Literal {
     Array(ArrayLiteral),
@@ -246,6 +260,16 @@ pub struct Program {
     pub return_distinctness: Distinctness,
     pub return_location: Option<Location>,
     pub return_visibility: Visibility,
+    /// The named local variables whose values can be displayed by the debugger.
+    /// This is only filled in when monomorphizing with [`monomorphize_debug`][super::monomorphize_debug].
+    pub debug_variables: BTreeMap<LocalId, DebugVariable>,
+}
+
+/// A named local variable along with the type its value is decoded as for display.
+#[derive(Debug, Clone, Hash)]
+pub struct DebugVariable {
+    pub name: String,
+    pub typ: PrintableType,
 }
 
 impl Program {
@@ -255,6 +279,7 @@ impl Program {
         return_distinctness: Distinctness,
         return_location: Option<Location>,
         return_visibility: Visibility,
+        debug_variables: BTreeMap<LocalId, DebugVariable>,
     ) -> Program {
         Program {
             functions,
@@ -262,6 +287,7 @@ impl Program {
             return_distinctness,
             return_location,
             return_visibility,
+            debug_variables,
         }
     }
 
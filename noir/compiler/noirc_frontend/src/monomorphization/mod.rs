@@ -26,8 +26,8 @@ use crate::{
     },
     node_interner::{self, DefinitionKind, NodeInterner, StmtId, TraitImplKind, TraitMethodId},
     token::FunctionAttribute,
-    ContractFunctionType, FunctionKind, Type, TypeBinding, TypeBindings, TypeVariable,
-    TypeVariableId, TypeVariableKind, UnaryOp, Visibility,
+    BinaryOpKind, ContractFunctionType, FunctionKind, Type, TypeBinding, TypeBindings,
+    TypeVariable, TypeVariableId, TypeVariableKind, UnaryOp, Visibility,
 };
 
 use self::ast::{Definition, FuncId, Function, LocalId, Program};
@@ -40,6 +40,21 @@ struct LambdaContext {
     captures: Vec<HirCapturedVar>,
 }
 
+/// Constrained loops are always fully unrolled, so they cannot be exited early. Instead, each
+/// `break` or `continue` within a constrained loop sets a flag which guards the rest of the loop.
+/// The flags are created the first time they are needed.
+struct LoopFlags {
+    break_flag: Option<LocalId>,
+    continue_flag: Option<LocalId>,
+
+    /// Incremented each time a `break` or `continue` of this loop is monomorphized.
+    /// Comparing this before and after a statement tells us whether that statement
+    /// may stop the current loop iteration early.
+    jumps: usize,
+
+    location: Location,
+}
+
 /// The context struct for the monomorphization pass.
 ///
 /// This struct holds the FIFO queue of functions to monomorphize, which is added to
@@ -76,7 +91,18 @@ struct Monomorphizer<'interner> {
 
     is_range_loop: bool,
 
+    /// True while monomorphizing the body of an unconstrained function. `break` and `continue`
+    /// are only kept as-is in unconstrained code, see `LoopFlags`.
+    in_unconstrained_function: bool,
+
+    /// The constrained loops enclosing the current expression, innermost last.
+    loops: Vec<LoopFlags>,
+
     return_location: Option<Location>,
+
+    /// The named local variables the debugger can display, see [`monomorphize_debug`].
+    /// This is `None` when the program is not being compiled for the debugger.
+    debug_variables: Option<BTreeMap<LocalId, ast::DebugVariable>>,
 }
 
 type HirType = crate::Type;
@@ -94,7 +120,21 @@ type HirType = crate::Type;
 /// but it can also be, for example, an arbitrary test function for running `nargo test`.
 #[tracing::instrument(level = "trace", skip(main, interner))]
 pub fn monomorphize(main: node_interner::FuncId, interner: &NodeInterner) -> Program {
+    monomorphize_program(Monomorphizer::new(interner), main)
+}
+
+/// Monomorphizes the program in the same way as [`monomorphize`], also recording the name and
+/// type of each local variable in [`Program::debug_variables`] so that the compiled program
+/// lets the debugger display their values.
+#[tracing::instrument(level = "trace", skip(main, interner))]
+pub fn monomorphize_debug(main: node_interner::FuncId, interner: &NodeInterner) -> Program {
     let mut monomorphizer = Monomorphizer::new(interner);
+    monomorphizer.debug_variables = Some(BTreeMap::new());
+    monomorphize_program(monomorphizer, main)
+}
+
+fn monomorphize_program(mut monomorphizer: Monomorphizer, main: node_interner::FuncId) -> Program {
+    let interner = monomorphizer.interner;
     let function_sig = monomorphizer.compile_main(main);
 
     while !monomorphizer.queue.is_empty() {
@@ -117,6 +157,7 @@ pub fn monomorphize(main: node_interner::FuncId, interner: &NodeInterner) -> Pro
         meta.return_distinctness,
         monomorphizer.return_location,
         meta.return_visibility,
+        monomorphizer.debug_variables.unwrap_or_default(),
     )
 }
 
@@ -132,7 +173,10 @@ impl<'interner> Monomorphizer<'interner> {
             interner,
             lambda_envs_stack: Vec::new(),
             is_range_loop: false,
+            in_unconstrained_function: false,
+            loops: Vec::new(),
             return_location: None,
+            debug_variables: None,
         }
     }
 
@@ -202,6 +246,14 @@ impl<'interner> Monomorphizer<'interner> {
 
     fn define_local(&mut self, id: node_interner::DefinitionId, new_id: LocalId) {
         self.locals.insert(id, new_id);
+
+        if let Some(debug_variables) = &mut self.debug_variables {
+            let typ = self.interner.id_type(id).follow_bindings();
+            if is_printable(&typ) {
+                let name = self.interner.definition_name(id).to_owned();
+                debug_variables.insert(new_id, ast::DebugVariable { name, typ: typ.into() });
+            }
+        }
     }
 
     /// Prerequisite: typ = typ.follow_bindings()
@@ -243,10 +295,12 @@ impl<'interner> Monomorphizer<'interner> {
 
         let parameters = self.parameters(&meta.parameters);
 
-        let body = self.expr(body_expr_id);
         let unconstrained = modifiers.is_unconstrained
             || matches!(modifiers.contract_function_type, Some(ContractFunctionType::Open));
 
+        self.in_unconstrained_function = unconstrained;
+        let body = self.expr(body_expr_id);
+
         let function = ast::Function { id, name, parameters, body, return_type, unconstrained };
         self.push_function(id, function);
     }
@@ -423,11 +477,14 @@ impl<'interner> Monomorphizer<'interner> {
                 })
             }
 
+            HirExpression::Match(match_expr) => self.match_expr(match_expr, expr),
+
             HirExpression::Tuple(fields) => {
                 let fields = vecmap(fields, |id| self.expr(id));
                 ast::Expression::Tuple(fields)
             }
             HirExpression::Constructor(constructor) => self.constructor(constructor, expr),
+            HirExpression::EnumConstructor(constructor) => self.enum_constructor(constructor, expr),
 
             HirExpression::Lambda(lambda) => self.lambda(lambda, expr),
 
@@ -495,9 +552,22 @@ impl<'interner> Monomorphizer<'interner> {
                 let index_variable = self.next_local_id();
                 self.define_local(for_loop.identifier.id, index_variable);
 
-                let block = Box::new(self.expr(for_loop.block));
+                let location = self.interner.expr_location(&for_loop.block);
+                let (block, flags) = self.loop_body(for_loop.block, location);
+
+                // Once a constrained loop is broken out of, the remaining iterations must do nothing
+                let block = match flags.as_ref().and_then(|flags| flags.break_flag) {
+                    Some(break_flag) => ast::Expression::If(ast::If {
+                        condition: Box::new(Self::not_flag(break_flag, "break_flag", location)),
+                        consequence: Box::new(block),
+                        alternative: None,
+                        typ: ast::Type::Unit,
+                    }),
+                    None => block,
+                };
+                let block = Box::new(block);
 
-                ast::Expression::For(ast::For {
+                let for_loop = ast::Expression::For(ast::For {
                     index_variable,
                     index_name: self.interner.definition_name(for_loop.identifier.id).to_owned(),
                     index_type: self.convert_type(&self.interner.id_type(for_loop.start_range)),
@@ -506,14 +576,152 @@ impl<'interner> Monomorphizer<'interner> {
                     start_range_location: self.interner.expr_location(&for_loop.start_range),
                     end_range_location: self.interner.expr_location(&for_loop.end_range),
                     block,
-                })
+                });
+                self.declare_break_flag(for_loop, flags)
             }
+            HirStatement::While(while_loop) => {
+                let condition_location = self.interner.expr_location(&while_loop.condition);
+                let condition = self.expr(while_loop.condition);
+
+                let location = self.interner.expr_location(&while_loop.block);
+                let (body, flags) = self.loop_body(while_loop.block, location);
+
+                // Once a constrained loop is broken out of, it must stop looping
+                let condition = match flags.as_ref().and_then(|flags| flags.break_flag) {
+                    Some(break_flag) => ast::Expression::Binary(ast::Binary {
+                        lhs: Box::new(Self::not_flag(break_flag, "break_flag", location)),
+                        operator: BinaryOpKind::And,
+                        rhs: Box::new(condition),
+                        location,
+                    }),
+                    None => condition,
+                };
+
+                let while_loop = ast::Expression::While(ast::While {
+                    condition: Box::new(condition),
+                    body: Box::new(body),
+                    condition_location,
+                });
+                self.declare_break_flag(while_loop, flags)
+            }
+            HirStatement::Break => self.loop_jump(true),
+            HirStatement::Continue => self.loop_jump(false),
             HirStatement::Expression(expr) => self.expr(expr),
             HirStatement::Semi(expr) => ast::Expression::Semi(Box::new(self.expr(expr))),
             HirStatement::Error => unreachable!(),
         }
     }
 
+    /// Monomorphize the body of a loop. If the loop is constrained, this also returns the
+    /// flags used by any `break` or `continue` within it, and resets the continue flag at
+    /// the start of each iteration.
+    fn loop_body(
+        &mut self,
+        body: node_interner::ExprId,
+        location: Location,
+    ) -> (ast::Expression, Option<LoopFlags>) {
+        if self.in_unconstrained_function {
+            return (self.expr(body), None);
+        }
+
+        self.loops.push(LoopFlags { break_flag: None, continue_flag: None, jumps: 0, location });
+        let body = self.expr(body);
+        let flags = self.loops.pop().unwrap();
+
+        let body = match flags.continue_flag {
+            Some(continue_flag) => ast::Expression::Block(vec![
+                Self::declare_flag(continue_flag, "continue_flag"),
+                body,
+            ]),
+            None => body,
+        };
+        (body, Some(flags))
+    }
+
+    /// Declares the break flag of a constrained loop, if it has one, before the loop itself.
+    fn declare_break_flag(
+        &mut self,
+        loop_expr: ast::Expression,
+        flags: Option<LoopFlags>,
+    ) -> ast::Expression {
+        match flags.and_then(|flags| flags.break_flag) {
+            Some(break_flag) => ast::Expression::Block(vec![
+                Self::declare_flag(break_flag, "break_flag"),
+                loop_expr,
+            ]),
+            None => loop_expr,
+        }
+    }
+
+    fn declare_flag(id: LocalId, name: &str) -> ast::Expression {
+        let expression = Box::new(ast::Expression::Literal(ast::Literal::Bool(false)));
+        ast::Expression::Let(ast::Let { id, mutable: true, name: name.to_owned(), expression })
+    }
+
+    fn flag_ident(id: LocalId, name: &str) -> ast::Ident {
+        ast::Ident {
+            location: None,
+            definition: Definition::Local(id),
+            mutable: true,
+            name: name.to_owned(),
+            typ: ast::Type::Bool,
+        }
+    }
+
+    fn not_flag(id: LocalId, name: &str, location: Location) -> ast::Expression {
+        let flag = ast::Expression::Ident(Self::flag_ident(id, name));
+        ast::Expression::Unary(ast::Unary {
+            operator: UnaryOp::Not,
+            rhs: Box::new(flag),
+            result_type: ast::Type::Bool,
+            location,
+        })
+    }
+
+    /// Monomorphize a `break` or `continue`. Within unconstrained code these remain as-is.
+    /// Within constrained code they instead set the corresponding flag of the innermost loop.
+    fn loop_jump(&mut self, is_break: bool) -> ast::Expression {
+        let Some(flags) = self.loops.last() else {
+            return if is_break { ast::Expression::Break } else { ast::Expression::Continue };
+        };
+
+        let existing_flag = if is_break { flags.break_flag } else { flags.continue_flag };
+        let id = existing_flag.unwrap_or_else(|| self.next_local_id());
+
+        let flags = self.loops.last_mut().unwrap();
+        flags.jumps += 1;
+        if is_break {
+            flags.break_flag = Some(id);
+        } else {
+            flags.continue_flag = Some(id);
+        }
+
+        let name = if is_break { "break_flag" } else { "continue_flag" };
+        ast::Expression::Assign(ast::Assign {
+            lvalue: ast::LValue::Ident(Self::flag_ident(id, name)),
+            expression: Box::new(ast::Expression::Literal(ast::Literal::Bool(true))),
+        })
+    }
+
+    /// Returns a condition which is true if the current iteration of the innermost constrained
+    /// loop has not yet been stopped by a `break` or `continue`.
+    fn loop_iteration_active(&self) -> ast::Expression {
+        let flags = self.loops.last().expect("Expected to be within a constrained loop");
+        let location = flags.location;
+
+        match (flags.break_flag, flags.continue_flag) {
+            (Some(break_flag), Some(continue_flag)) => ast::Expression::Binary(ast::Binary {
+                lhs: Box::new(Self::not_flag(break_flag, "break_flag", location)),
+                operator: BinaryOpKind::And,
+                rhs: Box::new(Self::not_flag(continue_flag, "continue_flag", location)),
+                location,
+            }),
+            (Some(break_flag), None) => Self::not_flag(break_flag, "break_flag", location),
+            (None, Some(continue_flag)) => Self::not_flag(continue_flag, "continue_flag", location),
+            (None, None) => unreachable!("Expected a break or continue flag to be set"),
+        }
+    }
+
     fn let_statement(&mut self, let_statement: HirLetStatement) -> ast::Expression {
         let expr = self.expr(let_statement.expression);
         let expected_type = self.interner.id_type(let_statement.expression);
@@ -569,8 +777,182 @@ impl<'interner> Monomorphizer<'interner> {
         ast::Expression::Block(new_exprs)
     }
 
+    /// Enums are represented as a tuple of the index of the active variant followed by
+    /// one tuple per variant holding that variant's arguments. The arguments of every
+    /// inactive variant are zeroed.
+    fn enum_constructor(
+        &mut self,
+        constructor: HirEnumConstructorExpression,
+        id: node_interner::ExprId,
+    ) -> ast::Expression {
+        let location = self.interner.expr_location(&id);
+        let variant_types = match self.convert_type(&self.interner.id_type(id)) {
+            ast::Type::Tuple(fields) => fields,
+            other => unreachable!("Expected enum to be represented as a tuple, found {other}"),
+        };
+
+        let tag = FieldElement::from(constructor.variant_index as u128);
+        let mut fields =
+            vec![ast::Expression::Literal(ast::Literal::Integer(tag, ast::Type::Field, location))];
+
+        let mut arguments = Some(constructor.arguments);
+        for (i, variant_type) in variant_types.iter().skip(1).enumerate() {
+            let field = if i == constructor.variant_index {
+                let arguments = arguments.take().unwrap_or_default();
+                ast::Expression::Tuple(vecmap(arguments, |argument| self.expr(argument)))
+            } else {
+                self.zeroed_value_of_type(variant_type, location)
+            };
+            fields.push(field);
+        }
+
+        ast::Expression::Tuple(fields)
+    }
+
+    /// Lowers a match into a chain of if expressions checking the variant index of the
+    /// matched value. Since each arm is then a plain branch, the result can be flattened
+    /// like any other if expression in constrained code.
+    fn match_expr(
+        &mut self,
+        match_expr: HirMatchExpression,
+        id: node_interner::ExprId,
+    ) -> ast::Expression {
+        let typ = self.convert_type(&self.interner.id_type(id));
+        let location = self.interner.expr_location(&match_expr.expression);
+
+        let scrutinee_hir_type = self.interner.id_type(match_expr.expression);
+        let scrutinee_id = self.next_local_id();
+        let scrutinee_name = "match".to_string();
+        let scrutinee_let = ast::Expression::Let(ast::Let {
+            id: scrutinee_id,
+            mutable: false,
+            name: scrutinee_name.clone(),
+            expression: Box::new(self.expr(match_expr.expression)),
+        });
+
+        let scrutinee = ast::Expression::Ident(ast::Ident {
+            location: None,
+            mutable: false,
+            definition: Definition::Local(scrutinee_id),
+            name: scrutinee_name,
+            typ: self.convert_type(&scrutinee_hir_type),
+        });
+
+        // Any arms after the first irrefutable one can never be reached
+        let mut arms = match_expr.arms;
+        if let Some(index) = arms.iter().position(|arm| arm.pattern.is_irrefutable()) {
+            arms.truncate(index + 1);
+        }
+
+        // Build the chain from the last arm backward. If the last arm matches a variant
+        // then the match is exhaustive without a catch-all and that arm needs no condition.
+        let mut chain: Option<ast::Expression> = None;
+        for arm in arms.into_iter().rev() {
+            let expr = match arm.pattern {
+                HirMatchPattern::Wildcard(_) => self.expr(arm.branch),
+                HirMatchPattern::Binding(ident) => {
+                    let value = scrutinee.clone();
+                    let binding = self.unpack_pattern(
+                        HirPattern::Identifier(ident),
+                        value,
+                        &scrutinee_hir_type,
+                    );
+                    ast::Expression::Block(vec![binding, self.expr(arm.branch)])
+                }
+                HirMatchPattern::Variant { variant_index, arguments, .. } => {
+                    let (_, argument_types) =
+                        unwrap_enum_type(&scrutinee_hir_type).swap_remove(variant_index);
+
+                    let variant_arguments = ast::Expression::ExtractTupleField(
+                        Box::new(scrutinee.clone()),
+                        variant_index + 1,
+                    );
+                    let arguments = arguments.into_iter().zip(argument_types);
+                    let bindings = self.unpack_tuple_pattern(variant_arguments, arguments);
+                    let branch = ast::Expression::Block(vec![bindings, self.expr(arm.branch)]);
+
+                    match chain.take() {
+                        None => branch,
+                        Some(alternative) => {
+                            let tag = FieldElement::from(variant_index as u128);
+                            let tag = ast::Literal::Integer(tag, ast::Type::Field, location);
+
+                            let condition = ast::Expression::Binary(ast::Binary {
+                                lhs: Box::new(ast::Expression::ExtractTupleField(
+                                    Box::new(scrutinee.clone()),
+                                    0,
+                                )),
+                                operator: crate::BinaryOpKind::Equal,
+                                rhs: Box::new(ast::Expression::Literal(tag)),
+                                location,
+                            });
+
+                            ast::Expression::If(ast::If {
+                                condition: Box::new(condition),
+                                consequence: Box::new(branch),
+                                alternative: Some(Box::new(alternative)),
+                                typ: typ.clone(),
+                            })
+                        }
+                    }
+                }
+            };
+            chain = Some(expr);
+        }
+
+        let chain = chain.unwrap_or_else(|| ast::Expression::Block(vec![]));
+        ast::Expression::Block(vec![scrutinee_let, chain])
+    }
+
     fn block(&mut self, statement_ids: Vec<StmtId>) -> ast::Expression {
-        ast::Expression::Block(vecmap(statement_ids, |id| self.statement(id)))
+        ast::Expression::Block(self.statements(&statement_ids))
+    }
+
+    /// Monomorphize each statement in a block. Within a constrained loop, any statements
+    /// following one which may `break` or `continue` are only executed if the loop
+    /// iteration is still active.
+    fn statements(&mut self, statement_ids: &[StmtId]) -> Vec<ast::Expression> {
+        let mut statements = Vec::with_capacity(statement_ids.len());
+
+        for (i, id) in statement_ids.iter().enumerate() {
+            let jumps_before = self.loops.last().map(|flags| flags.jumps);
+            statements.push(self.statement(*id));
+
+            let remaining = &statement_ids[i + 1..];
+            let jumps_after = self.loops.last().map(|flags| flags.jumps);
+            if !remaining.is_empty() && jumps_before != jumps_after {
+                statements.push(self.remaining_statements_in_loop(remaining));
+                break;
+            }
+        }
+
+        statements
+    }
+
+    /// Wraps the given statements in an `if` checking the current loop iteration is still active.
+    /// If the statements produce a value, a zeroed value is produced otherwise.
+    fn remaining_statements_in_loop(&mut self, statement_ids: &[StmtId]) -> ast::Expression {
+        let typ = match self.interner.statement(statement_ids.last().unwrap()) {
+            HirStatement::Expression(expr) => self.convert_type(&self.interner.id_type(expr)),
+            _ => ast::Type::Unit,
+        };
+
+        let consequence = ast::Expression::Block(self.statements(statement_ids));
+        let condition = self.loop_iteration_active();
+
+        let alternative = if typ == ast::Type::Unit {
+            None
+        } else {
+            let location = self.loops.last().unwrap().location;
+            Some(Box::new(self.zeroed_value_of_type(&typ, location)))
+        };
+
+        ast::Expression::If(ast::If {
+            condition: Box::new(condition),
+            consequence: Box::new(consequence),
+            alternative,
+            typ,
+        })
     }
 
     fn unpack_pattern(
@@ -784,6 +1166,16 @@ impl<'interner> Monomorphizer<'interner> {
                 monomorphized_default
             }
 
+            HirType::Struct(def, args) if def.borrow().is_enum() => {
+                // The variant index followed by the arguments of each variant
+                let variants = def.borrow().get_variants(args);
+                let variants = variants.into_iter().map(|(_, arguments)| {
+                    ast::Type::Tuple(vecmap(arguments, |argument| self.convert_type(&argument)))
+                });
+                let fields = std::iter::once(ast::Type::Field).chain(variants).collect();
+                ast::Type::Tuple(fields)
+            }
+
             HirType::Struct(def, args) => {
                 let fields = def.borrow().get_fields(args);
                 let fields = vecmap(fields, |(_, field)| self.convert_type(&field));
@@ -1171,12 +1563,20 @@ impl<'interner> Monomorphizer<'interner> {
     }
 
     fn lambda(&mut self, lambda: HirLambda, expr: node_interner::ExprId) -> ast::Expression {
-        if lambda.captures.is_empty() {
+        // Lambdas are always constrained and are never within the loops enclosing them
+        let in_unconstrained_function = std::mem::take(&mut self.in_unconstrained_function);
+        let loops = std::mem::take(&mut self.loops);
+
+        let lambda = if lambda.captures.is_empty() {
             self.lambda_no_capture(lambda)
         } else {
             let (setup, closure_variable) = self.lambda_with_setup(lambda, expr);
             ast::Expression::Block(vec![setup, closure_variable])
-        }
+        };
+
+        self.in_unconstrained_function = in_unconstrained_function;
+        self.loops = loops;
+        lambda
     }
 
     fn lambda_no_capture(&mut self, lambda: HirLambda) -> ast::Expression {
@@ -1571,6 +1971,40 @@ fn unwrap_struct_type(typ: &HirType) -> Vec<(String, HirType)> {
     }
 }
 
+fn unwrap_enum_type(typ: &HirType) -> Vec<(String, Vec<HirType>)> {
+    match typ {
+        HirType::Struct(def, args) => def.borrow().get_variants(args),
+        HirType::TypeVariable(binding, TypeVariableKind::Normal) => match &*binding.borrow() {
+            TypeBinding::Bound(binding) => unwrap_enum_type(binding),
+            TypeBinding::Unbound(_) => unreachable!(),
+        },
+        other => unreachable!("unwrap_enum_type: expected enum, found {:?}", other),
+    }
+}
+
+/// Returns whether values of this type can be displayed, i.e. converted into a [`PrintableType`].
+/// Expects the type's bindings to have been followed.
+fn is_printable(typ: &HirType) -> bool {
+    match typ {
+        HirType::FieldElement | HirType::Integer(..) | HirType::Bool => true,
+        HirType::TypeVariable(_, TypeVariableKind::IntegerOrField) => true,
+        HirType::Array(size, element) => {
+            size.evaluate_to_u64().is_some() && is_printable(element.as_ref())
+        }
+        HirType::String(size) => size.evaluate_to_u64().is_some(),
+        HirType::Struct(def, args) if def.borrow().is_enum() => def
+            .borrow()
+            .get_variants(args)
+            .iter()
+            .flat_map(|(_, arguments)| arguments)
+            .all(is_printable),
+        HirType::Struct(def, args) => {
+            def.borrow().get_fields(args).iter().all(|(_, field)| is_printable(field))
+        }
+        _ => false,
+    }
+}
+
 fn perform_instantiation_bindings(bindings: &TypeBindings) {
     for (var, binding) in bindings.values() {
         var.force_bind(binding.clone());
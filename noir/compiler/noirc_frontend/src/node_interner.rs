@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use arena::{Arena, Index};
 use fm::FileId;
@@ -7,7 +7,9 @@ use noirc_errors::{Location, Span, Spanned};
 
 use crate::ast::Ident;
 use crate::graph::CrateId;
-use crate::hir::def_collector::dc_crate::{UnresolvedStruct, UnresolvedTrait, UnresolvedTypeAlias};
+use crate::hir::def_collector::dc_crate::{
+    UnresolvedEnum, UnresolvedStruct, UnresolvedTrait, UnresolvedTypeAlias,
+};
 use crate::hir::def_map::{LocalModuleId, ModuleId};
 
 use crate::hir_def::stmt::HirLetStatement;
@@ -142,6 +144,28 @@ pub struct NodeInterner {
 
     // For trait implementation functions, this is their self type and trait they belong to
     func_id_to_trait: HashMap<FuncId, (Type, TraitId)>,
+
+    /// The item declared or referred to by each name in the source program, keyed by the file
+    /// and start of the name and holding the end of the name. Names never overlap, so the name
+    /// containing a location is the last one starting before it.
+    reference_locations: BTreeMap<(FileId, u32), (u32, ReferenceId)>,
+
+    /// The location of the name given to each item where it is declared
+    reference_declarations: HashMap<ReferenceId, Location>,
+
+    /// The locations of the names referring to each item, excluding its declaration
+    references: HashMap<ReferenceId, Vec<Location>>,
+}
+
+/// An item of the source program which can be declared and referred to by name.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ReferenceId {
+    Definition(DefinitionId),
+    /// A struct or an enum
+    Struct(StructId),
+    /// The field of a struct, given by its index
+    StructMember(StructId, usize),
+    Trait(TraitId),
 }
 
 /// A trait implementation is either a normal implementation that is present in the source
@@ -437,6 +461,9 @@ impl Default for NodeInterner {
             globals: HashMap::new(),
             struct_methods: HashMap::new(),
             primitive_methods: HashMap::new(),
+            reference_locations: BTreeMap::new(),
+            reference_declarations: HashMap::new(),
+            references: HashMap::new(),
         };
 
         // An empty block expression is used often, we add this into the `node` on startup
@@ -522,6 +549,11 @@ impl NodeInterner {
             types: Vec::new(),
         };
 
+        let name_span = unresolved_trait.trait_def.name.span();
+        self.add_declaration(
+            ReferenceId::Trait(type_id),
+            Location::new(name_span, unresolved_trait.file_id),
+        );
         self.traits.insert(type_id, new_trait);
     }
 
@@ -546,6 +578,9 @@ impl NodeInterner {
             (id, TypeVariable::unbound(id))
         });
 
+        let name_location = Location::new(name.span(), file_id);
+        self.add_declaration(ReferenceId::Struct(struct_id), name_location);
+
         let location = Location::new(typ.struct_def.span, file_id);
         let new_struct = StructType::new(struct_id, name, location, no_fields, generics);
         self.structs.insert(struct_id, Shared::new(new_struct));
@@ -553,6 +588,34 @@ impl NodeInterner {
         struct_id
     }
 
+    /// Enums share the same StructType representation as structs. The type is
+    /// created here without any variants, these are filled in once the enum is resolved.
+    pub fn new_enum(
+        &mut self,
+        typ: &UnresolvedEnum,
+        krate: CrateId,
+        local_id: LocalModuleId,
+        file_id: FileId,
+    ) -> StructId {
+        let enum_id = StructId(ModuleId { krate, local_id });
+        let name = typ.enum_def.name.clone();
+
+        let generics = vecmap(&typ.enum_def.generics, |_| {
+            // Temporary type variable ids before the enum is resolved to its actual ids.
+            let id = TypeVariableId(0);
+            (id, TypeVariable::unbound(id))
+        });
+
+        let name_location = Location::new(name.span(), file_id);
+        self.add_declaration(ReferenceId::Struct(enum_id), name_location);
+
+        let location = Location::new(typ.enum_def.span, file_id);
+        let new_enum = StructType::new(enum_id, name, location, Vec::new(), generics);
+        self.structs.insert(enum_id, Shared::new(new_enum));
+        self.struct_attributes.insert(enum_id, typ.enum_def.attributes.clone());
+        enum_id
+    }
+
     pub fn push_type_alias(&mut self, typ: &UnresolvedTypeAlias) -> TypeAliasId {
         let type_id = TypeAliasId(self.type_aliases.len());
 
@@ -668,6 +731,10 @@ impl NodeInterner {
     /// Note that the FuncId has been created already.
     /// See ModCollector for it's usage.
     pub fn push_fn_meta(&mut self, func_data: FuncMeta, func_id: FuncId) {
+        // The location of a function's definition spans its whole body, so its name is
+        // declared once the location of the name is known.
+        let id = ReferenceId::Definition(func_data.name.id);
+        self.add_declaration(id, func_data.name.location);
         self.func_meta.insert(func_id, func_data);
     }
 
@@ -679,8 +746,14 @@ impl NodeInterner {
         location: Location,
     ) -> DefinitionId {
         let id = DefinitionId(self.definitions.len());
-        if let DefinitionKind::Function(func_id) = definition {
-            self.function_definition_ids.insert(func_id, id);
+        match definition {
+            DefinitionKind::Function(func_id) => {
+                self.function_definition_ids.insert(func_id, id);
+            }
+            DefinitionKind::Local(_) | DefinitionKind::Global(_) => {
+                self.add_declaration(ReferenceId::Definition(id), location);
+            }
+            DefinitionKind::GenericType(_) => (),
         }
 
         self.definitions.push(DefinitionInfo { name, mutable, kind: definition, location });
@@ -899,6 +972,19 @@ impl NodeInterner {
         self.globals.clone()
     }
 
+    /// Returns the ids of all functions which have been given metadata during name resolution.
+    pub fn get_all_function_ids(&self) -> impl Iterator<Item = FuncId> + '_ {
+        self.func_meta.keys().copied()
+    }
+
+    pub fn get_all_structs(&self) -> impl Iterator<Item = &Shared<StructType>> {
+        self.structs.values()
+    }
+
+    pub fn get_all_traits(&self) -> impl Iterator<Item = &Trait> {
+        self.traits.values()
+    }
+
     /// Returns the type of an item stored in the Interner or Error if it was not found.
     pub fn id_type(&self, index: impl Into<Index>) -> Type {
         self.id_to_type.get(&index.into()).cloned().unwrap_or(Type::Error)
@@ -1242,6 +1328,36 @@ impl NodeInterner {
         self.find_matching_method(typ, methods, method_name)
     }
 
+    /// Returns the name and id of every method which may be called on a value of the given type,
+    /// including methods from trait impls. Methods from impls for all types `T` are not included.
+    pub fn get_methods_for_type(&self, typ: &Type) -> Vec<(&str, FuncId)> {
+        let methods: Vec<_> = match typ.follow_bindings() {
+            Type::Struct(struct_type, _) => {
+                let id = struct_type.borrow().id;
+                self.struct_methods
+                    .iter()
+                    .filter(|((struct_id, _), _)| *struct_id == id)
+                    .map(|((_, name), methods)| (name, methods))
+                    .collect()
+            }
+            typ => {
+                let Some(key) = get_type_method_key(&typ) else {
+                    return Vec::new();
+                };
+                self.primitive_methods
+                    .iter()
+                    .filter(|((method_key, _), _)| *method_key == key)
+                    .map(|((_, name), methods)| (name, methods))
+                    .collect()
+            }
+        };
+
+        methods
+            .into_iter()
+            .flat_map(|(name, methods)| methods.iter().map(move |id| (name.as_str(), id)))
+            .collect()
+    }
+
     pub fn lookup_primitive_trait_method_mut(
         &self,
         typ: &Type,
@@ -1308,7 +1424,9 @@ impl NodeInterner {
                     DefinitionKind::Function(func_id) => {
                         Some(self.function_meta(&func_id).location)
                     }
-                    DefinitionKind::Local(_local_id) => Some(definition_info.location),
+                    DefinitionKind::Local(_) | DefinitionKind::Global(_) => {
+                        Some(definition_info.location)
+                    }
                     _ => None,
                 }
             }
@@ -1328,6 +1446,67 @@ impl NodeInterner {
         }
     }
 
+    /// Returns the innermost expression whose [Location] contains the given [Location].
+    pub fn find_expression_at(&self, location: Location) -> Option<ExprId> {
+        let index = self.find_location_index(location)?.into();
+        matches!(self.nodes.get(index), Some(Node::Expression(_))).then_some(ExprId(index))
+    }
+
+    /// Records that the name at the given [Location] declares the given item.
+    pub fn add_declaration(&mut self, id: ReferenceId, location: Location) {
+        self.reference_declarations.insert(id, location);
+        self.add_reference_location(id, location);
+    }
+
+    /// Records that the name at the given [Location] refers to the given item.
+    pub fn add_reference(&mut self, id: ReferenceId, location: Location) {
+        self.references.entry(id).or_default().push(location);
+        self.add_reference_location(id, location);
+    }
+
+    fn add_reference_location(&mut self, id: ReferenceId, location: Location) {
+        let key = (location.file, location.span.start());
+        self.reference_locations.insert(key, (location.span.end(), id));
+    }
+
+    /// Returns the item declared or referred to by the name at the given [Location],
+    /// along with the [Location] of that name.
+    pub fn find_reference_at(&self, location: Location) -> Option<(ReferenceId, Location)> {
+        let key = (location.file, location.span.start());
+        let (&(file, start), &(end, id)) = self.reference_locations.range(..=key).next_back()?;
+        let name_location = Location::new(Span::from(start..end), file);
+        name_location.contains(&location).then_some((id, name_location))
+    }
+
+    /// Returns the definition referred to by the identifier at the given [Location],
+    /// or the definition whose name is declared at that [Location].
+    pub fn find_definition_at(&self, location: Location) -> Option<DefinitionId> {
+        match self.find_reference_at(location)? {
+            (ReferenceId::Definition(id), _) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Returns the [Location] of the name given to an item where it is declared.
+    pub fn reference_declaration(&self, id: ReferenceId) -> Option<Location> {
+        self.reference_declarations.get(&id).copied()
+    }
+
+    /// Returns the locations of all names which refer to the given item, sorted by file
+    /// and position. Only the final segment of a path is a reference to the item, so that
+    /// these locations can be replaced when renaming the item.
+    pub fn find_all_references(&self, id: ReferenceId, include_declaration: bool) -> Vec<Location> {
+        let mut references = self.references.get(&id).cloned().unwrap_or_default();
+
+        if include_declaration {
+            references.extend(self.reference_declaration(id));
+        }
+
+        references.sort_by_key(|location| (location.file, location.span));
+        references.dedup();
+        references
+    }
+
     /// Resolves the [Location] of the definition for a given [crate::hir_def::expr::HirMemberAccess]
     /// This is used to resolve the location of a struct member access.
     /// For example, in the expression `foo.bar` we want to resolve the location of `bar`
@@ -38,11 +38,12 @@ use crate::parser::{force, ignore_then_commit, statement_recovery};
 use crate::token::{Attribute, Attributes, Keyword, SecondaryAttribute, Token, TokenKind};
 use crate::{
     BinaryOp, BinaryOpKind, BlockExpression, ConstrainKind, ConstrainStatement, Distinctness,
-    ForLoopStatement, ForRange, FunctionDefinition, FunctionReturnType, FunctionVisibility, Ident,
-    IfExpression, InfixExpression, LValue, Lambda, Literal, NoirFunction, NoirStruct, NoirTrait,
-    NoirTraitImpl, NoirTypeAlias, Param, Path, PathKind, Pattern, Recoverable, Statement,
-    TraitBound, TraitImplItem, TraitItem, TypeImpl, UnaryOp, UnresolvedTraitConstraint,
-    UnresolvedTypeExpression, UseTree, UseTreeKind, Visibility,
+    EnumVariant, ForLoopStatement, ForRange, FunctionDefinition, FunctionReturnType,
+    FunctionVisibility, Ident, IfExpression, InfixExpression, LValue, Lambda, Literal,
+    MatchExpression, MatchPattern, NoirEnum, NoirFunction, NoirStruct, NoirTrait, NoirTraitImpl,
+    NoirTypeAlias, Param, Path, PathKind, Pattern, Recoverable, Statement, TraitBound,
+    TraitImplItem, TraitItem, TypeImpl, UnaryOp, UnresolvedTraitConstraint,
+    UnresolvedTypeExpression, UseTree, UseTreeKind, Visibility, WhileLoopStatement,
 };
 
 use chumsky::prelude::*;
@@ -84,6 +85,7 @@ fn module() -> impl NoirParser<ParsedModule> {
                     TopLevelStatement::Module(m) => push_item(ItemKind::ModuleDecl(m)),
                     TopLevelStatement::Import(i) => push_item(ItemKind::Import(i)),
                     TopLevelStatement::Struct(s) => push_item(ItemKind::Struct(s)),
+                    TopLevelStatement::Enum(e) => push_item(ItemKind::Enum(e)),
                     TopLevelStatement::Trait(t) => push_item(ItemKind::Trait(t)),
                     TopLevelStatement::TraitImpl(t) => push_item(ItemKind::TraitImpl(t)),
                     TopLevelStatement::Impl(i) => push_item(ItemKind::Impl(i)),
@@ -99,6 +101,7 @@ fn module() -> impl NoirParser<ParsedModule> {
 
 /// top_level_statement: function_definition
 ///                    | struct_definition
+///                    | enum_definition
 ///                    | trait_definition
 ///                    | implementation
 ///                    | submodule
@@ -111,6 +114,7 @@ fn top_level_statement(
     choice((
         function_definition(false).map(TopLevelStatement::Function),
         struct_definition(),
+        enum_definition(),
         trait_definition(),
         trait_implementation(),
         implementation(),
@@ -274,6 +278,46 @@ fn struct_definition() -> impl NoirParser<TopLevelStatement> {
         })
 }
 
+/// enum_definition: attributes? 'enum' ident generics '{' enum_variants '}'
+///
+/// enum_variants: enum_variant ',' enum_variants
+///              | enum_variant ','?
+///              | %empty
+///
+/// enum_variant: ident '(' type_list ')'
+///             | ident
+fn enum_definition() -> impl NoirParser<TopLevelStatement> {
+    use self::Keyword::Enum;
+    use Token::*;
+
+    let variant_arguments = parse_type().separated_by(just(Comma)).allow_trailing();
+    let variant = ident()
+        .then(parenthesized(variant_arguments).or_not())
+        .map(|(name, arguments)| EnumVariant { name, arguments: arguments.unwrap_or_default() });
+
+    let variants = variant
+        .separated_by(just(Comma))
+        .allow_trailing()
+        .delimited_by(just(LeftBrace), just(RightBrace))
+        .recover_with(nested_delimiters(
+            LeftBrace,
+            RightBrace,
+            [(LeftParen, RightParen), (LeftBracket, RightBracket)],
+            |_| vec![],
+        ));
+
+    attributes()
+        .or_not()
+        .then_ignore(keyword(Enum))
+        .then(ident())
+        .then(generics())
+        .then(variants)
+        .validate(|(((raw_attributes, name), generics), variants), span, emit| {
+            let attributes = validate_struct_attributes(raw_attributes, span, emit);
+            TopLevelStatement::Enum(NoirEnum { name, attributes, generics, variants, span })
+        })
+}
+
 fn type_alias_definition() -> impl NoirParser<TopLevelStatement> {
     use self::Keyword::Type;
 
@@ -803,7 +847,10 @@ where
             assertion_eq(expr_parser.clone()),
             declaration(expr_parser.clone()),
             assignment(expr_parser.clone()),
-            for_loop(expr_no_constructors, statement),
+            for_loop(expr_no_constructors.clone(), statement.clone()),
+            while_loop(expr_no_constructors, statement),
+            break_statement(),
+            continue_statement(),
             return_statement(expr_parser.clone()),
             expr_parser.map(StatementKind::Expression),
         ))
@@ -1474,6 +1521,78 @@ where
     })
 }
 
+/// match_expr: 'match' expression '{' match_arms '}'
+///
+/// match_arms: match_pattern '=>' expression ',' match_arms
+///           | match_pattern '=>' block_expression match_arms
+///           | match_pattern '=>' expression ','?
+///           | %empty
+fn match_expr<'a, P, P2>(
+    expr_parser: P,
+    expr_no_constructors: P2,
+) -> impl NoirParser<ExpressionKind> + 'a
+where
+    P: ExprParser + 'a,
+    P2: ExprParser + 'a,
+{
+    let arm = match_pattern()
+        .then_ignore(just(Token::FatArrow))
+        .then(expr_parser)
+        .then(just(Token::Comma).or_not().map(|comma| comma.is_some()));
+
+    let arms = arm.repeated().validate(|arms, _span, emit| {
+        let last_arm = arms.len().saturating_sub(1);
+        vecmap(arms.into_iter().enumerate(), |(i, ((pattern, branch), has_comma))| {
+            // As in rust, the comma separating two arms is only optional after a block
+            let is_block = matches!(branch.kind, ExpressionKind::Block(_));
+            if !has_comma && !is_block && i != last_arm {
+                emit(ParserError::with_reason(
+                    ParserErrorReason::MissingCommaBetweenMatchArms,
+                    branch.span,
+                ));
+            }
+            (pattern, branch)
+        })
+    });
+
+    keyword(Keyword::Match)
+        .ignore_then(expr_no_constructors)
+        .then(arms.delimited_by(just(Token::LeftBrace), just(Token::RightBrace)))
+        .map(|(expression, arms)| {
+            ExpressionKind::Match(Box::new(MatchExpression { expression, arms }))
+        })
+}
+
+/// match_pattern: path '(' pattern_list ')'
+///              | path
+///
+/// A plain path with a single segment is a wildcard if it is `_` and a binding otherwise,
+/// any other path refers to an enum variant.
+fn match_pattern() -> impl NoirParser<MatchPattern> {
+    let variant_arguments = pattern()
+        .separated_by(just(Token::Comma))
+        .allow_trailing()
+        .delimited_by(just(Token::LeftParen), just(Token::RightParen));
+
+    path()
+        .then(variant_arguments.or_not())
+        .map_with_span(|(mut path, arguments), span| match arguments {
+            Some(arguments) => MatchPattern::Variant(path, arguments, span),
+            None if path.kind != PathKind::Plain || path.segments.len() > 1 => {
+                MatchPattern::Variant(path, Vec::new(), span)
+            }
+            None => {
+                let name = path.pop();
+                if name.0.contents == "_" {
+                    MatchPattern::Wildcard(span)
+                } else {
+                    MatchPattern::Binding(name)
+                }
+            }
+        })
+        .labelled(ParsingRuleLabel::Pattern)
+}
+
 fn lambda<'a>(
     expr_parser: impl NoirParser<Expression> + 'a,
 ) -> impl NoirParser<ExpressionKind> + 'a {
@@ -1501,6 +1620,30 @@ where
         })
 }
 
+fn while_loop<'a, P, S>(
+    expr_no_constructors: P,
+    statement: S,
+) -> impl NoirParser<StatementKind> + 'a
+where
+    P: ExprParser + 'a,
+    S: NoirParser<StatementKind> + 'a,
+{
+    keyword(Keyword::While)
+        .ignore_then(expr_no_constructors)
+        .then(block_expr(statement))
+        .map_with_span(|(condition, block), span| {
+            StatementKind::While(WhileLoopStatement { condition, block, span })
+        })
+}
+
+fn break_statement() -> impl NoirParser<StatementKind> {
+    keyword(Keyword::Break).to(StatementKind::Break)
+}
+
+fn continue_statement() -> impl NoirParser<StatementKind> {
+    keyword(Keyword::Continue).to(StatementKind::Continue)
+}
+
 /// The 'range' of a for loop. Either an actual range `start .. end` or an array expression.
 fn for_range<P>(expr_no_constructors: P) -> impl NoirParser<ForRange>
 where
@@ -1600,7 +1743,8 @@ where
     S: NoirParser<StatementKind> + 'a,
 {
     choice((
-        if_expr(expr_no_constructors, statement.clone()),
+        if_expr(expr_no_constructors.clone(), statement.clone()),
+        match_expr(expr_parser.clone(), expr_no_constructors),
         array_expr(expr_parser.clone()),
         if allow_constructors {
             constructor(expr_parser.clone()).boxed()
@@ -2161,6 +2305,32 @@ mod test {
         );
     }
 
+    #[test]
+    fn parse_while_loop() {
+        parse_all(
+            while_loop(expression_no_constructors(expression()), fresh_statement()),
+            vec!["while x < 10 {}", "while true { foo; bar }", "while f(x) { break; }"],
+        );
+
+        parse_all_failing(
+            while_loop(expression_no_constructors(expression()), fresh_statement()),
+            vec![
+                "while {}",     // A while loop requires a condition
+                "while x < 10", // A while loop requires a body
+            ],
+        );
+    }
+
+    #[test]
+    fn parse_break_and_continue() {
+        parse_all(fresh_statement(), vec!["break", "continue"]);
+        parse_all(
+            block(fresh_statement()),
+            vec!["{ for i in 0..10 { if i == 5 { break; } } }", "{ while x { continue; } }"],
+        );
+        parse_all_failing(block(fresh_statement()), vec!["{ break continue }"]);
+    }
+
     #[test]
     fn parse_function() {
         parse_all(
@@ -2418,6 +2588,45 @@ mod test {
         parse_all_failing(struct_definition(), failing);
     }
 
+    #[test]
+    fn parse_enums() {
+        let cases = vec![
+            "enum Foo { }",
+            "enum Foo { A }",
+            "enum Bar { A, B(Field), }",
+            "enum Option<T> { Some(T), None }",
+            "enum Baz { Pair(Field, u8), Nested(Option<Field>) }",
+            "#[attribute] enum Baz { A, B }",
+        ];
+        parse_all(enum_definition(), cases);
+
+        let failing = vec![
+            "enum {  }",
+            "enum Foo;",
+            "enum Foo { A: Field }",
+            "#[oracle(some)] enum Foo { A }",
+        ];
+        parse_all_failing(enum_definition(), failing);
+    }
+
+    #[test]
+    fn parse_match_expr() {
+        let cases = vec![
+            "match x { }",
+            "match x { _ => 1 }",
+            "match x { y => y }",
+            "match x { Foo::A => 1, Foo::B(y) => y, }",
+            "match x { Foo::A => { 1 } Foo::B(y, _) => y }",
+            "match foo(x) { Option::Some((a, b)) => a + b, Option::None => 0 }",
+            "match x { crate::Foo::A => 1, _ => 2 }",
+        ];
+        parse_all(expression(), cases);
+
+        let failing =
+            vec!["match x { Foo::A => 1 Foo::B => 2 }", "match x { Foo::A 1 }", "match { _ => 1 }"];
+        parse_all_failing(expression(), failing);
+    }
+
     #[test]
     fn parse_type_aliases() {
         let cases = vec!["type foo = u8", "type bar = String", "type baz<T> = Vec<T>"];
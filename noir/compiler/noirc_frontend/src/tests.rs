@@ -5,12 +5,13 @@
 mod test {
 
     use core::panic;
-    use std::collections::BTreeMap;
+    use std::collections::{BTreeMap, HashMap};
 
     use fm::FileId;
 
     use iter_extended::vecmap;
-    use noirc_errors::Location;
+    use noirc_errors::{CustomDiagnostic, Location, Span};
+    use noirc_printable_type::PrintableType;
 
     use crate::hir::def_collector::dc_crate::CompilationError;
     use crate::hir::def_collector::errors::{DefCollectorErrorKind, DuplicateType};
@@ -19,12 +20,12 @@ mod test {
     use crate::hir::resolution::import::PathResolutionError;
     use crate::hir::type_check::TypeCheckError;
     use crate::hir::Context;
-    use crate::node_interner::{NodeInterner, StmtId};
+    use crate::node_interner::{NodeInterner, ReferenceId, StmtId};
 
     use crate::hir::def_collector::dc_crate::DefCollector;
     use crate::hir_def::expr::HirExpression;
     use crate::hir_def::stmt::HirStatement;
-    use crate::monomorphization::monomorphize;
+    use crate::monomorphization::{monomorphize, monomorphize_debug};
     use crate::parser::ParserErrorReason;
     use crate::ParsedModule;
     use crate::{
@@ -727,6 +728,8 @@ mod test {
                 HirStatement::Constrain(constr_stmt) => constr_stmt.0,
                 HirStatement::Semi(semi_expr) => semi_expr,
                 HirStatement::For(for_loop) => for_loop.block,
+                HirStatement::While(while_loop) => while_loop.block,
+                HirStatement::Break | HirStatement::Continue => continue,
                 HirStatement::Error => panic!("Invalid HirStatement!"),
             };
             let expr = interner.expression(&expr_id);
@@ -1112,4 +1115,333 @@ fn lambda$f1(mut env$l1: (Field)) -> Field {
 "#;
         check_rewrite(src, expected_rewrite);
     }
+
+    #[test]
+    fn exhaustive_enum_match() {
+        let src = r#"
+        enum Foo {
+            A,
+            B(Field, bool),
+        }
+
+        fn main(x: Field) -> pub Field {
+            let foo = if x == 0 { Foo::A } else { Foo::B(x, true) };
+            match foo {
+                Foo::A => 0,
+                Foo::B(y, _) => y,
+            }
+        }
+        "#;
+
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        monomorphize(main_func_id, &context.def_interner);
+    }
+
+    #[test]
+    fn monomorphize_debug_records_printable_variables() {
+        let src = r#"
+        struct Point {
+            x: Field,
+            y: u8,
+        }
+
+        fn main(x: Field) {
+            let point = Point { x, y: 2 };
+            let double = |a: Field| a * 2;
+            assert(double(point.x) != 0);
+        }
+        "#;
+
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+
+        // Variables are only recorded when compiling for the debugger
+        let program = monomorphize(main_func_id, &context.def_interner);
+        assert!(program.debug_variables.is_empty());
+
+        let program = monomorphize_debug(main_func_id, &context.def_interner);
+        let variables: HashMap<_, _> = program
+            .debug_variables
+            .values()
+            .map(|variable| (variable.name.as_str(), variable.typ.clone()))
+            .collect();
+
+        // `double` holds a closure, which can't be displayed
+        assert_eq!(variables.len(), 3, "Unexpected variables: {:?}", variables);
+        assert_eq!(variables["x"], PrintableType::Field);
+        assert_eq!(variables["a"], PrintableType::Field);
+        assert_eq!(
+            variables["point"],
+            PrintableType::Struct {
+                name: "Point".to_string(),
+                fields: vec![
+                    ("x".to_string(), PrintableType::Field),
+                    ("y".to_string(), PrintableType::UnsignedInteger { width: 8 }),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn non_exhaustive_enum_match() {
+        let src = r#"
+        enum Foo {
+            A,
+            B(Field),
+            C,
+        }
+
+        fn main() {
+            let _ = match Foo::A {
+                Foo::B(_) => 1,
+            };
+        }
+        "#;
+
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+
+        match &errors[0].0 {
+            CompilationError::TypeError(TypeCheckError::NonExhaustiveMatch {
+                missing_variants,
+                ..
+            }) => {
+                assert_eq!(missing_variants, &["Foo::A".to_string(), "Foo::C".to_string()]);
+            }
+            _ => panic!("Expected a non-exhaustive match error, got: {:?}", errors[0].0),
+        }
+
+        let diagnostic = CustomDiagnostic::from(errors.into_iter().next().unwrap().0);
+        assert_eq!(diagnostic.message, "Non-exhaustive match on type Foo");
+        assert_eq!(diagnostic.secondaries[0].message, "Missing cases `Foo::A`, `Foo::C`");
+    }
+
+    #[test]
+    fn non_exhaustive_field_match() {
+        let src = r#"
+        fn main(x: Field) {
+            let _: () = match x { };
+        }
+        "#;
+
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+
+        let diagnostic = CustomDiagnostic::from(errors.into_iter().next().unwrap().0);
+        assert_eq!(diagnostic.message, "Non-exhaustive match on type Field");
+        assert_eq!(diagnostic.secondaries[0].message, "Missing case `_`");
+    }
+
+    #[test]
+    fn unreachable_enum_match_arm() {
+        let src = r#"
+        enum Foo {
+            A,
+            B,
+        }
+
+        fn main() {
+            let _ = match Foo::A {
+                Foo::A => 1,
+                _ => 2,
+                Foo::B => 3,
+            };
+        }
+        "#;
+
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        assert!(matches!(
+            errors[0].0,
+            CompilationError::TypeError(TypeCheckError::UnreachableMatchArm { .. })
+        ));
+    }
+
+    #[test]
+    fn duplicate_enum_variant() {
+        let src = r#"
+        enum Foo {
+            A,
+            A(Field),
+        }
+
+        fn main() {}
+        "#;
+
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        assert!(matches!(
+            &errors[0].0,
+            CompilationError::DefinitionError(DefCollectorErrorKind::Duplicate {
+                typ: DuplicateType::EnumVariant,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn break_and_continue_in_loops() {
+        let src = r#"
+        fn main(x: Field) {
+            let mut i = 0;
+            while i != 10 {
+                i += 1;
+                if i == x {
+                    continue;
+                }
+                for _ in 0..3 {
+                    break;
+                }
+            }
+        }
+        "#;
+
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 0, "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn break_outside_loop() {
+        let src = r#"
+        fn main() {
+            break;
+        }
+        "#;
+
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        assert!(matches!(
+            errors[0].0,
+            CompilationError::ResolverError(ResolverError::JumpOutsideLoop { is_break: true, .. })
+        ));
+    }
+
+    #[test]
+    fn continue_in_lambda_within_loop() {
+        let src = r#"
+        fn main() {
+            for _ in 0..3 {
+                let f = || { continue; };
+                f();
+            }
+        }
+        "#;
+
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        assert!(matches!(
+            errors[0].0,
+            CompilationError::ResolverError(ResolverError::JumpOutsideLoop { is_break: false, .. })
+        ));
+    }
+
+    #[test]
+    fn while_condition_must_be_bool() {
+        let src = r#"
+        fn main() {
+            while 1 {}
+        }
+        "#;
+
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        assert!(matches!(
+            errors[0].0,
+            CompilationError::TypeError(TypeCheckError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn find_all_references_to_definitions() {
+        let src = r#"
+        fn foo(x: Field) -> Field {
+            let mut y = x + 1;
+            y = y * x;
+            y
+        }
+
+        fn main() {
+            let a = foo(1);
+            assert(foo(a) != a);
+        }
+        "#;
+
+        let (_, context, errors) = get_program(src);
+        assert_eq!(errors.len(), 0, "Expected no errors, got: {:?}", errors);
+        let interner = &context.def_interner;
+
+        let references_at = |needle: &str, include_declaration: bool| {
+            let index = src.find(needle).unwrap() as u32;
+            let location = Location::new(Span::single_char(index), FileId::dummy());
+            let definition = interner.find_definition_at(location).expect("Expected a definition");
+            let reference = ReferenceId::Definition(definition);
+            vecmap(interner.find_all_references(reference, include_declaration), |location| {
+                let span = location.span;
+                &src[span.start() as usize..span.end() as usize]
+            })
+        };
+
+        // From a use of the parameter `x`
+        assert_eq!(references_at("x;", true), vec!["x", "x", "x"]);
+        assert_eq!(references_at("x;", false), vec!["x", "x"]);
+        // From the variable being assigned to
+        assert_eq!(references_at("y = y", true), vec!["y", "y", "y", "y"]);
+        // From the declaration of `foo`
+        assert_eq!(references_at("foo", true), vec!["foo", "foo", "foo"]);
+        // From a call to `foo`
+        assert_eq!(references_at("foo(a)", false), vec!["foo", "foo"]);
+        // From the declaration of `a`
+        assert_eq!(references_at("a = ", true), vec!["a", "a", "a"]);
+    }
+
+    #[test]
+    fn find_all_references_to_types_traits_and_fields() {
+        let src = r#"
+        struct Foo { bar: Field }
+
+        trait Baz { fn baz(self) -> Field; }
+
+        impl Baz for Foo {
+            fn baz(self) -> Field { self.bar }
+        }
+
+        fn qux<T>(x: T) -> Field where T: Baz { x.baz() }
+
+        fn main() {
+            let mut foo = Foo { bar: 1 };
+            foo.bar = 2;
+            let Foo { bar } = foo;
+            assert(qux(foo) != bar);
+        }
+        "#;
+
+        let (_, context, errors) = get_program(src);
+        assert_eq!(errors.len(), 0, "Expected no errors, got: {:?}", errors);
+        let interner = &context.def_interner;
+
+        let references_at = |needle: &str| {
+            let index = src.find(needle).unwrap() as u32;
+            let location = Location::new(Span::single_char(index), FileId::dummy());
+            let (reference, _) = interner.find_reference_at(location).expect("Expected an item");
+            vecmap(interner.find_all_references(reference, true), |location| {
+                let span = location.span;
+                (&src[span.start() as usize..span.end() as usize], span.start())
+            })
+        };
+        let names = |references: Vec<(&'static str, u32)>| vecmap(references, |(name, _)| name);
+
+        // From the declaration of the struct, its use as a type, and in constructors and patterns
+        assert_eq!(names(references_at("Foo {")), vec!["Foo"; 4]);
+        assert_eq!(references_at("Foo {"), references_at("Foo { bar: 1"));
+        // From the declaration of the trait, in an impl and in a where clause
+        assert_eq!(names(references_at("Baz {")), vec!["Baz"; 3]);
+        // From the declaration of the field, in a member access, a constructor, an assignment
+        // and a pattern
+        assert_eq!(names(references_at("bar: Field")), vec!["bar"; 5]);
+        assert_eq!(references_at("bar: Field"), references_at("bar = 2"));
+    }
 }
@@ -6,7 +6,7 @@ use regex::{Captures, Regex};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(tag = "kind", rename_all = "lowercase")]
 pub enum PrintableType {
     Field,
@@ -33,7 +33,7 @@ pub enum PrintableType {
 
 impl PrintableType {
     /// Returns the number of field elements required to represent the type once encoded.
-    fn field_count(&self) -> u32 {
+    pub fn field_count(&self) -> u32 {
         match self {
             Self::Field
             | Self::SignedInteger { .. }
@@ -76,6 +76,12 @@ pub enum ForeignCallError {
 
     #[error("Failed calling external resolver. {0}")]
     ExternalResolverError(#[from] jsonrpc::Error),
+
+    #[error("Could not access foreign call transcript {}. {1}", .0.display())]
+    TranscriptError(std::path::PathBuf, std::io::Error),
+
+    #[error("Foreign call diverged from the recorded transcript. {0}")]
+    TranscriptMismatch(String),
 }
 
 impl TryFrom<&[ForeignCallParam]> for PrintableValueDisplay {
@@ -295,7 +301,7 @@ fn format_field_string(field: FieldElement) -> String {
 }
 
 /// Assumes that `field_iterator` contains enough [FieldElement] in order to decode the [PrintableType]
-fn decode_value(
+pub fn decode_value(
     field_iterator: &mut impl Iterator<Item = FieldElement>,
     typ: &PrintableType,
 ) -> PrintableValue {
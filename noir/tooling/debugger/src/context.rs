@@ -1,32 +1,182 @@
 use acvm::acir::circuit::{Circuit, Opcode, OpcodeLocation};
 use acvm::acir::native_types::{Witness, WitnessMap};
-use acvm::brillig_vm::{brillig::Value, Registers};
+use acvm::brillig_vm::{
+    brillig::{HeapArray, RegisterOrMemory, Value},
+    Registers,
+};
 use acvm::pwg::{
     ACVMStatus, BrilligSolver, BrilligSolverStatus, ForeignCallWaitInfo, StepResult, ACVM,
 };
 use acvm::{BlackBoxFunctionSolver, FieldElement};
 
+use fm::FileId;
 use nargo::artifacts::debug::DebugArtifact;
 use nargo::errors::{ExecutionError, Location};
 use nargo::ops::ForeignCallExecutor;
 use nargo::NargoError;
+use noirc_errors::debug_info::{DebugVarId, DebugVariable, DebugVariableValue};
+use noirc_printable_type::{decode_value, PrintableType, PrintableValue};
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Display;
+use std::mem::size_of;
+use std::str::FromStr;
+
+use thiserror::Error;
 
-use std::collections::{hash_set::Iter, HashSet};
+use crate::history::{Checkpoint, ExecutionHistory, DEFAULT_CHECKPOINTS_BUDGET};
 
 #[derive(Debug)]
 pub(super) enum DebugCommandResult {
     Done,
     Ok,
     BreakpointReached(OpcodeLocation),
+    WatchpointTriggered(Watchpoint),
     Error(NargoError),
 }
 
+/// A condition on the value of a witness which must hold for a breakpoint to stop execution,
+/// written as a comparison such as `_3==5` or `_2 < 0x10`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct BreakpointCondition {
+    witness: Witness,
+    comparison: Comparison,
+    value: FieldElement,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    LessThanOrEqual,
+    GreaterThanOrEqual,
+    LessThan,
+    GreaterThan,
+}
+
+impl Comparison {
+    /// All comparisons, ordered so that no symbol comes after a prefix of it.
+    const ALL: [Comparison; 6] = [
+        Comparison::Equal,
+        Comparison::NotEqual,
+        Comparison::LessThanOrEqual,
+        Comparison::GreaterThanOrEqual,
+        Comparison::LessThan,
+        Comparison::GreaterThan,
+    ];
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Equal => "==",
+            Comparison::NotEqual => "!=",
+            Comparison::LessThanOrEqual => "<=",
+            Comparison::GreaterThanOrEqual => ">=",
+            Comparison::LessThan => "<",
+            Comparison::GreaterThan => ">",
+        }
+    }
+
+    fn holds(&self, lhs: &FieldElement, rhs: &FieldElement) -> bool {
+        match self {
+            Comparison::Equal => lhs == rhs,
+            Comparison::NotEqual => lhs != rhs,
+            Comparison::LessThanOrEqual => lhs <= rhs,
+            Comparison::GreaterThanOrEqual => lhs >= rhs,
+            Comparison::LessThan => lhs < rhs,
+            Comparison::GreaterThan => lhs > rhs,
+        }
+    }
+}
+
+impl BreakpointCondition {
+    /// A condition on a witness which has not been solved yet never holds.
+    fn holds(&self, witness_map: &WitnessMap) -> bool {
+        witness_map
+            .get(&self.witness)
+            .is_some_and(|witness_value| self.comparison.holds(witness_value, &self.value))
+    }
+}
+
+impl Display for BreakpointCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "_{} {} {}", self.witness.witness_index(), self.comparison.symbol(), self.value)
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Invalid breakpoint condition `{0}`, expected a comparison of a witness such as `_3==5`")]
+pub(super) struct BreakpointConditionFromStrError(String);
+
+impl FromStr for BreakpointCondition {
+    type Err = BreakpointConditionFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = || {
+            let (comparison, (lhs, rhs)) = Comparison::ALL
+                .into_iter()
+                .find_map(|comparison| Some((comparison, s.split_once(comparison.symbol())?)))?;
+            let witness = parse_witness(lhs.trim())?;
+            let value = FieldElement::try_from_str(rhs.trim())?;
+            Some(BreakpointCondition { witness, comparison, value })
+        };
+        parse().ok_or_else(|| BreakpointConditionFromStrError(s.to_string()))
+    }
+}
+
+/// A witness or Brillig memory cell which stops execution when it is written,
+/// written as `_3` for a witness and `memory[3]` for a memory cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) enum Watchpoint {
+    Witness(Witness),
+    BrilligMemory(usize),
+}
+
+impl Display for Watchpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Watchpoint::Witness(witness) => write!(f, "_{}", witness.witness_index()),
+            Watchpoint::BrilligMemory(address) => write!(f, "memory[{address}]"),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Invalid watchpoint `{0}`, expected a witness such as `_3` or a memory cell such as `memory[3]`")]
+pub(super) struct WatchpointFromStrError(String);
+
+impl FromStr for Watchpoint {
+    type Err = WatchpointFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let memory_address = s.strip_prefix("memory[").and_then(|s| s.strip_suffix(']'));
+        let watchpoint = match memory_address {
+            Some(address) => address.parse().ok().map(Watchpoint::BrilligMemory),
+            None => parse_witness(s).map(Watchpoint::Witness),
+        };
+        watchpoint.ok_or_else(|| WatchpointFromStrError(s.to_string()))
+    }
+}
+
+/// Parses a witness written as its index prefixed with an underscore, like the debugger displays them.
+fn parse_witness(s: &str) -> Option<Witness> {
+    s.strip_prefix('_')?.parse().ok().map(Witness)
+}
+
 pub(super) struct DebugContext<'a, B: BlackBoxFunctionSolver> {
     acvm: ACVM<'a, B>,
     brillig_solver: Option<BrilligSolver<'a, B>>,
     foreign_call_executor: Box<dyn ForeignCallExecutor + 'a>,
     debug_artifact: &'a DebugArtifact,
-    breakpoints: HashSet<OpcodeLocation>,
+    /// The locations of the breakpoints, along with the condition which must hold
+    /// for them to stop execution if there is one.
+    breakpoints: HashMap<OpcodeLocation, Option<BreakpointCondition>>,
+    watchpoints: BTreeSet<Watchpoint>,
+    /// Values of the variables assigned so far in the Brillig block being executed,
+    /// encoded as the field elements their type is decoded from.
+    brillig_variables: BTreeMap<DebugVarId, Vec<FieldElement>>,
+    history: ExecutionHistory<'a, B>,
+    /// The number of witnesses of the circuit, used to estimate the size of checkpoints
+    witness_count: usize,
 }
 
 impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
@@ -42,7 +192,11 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
             brillig_solver: None,
             foreign_call_executor,
             debug_artifact,
-            breakpoints: HashSet::new(),
+            breakpoints: HashMap::new(),
+            watchpoints: BTreeSet::new(),
+            brillig_variables: BTreeMap::new(),
+            history: ExecutionHistory::new(DEFAULT_CHECKPOINTS_BUDGET),
+            witness_count: circuit.current_witness_index as usize + 1,
         }
     }
 
@@ -59,7 +213,9 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
         witness: Witness,
         value: FieldElement,
     ) -> Option<FieldElement> {
-        self.acvm.overwrite_witness(witness, value)
+        let old_value = self.acvm.overwrite_witness(witness, value);
+        self.pin_modified_state();
+        old_value
     }
 
     pub(super) fn get_current_opcode_location(&self) -> Option<OpcodeLocation> {
@@ -197,6 +353,7 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
         match solver.step() {
             Ok(BrilligSolverStatus::InProgress) => {
                 self.brillig_solver = Some(solver);
+                self.record_brillig_variables();
                 if self.breakpoint_reached() {
                     DebugCommandResult::BreakpointReached(
                         self.get_current_opcode_location()
@@ -207,6 +364,7 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
                 }
             }
             Ok(BrilligSolverStatus::Finished) => {
+                self.brillig_variables.clear();
                 let status = self.acvm.finish_brillig_with_solver(solver);
                 self.handle_acvm_status(status)
             }
@@ -221,9 +379,15 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
     }
 
     fn handle_foreign_call(&mut self, foreign_call: ForeignCallWaitInfo) -> DebugCommandResult {
-        let foreign_call_result = self.foreign_call_executor.execute(&foreign_call);
+        // Foreign calls are only executed the first time a step is taken, so that
+        // stepping back and forth over them doesn't repeat their side effects.
+        let foreign_call_result = match self.history.foreign_call_result() {
+            Some(foreign_call_result) => Ok(foreign_call_result.clone()),
+            None => self.foreign_call_executor.execute(&foreign_call),
+        };
         match foreign_call_result {
             Ok(foreign_call_result) => {
+                self.history.record_foreign_call_result(foreign_call_result.clone());
                 if let Some(mut solver) = self.brillig_solver.take() {
                     solver.resolve_pending_foreign_call(foreign_call_result);
                     self.brillig_solver = Some(solver);
@@ -243,7 +407,10 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
         }
 
         match status {
-            ACVMStatus::Solved => DebugCommandResult::Done,
+            ACVMStatus::Solved => match self.foreign_call_executor.finish() {
+                Ok(()) => DebugCommandResult::Done,
+                Err(error) => DebugCommandResult::Error(error.into()),
+            },
             ACVMStatus::InProgress => {
                 if self.breakpoint_reached() {
                     DebugCommandResult::BreakpointReached(
@@ -264,17 +431,156 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
     }
 
     pub(super) fn step_into_opcode(&mut self) -> DebugCommandResult {
-        if self.brillig_solver.is_some() {
-            return self.step_brillig_opcode();
+        let watched_values = self.get_watched_values();
+        let result = self.execute_step();
+        self.check_watchpoints(watched_values, result)
+    }
+
+    /// Executes the next opcode, or the next Brillig opcode if executing a Brillig block,
+    /// recording the step in the execution history.
+    fn execute_step(&mut self) -> DebugCommandResult {
+        if self.history.needs_checkpoint() {
+            let checkpoint = self.checkpoint(false);
+            self.history.add_checkpoint(checkpoint);
         }
+        let location = self.get_current_opcode_location();
 
-        match self.acvm.step_into_brillig_opcode() {
-            StepResult::IntoBrillig(solver) => {
-                self.brillig_solver = Some(solver);
-                self.step_brillig_opcode()
+        let result = if self.brillig_solver.is_some() {
+            self.step_brillig_opcode()
+        } else {
+            match self.acvm.step_into_brillig_opcode() {
+                StepResult::IntoBrillig(solver) => {
+                    self.brillig_solver = Some(solver);
+                    self.record_brillig_variables();
+                    self.step_brillig_opcode()
+                }
+                StepResult::Status(status) => self.handle_acvm_status(status),
+            }
+        };
+
+        self.history.record_step(location);
+        // Modifications made by the user are part of the history, so they are
+        // applied again when replaying the steps which led to them
+        if let Some(checkpoint) = self.history.pinned_checkpoint() {
+            self.restore_checkpoint(checkpoint.clone());
+        }
+        result
+    }
+
+    fn checkpoint(&self, pinned: bool) -> Checkpoint<'a, B> {
+        let brillig_values = self
+            .brillig_solver
+            .as_ref()
+            .map_or(0, |solver| solver.get_registers().inner.len() + solver.get_memory().len());
+        let variables_size: usize = self.brillig_variables.values().map(Vec::len).sum();
+        let size = self.witness_count * size_of::<(Witness, FieldElement)>()
+            + brillig_values * size_of::<Value>()
+            + variables_size * size_of::<FieldElement>();
+        Checkpoint {
+            acvm: self.acvm.clone(),
+            brillig_solver: self.brillig_solver.clone(),
+            brillig_variables: self.brillig_variables.clone(),
+            size,
+            pinned,
+        }
+    }
+
+    fn restore_checkpoint(&mut self, checkpoint: Checkpoint<'a, B>) {
+        self.acvm = checkpoint.acvm;
+        self.brillig_solver = checkpoint.brillig_solver;
+        self.brillig_variables = checkpoint.brillig_variables;
+    }
+
+    /// Checkpoints the current state after the user modified it, as it can't
+    /// be reached anymore by replaying the steps which led to it.
+    fn pin_modified_state(&mut self) {
+        self.history.discard_future();
+        let checkpoint = self.checkpoint(true);
+        self.history.add_checkpoint(checkpoint);
+    }
+
+    /// Restores the state execution was at before the given past step.
+    fn rewind_to_step(&mut self, step: usize) {
+        let Some((checkpoint_step, checkpoint)) = self.history.checkpoint_before(step) else {
+            unreachable!("The state before the first step is always checkpointed");
+        };
+        self.restore_checkpoint(checkpoint.clone());
+        self.history.rewind_to(checkpoint_step);
+        while self.history.current_step() < step {
+            self.execute_step();
+        }
+    }
+
+    /// Returns the first step of the run of consecutive steps taken at the same
+    /// location as the given one, i.e. the step at which that location was reached.
+    fn first_step_at_location(&self, mut step: usize, location: Option<OpcodeLocation>) -> usize {
+        while step > 0 && self.history.location_at(step - 1) == location {
+            step -= 1;
+        }
+        step
+    }
+
+    pub(super) fn is_at_start(&self) -> bool {
+        self.history.current_step() == 0
+    }
+
+    /// Goes back to the state execution was at when it reached the opcode executed before the current one.
+    pub(super) fn step_back_into_opcode(&mut self) -> DebugCommandResult {
+        let current_location = self.get_current_opcode_location();
+        let step = self.first_step_at_location(self.history.current_step(), current_location);
+        if step > 0 {
+            let previous_location = self.history.location_at(step - 1);
+            self.rewind_to_step(self.first_step_at_location(step - 1, previous_location));
+        }
+        DebugCommandResult::Ok
+    }
+
+    /// Goes back to the state execution was at when it reached the source location
+    /// executed before the current one, mirroring `next`.
+    pub(super) fn step_back_source_location(&mut self) -> DebugCommandResult {
+        let source_location_at = |step: usize| {
+            self.history.location_at(step).and_then(|location| {
+                self.debug_artifact.debug_symbols[0].opcode_location(&location)
+            })
+        };
+        let current_source_location = self.get_current_source_location();
+        let mut step = self.history.current_step();
+        while step > 0 {
+            step -= 1;
+            let source_location = source_location_at(step);
+            if source_location.is_some() && source_location != current_source_location {
+                while step > 0 && source_location_at(step - 1) == source_location {
+                    step -= 1;
+                }
+                break;
+            }
+        }
+        self.rewind_to_step(step);
+        DebugCommandResult::Ok
+    }
+
+    /// Goes back to the last time a breakpoint was reached before reaching the current
+    /// opcode, or to the start of the program if no breakpoint was reached.
+    pub(super) fn reverse_continue(&mut self) -> DebugCommandResult {
+        let current_location = self.get_current_opcode_location();
+        let current_step =
+            self.first_step_at_location(self.history.current_step(), current_location);
+        for step in (0..current_step).rev() {
+            let location = self.history.location_at(step);
+            let reached_at_step = step == 0 || self.history.location_at(step - 1) != location;
+            let is_breakpoint = location.is_some_and(|location| self.is_breakpoint_set(&location));
+            if reached_at_step && is_breakpoint {
+                self.rewind_to_step(step);
+                // Conditions can only be evaluated on the state at the breakpoint
+                if self.breakpoint_reached() {
+                    return DebugCommandResult::BreakpointReached(
+                        location.expect("Breakpoint reached but we have no location"),
+                    );
+                }
             }
-            StepResult::Status(status) => self.handle_acvm_status(status),
         }
+        self.rewind_to_step(0);
+        DebugCommandResult::Ok
     }
 
     fn currently_executing_brillig(&self) -> bool {
@@ -318,8 +624,7 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
         if self.currently_executing_brillig() {
             self.step_out_of_brillig_opcode()
         } else {
-            let status = self.acvm.solve_opcode();
-            self.handle_acvm_status(status)
+            self.step_into_opcode()
         }
     }
 
@@ -337,6 +642,25 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
         }
     }
 
+    /// Steps until execution returns from the Noir function being executed to its caller,
+    /// or runs until the end of the program if there is none.
+    pub(super) fn step_out(&mut self) -> DebugCommandResult {
+        let mut callers = self.get_current_source_location().unwrap_or_default();
+        callers.pop();
+        loop {
+            let result = self.step_into_opcode();
+            if !matches!(result, DebugCommandResult::Ok) {
+                return result;
+            }
+            let Some(call_stack) = self.get_current_source_location() else {
+                continue;
+            };
+            if call_stack.len() <= callers.len() || !call_stack.starts_with(&callers) {
+                return DebugCommandResult::Ok;
+            }
+        }
+    }
+
     pub(super) fn cont(&mut self) -> DebugCommandResult {
         loop {
             let result = self.step_into_opcode();
@@ -359,6 +683,7 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
     pub(super) fn set_brillig_register(&mut self, register_index: usize, value: FieldElement) {
         if let Some(solver) = self.brillig_solver.as_mut() {
             solver.set_register(register_index, value.into());
+            self.pin_modified_state();
         }
     }
 
@@ -369,17 +694,209 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
     pub(super) fn write_brillig_memory(&mut self, ptr: usize, value: FieldElement) {
         if let Some(solver) = self.brillig_solver.as_mut() {
             solver.write_memory_at(ptr, value.into());
+            self.pin_modified_state();
+        }
+    }
+
+    /// Returns the variables which have been assigned up to the current point of execution,
+    /// along with their latest value, ordered by their declaration. Shadowed variables are omitted.
+    ///
+    /// Variables held in the Brillig VM are only available while executing the Brillig block
+    /// which assigned them.
+    pub(super) fn get_variables(&self) -> Vec<(&'a DebugVariable, PrintableValue)> {
+        let Some(debug_info) = self.debug_artifact.debug_symbols.first() else {
+            return Vec::new();
+        };
+        let current_acir_index = self.get_current_acir_index();
+
+        let mut values: BTreeMap<DebugVarId, Vec<FieldElement>> = BTreeMap::new();
+        for (location, assignments) in &debug_info.variable_assignments {
+            let OpcodeLocation::Acir(acir_index) = location else {
+                continue;
+            };
+            // Once execution has finished every assignment has been made.
+            if current_acir_index.is_some_and(|current| *acir_index > current) {
+                continue;
+            }
+            for (id, value) in assignments {
+                let DebugVariableValue::Witnesses(witnesses) = value else {
+                    continue;
+                };
+                let witness_map = self.acvm.witness_map();
+                let fields: Option<Vec<_>> =
+                    witnesses.iter().map(|witness| witness_map.get(witness).copied()).collect();
+                if let Some(fields) = fields {
+                    values.insert(*id, fields);
+                }
+            }
+        }
+        values.extend(self.brillig_variables.iter().map(|(id, fields)| (*id, fields.clone())));
+
+        // A variable shadowing another of the same name is declared after it, so has a greater id.
+        let mut visible_variables = BTreeMap::new();
+        for (id, fields) in values {
+            let Some(variable) = debug_info.variables.get(&id) else {
+                continue;
+            };
+            if fields.len() == variable.typ.field_count() as usize {
+                visible_variables
+                    .insert((&variable.function, &variable.name), (id, variable, fields));
+            }
+        }
+
+        let mut variables: Vec<_> = visible_variables.into_values().collect();
+        variables.sort_by_key(|(id, _, _)| *id);
+        variables
+            .into_iter()
+            .map(|(_, variable, fields)| {
+                (variable, decode_value(&mut fields.into_iter(), &variable.typ))
+            })
+            .collect()
+    }
+
+    /// Records the values of the variables assigned once execution reaches the current Brillig opcode.
+    fn record_brillig_variables(&mut self) {
+        let Some(location) = self.get_current_opcode_location() else {
+            return;
+        };
+        let Some(solver) = &self.brillig_solver else {
+            return;
+        };
+        let Some(debug_info) = self.debug_artifact.debug_symbols.first() else {
+            return;
+        };
+        let Some(assignments) = debug_info.variable_assignments.get(&location) else {
+            return;
+        };
+        for (id, value) in assignments {
+            let (DebugVariableValue::Brillig(value), Some(variable)) =
+                (value, debug_info.variables.get(id))
+            else {
+                continue;
+            };
+            let fields = read_brillig_value(
+                solver.get_registers(),
+                solver.get_memory(),
+                value,
+                &variable.typ,
+            );
+            if let Some(fields) = fields {
+                self.brillig_variables.insert(*id, fields);
+            }
         }
     }
 
     fn breakpoint_reached(&self) -> bool {
-        if let Some(location) = self.get_current_opcode_location() {
-            self.breakpoints.contains(&location)
-        } else {
-            false
+        let Some(location) = self.get_current_opcode_location() else {
+            return false;
+        };
+        match self.breakpoints.get(&location) {
+            Some(Some(condition)) => condition.holds(self.acvm.witness_map()),
+            Some(None) => true,
+            None => false,
         }
     }
 
+    pub(super) fn get_watched_value(&self, watchpoint: &Watchpoint) -> Option<FieldElement> {
+        match watchpoint {
+            Watchpoint::Witness(witness) => self.acvm.witness_map().get(witness).copied(),
+            Watchpoint::BrilligMemory(address) => self
+                .get_brillig_memory()
+                .and_then(|memory| memory.get(*address))
+                .map(|value| value.to_field()),
+        }
+    }
+
+    fn get_watched_values(&self) -> Vec<(Watchpoint, Option<FieldElement>)> {
+        self.watchpoints
+            .iter()
+            .map(|watchpoint| (*watchpoint, self.get_watched_value(watchpoint)))
+            .collect()
+    }
+
+    /// Stops at the first watchpoint whose value changed since `watched_values` were taken,
+    /// unless the step which was taken in between already finished or failed.
+    fn check_watchpoints(
+        &self,
+        watched_values: Vec<(Watchpoint, Option<FieldElement>)>,
+        result: DebugCommandResult,
+    ) -> DebugCommandResult {
+        if !matches!(result, DebugCommandResult::Ok | DebugCommandResult::BreakpointReached(_)) {
+            return result;
+        }
+        let written = watched_values.into_iter().find_map(|(watchpoint, old_value)| {
+            let new_value = self.get_watched_value(&watchpoint);
+            (new_value.is_some() && new_value != old_value).then_some(watchpoint)
+        });
+        match written {
+            Some(watchpoint) => DebugCommandResult::WatchpointTriggered(watchpoint),
+            None => result,
+        }
+    }
+
+    /// Returns the opcode locations at which execution of a source line starts, i.e. those whose
+    /// call stack goes through the line while the opcode executed before them does not. If no opcode
+    /// maps to the given line, the closest line after it in the same file which has any is used,
+    /// so the line the locations belong to is returned along with them.
+    pub(super) fn find_opcode_locations_at_line(
+        &self,
+        file_id: FileId,
+        line: usize,
+    ) -> Option<(usize, Vec<OpcodeLocation>)> {
+        let debug_info = self.debug_artifact.debug_symbols.first()?;
+
+        // The lines of the file each opcode's call stack goes through, or `None` for the
+        // opcodes which are not mapped to any source location.
+        let opcode_lines: Vec<(OpcodeLocation, Option<BTreeSet<usize>>)> = self
+            .iterate_opcode_locations()
+            .map(|location| {
+                let lines = debug_info.opcode_location(&location).map(|call_stack| {
+                    call_stack
+                        .into_iter()
+                        .filter(|source_location| source_location.file == file_id)
+                        .filter_map(|source_location| {
+                            self.debug_artifact.location_line_number(source_location).ok()
+                        })
+                        .collect()
+                });
+                (location, lines)
+            })
+            .collect();
+
+        let line = opcode_lines
+            .iter()
+            .filter_map(|(_, lines)| lines.as_ref()?.range(line..).next())
+            .min()
+            .copied()?;
+
+        let mut locations = Vec::new();
+        let mut previous_on_line = false;
+        for (location, lines) in &opcode_lines {
+            let Some(lines) = lines else {
+                continue;
+            };
+            let on_line = lines.contains(&line);
+            if on_line && !previous_on_line {
+                locations.push(*location);
+            }
+            previous_on_line = on_line;
+        }
+        Some((line, locations))
+    }
+
+    /// Iterates over all the locations execution can stop at, in the order of the opcodes.
+    /// The first opcode of a Brillig block is located at the block itself.
+    fn iterate_opcode_locations(&self) -> impl Iterator<Item = OpcodeLocation> + '_ {
+        self.get_opcodes_sizes().into_iter().enumerate().flat_map(|(acir_index, size)| {
+            std::iter::once(OpcodeLocation::Acir(acir_index)).chain(
+                (1..size).map(move |brillig_index| OpcodeLocation::Brillig {
+                    acir_index,
+                    brillig_index,
+                }),
+            )
+        })
+    }
+
     pub(super) fn is_valid_opcode_location(&self, location: &OpcodeLocation) -> bool {
         let opcodes = self.get_opcodes();
         match *location {
@@ -399,21 +916,46 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
     }
 
     pub(super) fn is_breakpoint_set(&self, location: &OpcodeLocation) -> bool {
-        self.breakpoints.contains(location)
+        self.breakpoints.contains_key(location)
     }
 
     pub(super) fn add_breakpoint(&mut self, location: OpcodeLocation) -> bool {
-        self.breakpoints.insert(location)
+        self.add_conditional_breakpoint(location, None)
+    }
+
+    /// Sets a breakpoint which only stops execution when the given condition holds, replacing
+    /// the condition of any breakpoint already at the location. Returns false if the same
+    /// breakpoint was already set.
+    pub(super) fn add_conditional_breakpoint(
+        &mut self,
+        location: OpcodeLocation,
+        condition: Option<BreakpointCondition>,
+    ) -> bool {
+        self.breakpoints.insert(location, condition) != Some(condition)
     }
 
     pub(super) fn delete_breakpoint(&mut self, location: &OpcodeLocation) -> bool {
-        self.breakpoints.remove(location)
+        self.breakpoints.remove(location).is_some()
     }
 
-    pub(super) fn iterate_breakpoints(&self) -> Iter<'_, OpcodeLocation> {
+    pub(super) fn iterate_breakpoints(
+        &self,
+    ) -> impl Iterator<Item = (&OpcodeLocation, &Option<BreakpointCondition>)> {
         self.breakpoints.iter()
     }
 
+    pub(super) fn add_watchpoint(&mut self, watchpoint: Watchpoint) -> bool {
+        self.watchpoints.insert(watchpoint)
+    }
+
+    pub(super) fn delete_watchpoint(&mut self, watchpoint: &Watchpoint) -> bool {
+        self.watchpoints.remove(watchpoint)
+    }
+
+    pub(super) fn iterate_watchpoints(&self) -> impl Iterator<Item = &Watchpoint> {
+        self.watchpoints.iter()
+    }
+
     pub(super) fn clear_breakpoints(&mut self) {
         self.breakpoints.clear();
     }
@@ -427,6 +969,109 @@ impl<'a, B: BlackBoxFunctionSolver> DebugContext<'a, B> {
     }
 }
 
+/// Reads a value of type `typ` held in the Brillig VM, encoded as the field elements it is decoded from.
+///
+/// Returns `None` if the value refers to memory which has not been written.
+fn read_brillig_value(
+    registers: &Registers,
+    memory: &[Value],
+    value: &[RegisterOrMemory],
+    typ: &PrintableType,
+) -> Option<Vec<FieldElement>> {
+    let mut fields = Vec::new();
+    read_from_registers(registers, memory, &mut value.iter(), typ, &mut fields)?;
+    Some(fields)
+}
+
+/// Reads a value of type `typ` whose fields are held in consecutive registers,
+/// where arrays are held in a register pointing to their elements in memory.
+fn read_from_registers<'r>(
+    registers: &Registers,
+    memory: &[Value],
+    value: &mut impl Iterator<Item = &'r RegisterOrMemory>,
+    typ: &PrintableType,
+    fields: &mut Vec<FieldElement>,
+) -> Option<()> {
+    match typ {
+        PrintableType::Struct { fields: field_types, .. } => {
+            for (_, field_type) in field_types {
+                read_from_registers(registers, memory, value, field_type, fields)?;
+            }
+        }
+        PrintableType::Array { .. } | PrintableType::String { .. } => {
+            let RegisterOrMemory::HeapArray(HeapArray { pointer, .. }) = value.next()? else {
+                return None;
+            };
+            let pointer = memory_address(registers.get(*pointer))?;
+            read_array_from_memory(memory, pointer, typ, fields)?;
+        }
+        _ => {
+            let RegisterOrMemory::RegisterIndex(register) = value.next()? else {
+                return None;
+            };
+            fields.push(registers.get(*register).to_field());
+        }
+    }
+    Some(())
+}
+
+/// Reads the elements of an array whose first element is at `pointer`.
+fn read_array_from_memory(
+    memory: &[Value],
+    pointer: usize,
+    typ: &PrintableType,
+    fields: &mut Vec<FieldElement>,
+) -> Option<()> {
+    let mut address = pointer;
+    match typ {
+        PrintableType::Array { length, typ } => {
+            for _ in 0..*length {
+                read_from_memory(memory, &mut address, typ, fields)?;
+            }
+        }
+        PrintableType::String { length } => {
+            for _ in 0..*length {
+                read_from_memory(memory, &mut address, &PrintableType::Field, fields)?;
+            }
+        }
+        _ => unreachable!("Expected an array type, found {typ:?}"),
+    }
+    Some(())
+}
+
+/// Reads a value of type `typ` whose fields are stored in memory starting at `address`,
+/// leaving `address` just after them. Nested arrays are stored as a reference to the
+/// pointer to their elements.
+fn read_from_memory(
+    memory: &[Value],
+    address: &mut usize,
+    typ: &PrintableType,
+    fields: &mut Vec<FieldElement>,
+) -> Option<()> {
+    match typ {
+        PrintableType::Struct { fields: field_types, .. } => {
+            for (_, field_type) in field_types {
+                read_from_memory(memory, address, field_type, fields)?;
+            }
+        }
+        PrintableType::Array { .. } | PrintableType::String { .. } => {
+            let reference = memory_address(*memory.get(*address)?)?;
+            *address += 1;
+            let pointer = memory_address(*memory.get(reference)?)?;
+            read_array_from_memory(memory, pointer, typ, fields)?;
+        }
+        _ => {
+            fields.push(memory.get(*address)?.to_field());
+            *address += 1;
+        }
+    }
+    Some(())
+}
+
+fn memory_address(value: Value) -> Option<usize> {
+    value.to_field().try_to_u64().and_then(|address| usize::try_from(address).ok())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -445,6 +1090,8 @@ mod tests {
         },
     };
     use nargo::{artifacts::debug::DebugArtifact, ops::DefaultForeignCallExecutor};
+    use noirc_driver::DebugFile;
+    use noirc_errors::{debug_info::DebugInfo, Location, Span};
     use std::collections::BTreeMap;
 
     struct StubbedSolver;
@@ -757,4 +1404,358 @@ mod tests {
             (Some(OpcodeLocation::Brillig { acir_index: 0, brillig_index: 2 }), 0)
         );
     }
+
+    /// A circuit with a Brillig block computing `z = x + y`, followed by an opcode asserting it.
+    fn brillig_sum_circuit() -> Circuit {
+        let fe_1 = FieldElement::one();
+        let (w_x, w_y, w_z) = (Witness(1), Witness(2), Witness(3));
+        let brillig_opcodes = Brillig {
+            inputs: vec![
+                BrilligInputs::Single(Expression {
+                    linear_combinations: vec![(fe_1, w_x)],
+                    ..Expression::default()
+                }),
+                BrilligInputs::Single(Expression {
+                    linear_combinations: vec![(fe_1, w_y)],
+                    ..Expression::default()
+                }),
+            ],
+            outputs: vec![BrilligOutputs::Simple(w_z)],
+            bytecode: vec![
+                BrilligOpcode::BinaryFieldOp {
+                    destination: RegisterIndex::from(0),
+                    op: BinaryFieldOp::Add,
+                    lhs: RegisterIndex::from(0),
+                    rhs: RegisterIndex::from(1),
+                },
+                BrilligOpcode::Store {
+                    destination_pointer: RegisterIndex::from(1),
+                    source: RegisterIndex::from(0),
+                },
+                BrilligOpcode::Stop,
+            ],
+            predicate: None,
+        };
+        let opcodes = vec![
+            Opcode::Brillig(brillig_opcodes),
+            Opcode::AssertZero(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(fe_1, w_x), (fe_1, w_y), (-fe_1, w_z)],
+                q_c: FieldElement::zero(),
+            }),
+        ];
+        Circuit { current_witness_index: 3, opcodes, ..Circuit::default() }
+    }
+
+    #[test]
+    fn test_conditional_breakpoint() {
+        let circuit = &brillig_sum_circuit();
+        let debug_artifact =
+            &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new(), warnings: vec![] };
+        let initial_witness: WitnessMap =
+            BTreeMap::from([(Witness(1), FieldElement::one()), (Witness(2), FieldElement::one())])
+                .into();
+        let new_context = || {
+            DebugContext::new(
+                &StubbedSolver,
+                circuit,
+                debug_artifact,
+                initial_witness.clone(),
+                Box::new(DefaultForeignCallExecutor::new(true, None)),
+            )
+        };
+
+        // z = 2, so the breakpoint is not reached when the condition does not hold
+        let mut context = new_context();
+        let condition = "_3 == 5".parse().unwrap();
+        assert!(context.add_conditional_breakpoint(OpcodeLocation::Acir(1), Some(condition)));
+        assert!(!context.add_conditional_breakpoint(OpcodeLocation::Acir(1), Some(condition)));
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+
+        let mut context = new_context();
+        let condition = "_3==2".parse().unwrap();
+        assert!(context.add_conditional_breakpoint(OpcodeLocation::Acir(1), Some(condition)));
+        let result = context.cont();
+        assert!(matches!(result, DebugCommandResult::BreakpointReached(OpcodeLocation::Acir(1))));
+    }
+
+    #[test]
+    fn test_parse_breakpoint_condition() {
+        let condition: BreakpointCondition = "_3<=0x10".parse().unwrap();
+        assert_eq!(condition.witness, Witness(3));
+        assert_eq!(condition.comparison, Comparison::LessThanOrEqual);
+        assert_eq!(condition.value, FieldElement::from(16_u128));
+
+        let condition: BreakpointCondition = "_1 > 2".parse().unwrap();
+        assert_eq!(condition.comparison, Comparison::GreaterThan);
+
+        assert!("_1 = 2".parse::<BreakpointCondition>().is_err());
+        assert!("x == 2".parse::<BreakpointCondition>().is_err());
+        assert!("_1 == y".parse::<BreakpointCondition>().is_err());
+    }
+
+    #[test]
+    fn test_watchpoints() {
+        let circuit = &brillig_sum_circuit();
+        let debug_artifact =
+            &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new(), warnings: vec![] };
+        let initial_witness =
+            BTreeMap::from([(Witness(1), FieldElement::one()), (Witness(2), FieldElement::one())])
+                .into();
+        let mut context = DebugContext::new(
+            &StubbedSolver,
+            circuit,
+            debug_artifact,
+            initial_witness,
+            Box::new(DefaultForeignCallExecutor::new(true, None)),
+        );
+
+        assert_eq!("memory[1]".parse::<Watchpoint>().unwrap(), Watchpoint::BrilligMemory(1));
+        assert_eq!("_3".parse::<Watchpoint>().unwrap(), Watchpoint::Witness(Witness(3)));
+        assert!(context.add_watchpoint("memory[1]".parse().unwrap()));
+        assert!(context.add_watchpoint("_3".parse().unwrap()));
+
+        // the store writes z into the memory cell pointed to by register 1, holding y = 1
+        let result = context.cont();
+        assert!(matches!(
+            result,
+            DebugCommandResult::WatchpointTriggered(Watchpoint::BrilligMemory(1))
+        ));
+        assert_eq!(
+            context.get_current_opcode_location(),
+            Some(OpcodeLocation::Brillig { acir_index: 0, brillig_index: 2 })
+        );
+        assert_eq!(
+            context.get_watched_value(&Watchpoint::BrilligMemory(1)),
+            Some(FieldElement::from(2_u128))
+        );
+
+        // z is solved once the Brillig block finishes
+        let result = context.cont();
+        assert!(matches!(
+            result,
+            DebugCommandResult::WatchpointTriggered(Watchpoint::Witness(Witness(3)))
+        ));
+        assert_eq!(context.get_current_opcode_location(), Some(OpcodeLocation::Acir(1)));
+
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+    }
+
+    /// A debug artifact for a program whose `main` calls a function `foo`, along with a circuit
+    /// whose opcodes are mapped to it as follows:
+    /// - opcode 0 to line 2, in `main`
+    /// - opcodes 1 and 2 to lines 6 and 7, in `foo` called from line 2
+    /// - opcode 3 to line 3, in `main`
+    fn call_debug_artifact() -> (Circuit, DebugArtifact) {
+        let source = "fn main() {\n    foo();\n    assert(true);\n}\nfn foo() {\n    assert(true);\n    assert(true);\n}\n";
+        let file_id = FileId::dummy();
+        let line_location = |line: usize| {
+            let start =
+                source.split_inclusive('\n').take(line - 1).map(str::len).sum::<usize>() + 4;
+            Location::new(Span::from(start as u32..start as u32 + 3), file_id)
+        };
+        let locations = BTreeMap::from([
+            (OpcodeLocation::Acir(0), vec![line_location(2)]),
+            (OpcodeLocation::Acir(1), vec![line_location(2), line_location(6)]),
+            (OpcodeLocation::Acir(2), vec![line_location(2), line_location(7)]),
+            (OpcodeLocation::Acir(3), vec![line_location(3)]),
+        ]);
+        let debug_file = DebugFile { source: source.to_string(), path: "src/main.nr".into() };
+        let debug_artifact = DebugArtifact {
+            debug_symbols: vec![DebugInfo::new(locations)],
+            file_map: BTreeMap::from([(file_id, debug_file)]),
+            warnings: vec![],
+        };
+        let opcodes = vec![Opcode::AssertZero(Expression::default()); 4];
+        (Circuit { opcodes, ..Circuit::default() }, debug_artifact)
+    }
+
+    #[test]
+    fn test_find_opcode_locations_at_line() {
+        let (circuit, debug_artifact) = call_debug_artifact();
+        let context = DebugContext::new(
+            &StubbedSolver,
+            &circuit,
+            &debug_artifact,
+            WitnessMap::new(),
+            Box::new(DefaultForeignCallExecutor::new(true, None)),
+        );
+        let file_id = FileId::dummy();
+
+        // execution of line 2 starts at opcode 0, and continues in the call to `foo`
+        assert_eq!(
+            context.find_opcode_locations_at_line(file_id, 2),
+            Some((2, vec![OpcodeLocation::Acir(0)]))
+        );
+        // line 1 has no opcodes so the next line which does is used
+        assert_eq!(
+            context.find_opcode_locations_at_line(file_id, 1),
+            Some((2, vec![OpcodeLocation::Acir(0)]))
+        );
+        assert_eq!(
+            context.find_opcode_locations_at_line(file_id, 7),
+            Some((7, vec![OpcodeLocation::Acir(2)]))
+        );
+        assert_eq!(context.find_opcode_locations_at_line(file_id, 8), None);
+    }
+
+    #[test]
+    fn test_step_out() {
+        let (circuit, debug_artifact) = call_debug_artifact();
+        let mut context = DebugContext::new(
+            &StubbedSolver,
+            &circuit,
+            &debug_artifact,
+            WitnessMap::new(),
+            Box::new(DefaultForeignCallExecutor::new(true, None)),
+        );
+
+        assert!(matches!(context.step_into_opcode(), DebugCommandResult::Ok));
+        assert_eq!(context.get_current_opcode_location(), Some(OpcodeLocation::Acir(1)));
+
+        // stepping out of `foo` returns to `main`
+        assert!(matches!(context.step_out(), DebugCommandResult::Ok));
+        assert_eq!(context.get_current_opcode_location(), Some(OpcodeLocation::Acir(3)));
+
+        // stepping out of `main` runs until the end of the program
+        assert!(matches!(context.step_out(), DebugCommandResult::Done));
+    }
+
+    #[test]
+    fn test_step_back_and_reverse_continue() {
+        let circuit = &brillig_sum_circuit();
+        let debug_artifact =
+            &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new(), warnings: vec![] };
+        let initial_witness: WitnessMap =
+            BTreeMap::from([(Witness(1), FieldElement::one()), (Witness(2), FieldElement::one())])
+                .into();
+        let mut context = DebugContext::new(
+            &StubbedSolver,
+            circuit,
+            debug_artifact,
+            initial_witness,
+            Box::new(DefaultForeignCallExecutor::new(true, None)),
+        );
+        let brillig_location =
+            |brillig_index| Some(OpcodeLocation::Brillig { acir_index: 0, brillig_index });
+
+        // nothing to go back to before the first step
+        assert!(context.is_at_start());
+        assert!(matches!(context.step_back_into_opcode(), DebugCommandResult::Ok));
+        assert_eq!(context.get_current_opcode_location(), Some(OpcodeLocation::Acir(0)));
+
+        assert!(matches!(context.step_into_opcode(), DebugCommandResult::Ok));
+        assert!(matches!(context.step_into_opcode(), DebugCommandResult::Ok));
+        assert_eq!(context.get_current_opcode_location(), brillig_location(2));
+        assert_eq!(context.get_brillig_registers().unwrap().inner[0], Value::from(2u128));
+
+        assert!(matches!(context.step_back_into_opcode(), DebugCommandResult::Ok));
+        assert_eq!(context.get_current_opcode_location(), brillig_location(1));
+        assert_eq!(context.get_brillig_registers().unwrap().inner[0], Value::from(2u128));
+
+        assert!(matches!(context.step_back_into_opcode(), DebugCommandResult::Ok));
+        assert_eq!(context.get_current_opcode_location(), Some(OpcodeLocation::Acir(0)));
+        assert_eq!(context.get_brillig_registers(), None);
+        assert!(context.is_at_start());
+
+        context.add_breakpoint(OpcodeLocation::Brillig { acir_index: 0, brillig_index: 1 });
+        assert!(matches!(context.cont(), DebugCommandResult::BreakpointReached(..)));
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+        assert_eq!(context.get_current_opcode_location(), None);
+
+        // going backwards stops at the breakpoint, then at the start of the program
+        let result = context.reverse_continue();
+        assert!(matches!(
+            result,
+            DebugCommandResult::BreakpointReached(OpcodeLocation::Brillig {
+                acir_index: 0,
+                brillig_index: 1
+            })
+        ));
+        assert_eq!(context.get_brillig_registers().unwrap().inner[0], Value::from(2u128));
+        assert!(matches!(context.reverse_continue(), DebugCommandResult::Ok));
+        assert!(context.is_at_start());
+
+        // the replayed execution reaches the same result
+        context.clear_breakpoints();
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+        assert_eq!(context.get_witness_map()[&Witness(3)], FieldElement::from(2u128));
+    }
+
+    #[test]
+    fn test_step_back_within_checkpoints_budget() {
+        let circuit = &brillig_sum_circuit();
+        let debug_artifact =
+            &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new(), warnings: vec![] };
+        let initial_witness: WitnessMap =
+            BTreeMap::from([(Witness(1), FieldElement::one()), (Witness(2), FieldElement::one())])
+                .into();
+        let mut context = DebugContext::new(
+            &StubbedSolver,
+            circuit,
+            debug_artifact,
+            initial_witness,
+            Box::new(DefaultForeignCallExecutor::new(true, None)),
+        );
+        // only the initial state fits in the budget
+        context.history = ExecutionHistory::new(1);
+
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+        assert_eq!(context.history.checkpoint_before(usize::MAX).map(|(step, _)| step), Some(0));
+
+        assert!(matches!(context.step_back_into_opcode(), DebugCommandResult::Ok));
+        assert_eq!(context.get_current_opcode_location(), Some(OpcodeLocation::Acir(1)));
+        assert!(matches!(context.step_back_into_opcode(), DebugCommandResult::Ok));
+        assert_eq!(
+            context.get_current_opcode_location(),
+            Some(OpcodeLocation::Brillig { acir_index: 0, brillig_index: 2 })
+        );
+
+        // overwritten values are kept when stepping back and forth over them
+        assert!(matches!(context.step_into_opcode(), DebugCommandResult::Ok));
+        context.overwrite_witness(Witness(1), FieldElement::from(5u128));
+        assert!(matches!(context.step_into_opcode(), DebugCommandResult::Error(..)));
+        assert!(matches!(context.step_back_into_opcode(), DebugCommandResult::Ok));
+        assert_eq!(context.get_witness_map()[&Witness(1)], FieldElement::one());
+        assert!(matches!(context.step_into_opcode(), DebugCommandResult::Ok));
+        assert_eq!(context.get_current_opcode_location(), Some(OpcodeLocation::Acir(1)));
+        assert_eq!(context.get_witness_map()[&Witness(1)], FieldElement::from(5u128));
+    }
+
+    #[test]
+    fn test_read_brillig_value_with_nested_arrays() {
+        // struct Foo { a: Field, b: [[u8; 2]; 2] }
+        let typ = PrintableType::Struct {
+            name: "Foo".to_string(),
+            fields: vec![
+                ("a".to_string(), PrintableType::Field),
+                (
+                    "b".to_string(),
+                    PrintableType::Array {
+                        length: 2,
+                        typ: Box::new(PrintableType::Array {
+                            length: 2,
+                            typ: Box::new(PrintableType::UnsignedInteger { width: 8 }),
+                        }),
+                    },
+                ),
+            ],
+        };
+        // `a` is held in register 0 and register 1 points to the elements of `b` in memory
+        let value = vec![
+            RegisterOrMemory::RegisterIndex(RegisterIndex::from(0)),
+            RegisterOrMemory::HeapArray(HeapArray { pointer: RegisterIndex::from(1), size: 2 }),
+        ];
+        let registers = Registers::load(vec![Value::from(7_usize), Value::from(0_usize)]);
+        // Each element of `b` is a reference to a (pointer, reference count) pair
+        let memory: Vec<Value> =
+            [2_usize, 4, 6, 1, 8, 1, 1, 2, 3, 4].into_iter().map(Value::from).collect();
+
+        let fields = read_brillig_value(&registers, &memory, &value, &typ);
+        let expected = [7_usize, 1, 2, 3, 4].into_iter().map(FieldElement::from).collect();
+        assert_eq!(fields, Some(expected));
+
+        // Values pointing past the end of the memory cannot be read
+        assert_eq!(read_brillig_value(&registers, &memory[..5], &value, &typ), None);
+    }
 }
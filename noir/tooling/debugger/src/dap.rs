@@ -17,15 +17,16 @@ use dap::requests::{Command, Request, SetBreakpointsArguments};
 use dap::responses::{
     ContinueResponse, DisassembleResponse, ResponseBody, ScopesResponse, SetBreakpointsResponse,
     SetExceptionBreakpointsResponse, SetInstructionBreakpointsResponse, StackTraceResponse,
-    ThreadsResponse,
+    ThreadsResponse, VariablesResponse,
 };
 use dap::server::Server;
 use dap::types::{
-    Breakpoint, DisassembledInstruction, Source, StackFrame, SteppingGranularity,
-    StoppedEventReason, Thread,
+    Breakpoint, DisassembledInstruction, Scope, Source, StackFrame, SteppingGranularity,
+    StoppedEventReason, Thread, Variable,
 };
 use nargo::artifacts::debug::DebugArtifact;
 use nargo::ops::DefaultForeignCallExecutor;
+use noirc_printable_type::{PrintableType, PrintableValue, PrintableValueDisplay};
 
 use fm::FileId;
 use noirc_driver::CompiledProgram;
@@ -39,6 +40,9 @@ pub struct DapSession<'a, R: Read, W: Write, B: BlackBoxFunctionSolver> {
     next_breakpoint_id: i64,
     instruction_breakpoints: Vec<(OpcodeLocation, i64)>,
     source_breakpoints: BTreeMap<FileId, Vec<(OpcodeLocation, i64)>>,
+    /// The variables listed under each `variablesReference` handed out by the last `scopes`
+    /// request. A reference is the index of its variables in this list plus one.
+    variable_references: Vec<Vec<Variable>>,
 }
 
 // BTreeMap<FileId, Vec<(usize, OpcodeLocation)>
@@ -68,6 +72,7 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver> DapSession<'a, R, W, B> {
             next_breakpoint_id: 1,
             instruction_breakpoints: vec![],
             source_breakpoints: BTreeMap::new(),
+            variable_references: vec![],
         }
     }
 
@@ -184,7 +189,7 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver> DapSession<'a, R, W, B> {
                         args.granularity.as_ref().unwrap_or(&SteppingGranularity::Statement);
                     match granularity {
                         SteppingGranularity::Instruction => self.handle_step(req)?,
-                        _ => self.handle_next(req)?,
+                        _ => self.handle_step_out(req)?,
                     }
                 }
                 Command::Next(ref args) => {
@@ -198,12 +203,22 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver> DapSession<'a, R, W, B> {
                 Command::Continue(_) => {
                     self.handle_continue(req)?;
                 }
+                Command::StepBack(ref args) => {
+                    let granularity =
+                        args.granularity.as_ref().unwrap_or(&SteppingGranularity::Statement);
+                    match granularity {
+                        SteppingGranularity::Instruction => self.handle_step_back(req)?,
+                        _ => self.handle_step_back_statement(req)?,
+                    }
+                }
+                Command::ReverseContinue(_) => {
+                    self.handle_reverse_continue(req)?;
+                }
                 Command::Scopes(_) => {
-                    // FIXME: this needs a proper implementation when we can
-                    // show the parameters and variables
-                    self.server.respond(
-                        req.success(ResponseBody::Scopes(ScopesResponse { scopes: vec![] })),
-                    )?;
+                    self.handle_scopes(req)?;
+                }
+                Command::Variables(_) => {
+                    self.handle_variables(req)?;
                 }
                 _ => {
                     eprintln!("ERROR: unhandled command: {:?}", req.command);
@@ -252,6 +267,84 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver> DapSession<'a, R, W, B> {
         Ok(())
     }
 
+    /// Responds with a scope for each function whose variables have been assigned,
+    /// building the variables which can then be requested under those scopes.
+    fn handle_scopes(&mut self, req: Request) -> Result<(), ServerError> {
+        self.variable_references.clear();
+        let mut scopes: Vec<Scope> = Vec::new();
+        for (variable, value) in self.context.get_variables() {
+            if scopes.last().map(|scope| &scope.name) != Some(&variable.function) {
+                self.variable_references.push(vec![]);
+                scopes.push(Scope {
+                    name: variable.function.clone(),
+                    variables_reference: self.variable_references.len() as i64,
+                    ..Scope::default()
+                });
+            }
+            let scope_index = self.variable_references.len() - 1;
+            let variable = self.build_variable(variable.name.clone(), value, &variable.typ);
+            self.variable_references[scope_index].push(variable);
+        }
+
+        self.server.respond(req.success(ResponseBody::Scopes(ScopesResponse { scopes })))?;
+        Ok(())
+    }
+
+    /// Builds the variable displaying `value`. The elements of arrays and the fields of structs
+    /// are registered as its children so that they can be expanded.
+    fn build_variable(
+        &mut self,
+        name: String,
+        value: PrintableValue,
+        typ: &PrintableType,
+    ) -> Variable {
+        let display = PrintableValueDisplay::Plain(value.clone(), typ.clone()).to_string();
+        let children: Vec<_> = match (value, typ) {
+            (PrintableValue::Vec(elements), PrintableType::Array { typ, .. }) => elements
+                .into_iter()
+                .enumerate()
+                .map(|(index, element)| (index.to_string(), element, typ.as_ref()))
+                .collect(),
+            (PrintableValue::Struct(mut fields), PrintableType::Struct { fields: types, .. }) => {
+                types
+                    .iter()
+                    .filter_map(|(name, typ)| Some((name.clone(), fields.remove(name)?, typ)))
+                    .collect()
+            }
+            _ => vec![],
+        };
+
+        let variables_reference = if children.is_empty() {
+            0
+        } else {
+            // Reserve the reference before building the children, which may register their own.
+            self.variable_references.push(vec![]);
+            let reference = self.variable_references.len();
+            let children = children
+                .into_iter()
+                .map(|(name, value, typ)| self.build_variable(name, value, typ))
+                .collect();
+            self.variable_references[reference - 1] = children;
+            reference as i64
+        };
+
+        Variable { name, value: display, variables_reference, ..Variable::default() }
+    }
+
+    fn handle_variables(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::Variables(ref args) = req.command else {
+            unreachable!("handle_variables called on a non variables request");
+        };
+        let variables = usize::try_from(args.variables_reference - 1)
+            .ok()
+            .and_then(|index| self.variable_references.get(index))
+            .cloned()
+            .unwrap_or_default();
+        self.server
+            .respond(req.success(ResponseBody::Variables(VariablesResponse { variables })))?;
+        Ok(())
+    }
+
     fn handle_disassemble(&mut self, req: Request) -> Result<(), ServerError> {
         let Command::Disassemble(ref args) = req.command else {
             unreachable!("handle_disassemble called on a non disassemble request");
@@ -322,6 +415,13 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver> DapSession<'a, R, W, B> {
         self.handle_execution_result(result)
     }
 
+    fn handle_step_out(&mut self, req: Request) -> Result<(), ServerError> {
+        let result = self.context.step_out();
+        eprintln!("INFO: stepped out with result {result:?}");
+        self.server.respond(req.ack()?)?;
+        self.handle_execution_result(result)
+    }
+
     fn handle_continue(&mut self, req: Request) -> Result<(), ServerError> {
         let result = self.context.cont();
         eprintln!("INFO: continue with result {result:?}");
@@ -331,6 +431,27 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver> DapSession<'a, R, W, B> {
         self.handle_execution_result(result)
     }
 
+    fn handle_step_back(&mut self, req: Request) -> Result<(), ServerError> {
+        let result = self.context.step_back_into_opcode();
+        eprintln!("INFO: stepped back by instruction with result {result:?}");
+        self.server.respond(req.ack()?)?;
+        self.handle_execution_result(result)
+    }
+
+    fn handle_step_back_statement(&mut self, req: Request) -> Result<(), ServerError> {
+        let result = self.context.step_back_source_location();
+        eprintln!("INFO: stepped back by statement with result {result:?}");
+        self.server.respond(req.ack()?)?;
+        self.handle_execution_result(result)
+    }
+
+    fn handle_reverse_continue(&mut self, req: Request) -> Result<(), ServerError> {
+        let result = self.context.reverse_continue();
+        eprintln!("INFO: reverse continue with result {result:?}");
+        self.server.respond(req.ack()?)?;
+        self.handle_execution_result(result)
+    }
+
     fn find_breakpoints_at_location(&self, opcode_location: &OpcodeLocation) -> Vec<i64> {
         let mut result = vec![];
         for (location, id) in &self.instruction_breakpoints {
@@ -376,6 +497,17 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver> DapSession<'a, R, W, B> {
                     hit_breakpoint_ids: Some(breakpoint_ids),
                 }))?;
             }
+            DebugCommandResult::WatchpointTriggered(watchpoint) => {
+                self.server.send_event(Event::Stopped(StoppedEventBody {
+                    reason: StoppedEventReason::Breakpoint,
+                    description: Some(format!("Paused at watchpoint on {watchpoint}")),
+                    thread_id: Some(0),
+                    preserve_focus_hint: Some(false),
+                    text: None,
+                    all_threads_stopped: Some(false),
+                    hit_breakpoint_ids: None,
+                }))?;
+            }
             DebugCommandResult::Error(err) => {
                 self.server.send_event(Event::Stopped(StoppedEventBody {
                     reason: StoppedEventReason::Exception,
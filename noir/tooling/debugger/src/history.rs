@@ -0,0 +1,149 @@
+use acvm::acir::brillig::ForeignCallResult;
+use acvm::acir::circuit::OpcodeLocation;
+use acvm::pwg::{BrilligSolver, ACVM};
+use acvm::{BlackBoxFunctionSolver, FieldElement};
+use noirc_errors::debug_info::DebugVarId;
+
+use std::collections::BTreeMap;
+
+/// The approximate amount of memory, in bytes, the checkpoints of a debugging session may take by default.
+pub(super) const DEFAULT_CHECKPOINTS_BUDGET: usize = 64 * 1024 * 1024;
+
+/// The state of the ACVM and Brillig VM before some step of the execution.
+pub(super) struct Checkpoint<'a, B: BlackBoxFunctionSolver> {
+    pub(super) acvm: ACVM<'a, B>,
+    pub(super) brillig_solver: Option<BrilligSolver<'a, B>>,
+    pub(super) brillig_variables: BTreeMap<DebugVarId, Vec<FieldElement>>,
+    /// The approximate amount of memory taken by the checkpoint, in bytes
+    pub(super) size: usize,
+    /// Whether the state was modified by the user rather than reached by executing the program,
+    /// in which case it cannot be recovered by replaying steps and so the checkpoint is never dropped.
+    pub(super) pinned: bool,
+}
+
+impl<'a, B: BlackBoxFunctionSolver> Clone for Checkpoint<'a, B> {
+    fn clone(&self) -> Self {
+        Self {
+            acvm: self.acvm.clone(),
+            brillig_solver: self.brillig_solver.clone(),
+            brillig_variables: self.brillig_variables.clone(),
+            size: self.size,
+            pinned: self.pinned,
+        }
+    }
+}
+
+/// Records the steps taken during a debugging session so that execution can be rewound to any of them.
+///
+/// Rather than keeping the state before every step, checkpoints are taken every `checkpoint_interval`
+/// steps, and rewinding to a step restores the closest checkpoint before it and replays the steps in
+/// between, resolving foreign calls with their recorded results. Whenever the checkpoints go over the
+/// memory budget every other one is dropped and the interval doubles, so that the whole execution
+/// remains reachable at the cost of longer replays.
+pub(super) struct ExecutionHistory<'a, B: BlackBoxFunctionSolver> {
+    /// The number of steps taken to reach the current state
+    step: usize,
+    checkpoints: BTreeMap<usize, Checkpoint<'a, B>>,
+    checkpoint_interval: usize,
+    checkpoints_size: usize,
+    budget: usize,
+    /// The opcode location execution was at before each step
+    locations: Vec<Option<OpcodeLocation>>,
+    /// The results of the foreign calls resolved by each step
+    foreign_call_results: BTreeMap<usize, ForeignCallResult>,
+}
+
+impl<'a, B: BlackBoxFunctionSolver> ExecutionHistory<'a, B> {
+    pub(super) fn new(budget: usize) -> Self {
+        Self {
+            step: 0,
+            checkpoints: BTreeMap::new(),
+            checkpoint_interval: 1,
+            checkpoints_size: 0,
+            budget,
+            locations: Vec::new(),
+            foreign_call_results: BTreeMap::new(),
+        }
+    }
+
+    pub(super) fn current_step(&self) -> usize {
+        self.step
+    }
+
+    /// Returns the opcode location execution was at before the given past step.
+    pub(super) fn location_at(&self, step: usize) -> Option<OpcodeLocation> {
+        self.locations[step]
+    }
+
+    pub(super) fn needs_checkpoint(&self) -> bool {
+        self.step % self.checkpoint_interval == 0 && !self.checkpoints.contains_key(&self.step)
+    }
+
+    /// Stores the checkpoint of the state before the current step, dropping
+    /// checkpoints as needed to remain within the memory budget.
+    pub(super) fn add_checkpoint(&mut self, checkpoint: Checkpoint<'a, B>) {
+        self.checkpoints_size += checkpoint.size;
+        if let Some(replaced) = self.checkpoints.insert(self.step, checkpoint) {
+            self.checkpoints_size -= replaced.size;
+        }
+
+        while self.checkpoints_size > self.budget {
+            self.checkpoint_interval *= 2;
+            let interval = self.checkpoint_interval;
+            let checkpoints_count = self.checkpoints.len();
+            self.checkpoints.retain(|step, checkpoint| checkpoint.pinned || step % interval == 0);
+            self.checkpoints_size =
+                self.checkpoints.values().map(|checkpoint| checkpoint.size).sum();
+            if self.checkpoints.len() == checkpoints_count {
+                // Only the initial state and pinned checkpoints are left
+                break;
+            }
+        }
+    }
+
+    /// Returns the checkpoint of the state the user left the current step in, if they modified it.
+    pub(super) fn pinned_checkpoint(&self) -> Option<&Checkpoint<'a, B>> {
+        self.checkpoints.get(&self.step).filter(|checkpoint| checkpoint.pinned)
+    }
+
+    /// Returns the latest checkpoint taken at or before the given step, along with its step.
+    pub(super) fn checkpoint_before(&self, step: usize) -> Option<(usize, &Checkpoint<'a, B>)> {
+        self.checkpoints.range(..=step).next_back().map(|(step, checkpoint)| (*step, checkpoint))
+    }
+
+    /// Moves back to the given step, whose state has just been restored from its checkpoint.
+    pub(super) fn rewind_to(&mut self, step: usize) {
+        assert!(step <= self.step, "Cannot rewind to a step which has not been taken yet");
+        self.step = step;
+    }
+
+    pub(super) fn record_step(&mut self, location: Option<OpcodeLocation>) {
+        // Replayed steps are always taken at the same location as they were before
+        if self.step < self.locations.len() {
+            self.locations[self.step] = location;
+        } else {
+            self.locations.push(location);
+        }
+        self.step += 1;
+    }
+
+    /// Returns the result of the foreign call resolved by the current step
+    /// if the step has been taken before.
+    pub(super) fn foreign_call_result(&self) -> Option<&ForeignCallResult> {
+        self.foreign_call_results.get(&self.step)
+    }
+
+    pub(super) fn record_foreign_call_result(&mut self, result: ForeignCallResult) {
+        self.foreign_call_results.insert(self.step, result);
+    }
+
+    /// Forgets about the steps which were taken after the current one, which must
+    /// be done when the current state is modified as they no longer follow from it.
+    pub(super) fn discard_future(&mut self) {
+        self.locations.truncate(self.step);
+        self.foreign_call_results.split_off(&self.step);
+        for (_, checkpoint) in self.checkpoints.split_off(&self.step) {
+            self.checkpoints_size -= checkpoint.size;
+        }
+    }
+}
@@ -1,5 +1,6 @@
 mod context;
 mod dap;
+mod history;
 mod repl;
 mod source_code_printer;
 
@@ -12,16 +13,22 @@ use acvm::{acir::circuit::Circuit, acir::native_types::WitnessMap};
 
 use nargo::artifacts::debug::DebugArtifact;
 
+use nargo::ops::ForeignCallExecutor;
 use nargo::NargoError;
 use noirc_driver::CompiledProgram;
 
+/// Creates the executor which resolves the foreign calls of a debugging session.
+/// It is called again each time the session is restarted.
+pub type ForeignCallExecutorFactory = dyn Fn() -> Result<Box<dyn ForeignCallExecutor>, NargoError>;
+
 pub fn debug_circuit<B: BlackBoxFunctionSolver>(
     blackbox_solver: &B,
     circuit: &Circuit,
     debug_artifact: DebugArtifact,
     initial_witness: WitnessMap,
+    new_foreign_call_executor: &ForeignCallExecutorFactory,
 ) -> Result<Option<WitnessMap>, NargoError> {
-    repl::run(blackbox_solver, circuit, &debug_artifact, initial_witness)
+    repl::run(blackbox_solver, circuit, &debug_artifact, initial_witness, new_foreign_call_executor)
 }
 
 pub fn run_dap_loop<R: Read, W: Write, B: BlackBoxFunctionSolver>(
@@ -1,15 +1,52 @@
-use crate::context::{DebugCommandResult, DebugContext};
+use crate::context::{BreakpointCondition, DebugCommandResult, DebugContext, Watchpoint};
 
 use acvm::acir::circuit::{Circuit, Opcode, OpcodeLocation};
 use acvm::acir::native_types::{Witness, WitnessMap};
 use acvm::{BlackBoxFunctionSolver, FieldElement};
 
-use nargo::{artifacts::debug::DebugArtifact, ops::DefaultForeignCallExecutor, NargoError};
+use nargo::{artifacts::debug::DebugArtifact, NargoError};
+use noirc_printable_type::PrintableValueDisplay;
 
 use easy_repl::{command, CommandStatus, Repl};
+use fm::FileId;
 use std::cell::RefCell;
+use std::path::Path;
+use std::str::FromStr;
+use thiserror::Error;
 
 use crate::source_code_printer::print_source_code_location;
+use crate::ForeignCallExecutorFactory;
+
+/// Where a breakpoint is set, given either as an opcode location such as `3.12`
+/// or as a source line such as `main.nr:42`.
+#[derive(Debug, Clone)]
+enum BreakpointLocation {
+    Opcode(OpcodeLocation),
+    SourceLine { file: String, line: usize },
+}
+
+#[derive(Error, Debug)]
+#[error("Invalid breakpoint location `{0}`, expected an opcode location such as `3.12` or a source line such as `main.nr:42`")]
+struct BreakpointLocationFromStrError(String);
+
+impl FromStr for BreakpointLocation {
+    type Err = BreakpointLocationFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(location) = OpcodeLocation::from_str(s) {
+            return Ok(BreakpointLocation::Opcode(location));
+        }
+        match s.rsplit_once(':') {
+            Some((file, line)) if !file.is_empty() => match line.parse() {
+                Ok(line) if line > 0 => {
+                    Ok(BreakpointLocation::SourceLine { file: file.to_string(), line })
+                }
+                _ => Err(BreakpointLocationFromStrError(s.to_string())),
+            },
+            _ => Err(BreakpointLocationFromStrError(s.to_string())),
+        }
+    }
+}
 
 pub struct ReplDebugger<'a, B: BlackBoxFunctionSolver> {
     context: DebugContext<'a, B>,
@@ -17,6 +54,7 @@ pub struct ReplDebugger<'a, B: BlackBoxFunctionSolver> {
     circuit: &'a Circuit,
     debug_artifact: &'a DebugArtifact,
     initial_witness: WitnessMap,
+    new_foreign_call_executor: &'a ForeignCallExecutorFactory,
     last_result: DebugCommandResult,
 }
 
@@ -26,22 +64,24 @@ impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
         circuit: &'a Circuit,
         debug_artifact: &'a DebugArtifact,
         initial_witness: WitnessMap,
-    ) -> Self {
+        new_foreign_call_executor: &'a ForeignCallExecutorFactory,
+    ) -> Result<Self, NargoError> {
         let context = DebugContext::new(
             blackbox_solver,
             circuit,
             debug_artifact,
             initial_witness.clone(),
-            Box::new(DefaultForeignCallExecutor::new(true, None)),
+            new_foreign_call_executor()?,
         );
-        Self {
+        Ok(Self {
             context,
             blackbox_solver,
             circuit,
             debug_artifact,
             initial_witness,
+            new_foreign_call_executor,
             last_result: DebugCommandResult::Ok,
-        }
+        })
     }
 
     pub fn show_current_vm_status(&self) {
@@ -124,27 +164,101 @@ impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
         }
     }
 
-    fn add_breakpoint_at(&mut self, location: OpcodeLocation) {
-        if !self.context.is_valid_opcode_location(&location) {
-            println!("Invalid opcode location {location}");
-        } else if self.context.add_breakpoint(location) {
-            println!("Added breakpoint at opcode {location}");
+    fn find_source_file(&self, file: &str) -> Option<FileId> {
+        let path = Path::new(file);
+        let matching_files: Vec<_> = self
+            .debug_artifact
+            .file_map
+            .iter()
+            .filter(|(_, debug_file)| debug_file.path.ends_with(path))
+            .collect();
+        match matching_files.as_slice() {
+            [(file_id, _)] => Some(**file_id),
+            [] => {
+                println!("No source file matches {file}");
+                None
+            }
+            _ => {
+                println!("Source file {file} is ambiguous, it matches:");
+                for (_, debug_file) in matching_files {
+                    println!("  {}", debug_file.path.display());
+                }
+                None
+            }
+        }
+    }
+
+    fn resolve_breakpoint_location(&self, location: BreakpointLocation) -> Vec<OpcodeLocation> {
+        let (file, line) = match location {
+            BreakpointLocation::Opcode(location) => return vec![location],
+            BreakpointLocation::SourceLine { file, line } => (file, line),
+        };
+        let Some(file_id) = self.find_source_file(&file) else {
+            return vec![];
+        };
+        match self.context.find_opcode_locations_at_line(file_id, line) {
+            Some((found_line, locations)) => {
+                if found_line != line {
+                    println!("No opcodes at {file}:{line}, using {file}:{found_line} instead");
+                }
+                locations
+            }
+            None => {
+                println!("No opcodes at or after {file}:{line}");
+                vec![]
+            }
+        }
+    }
+
+    fn add_breakpoint_at(
+        &mut self,
+        location: BreakpointLocation,
+        condition: Option<BreakpointCondition>,
+    ) {
+        for location in self.resolve_breakpoint_location(location) {
+            if !self.context.is_valid_opcode_location(&location) {
+                println!("Invalid opcode location {location}");
+            } else if !self.context.add_conditional_breakpoint(location, condition) {
+                println!("Breakpoint at opcode {location} already set");
+            } else if let Some(condition) = condition {
+                println!("Added breakpoint at opcode {location} if {condition}");
+            } else {
+                println!("Added breakpoint at opcode {location}");
+            }
+        }
+    }
+
+    fn delete_breakpoint_at(&mut self, location: BreakpointLocation) {
+        for location in self.resolve_breakpoint_location(location) {
+            if self.context.delete_breakpoint(&location) {
+                println!("Breakpoint at opcode {location} deleted");
+            } else {
+                println!("Breakpoint at opcode {location} not set");
+            }
+        }
+    }
+
+    fn add_watchpoint(&mut self, watchpoint: Watchpoint) {
+        if self.context.add_watchpoint(watchpoint) {
+            println!("Added watchpoint on {watchpoint}");
         } else {
-            println!("Breakpoint at opcode {location} already set");
+            println!("Watchpoint on {watchpoint} already set");
         }
     }
 
-    fn delete_breakpoint_at(&mut self, location: OpcodeLocation) {
-        if self.context.delete_breakpoint(&location) {
-            println!("Breakpoint at opcode {location} deleted");
+    fn delete_watchpoint(&mut self, watchpoint: Watchpoint) {
+        if self.context.delete_watchpoint(&watchpoint) {
+            println!("Watchpoint on {watchpoint} deleted");
         } else {
-            println!("Breakpoint at opcode {location} not set");
+            println!("Watchpoint on {watchpoint} not set");
         }
     }
 
     fn validate_in_progress(&self) -> bool {
         match self.last_result {
-            DebugCommandResult::Ok | DebugCommandResult::BreakpointReached(..) => true,
+            DebugCommandResult::Ok
+            | DebugCommandResult::BreakpointReached(..)
+            | DebugCommandResult::WatchpointTriggered(..) => true,
             DebugCommandResult::Done => {
                 println!("Execution finished");
                 false
@@ -162,6 +276,12 @@ impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
             DebugCommandResult::BreakpointReached(location) => {
                 println!("Stopped at breakpoint in opcode {}", location);
             }
+            DebugCommandResult::WatchpointTriggered(watchpoint) => {
+                match self.context.get_watched_value(watchpoint) {
+                    Some(value) => println!("Stopped at watchpoint, {watchpoint} = {value}"),
+                    None => println!("Stopped at watchpoint on {watchpoint}"),
+                }
+            }
             DebugCommandResult::Error(error) => {
                 println!("ERROR: {}", error);
             }
@@ -192,6 +312,13 @@ impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
         }
     }
 
+    fn finish(&mut self) {
+        if self.validate_in_progress() {
+            let result = self.context.step_out();
+            self.handle_debug_command_result(result);
+        }
+    }
+
     fn cont(&mut self) {
         if self.validate_in_progress() {
             println!("(Continuing execution...)");
@@ -200,18 +327,54 @@ impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
         }
     }
 
+    fn step_back(&mut self) {
+        if self.context.is_at_start() {
+            println!("Already at the start of the program");
+            return;
+        }
+        let result = self.context.step_back_into_opcode();
+        self.handle_debug_command_result(result);
+    }
+
+    fn reverse_continue(&mut self) {
+        if self.context.is_at_start() {
+            println!("Already at the start of the program");
+            return;
+        }
+        println!("(Continuing execution backwards...)");
+        let result = self.context.reverse_continue();
+        if self.context.is_at_start() {
+            println!("Reached the start of the program");
+        }
+        self.handle_debug_command_result(result);
+    }
+
     fn restart_session(&mut self) {
-        let breakpoints: Vec<OpcodeLocation> =
-            self.context.iterate_breakpoints().copied().collect();
+        let foreign_call_executor = match (self.new_foreign_call_executor)() {
+            Ok(foreign_call_executor) => foreign_call_executor,
+            Err(err) => {
+                println!("Could not restart debugging session: {err}");
+                return;
+            }
+        };
+        let breakpoints: Vec<(OpcodeLocation, Option<BreakpointCondition>)> = self
+            .context
+            .iterate_breakpoints()
+            .map(|(location, condition)| (*location, *condition))
+            .collect();
+        let watchpoints: Vec<Watchpoint> = self.context.iterate_watchpoints().copied().collect();
         self.context = DebugContext::new(
             self.blackbox_solver,
             self.circuit,
             self.debug_artifact,
             self.initial_witness.clone(),
-            Box::new(DefaultForeignCallExecutor::new(true, None)),
+            foreign_call_executor,
         );
-        for opcode_location in breakpoints {
-            self.context.add_breakpoint(opcode_location);
+        for (opcode_location, condition) in breakpoints {
+            self.context.add_conditional_breakpoint(opcode_location, condition);
+        }
+        for watchpoint in watchpoints {
+            self.context.add_watchpoint(watchpoint);
         }
         self.last_result = DebugCommandResult::Ok;
         println!("Restarted debugging session.");
@@ -243,6 +406,24 @@ impl<'a, B: BlackBoxFunctionSolver> ReplDebugger<'a, B> {
         println!("_{} = {value}", index);
     }
 
+    pub fn show_vars(&self) {
+        let variables = self.context.get_variables();
+        if variables.is_empty() {
+            println!("No variables have been assigned yet");
+            return;
+        }
+
+        let mut current_function = None;
+        for (variable, value) in variables {
+            if current_function != Some(&variable.function) {
+                println!("{}:", variable.function);
+                current_function = Some(&variable.function);
+            }
+            let value = PrintableValueDisplay::Plain(value, variable.typ.clone());
+            println!("  {} = {value}", variable.name);
+        }
+    }
+
     pub fn show_brillig_registers(&self) {
         if !self.context.is_executing_brillig() {
             println!("Not executing a Brillig block");
@@ -319,9 +500,15 @@ pub fn run<B: BlackBoxFunctionSolver>(
     circuit: &Circuit,
     debug_artifact: &DebugArtifact,
     initial_witness: WitnessMap,
+    new_foreign_call_executor: &ForeignCallExecutorFactory,
 ) -> Result<Option<WitnessMap>, NargoError> {
-    let context =
-        RefCell::new(ReplDebugger::new(blackbox_solver, circuit, debug_artifact, initial_witness));
+    let context = RefCell::new(ReplDebugger::new(
+        blackbox_solver,
+        circuit,
+        debug_artifact,
+        initial_witness,
+        new_foreign_call_executor,
+    )?);
     let ref_context = &context;
 
     ref_context.borrow().show_current_vm_status();
@@ -357,6 +544,16 @@ pub fn run<B: BlackBoxFunctionSolver>(
                 }
             },
         )
+        .add(
+            "finish",
+            command! {
+                "step until the current function returns to its caller",
+                () => || {
+                    ref_context.borrow_mut().finish();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .add(
             "continue",
             command! {
@@ -367,6 +564,26 @@ pub fn run<B: BlackBoxFunctionSolver>(
                 }
             },
         )
+        .add(
+            "back",
+            command! {
+                "step back to the previous opcode",
+                () => || {
+                    ref_context.borrow_mut().step_back();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "rcontinue",
+            command! {
+                "continue execution backwards until the previous breakpoint",
+                () => || {
+                    ref_context.borrow_mut().reverse_continue();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .add(
             "restart",
             command! {
@@ -390,9 +607,19 @@ pub fn run<B: BlackBoxFunctionSolver>(
         .add(
             "break",
             command! {
-                "add a breakpoint at an opcode location",
-                (LOCATION:OpcodeLocation) => |location| {
-                    ref_context.borrow_mut().add_breakpoint_at(location);
+                "add a breakpoint at an opcode location or source line (e.g. 3.12 or main.nr:42)",
+                (LOCATION:BreakpointLocation) => |location| {
+                    ref_context.borrow_mut().add_breakpoint_at(location, None);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "break",
+            command! {
+                "add a breakpoint which only stops when a witness condition holds (e.g. _3==5)",
+                (LOCATION:BreakpointLocation, CONDITION:BreakpointCondition) => |location, condition| {
+                    ref_context.borrow_mut().add_breakpoint_at(location, Some(condition));
                     Ok(CommandStatus::Done)
                 }
             },
@@ -400,13 +627,33 @@ pub fn run<B: BlackBoxFunctionSolver>(
         .add(
             "delete",
             command! {
-                "delete breakpoint at an opcode location",
-                (LOCATION:OpcodeLocation) => |location| {
+                "delete breakpoint at an opcode location or source line",
+                (LOCATION:BreakpointLocation) => |location| {
                     ref_context.borrow_mut().delete_breakpoint_at(location);
                     Ok(CommandStatus::Done)
                 }
             },
         )
+        .add(
+            "watch",
+            command! {
+                "stop when a witness or Brillig memory cell is written (e.g. _3 or memory[3])",
+                (WATCHPOINT:Watchpoint) => |watchpoint| {
+                    ref_context.borrow_mut().add_watchpoint(watchpoint);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "unwatch",
+            command! {
+                "delete watchpoint on a witness or Brillig memory cell",
+                (WATCHPOINT:Watchpoint) => |watchpoint| {
+                    ref_context.borrow_mut().delete_watchpoint(watchpoint);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .add(
             "witness",
             command! {
@@ -437,6 +684,16 @@ pub fn run<B: BlackBoxFunctionSolver>(
                 }
             },
         )
+        .add(
+            "vars",
+            command! {
+                "show the values of the variables assigned so far",
+                () => || {
+                    ref_context.borrow().show_vars();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .add(
             "registers",
             command! {
@@ -17,10 +17,10 @@ use async_lsp::{
     router::Router, AnyEvent, AnyNotification, AnyRequest, ClientSocket, Error, LspService,
     ResponseError,
 };
-use fm::codespan_files as files;
+use fm::{codespan_files as files, FileManager};
 use lsp_types::CodeLens;
-use nargo::workspace::Workspace;
-use nargo_toml::{find_file_manifest, resolve_workspace_from_toml, PackageSelection};
+use nargo::{insert_all_files_for_workspace_into_file_manager, workspace::Workspace};
+use nargo_toml::{find_file_manifest, resolve_workspace_from_toml, LockMode, PackageSelection};
 use noirc_driver::{file_manager_with_stdlib, prepare_crate, NOIR_ARTIFACT_VERSION_STRING};
 use noirc_frontend::{
     graph::{CrateId, CrateName},
@@ -29,12 +29,14 @@ use noirc_frontend::{
 };
 
 use notifications::{
-    on_did_change_configuration, on_did_change_text_document, on_did_close_text_document,
-    on_did_open_text_document, on_did_save_text_document, on_exit, on_initialized,
+    on_check_document, on_did_change_configuration, on_did_change_text_document,
+    on_did_close_text_document, on_did_open_text_document, on_did_save_text_document, on_exit,
+    on_initialized, CheckDocument, PackageDiagnostics,
 };
 use requests::{
-    on_code_lens_request, on_formatting, on_goto_definition_request, on_initialize,
-    on_profile_run_request, on_shutdown, on_test_run_request, on_tests_request,
+    on_code_lens_request, on_completion_request, on_document_symbol_request, on_formatting,
+    on_goto_definition_request, on_hover_request, on_initialize, on_profile_run_request,
+    on_references_request, on_rename_request, on_shutdown, on_test_run_request, on_tests_request,
 };
 use serde_json::Value as JsonValue;
 use thiserror::Error;
@@ -62,8 +64,13 @@ pub struct LspState {
     solver: WrapperSolver,
     open_documents_count: usize,
     input_files: HashMap<String, String>,
+    /// The latest version of each open document, used to discard checks scheduled for older versions
+    document_versions: HashMap<String, i32>,
     cached_lenses: HashMap<String, Vec<CodeLens>>,
     cached_definitions: HashMap<String, NodeInterner>,
+    /// The diagnostics of each workspace package, so that packages are only checked again when
+    /// they or their dependencies change
+    cached_diagnostics: HashMap<String, PackageDiagnostics>,
 }
 
 impl LspState {
@@ -73,8 +80,10 @@ impl LspState {
             root_path: None,
             solver: WrapperSolver(Box::new(solver)),
             input_files: HashMap::new(),
+            document_versions: HashMap::new(),
             cached_lenses: HashMap::new(),
             cached_definitions: HashMap::new(),
+            cached_diagnostics: HashMap::new(),
             open_documents_count: 0,
         }
     }
@@ -97,13 +106,19 @@ impl NargoLspService {
             .request::<request::NargoTestRun, _>(on_test_run_request)
             .request::<request::NargoProfileRun, _>(on_profile_run_request)
             .request::<request::GotoDefinition, _>(on_goto_definition_request)
+            .request::<request::Hover, _>(on_hover_request)
+            .request::<request::References, _>(on_references_request)
+            .request::<request::Rename, _>(on_rename_request)
+            .request::<request::DocumentSymbol, _>(on_document_symbol_request)
+            .request::<request::Completion, _>(on_completion_request)
             .notification::<notification::Initialized>(on_initialized)
             .notification::<notification::DidChangeConfiguration>(on_did_change_configuration)
             .notification::<notification::DidOpenTextDocument>(on_did_open_text_document)
             .notification::<notification::DidChangeTextDocument>(on_did_change_text_document)
             .notification::<notification::DidCloseTextDocument>(on_did_close_text_document)
             .notification::<notification::DidSaveTextDocument>(on_did_save_text_document)
-            .notification::<notification::Exit>(on_exit);
+            .notification::<notification::Exit>(on_exit)
+            .event::<CheckDocument>(on_check_document);
         Self { router }
     }
 }
@@ -211,12 +226,38 @@ pub(crate) fn resolve_workspace_for_source_path(file_path: &Path) -> Result<Work
         &toml_path,
         PackageSelection::All,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        LockMode::Update,
     )
     .map_err(|err| LspError::WorkspaceResolutionError(err.to_string()))?;
 
     Ok(workspace)
 }
 
+/// Creates a file manager containing the stdlib and all the files of the workspace, where the
+/// contents of the documents open in the editor are used instead of the files on disk.
+pub(crate) fn workspace_file_manager(state: &LspState, workspace: &Workspace) -> FileManager {
+    let mut file_manager = file_manager_with_stdlib(&workspace.root_dir);
+    insert_workspace_files_into_file_manager(state, workspace, &mut file_manager);
+    file_manager
+}
+
+/// Inserts the open documents followed by all the files of the workspace into the file manager.
+///
+/// Files which are already in the file manager are never replaced, so the contents of the open
+/// documents take precedence over the files on disk.
+pub(crate) fn insert_workspace_files_into_file_manager(
+    state: &LspState,
+    workspace: &Workspace,
+    file_manager: &mut FileManager,
+) {
+    for (uri, source) in &state.input_files {
+        if let Some(path) = Url::parse(uri).ok().and_then(|uri| uri.to_file_path().ok()) {
+            file_manager.add_file_with_source(&path, source.clone());
+        }
+    }
+    insert_all_files_for_workspace_into_file_manager(workspace, file_manager);
+}
+
 /// Prepares a package from a source string
 /// This is useful for situations when we don't need dependencies
 /// and just need to operate on single file.
@@ -1,20 +1,29 @@
-use std::ops::ControlFlow;
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use async_lsp::{ErrorCode, LanguageClient, ResponseError};
-use nargo::{insert_all_files_for_workspace_into_file_manager, prepare_package};
-use noirc_driver::{check_crate, file_manager_with_stdlib};
+use fm::FileManager;
+use nargo::{
+    package::{Dependency, Package},
+    prepare_package,
+};
+use noirc_driver::check_crate;
 use noirc_errors::{DiagnosticKind, FileDiagnostic};
 
-use crate::requests::collect_lenses_for_package;
 use crate::types::{
     notification, Diagnostic, DiagnosticSeverity, DidChangeConfigurationParams,
     DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
-    DidSaveTextDocumentParams, InitializedParams, NargoPackageTests, PublishDiagnosticsParams,
+    DidSaveTextDocumentParams, InitializedParams, NargoPackageTests, PublishDiagnosticsParams, Url,
 };
 
 use crate::{
-    byte_span_to_range, get_package_tests_in_crate, prepare_source,
-    resolve_workspace_for_source_path, LspState,
+    byte_span_to_range, get_package_tests_in_crate, resolve_workspace_for_source_path,
+    workspace_file_manager, LspState,
 };
 
 pub(super) fn on_initialized(
@@ -35,9 +44,9 @@ pub(super) fn on_did_open_text_document(
     state: &mut LspState,
     params: DidOpenTextDocumentParams,
 ) -> ControlFlow<Result<(), async_lsp::Error>> {
-    state.input_files.insert(params.text_document.uri.to_string(), params.text_document.text);
-
     let document_uri = params.text_document.uri;
+    state.input_files.insert(document_uri.to_string(), params.text_document.text);
+    state.document_versions.insert(document_uri.to_string(), params.text_document.version);
 
     match process_noir_document(document_uri, state) {
         Ok(_) => {
@@ -53,37 +62,12 @@ pub(super) fn on_did_change_text_document(
     params: DidChangeTextDocumentParams,
 ) -> ControlFlow<Result<(), async_lsp::Error>> {
     let text = params.content_changes.into_iter().next().unwrap().text;
-    state.input_files.insert(params.text_document.uri.to_string(), text.clone());
-
-    let (mut context, crate_id) = prepare_source(text);
-    let _ = check_crate(&mut context, crate_id, false, false);
-
-    let workspace = match resolve_workspace_for_source_path(
-        params.text_document.uri.to_file_path().unwrap().as_path(),
-    ) {
-        Ok(workspace) => workspace,
-        Err(lsp_error) => {
-            return ControlFlow::Break(Err(ResponseError::new(
-                ErrorCode::REQUEST_FAILED,
-                lsp_error.to_string(),
-            )
-            .into()))
-        }
-    };
-    let package = match workspace.members.first() {
-        Some(package) => package,
-        None => {
-            return ControlFlow::Break(Err(ResponseError::new(
-                ErrorCode::REQUEST_FAILED,
-                "Selected workspace has no members",
-            )
-            .into()))
-        }
-    };
-
-    let lenses = collect_lenses_for_package(&context, crate_id, &workspace, package, None);
+    let document_uri = params.text_document.uri;
+    let version = params.text_document.version;
+    state.input_files.insert(document_uri.to_string(), text);
+    state.document_versions.insert(document_uri.to_string(), version);
 
-    state.cached_lenses.insert(params.text_document.uri.to_string(), lenses);
+    schedule_check(state, CheckDocument { uri: document_uri, version });
 
     ControlFlow::Continue(())
 }
@@ -93,12 +77,14 @@ pub(super) fn on_did_close_text_document(
     params: DidCloseTextDocumentParams,
 ) -> ControlFlow<Result<(), async_lsp::Error>> {
     state.input_files.remove(&params.text_document.uri.to_string());
+    state.document_versions.remove(&params.text_document.uri.to_string());
     state.cached_lenses.remove(&params.text_document.uri.to_string());
 
     state.open_documents_count -= 1;
 
     if state.open_documents_count == 0 {
         state.cached_definitions.clear();
+        state.cached_diagnostics.clear();
     }
 
     ControlFlow::Continue(())
@@ -116,6 +102,60 @@ pub(super) fn on_did_save_text_document(
     }
 }
 
+/// How long a document has to stop changing for before it is checked
+const CHECK_DEBOUNCE_DELAY: Duration = Duration::from_millis(300);
+
+/// Event emitted back to the server to check a document once it has stopped changing
+pub(crate) struct CheckDocument {
+    uri: Url,
+    version: i32,
+}
+
+/// Emits `event` after [CHECK_DEBOUNCE_DELAY], so that it can be discarded if the document
+/// keeps changing in the meantime.
+///
+/// Only checks which haven't started yet are discarded: checks run on the server's event loop
+/// and a check which has started always runs to completion.
+fn schedule_check(state: &LspState, event: CheckDocument) {
+    let client = state.client.clone();
+    match tokio::runtime::Handle::try_current() {
+        Ok(runtime) => {
+            runtime.spawn(async move {
+                tokio::time::sleep(CHECK_DEBOUNCE_DELAY).await;
+                let _ = client.emit(event);
+            });
+        }
+        // Events are handled in order, so changes made while the event is queued still
+        // cause it to be discarded.
+        Err(_) => {
+            let _ = client.emit(event);
+        }
+    }
+}
+
+pub(crate) fn on_check_document(
+    state: &mut LspState,
+    event: CheckDocument,
+) -> ControlFlow<Result<(), async_lsp::Error>> {
+    // The document has changed since this check was scheduled, or it has been closed, so the
+    // check is stale. A more recent check has been scheduled if the document is still open.
+    if state.document_versions.get(&event.uri.to_string()) != Some(&event.version) {
+        return ControlFlow::Continue(());
+    }
+
+    match process_noir_document(event.uri, state) {
+        Ok(_) => ControlFlow::Continue(()),
+        Err(err) => ControlFlow::Break(Err(err)),
+    }
+}
+
+/// The diagnostics found when a package was last checked
+pub(crate) struct PackageDiagnostics {
+    /// Fingerprint of the sources of the package and of its dependencies when it was checked
+    pub(crate) fingerprint: u64,
+    diagnostics: Vec<(PathBuf, Diagnostic)>,
+}
+
 fn process_noir_document(
     document_uri: lsp_types::Url,
     state: &mut LspState,
@@ -128,81 +168,142 @@ fn process_noir_document(
         ResponseError::new(ErrorCode::REQUEST_FAILED, lsp_error.to_string())
     })?;
 
-    let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
-    insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
+    let workspace_file_manager = workspace_file_manager(state, &workspace);
+
+    for package in workspace.into_iter() {
+        let package_root_dir: String = package.root_dir.as_os_str().to_string_lossy().into();
+
+        // Packages are only checked again when their sources or those of their dependencies
+        // change, unless the document's lenses haven't been collected yet. Results are cached
+        // per workspace package rather than per crate: when a package is checked again, its
+        // dependencies and the stdlib are checked again along with it.
+        let fingerprint = package_fingerprint(&workspace_file_manager, package);
+        let is_cached = state
+            .cached_diagnostics
+            .get(&package_root_dir)
+            .is_some_and(|cached| cached.fingerprint == fingerprint);
+        if is_cached && state.cached_lenses.contains_key(&document_uri.to_string()) {
+            continue;
+        }
+
+        let (mut context, crate_id) = prepare_package(&workspace_file_manager, package);
 
-    let diagnostics: Vec<_> = workspace
-        .into_iter()
-        .flat_map(|package| -> Vec<Diagnostic> {
-            let (mut context, crate_id) = prepare_package(&workspace_file_manager, package);
+        let file_diagnostics = match check_crate(&mut context, crate_id, false, false) {
+            Ok(((), warnings)) => warnings,
+            Err(errors_and_warnings) => errors_and_warnings,
+        };
 
-            let file_diagnostics = match check_crate(&mut context, crate_id, false, false) {
-                Ok(((), warnings)) => warnings,
-                Err(errors_and_warnings) => errors_and_warnings,
-            };
+        // We don't add test headings for a package if it contains no `#[test]` functions
+        if let Some(tests) = get_package_tests_in_crate(&context, &crate_id, &package.name) {
+            let _ = state.client.notify::<notification::NargoUpdateTests>(NargoPackageTests {
+                package: package.name.to_string(),
+                tests,
+            });
+        }
 
-            let package_root_dir: String = package.root_dir.as_os_str().to_string_lossy().into();
+        let collected_lenses = crate::requests::collect_lenses_for_package(
+            &context,
+            crate_id,
+            &workspace,
+            package,
+            Some(&file_path),
+        );
+        state.cached_lenses.insert(document_uri.to_string(), collected_lenses);
+
+        let fm = &context.file_manager;
+        let files = fm.as_file_map();
+
+        let diagnostics = file_diagnostics
+            .into_iter()
+            .map(|FileDiagnostic { file_id, diagnostic, call_stack: _ }| {
+                // TODO: Should this be the first item in secondaries? Should we bail when we find a range?
+                let range = diagnostic
+                    .secondaries
+                    .into_iter()
+                    .filter_map(|sec| byte_span_to_range(files, file_id, sec.span.into()))
+                    .last()
+                    .unwrap_or_default();
+
+                let severity = match diagnostic.kind {
+                    DiagnosticKind::Error => DiagnosticSeverity::ERROR,
+                    DiagnosticKind::Warning => DiagnosticSeverity::WARNING,
+                };
+                let diagnostic = Diagnostic {
+                    range,
+                    severity: Some(severity),
+                    message: diagnostic.message,
+                    ..Default::default()
+                };
+                (fm.path(file_id).to_path_buf(), diagnostic)
+            })
+            .collect();
+
+        state.cached_definitions.insert(package_root_dir.clone(), context.def_interner);
+        state
+            .cached_diagnostics
+            .insert(package_root_dir, PackageDiagnostics { fingerprint, diagnostics });
+    }
 
-            // We don't add test headings for a package if it contains no `#[test]` functions
-            if let Some(tests) = get_package_tests_in_crate(&context, &crate_id, &package.name) {
-                let _ = state.client.notify::<notification::NargoUpdateTests>(NargoPackageTests {
-                    package: package.name.to_string(),
-                    tests,
-                });
+    // Changing a document can affect the diagnostics of the other open documents of the workspace
+    let open_documents = state
+        .input_files
+        .keys()
+        .filter_map(|uri| Url::parse(uri).ok())
+        .filter(|uri| *uri != document_uri)
+        .filter(|uri| uri.to_file_path().is_ok_and(|path| path.starts_with(&workspace.root_dir)));
+    let document_uris: Vec<_> =
+        std::iter::once(document_uri.clone()).chain(open_documents).collect();
+    for uri in document_uris {
+        let Ok(path) = uri.to_file_path() else {
+            continue;
+        };
+        // Files used by several packages of the workspace are reported once
+        let mut diagnostics = Vec::new();
+        for (diagnostic_path, diagnostic) in
+            state.cached_diagnostics.values().flat_map(|cached| &cached.diagnostics)
+        {
+            if *diagnostic_path == path && !diagnostics.contains(diagnostic) {
+                diagnostics.push(diagnostic.clone());
             }
+        }
+        let _ = state.client.publish_diagnostics(PublishDiagnosticsParams {
+            uri,
+            version: None,
+            diagnostics,
+        });
+    }
+
+    Ok(())
+}
 
-            let collected_lenses = crate::requests::collect_lenses_for_package(
-                &context,
-                crate_id,
-                &workspace,
-                package,
-                Some(&file_path),
-            );
-            state.cached_lenses.insert(document_uri.to_string(), collected_lenses);
-
-            state.cached_definitions.insert(package_root_dir, context.def_interner);
-
-            let fm = &context.file_manager;
-            let files = fm.as_file_map();
-
-            file_diagnostics
-                .into_iter()
-                .filter_map(|FileDiagnostic { file_id, diagnostic, call_stack: _ }| {
-                    // Ignore diagnostics for any file that wasn't the file we saved
-                    // TODO: In the future, we could create "related" diagnostics for these files
-                    if fm.path(file_id) != file_path {
-                        return None;
-                    }
-
-                    // TODO: Should this be the first item in secondaries? Should we bail when we find a range?
-                    let range = diagnostic
-                        .secondaries
-                        .into_iter()
-                        .filter_map(|sec| byte_span_to_range(files, file_id, sec.span.into()))
-                        .last()
-                        .unwrap_or_default();
-
-                    let severity = match diagnostic.kind {
-                        DiagnosticKind::Error => DiagnosticSeverity::ERROR,
-                        DiagnosticKind::Warning => DiagnosticSeverity::WARNING,
-                    };
-                    Some(Diagnostic {
-                        range,
-                        severity: Some(severity),
-                        message: diagnostic.message,
-                        ..Default::default()
-                    })
-                })
-                .collect()
-        })
+/// Hashes the sources of the package and of all of its dependencies
+pub(crate) fn package_fingerprint(file_manager: &FileManager, package: &Package) -> u64 {
+    fn collect_root_dirs<'a>(package: &'a Package, root_dirs: &mut Vec<&'a Path>) {
+        root_dirs.push(&package.root_dir);
+        for dependency in package.dependencies.values() {
+            match dependency {
+                Dependency::Local { package } | Dependency::Remote { package } => {
+                    collect_root_dirs(package, root_dirs);
+                }
+            }
+        }
+    }
+
+    let mut root_dirs = Vec::new();
+    collect_root_dirs(package, &mut root_dirs);
+
+    let mut files: Vec<_> = file_manager
+        .paths()
+        .filter(|(_, path)| root_dirs.iter().any(|root_dir| path.starts_with(root_dir)))
         .collect();
-    let _ = state.client.publish_diagnostics(PublishDiagnosticsParams {
-        uri: document_uri,
-        version: None,
-        diagnostics,
-    });
+    files.sort_by_key(|(_, path)| *path);
 
-    Ok(())
+    let mut hasher = DefaultHasher::new();
+    for (file_id, path) in files {
+        path.hash(&mut hasher);
+        file_manager.fetch_file(file_id).hash(&mut hasher);
+    }
+    hasher.finish()
 }
 
 pub(super) fn on_exit(
@@ -211,3 +312,96 @@ pub(super) fn on_exit(
 ) -> ControlFlow<Result<(), async_lsp::Error>> {
     ControlFlow::Continue(())
 }
+
+#[cfg(test)]
+mod notification_tests {
+    use lsp_types::{
+        TextDocumentContentChangeEvent, TextDocumentItem, VersionedTextDocumentIdentifier,
+    };
+    use tokio::test;
+
+    use crate::requests::test_utils::init_lsp_server;
+
+    use super::*;
+
+    fn error_messages(state: &LspState) -> Vec<String> {
+        state
+            .cached_diagnostics
+            .values()
+            .flat_map(|cached| &cached.diagnostics)
+            .filter(|(_, diagnostic)| diagnostic.severity == Some(DiagnosticSeverity::ERROR))
+            .map(|(_, diagnostic)| diagnostic.message.clone())
+            .collect()
+    }
+
+    #[test]
+    async fn test_checks_changed_documents() {
+        let (mut state, noir_text_document) = init_lsp_server("7_function");
+        let file_path = noir_text_document.to_file_path().unwrap();
+        let source = std::fs::read_to_string(&file_path).unwrap();
+
+        let _ = on_did_open_text_document(
+            &mut state,
+            DidOpenTextDocumentParams {
+                text_document: TextDocumentItem {
+                    uri: noir_text_document.clone(),
+                    language_id: "noir".to_string(),
+                    version: 1,
+                    text: source.clone(),
+                },
+            },
+        );
+        assert_eq!(state.cached_diagnostics.len(), 1);
+        assert!(error_messages(&state).is_empty());
+
+        let change = |version, text: String| DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier {
+                uri: noir_text_document.clone(),
+                version,
+            },
+            content_changes: vec![TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text,
+            }],
+        };
+        let _ = on_did_change_text_document(&mut state, change(2, format!("{source}\nfn broken(")));
+        let _ = on_did_change_text_document(
+            &mut state,
+            change(3, format!("{source}\nfn broken() -> u8 {{ true }}")),
+        );
+        // Documents are only checked once they stop changing
+        assert!(error_messages(&state).is_empty());
+
+        // Checks scheduled for previous versions of the document are discarded
+        let stale_check = CheckDocument { uri: noir_text_document.clone(), version: 2 };
+        let _ = on_check_document(&mut state, stale_check);
+        assert!(error_messages(&state).is_empty());
+
+        let check = CheckDocument { uri: noir_text_document.clone(), version: 3 };
+        let _ = on_check_document(&mut state, check);
+        assert_eq!(error_messages(&state).len(), 1);
+        let (path, _) = &state.cached_diagnostics.values().next().unwrap().diagnostics[0];
+        assert_eq!(*path, file_path);
+    }
+
+    #[test]
+    async fn test_package_fingerprint_changes_with_open_documents() {
+        let (mut state, noir_text_document) = init_lsp_server("7_function");
+        let file_path = noir_text_document.to_file_path().unwrap();
+        let workspace = resolve_workspace_for_source_path(&file_path).unwrap();
+        let package = workspace.members.first().unwrap();
+
+        let fingerprint = |state: &LspState| {
+            package_fingerprint(&workspace_file_manager(state, &workspace), package)
+        };
+        let on_disk = fingerprint(&state);
+
+        let source = std::fs::read_to_string(&file_path).unwrap();
+        state.input_files.insert(noir_text_document.to_string(), source.clone());
+        assert_eq!(fingerprint(&state), on_disk);
+
+        state.input_files.insert(noir_text_document.to_string(), format!("{source}\n// Edited"));
+        assert_ne!(fingerprint(&state), on_disk);
+    }
+}
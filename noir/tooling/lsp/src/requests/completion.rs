@@ -0,0 +1,716 @@
+use std::{
+    collections::HashSet,
+    future::{self, Future},
+};
+
+use async_lsp::{ErrorCode, ResponseError};
+use fm::codespan_files::SimpleFile;
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionParams, CompletionResponse,
+    TextDocumentPositionParams,
+};
+use noirc_driver::file_manager_with_stdlib;
+use noirc_errors::{Location, Span};
+use noirc_frontend::{
+    graph::CrateId,
+    hir::{
+        def_map::{LocalModuleId, ModuleDefId, ModuleId},
+        resolution::path_resolver::resolve_path,
+        Context,
+    },
+    hir_def::{
+        expr::{HirExpression, HirIdent, HirMatchPattern},
+        stmt::{HirPattern, HirStatement},
+    },
+    node_interner::{ExprId, FuncId, NodeInterner},
+    Ident, Path, PathKind, Type,
+};
+
+use crate::{
+    insert_workspace_files_into_file_manager, resolve_workspace_for_source_path,
+    types::CompletionResult, LspState,
+};
+
+use super::{
+    file_id_for_path, find_package, goto_definition::position_to_byte_index, hover::format_function,
+};
+
+/// Inserted in place of the member or path segment being completed when nothing has been typed
+/// yet, so that the expression before it can still be parsed.
+const PLACEHOLDER_IDENT: &str = "__completion_placeholder";
+
+pub(crate) fn on_completion_request(
+    state: &mut LspState,
+    params: CompletionParams,
+) -> impl Future<Output = Result<CompletionResult, ResponseError>> {
+    future::ready(on_completion_inner(state, params.text_document_position))
+}
+
+fn on_completion_inner(
+    state: &LspState,
+    text_document_position: TextDocumentPositionParams,
+) -> Result<CompletionResult, ResponseError> {
+    let TextDocumentPositionParams { text_document, position } = text_document_position;
+    let file_path = text_document.uri.to_file_path().map_err(|_| {
+        ResponseError::new(ErrorCode::REQUEST_FAILED, "URI is not a valid file path")
+    })?;
+
+    // Completion is requested while typing, so the buffer is usually more recent than the file
+    // on disk and the interner cached when the file was last saved can't be used.
+    let source = match state.input_files.get(&text_document.uri.to_string()) {
+        Some(source) => source.clone(),
+        None => std::fs::read_to_string(&file_path)
+            .map_err(|err| ResponseError::new(ErrorCode::REQUEST_FAILED, err.to_string()))?,
+    };
+    let cursor = position_to_byte_index(&SimpleFile::new("", source.as_str()), (), &position)
+        .map_err(|err| {
+            ResponseError::new(
+                ErrorCode::REQUEST_FAILED,
+                format!("Could not convert position to byte index. Error: {:?}", err),
+            )
+        })?;
+    let Some(request) = CompletionRequest::new(&source, cursor) else {
+        return Ok(None);
+    };
+
+    let workspace = resolve_workspace_for_source_path(&file_path)
+        .map_err(|err| ResponseError::new(ErrorCode::REQUEST_FAILED, err))?;
+    let package = find_package(&workspace, &file_path)?;
+
+    let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
+    // Files which are already in the file manager are not replaced when inserting the files of
+    // the workspace, so the patched buffer is compiled instead of the document.
+    workspace_file_manager.add_file_with_source(&file_path, request.patch_source(&source));
+    insert_workspace_files_into_file_manager(state, &workspace, &mut workspace_file_manager);
+    let file_id = file_id_for_path(&workspace_file_manager, &file_path)?;
+
+    let (mut context, crate_id) = nargo::prepare_package(&workspace_file_manager, package);
+    // Parse errors are recovered from, so the rest of the buffer is still type checked
+    let _ = noirc_driver::check_crate(&mut context, crate_id, false, false);
+
+    let location = |index: usize| Location::new(Span::single_char(index as u32), file_id);
+    let items = match &request.kind {
+        CompletionKind::Member { receiver_end } => {
+            member_completions(&context.def_interner, location(*receiver_end))
+        }
+        CompletionKind::Path { segments } => {
+            path_completions(&context, crate_id, location(cursor), segments)
+        }
+        CompletionKind::Name => name_completions(&context, crate_id, location(cursor)),
+    };
+
+    let mut labels = HashSet::new();
+    let items: Vec<_> = items
+        .into_iter()
+        .filter(|item| item.label.starts_with(&request.prefix) && item.label != PLACEHOLDER_IDENT)
+        .filter(|item| labels.insert(item.label.clone()))
+        .collect();
+
+    Ok((!items.is_empty()).then_some(CompletionResponse::Array(items)))
+}
+
+/// What is being completed at the cursor
+enum CompletionKind {
+    /// A field or method after `receiver.`, where `receiver_end` is the index of the last byte
+    /// of the receiver expression.
+    Member { receiver_end: usize },
+    /// An item of the module, type or trait named by the segments before `::`
+    Path { segments: Vec<String> },
+    /// A local variable or an item of the current module
+    Name,
+}
+
+struct CompletionRequest {
+    kind: CompletionKind,
+    /// The part of the identifier which has already been typed before the cursor
+    prefix: String,
+    cursor: usize,
+}
+
+impl CompletionRequest {
+    fn new(source: &str, cursor: usize) -> Option<CompletionRequest> {
+        let before_cursor = source.get(..cursor)?;
+        let prefix = trailing_identifier(before_cursor);
+        let before_prefix = &before_cursor[..before_cursor.len() - prefix.len()];
+
+        let kind = if let Some(before_dot) = before_prefix.strip_suffix('.') {
+            // Ranges such as `0..` are not member accesses
+            if before_dot.ends_with('.') {
+                return None;
+            }
+            let receiver = before_dot.trim_end();
+            if receiver.is_empty() {
+                return None;
+            }
+            CompletionKind::Member { receiver_end: receiver.len() - 1 }
+        } else if let Some(mut before_separator) = before_prefix.strip_suffix("::") {
+            let mut segments = Vec::new();
+            loop {
+                let segment = trailing_identifier(before_separator);
+                if segment.is_empty() {
+                    break;
+                }
+                segments.push(segment.to_owned());
+                before_separator = &before_separator[..before_separator.len() - segment.len()];
+                match before_separator.strip_suffix("::") {
+                    Some(rest) => before_separator = rest,
+                    None => break,
+                }
+            }
+            if segments.is_empty() {
+                return None;
+            }
+            segments.reverse();
+            CompletionKind::Path { segments }
+        } else {
+            CompletionKind::Name
+        };
+
+        Some(CompletionRequest { kind, prefix: prefix.to_owned(), cursor })
+    }
+
+    /// Patches the source so that the statement being typed can be parsed, keeping the byte
+    /// offsets of everything before the cursor unchanged.
+    fn patch_source(&self, source: &str) -> String {
+        let (before_cursor, after_cursor) = source.split_at(self.cursor);
+        let mut patched = before_cursor.to_owned();
+
+        if self.prefix.is_empty() && !matches!(self.kind, CompletionKind::Name) {
+            patched.push_str(PLACEHOLDER_IDENT);
+        }
+
+        // A statement which hasn't been terminated yet would otherwise swallow the next one
+        let rest_of_line = after_cursor.split('\n').next().unwrap_or_default();
+        let next_statement = after_cursor.trim_start().chars().next();
+        if rest_of_line.trim().is_empty()
+            && next_statement.is_some_and(|char| char.is_ascii_alphabetic() || char == '_')
+        {
+            patched.push(';');
+        }
+
+        patched.push_str(after_cursor);
+        patched
+    }
+}
+
+fn trailing_identifier(source: &str) -> &str {
+    let start = source
+        .char_indices()
+        .rev()
+        .take_while(|(_, char)| char.is_ascii_alphanumeric() || *char == '_')
+        .last()
+        .map_or(source.len(), |(index, _)| index);
+    &source[start..]
+}
+
+fn completion_item(
+    label: impl Into<String>,
+    kind: CompletionItemKind,
+    detail: Option<String>,
+) -> CompletionItem {
+    CompletionItem { label: label.into(), kind: Some(kind), detail, ..Default::default() }
+}
+
+/// Completes the fields and methods of the expression ending at `receiver_location`
+fn member_completions(interner: &NodeInterner, receiver_location: Location) -> Vec<CompletionItem> {
+    let Some(receiver) = interner.find_expression_at(receiver_location) else {
+        return Vec::new();
+    };
+
+    // Fields and methods are accessed through references automatically
+    let mut typ = interner.id_type(receiver).follow_bindings();
+    while let Type::MutableReference(element) = typ {
+        typ = *element;
+    }
+
+    let mut items = Vec::new();
+    match &typ {
+        Type::Struct(struct_type, generics) => {
+            for (name, field_type) in struct_type.borrow().get_fields(generics) {
+                items.push(completion_item(
+                    name,
+                    CompletionItemKind::FIELD,
+                    Some(field_type.to_string()),
+                ));
+            }
+        }
+        Type::Tuple(elements) => {
+            for (index, element) in elements.iter().enumerate() {
+                items.push(completion_item(
+                    index.to_string(),
+                    CompletionItemKind::FIELD,
+                    Some(element.to_string()),
+                ));
+            }
+        }
+        _ => (),
+    }
+
+    let mut methods = interner.get_methods_for_type(&typ);
+    methods.retain(|(_, func_id)| has_self_parameter(interner, *func_id));
+    methods.sort_by_key(|(name, _)| *name);
+    items.extend(methods.into_iter().map(|(name, func_id)| {
+        completion_item(name, CompletionItemKind::METHOD, Some(format_function(interner, func_id)))
+    }));
+    items
+}
+
+fn has_self_parameter(interner: &NodeInterner, func_id: FuncId) -> bool {
+    let Some(meta) = interner.try_function_meta(&func_id) else {
+        return false;
+    };
+    let mut pattern = match meta.parameters.0.first() {
+        Some((pattern, _, _)) => pattern,
+        None => return false,
+    };
+    while let HirPattern::Mutable(inner, _) = pattern {
+        pattern = inner;
+    }
+    matches!(pattern, HirPattern::Identifier(ident) if interner.definition_name(ident.id) == "self")
+}
+
+/// Completes the items named by `segments::`, which may be a module, a crate dependency,
+/// a type or a trait.
+fn path_completions(
+    context: &Context,
+    crate_id: CrateId,
+    location: Location,
+    segments: &[String],
+) -> Vec<CompletionItem> {
+    let module_id = module_at(context, location).unwrap_or_else(|| ModuleId {
+        krate: crate_id,
+        local_id: context.def_maps()[&crate_id].root(),
+    });
+
+    let (kind, segments) = match segments.split_first() {
+        Some((first, rest)) if first == "crate" => (PathKind::Crate, rest),
+        Some((first, rest)) if first == "dep" => (PathKind::Dep, rest),
+        _ => (PathKind::Plain, segments),
+    };
+
+    let definition = match kind {
+        PathKind::Dep if segments.is_empty() => {
+            let dependencies = &context.crate_graph[module_id.krate].dependencies;
+            return dependencies
+                .iter()
+                .map(|dependency| {
+                    completion_item(dependency.name.to_string(), CompletionItemKind::MODULE, None)
+                })
+                .collect();
+        }
+        PathKind::Crate if segments.is_empty() => ModuleDefId::ModuleId(ModuleId {
+            krate: module_id.krate,
+            local_id: context.def_maps()[&module_id.krate].root(),
+        }),
+        _ => {
+            let segments = segments.iter().map(|segment| Ident::from(segment.as_str())).collect();
+            let path = Path { segments, kind, span: Span::default() };
+            match resolve_path(context.def_maps(), module_id, path) {
+                Ok(definition) => definition,
+                Err(_) => return Vec::new(),
+            }
+        }
+    };
+
+    let interner = &context.def_interner;
+    match definition {
+        ModuleDefId::ModuleId(module_id) => module_completions(context, module_id),
+        ModuleDefId::TypeId(struct_id) => {
+            let struct_type = interner.get_struct(struct_id);
+            let struct_type = struct_type.borrow();
+            let variants = struct_type.variant_names().into_iter().map(|variant| {
+                completion_item(variant.to_string(), CompletionItemKind::ENUM_MEMBER, None)
+            });
+
+            let typ = Type::Struct(
+                interner.get_struct(struct_id),
+                vec![Type::Error; struct_type.generics.len()],
+            );
+            let mut methods = interner.get_methods_for_type(&typ);
+            methods.sort_by_key(|(name, _)| *name);
+            let methods = methods.into_iter().map(|(name, func_id)| {
+                let detail = Some(format_function(interner, func_id));
+                completion_item(name, CompletionItemKind::FUNCTION, detail)
+            });
+
+            variants.chain(methods).collect()
+        }
+        ModuleDefId::TraitId(trait_id) => interner
+            .get_trait(trait_id)
+            .methods
+            .iter()
+            .map(|method| {
+                completion_item(method.name.to_string(), CompletionItemKind::METHOD, None)
+            })
+            .collect(),
+        ModuleDefId::FunctionId(_) | ModuleDefId::TypeAliasId(_) | ModuleDefId::GlobalId(_) => {
+            Vec::new()
+        }
+    }
+}
+
+/// Completes the local variables in scope at `location` followed by the items of the
+/// surrounding module.
+fn name_completions(
+    context: &Context,
+    crate_id: CrateId,
+    location: Location,
+) -> Vec<CompletionItem> {
+    let interner = &context.def_interner;
+    let mut items = Vec::new();
+
+    if let Some(func_id) = function_at(interner, location) {
+        let meta = interner.function_meta(&func_id);
+        let mut locals = Vec::new();
+        for (pattern, _, _) in &meta.parameters.0 {
+            pattern_identifiers(pattern, &mut locals);
+        }
+        let body = *interner.function(&func_id).as_expr();
+        locals_in_scope(interner, body, location.span.start(), &mut locals);
+
+        // Later declarations shadow earlier ones, so they are listed first
+        items.extend(locals.into_iter().rev().map(|ident| {
+            let name = interner.definition_name(ident.id);
+            let detail = Some(interner.id_type(ident.id).to_string());
+            completion_item(name, CompletionItemKind::VARIABLE, detail)
+        }));
+    }
+
+    let module_id = module_at(context, location).unwrap_or_else(|| ModuleId {
+        krate: crate_id,
+        local_id: context.def_maps()[&crate_id].root(),
+    });
+    items.extend(module_completions(context, module_id));
+    items
+}
+
+/// Completes every item visible within the given module, including imported items
+fn module_completions(context: &Context, module_id: ModuleId) -> Vec<CompletionItem> {
+    let interner = &context.def_interner;
+    let module = module_id.module(context.def_maps());
+
+    let mut items: Vec<_> = module
+        .scope_definitions()
+        .map(|(name, definition)| {
+            let (kind, detail) = match definition {
+                ModuleDefId::ModuleId(_) => (CompletionItemKind::MODULE, None),
+                ModuleDefId::FunctionId(func_id) => {
+                    (CompletionItemKind::FUNCTION, Some(format_function(interner, func_id)))
+                }
+                ModuleDefId::TypeId(struct_id) => {
+                    if interner.get_struct(struct_id).borrow().is_enum() {
+                        (CompletionItemKind::ENUM, None)
+                    } else {
+                        (CompletionItemKind::STRUCT, None)
+                    }
+                }
+                ModuleDefId::TypeAliasId(alias_id) => {
+                    let alias = interner.get_type_alias(alias_id);
+                    (CompletionItemKind::STRUCT, Some(format!("type {name} = {}", alias.typ)))
+                }
+                ModuleDefId::TraitId(_) => (CompletionItemKind::INTERFACE, None),
+                ModuleDefId::GlobalId(stmt_id) => {
+                    let ident = interner.let_statement(&stmt_id).ident();
+                    let detail = format!("global {name}: {}", interner.id_type(ident.id));
+                    (CompletionItemKind::CONSTANT, Some(detail))
+                }
+            };
+            completion_item(name.to_string(), kind, detail)
+        })
+        .collect();
+    items.sort_by(|a, b| a.label.cmp(&b.label));
+    items
+}
+
+/// Returns the function whose definition contains the given location
+fn function_at(interner: &NodeInterner, location: Location) -> Option<FuncId> {
+    interner
+        .get_all_function_ids()
+        .filter(|func_id| {
+            let definition = interner.definition(interner.function_definition_id(*func_id));
+            definition.location.contains(&location)
+        })
+        .min_by_key(|func_id| {
+            let span = interner.definition(interner.function_definition_id(*func_id)).location.span;
+            span.end() - span.start()
+        })
+}
+
+/// Returns the module the given location is in: the module of the surrounding function if
+/// there is one, otherwise the module of the file itself.
+fn module_at(context: &Context, location: Location) -> Option<ModuleId> {
+    if let Some(func_id) = function_at(&context.def_interner, location) {
+        return Some(context.def_interner.function_module(func_id));
+    }
+
+    context.def_maps().iter().find_map(|(krate, def_map)| {
+        def_map.modules().iter().find_map(|(index, module)| {
+            // Modules declared inline share the file of their parent
+            let parent_file = module.parent.map(|parent| def_map.modules()[parent.0].location.file);
+            (module.location.file == location.file && parent_file != Some(location.file))
+                .then_some(ModuleId { krate: *krate, local_id: LocalModuleId(index) })
+        })
+    })
+}
+
+/// Collects the local variables declared within `expr_id` which are in scope at `cursor`,
+/// in the order they are declared.
+fn locals_in_scope(
+    interner: &NodeInterner,
+    expr_id: ExprId,
+    cursor: u32,
+    locals: &mut Vec<HirIdent>,
+) {
+    let contains = |expr_id: &ExprId| {
+        let span = interner.expr_span(expr_id);
+        span.start() <= cursor && cursor <= span.end()
+    };
+    let descend = |children: &[ExprId], locals: &mut Vec<HirIdent>| {
+        if let Some(child) = children.iter().find(|child| contains(child)) {
+            locals_in_scope(interner, *child, cursor, locals);
+        }
+    };
+
+    match interner.expression(&expr_id) {
+        HirExpression::Block(block) => {
+            for statement in block.statements() {
+                match interner.statement(statement) {
+                    HirStatement::Let(let_statement) => {
+                        let expression = let_statement.expression;
+                        if contains(&expression) {
+                            descend(&[expression], locals);
+                        } else if interner.expr_span(&expression).end() < cursor {
+                            pattern_identifiers(&let_statement.pattern, locals);
+                        }
+                    }
+                    HirStatement::For(for_statement) => {
+                        if contains(&for_statement.block) {
+                            locals.push(for_statement.identifier);
+                        }
+                        let children = [
+                            for_statement.start_range,
+                            for_statement.end_range,
+                            for_statement.block,
+                        ];
+                        descend(&children, locals);
+                    }
+                    HirStatement::While(while_statement) => {
+                        descend(&[while_statement.condition, while_statement.block], locals);
+                    }
+                    HirStatement::Constrain(constrain) => descend(&[constrain.0], locals),
+                    HirStatement::Assign(assign) => descend(&[assign.expression], locals),
+                    HirStatement::Expression(expression) | HirStatement::Semi(expression) => {
+                        descend(&[expression], locals);
+                    }
+                    HirStatement::Break | HirStatement::Continue | HirStatement::Error => (),
+                }
+            }
+        }
+        HirExpression::Lambda(lambda) => {
+            if contains(&lambda.body) {
+                for (pattern, _) in &lambda.parameters {
+                    pattern_identifiers(pattern, locals);
+                }
+                descend(&[lambda.body], locals);
+            }
+        }
+        HirExpression::Match(match_expression) => {
+            descend(&[match_expression.expression], locals);
+            if let Some(arm) = match_expression.arms.iter().find(|arm| contains(&arm.branch)) {
+                match &arm.pattern {
+                    HirMatchPattern::Binding(ident) => locals.push(*ident),
+                    HirMatchPattern::Variant { arguments, .. } => {
+                        for argument in arguments {
+                            pattern_identifiers(argument, locals);
+                        }
+                    }
+                    HirMatchPattern::Wildcard(_) => (),
+                }
+                descend(&[arm.branch], locals);
+            }
+        }
+        HirExpression::If(if_expression) => {
+            let mut children = vec![if_expression.condition, if_expression.consequence];
+            children.extend(if_expression.alternative);
+            descend(&children, locals);
+        }
+        HirExpression::Prefix(prefix) => descend(&[prefix.rhs], locals),
+        HirExpression::Infix(infix) => descend(&[infix.lhs, infix.rhs], locals),
+        HirExpression::Index(index) => descend(&[index.collection, index.index], locals),
+        HirExpression::MemberAccess(member_access) => descend(&[member_access.lhs], locals),
+        HirExpression::Cast(cast) => descend(&[cast.lhs], locals),
+        HirExpression::Tuple(elements) => descend(&elements, locals),
+        HirExpression::Call(call) => {
+            let mut children = vec![call.func];
+            children.extend(call.arguments);
+            descend(&children, locals);
+        }
+        HirExpression::MethodCall(method_call) => {
+            let mut children = vec![method_call.object];
+            children.extend(method_call.arguments);
+            descend(&children, locals);
+        }
+        HirExpression::Constructor(constructor) => {
+            let children: Vec<_> = constructor.fields.iter().map(|(_, field)| *field).collect();
+            descend(&children, locals);
+        }
+        HirExpression::EnumConstructor(constructor) => descend(&constructor.arguments, locals),
+        HirExpression::Ident(_)
+        | HirExpression::Literal(_)
+        | HirExpression::TraitMethodReference(_)
+        | HirExpression::Error => (),
+    }
+}
+
+fn pattern_identifiers(pattern: &HirPattern, identifiers: &mut Vec<HirIdent>) {
+    match pattern {
+        HirPattern::Identifier(ident) => identifiers.push(*ident),
+        HirPattern::Mutable(pattern, _) => pattern_identifiers(pattern, identifiers),
+        HirPattern::Tuple(patterns, _) => {
+            for pattern in patterns {
+                pattern_identifiers(pattern, identifiers);
+            }
+        }
+        HirPattern::Struct(_, fields, _) => {
+            for (_, pattern) in fields {
+                pattern_identifiers(pattern, identifiers);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod completion_tests {
+    use lsp_types::Position;
+    use tokio::test;
+
+    use crate::requests::test_utils::{init_lsp_server, text_document_position};
+
+    use super::*;
+
+    /// Requests completions at the end of `line` after inserting it into the open buffer of
+    /// `7_function`'s `main.nr` before the given line, along with `items` at the end of the file.
+    async fn completions_at(line_number: u32, line: &str, items: &str) -> Vec<CompletionItem> {
+        let (mut state, noir_text_document) = init_lsp_server("7_function");
+
+        let path = noir_text_document.to_file_path().unwrap();
+        let source = std::fs::read_to_string(path).unwrap();
+        let mut lines: Vec<&str> = source.lines().collect();
+        lines.insert(line_number as usize, line);
+        lines.push(items);
+        state.input_files.insert(noir_text_document.to_string(), lines.join("\n"));
+
+        let position = Position { line: line_number, character: line.len() as u32 };
+        let params = CompletionParams {
+            text_document_position: text_document_position(noir_text_document, position),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: None,
+        };
+
+        match on_completion_request(&mut state, params)
+            .await
+            .expect("Could execute on_completion_request")
+        {
+            Some(CompletionResponse::Array(items)) => items,
+            Some(CompletionResponse::List(list)) => list.items,
+            None => Vec::new(),
+        }
+    }
+
+    fn labels(items: &[CompletionItem]) -> Vec<&str> {
+        items.iter().map(|item| item.label.as_str()).collect()
+    }
+
+    #[test]
+    async fn test_completion_of_struct_fields() {
+        // Before `ss.a = 61;`, after `let my = my2 { aa: ss, bb: ss };`
+        let items = completions_at(97, "    my.", "").await;
+        assert_eq!(labels(&items), vec!["aa", "bb"]);
+        assert_eq!(items[0].detail.as_deref(), Some("my_struct"));
+
+        let items = completions_at(97, "    my.aa.", "").await;
+        assert_eq!(labels(&items), vec!["a", "b"]);
+
+        let items = completions_at(97, "    my.b", "").await;
+        assert_eq!(labels(&items), vec!["bb"]);
+    }
+
+    #[test]
+    async fn test_completion_of_methods() {
+        let impls = "
+impl my_struct {
+    fn sum(self) -> u32 { self.a + self.b }
+    fn new() -> Self { my_struct { a: 0, b: 0 } }
+}
+trait Describe { fn describe(self) -> Field; }
+impl Describe for my_struct { fn describe(self) -> Field { 0 } }";
+
+        let items = completions_at(97, "    ss.", impls).await;
+        assert_eq!(labels(&items), vec!["a", "b", "describe", "sum"]);
+        assert_eq!(items[3].detail.as_deref(), Some("fn sum(self: my_struct) -> u32"));
+
+        let items = completions_at(97, "    let s = my_struct::", impls).await;
+        assert_eq!(labels(&items), vec!["describe", "new", "sum"]);
+
+        // Methods of primitive types come from the stdlib
+        let items = completions_at(97, "    a.to_le_b", "").await;
+        assert_eq!(labels(&items), vec!["to_le_bits", "to_le_bytes"]);
+    }
+
+    #[test]
+    async fn test_completion_of_paths() {
+        let items = completions_at(97, "    dep::", "").await;
+        assert_eq!(labels(&items), vec!["std"]);
+
+        let items = completions_at(97, "    dep::std::has", "").await;
+        assert_eq!(labels(&items), vec!["hash"]);
+
+        let items = completions_at(97, "    let h = dep::std::hash::pedersen_h", "").await;
+        assert!(labels(&items).contains(&"pedersen_hash"));
+
+        let items = completions_at(97, "    crate::test_multiple", "").await;
+        assert!(labels(&items).contains(&"test_multiple6"));
+    }
+
+    #[test]
+    async fn test_completion_of_local_variables() {
+        // Before `(x, ab)` in the block assigned to `my_block`
+        let items = completions_at(103, "        a", "").await;
+        let labels = labels(&items);
+        assert_eq!(labels[..4], ["ab", "arr2", "arr1", "a"]);
+        assert!(labels.contains(&"arr_to_field"));
+        // Locals of other functions are not in scope
+        assert!(!labels.contains(&"as_field"));
+
+        let items = completions_at(103, "        my", "").await;
+        let labels: Vec<_> = items.iter().map(|item| item.label.as_str()).collect();
+        // `my_block` is still being declared
+        assert_eq!(labels, vec!["my", "my2", "my_struct"]);
+    }
+
+    #[test]
+    async fn test_completion_without_buffer() {
+        let (mut state, noir_text_document) = init_lsp_server("7_function");
+
+        // Completes the `f` of `x = f2(x);` from the file on disk
+        let params = CompletionParams {
+            text_document_position: text_document_position(
+                noir_text_document,
+                Position { line: 3, character: 9 },
+            ),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: None,
+        };
+        let response = on_completion_request(&mut state, params)
+            .await
+            .expect("Could execute on_completion_request");
+        let Some(CompletionResponse::Array(items)) = response else {
+            panic!("Expected completion items");
+        };
+        let labels = labels(&items);
+        assert!(labels.contains(&"f2"));
+        assert!(labels.iter().all(|label| label.starts_with('f')));
+    }
+}
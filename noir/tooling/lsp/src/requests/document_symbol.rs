@@ -0,0 +1,185 @@
+use std::future::{self, Future};
+
+use async_lsp::ResponseError;
+use fm::{FileId, FileMap};
+use lsp_types::{DocumentSymbol, DocumentSymbolParams, DocumentSymbolResponse, SymbolKind};
+use noirc_errors::Span;
+use noirc_frontend::node_interner::NodeInterner;
+
+use crate::{byte_span_to_range, types::DocumentSymbolResult, LspState};
+
+use super::{hover::format_function, process_document_request};
+
+pub(crate) fn on_document_symbol_request(
+    state: &mut LspState,
+    params: DocumentSymbolParams,
+) -> impl Future<Output = Result<DocumentSymbolResult, ResponseError>> {
+    let result =
+        process_document_request(state, &params.text_document.uri, |file_id, interner, files| {
+            let symbols = collect_document_symbols(file_id, interner, files);
+            (!symbols.is_empty()).then_some(DocumentSymbolResponse::Nested(symbols))
+        });
+    future::ready(result)
+}
+
+/// Collects the functions, structs, traits and globals declared in the given file,
+/// ordered by their position in the file.
+fn collect_document_symbols(
+    file_id: FileId,
+    interner: &NodeInterner,
+    files: &FileMap,
+) -> Vec<DocumentSymbol> {
+    let symbol = |name: String, kind, detail, span: Span, name_span: Span, children| {
+        let range = byte_span_to_range(files, file_id, span.into())?;
+        let selection_range = byte_span_to_range(files, file_id, name_span.into())?;
+        #[allow(deprecated)]
+        Some(DocumentSymbol {
+            name,
+            detail,
+            kind,
+            tags: None,
+            deprecated: None,
+            range,
+            selection_range,
+            children,
+        })
+    };
+
+    let mut symbols = Vec::new();
+
+    let traits: Vec<_> =
+        interner.get_all_traits().filter(|the_trait| the_trait.location.file == file_id).collect();
+    for the_trait in &traits {
+        let methods = the_trait
+            .methods
+            .iter()
+            .filter_map(|method| {
+                let name_span = method.name.span();
+                symbol(
+                    method.name.to_string(),
+                    SymbolKind::METHOD,
+                    None,
+                    name_span,
+                    name_span,
+                    None,
+                )
+            })
+            .collect();
+        symbols.extend(symbol(
+            the_trait.name.to_string(),
+            SymbolKind::INTERFACE,
+            None,
+            the_trait.location.span,
+            the_trait.name.span(),
+            Some(methods),
+        ));
+    }
+
+    for func_id in interner.get_all_function_ids() {
+        let name_location = interner.function_meta(&func_id).name.location;
+        // Default implementations of trait methods are already listed under their trait
+        let in_trait = traits.iter().any(|the_trait| the_trait.location.contains(&name_location));
+        if name_location.file != file_id || in_trait {
+            continue;
+        }
+
+        let definition = interner.definition(interner.function_definition_id(func_id));
+        symbols.extend(symbol(
+            interner.function_name(&func_id).to_owned(),
+            SymbolKind::FUNCTION,
+            Some(format_function(interner, func_id)),
+            definition.location.span,
+            name_location.span,
+            None,
+        ));
+    }
+
+    for struct_type in interner.get_all_structs() {
+        let struct_type = struct_type.borrow();
+        if struct_type.location.file != file_id {
+            continue;
+        }
+
+        let (kind, children) = if struct_type.is_enum() {
+            (SymbolKind::ENUM, None)
+        } else {
+            let mut field_names: Vec<_> = struct_type.field_names().into_iter().collect();
+            field_names.sort_by_key(|field_name| field_name.span());
+            let fields = field_names
+                .into_iter()
+                .filter_map(|field_name| {
+                    let span = field_name.span();
+                    symbol(field_name.to_string(), SymbolKind::FIELD, None, span, span, None)
+                })
+                .collect();
+            (SymbolKind::STRUCT, Some(fields))
+        };
+        symbols.extend(symbol(
+            struct_type.name.to_string(),
+            kind,
+            None,
+            struct_type.location.span,
+            struct_type.name.span(),
+            children,
+        ));
+    }
+
+    for (stmt_id, _) in interner.get_all_globals() {
+        let ident = interner.let_statement(&stmt_id).ident();
+        if ident.location.file != file_id {
+            continue;
+        }
+
+        let name = interner.definition_name(ident.id).to_owned();
+        let detail = format!("global {name}: {}", interner.id_type(ident.id));
+        let span = ident.location.span;
+        symbols.extend(symbol(name, SymbolKind::CONSTANT, Some(detail), span, span, None));
+    }
+
+    symbols.sort_by_key(|symbol| symbol.range.start);
+    symbols
+}
+
+#[cfg(test)]
+mod document_symbol_tests {
+    use lsp_types::TextDocumentIdentifier;
+    use tokio::test;
+
+    use crate::requests::test_utils::init_lsp_server;
+
+    use super::*;
+
+    #[test]
+    async fn test_on_document_symbol_request() {
+        let (mut state, noir_text_document) = init_lsp_server("7_function");
+
+        let params = DocumentSymbolParams {
+            text_document: TextDocumentIdentifier { uri: noir_text_document },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+
+        let response = on_document_symbol_request(&mut state, params)
+            .await
+            .expect("Could execute on_document_symbol_request")
+            .expect("Expected document symbols");
+        let DocumentSymbolResponse::Nested(symbols) = response else {
+            panic!("Expected nested document symbols");
+        };
+
+        let f2 = symbols.iter().find(|symbol| symbol.name == "f2").expect("Expected `f2`");
+        assert_eq!(f2.kind, SymbolKind::FUNCTION);
+        assert_eq!(f2.detail.as_deref(), Some("fn f2(mut x: Field) -> Field"));
+        assert_eq!((f2.range.start.line, f2.range.end.line), (7, 10));
+        assert_eq!(f2.selection_range.start.line, 7);
+
+        let my_struct =
+            symbols.iter().find(|symbol| symbol.name == "my_struct").expect("Expected `my_struct`");
+        assert_eq!(my_struct.kind, SymbolKind::STRUCT);
+        let fields: Vec<&str> =
+            my_struct.children.iter().flatten().map(|field| field.name.as_str()).collect();
+        assert_eq!(fields, vec!["a", "b"]);
+
+        assert!(symbols.windows(2).all(|pair| pair[0].range.start <= pair[1].range.start));
+    }
+}
@@ -1,13 +1,12 @@
 use std::future::{self, Future};
 
-use crate::resolve_workspace_for_source_path;
 use crate::{types::GotoDefinitionResult, LspState};
-use async_lsp::{ErrorCode, ResponseError};
+use async_lsp::ResponseError;
 use fm::codespan_files::Error;
 use lsp_types::{GotoDefinitionParams, GotoDefinitionResponse, Location};
 use lsp_types::{Position, Url};
-use nargo::insert_all_files_for_workspace_into_file_manager;
-use noirc_driver::file_manager_with_stdlib;
+
+use super::process_request;
 
 pub(crate) fn on_goto_definition_request(
     state: &mut LspState,
@@ -18,65 +17,21 @@ pub(crate) fn on_goto_definition_request(
 }
 
 fn on_goto_definition_inner(
-    _state: &mut LspState,
+    state: &mut LspState,
     params: GotoDefinitionParams,
 ) -> Result<GotoDefinitionResult, ResponseError> {
-    let file_path =
-        params.text_document_position_params.text_document.uri.to_file_path().map_err(|_| {
-            ResponseError::new(ErrorCode::REQUEST_FAILED, "URI is not a valid file path")
-        })?;
-
-    let workspace = resolve_workspace_for_source_path(file_path.as_path()).unwrap();
-    let package = workspace.members.first().unwrap();
-
-    let package_root_path: String = package.root_dir.as_os_str().to_string_lossy().into();
-
-    let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
-    insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
-
-    let (mut context, crate_id) = nargo::prepare_package(&workspace_file_manager, package);
-
-    let interner;
-    if let Some(def_interner) = _state.cached_definitions.get(&package_root_path) {
-        interner = def_interner;
-    } else {
-        // We ignore the warnings and errors produced by compilation while resolving the definition
-        let _ = noirc_driver::check_crate(&mut context, crate_id, false, false);
-        interner = &context.def_interner;
-    }
-
-    let files = context.file_manager.as_file_map();
-    let file_id = context.file_manager.name_to_id(file_path.clone()).ok_or(ResponseError::new(
-        ErrorCode::REQUEST_FAILED,
-        format!("Could not find file in file manager. File path: {:?}", file_path),
-    ))?;
-    let byte_index =
-        position_to_byte_index(files, file_id, &params.text_document_position_params.position)
-            .map_err(|err| {
-                ResponseError::new(
-                    ErrorCode::REQUEST_FAILED,
-                    format!("Could not convert position to byte index. Error: {:?}", err),
-                )
-            })?;
-
-    let search_for_location = noirc_errors::Location {
-        file: file_id,
-        span: noirc_errors::Span::single_char(byte_index as u32),
-    };
-
-    let goto_definition_response =
-        interner.get_definition_location_from(search_for_location).and_then(|found_location| {
+    process_request(state, params.text_document_position_params, |location, interner, files| {
+        interner.get_definition_location_from(location).and_then(|found_location| {
             let file_id = found_location.file;
             let definition_position = to_lsp_location(files, file_id, found_location.span)?;
             let response: GotoDefinitionResponse =
                 GotoDefinitionResponse::from(definition_position).to_owned();
             Some(response)
-        });
-
-    Ok(goto_definition_response)
+        })
+    })
 }
 
-fn to_lsp_location<'a, F>(
+pub(crate) fn to_lsp_location<'a, F>(
     files: &'a F,
     file_id: F::FileId,
     definition_span: noirc_errors::Span,
@@ -0,0 +1,170 @@
+use std::future::{self, Future};
+
+use async_lsp::ResponseError;
+use lsp_types::{Hover, HoverContents, HoverParams, MarkupContent, MarkupKind};
+use noirc_frontend::{
+    hir_def::{expr::HirExpression, stmt::HirPattern},
+    node_interner::{DefinitionId, DefinitionKind, FuncId, NodeInterner, ReferenceId},
+    Type,
+};
+
+use crate::{byte_span_to_range, types::HoverResult, LspState};
+
+use super::process_request;
+
+pub(crate) fn on_hover_request(
+    state: &mut LspState,
+    params: HoverParams,
+) -> impl Future<Output = Result<HoverResult, ResponseError>> {
+    let result = process_request(
+        state,
+        params.text_document_position_params,
+        |location, interner, files| {
+            let (contents, location) = hover_contents(interner, location)?;
+            Some(Hover {
+                contents: HoverContents::Markup(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: format!("```noir\n{contents}\n```"),
+                }),
+                range: byte_span_to_range(files, location.file, location.span.into()),
+            })
+        },
+    );
+    future::ready(result)
+}
+
+/// Describes the item found at the given location, returning the description along with
+/// the location of the item it describes.
+fn hover_contents(
+    interner: &NodeInterner,
+    location: noirc_errors::Location,
+) -> Option<(String, noirc_errors::Location)> {
+    let expression = interner
+        .find_expression_at(location)
+        .map(|expr_id| (expr_id, interner.expression(&expr_id)));
+
+    // Prefer the type of an identifier expression, which has been instantiated at this use
+    if let Some((expr_id, HirExpression::Ident(ident))) = &expression {
+        if interner.try_definition(ident.id).is_some() {
+            let typ = interner.id_type(expr_id);
+            return Some((
+                format_definition(interner, ident.id, typ),
+                interner.expr_location(expr_id),
+            ));
+        }
+    }
+
+    if let Some((ReferenceId::Definition(definition_id), name_location)) =
+        interner.find_reference_at(location)
+    {
+        let typ = interner.id_type(definition_id);
+        return Some((format_definition(interner, definition_id, typ), name_location));
+    }
+
+    match expression? {
+        (expr_id, HirExpression::MemberAccess(member_access)) => Some((
+            format!("{}: {}", member_access.rhs, interner.id_type(expr_id)),
+            interner.expr_location(&expr_id),
+        )),
+        _ => None,
+    }
+}
+
+fn format_definition(interner: &NodeInterner, id: DefinitionId, typ: Type) -> String {
+    let definition = interner.definition(id);
+    match definition.kind {
+        DefinitionKind::Function(func_id) => format_function(interner, func_id),
+        DefinitionKind::Global(_) => format!("global {}: {typ}", definition.name),
+        DefinitionKind::Local(_) => {
+            let mutable = if definition.mutable { "mut " } else { "" };
+            format!("{mutable}{}: {typ}", definition.name)
+        }
+        DefinitionKind::GenericType(_) => definition.name.clone(),
+    }
+}
+
+/// Formats the signature of a function, e.g. `fn foo(x: Field, mut y: u8) -> Field`.
+pub(super) fn format_function(interner: &NodeInterner, func_id: FuncId) -> String {
+    let name = interner.function_name(&func_id);
+    let Some(meta) = interner.try_function_meta(&func_id) else {
+        return format!("fn {name}");
+    };
+
+    let parameters: Vec<String> = meta
+        .parameters
+        .0
+        .iter()
+        .map(|(pattern, typ, _visibility)| format!("{}: {typ}", format_pattern(interner, pattern)))
+        .collect();
+    let return_type = match meta.return_type() {
+        Type::Unit => String::new(),
+        typ => format!(" -> {typ}"),
+    };
+    let unconstrained =
+        if interner.function_modifiers(&func_id).is_unconstrained { "unconstrained " } else { "" };
+
+    format!("{unconstrained}fn {name}({}){return_type}", parameters.join(", "))
+}
+
+fn format_pattern(interner: &NodeInterner, pattern: &HirPattern) -> String {
+    match pattern {
+        HirPattern::Identifier(ident) => interner.definition_name(ident.id).to_owned(),
+        HirPattern::Mutable(pattern, _) => format!("mut {}", format_pattern(interner, pattern)),
+        HirPattern::Tuple(patterns, _) => {
+            let patterns: Vec<String> =
+                patterns.iter().map(|pattern| format_pattern(interner, pattern)).collect();
+            format!("({})", patterns.join(", "))
+        }
+        HirPattern::Struct(typ, fields, _) => {
+            let fields: Vec<String> =
+                fields.iter().map(|(_, pattern)| format_pattern(interner, pattern)).collect();
+            format!("{typ} {{ {} }}", fields.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod hover_tests {
+    use lsp_types::Position;
+    use tokio::test;
+
+    use crate::requests::test_utils::{init_lsp_server, text_document_position};
+
+    use super::*;
+
+    async fn hover_at(line: u32, character: u32) -> String {
+        let (mut state, noir_text_document) = init_lsp_server("7_function");
+
+        let params = HoverParams {
+            text_document_position_params: text_document_position(
+                noir_text_document,
+                Position { line, character },
+            ),
+            work_done_progress_params: Default::default(),
+        };
+
+        let hover = on_hover_request(&mut state, params)
+            .await
+            .expect("Could execute on_hover_request")
+            .expect("Expected hover contents");
+        match hover.contents {
+            HoverContents::Markup(MarkupContent { value, .. }) => value,
+            contents => panic!("Unexpected hover contents: {contents:?}"),
+        }
+    }
+
+    #[test]
+    async fn test_on_hover_function_call() {
+        // `f2` in `x = f2(x);`
+        assert_eq!(hover_at(3, 9).await, "```noir\nfn f2(mut x: Field) -> Field\n```");
+    }
+
+    #[test]
+    async fn test_on_hover_local_variable() {
+        // Both occurrences of `ab` in `ab = ab + a;`
+        assert_eq!(hover_at(102, 8).await, "```noir\nmut ab: Field\n```");
+        assert_eq!(hover_at(102, 14).await, "```noir\nmut ab: Field\n```");
+        // The parameter `a` of `main`
+        assert_eq!(hover_at(92, 24).await, "```noir\na: Field\n```");
+    }
+}
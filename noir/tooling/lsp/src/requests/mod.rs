@@ -1,14 +1,24 @@
 use std::future::Future;
+use std::path::Path;
 
 use crate::types::{CodeLensOptions, InitializeParams};
-use async_lsp::ResponseError;
-use lsp_types::{Position, TextDocumentSyncCapability, TextDocumentSyncKind};
+use async_lsp::{ErrorCode, ResponseError};
+use fm::{FileId, FileManager, FileMap};
+use lsp_types::{
+    CompletionOptions, HoverProviderCapability, OneOf, Position, TextDocumentPositionParams,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use nargo::{package::Package, workspace::Workspace};
 use nargo_fmt::Config;
+use noirc_frontend::node_interner::NodeInterner;
 use serde::{Deserialize, Serialize};
 
+use self::goto_definition::position_to_byte_index;
 use crate::{
+    notifications::package_fingerprint,
+    resolve_workspace_for_source_path,
     types::{InitializeResult, NargoCapability, NargoTestsOptions, ServerCapabilities},
-    LspState,
+    workspace_file_manager, LspState,
 };
 
 // Handlers
@@ -22,15 +32,22 @@ use crate::{
 // and params passed in.
 
 mod code_lens_request;
+mod completion;
+mod document_symbol;
 mod goto_definition;
+mod hover;
 mod profile_run;
+mod references;
+mod rename;
 mod test_run;
 mod tests;
 
 pub(crate) use {
     code_lens_request::collect_lenses_for_package, code_lens_request::on_code_lens_request,
-    goto_definition::on_goto_definition_request, profile_run::on_profile_run_request,
-    test_run::on_test_run_request, tests::on_tests_request,
+    completion::on_completion_request, document_symbol::on_document_symbol_request,
+    goto_definition::on_goto_definition_request, hover::on_hover_request,
+    profile_run::on_profile_run_request, references::on_references_request,
+    rename::on_rename_request, test_run::on_test_run_request, tests::on_tests_request,
 };
 
 /// LSP client will send initialization request after the server has started.
@@ -87,13 +104,171 @@ pub(crate) fn on_initialize(
                 code_lens_provider: code_lens,
                 document_formatting_provider: true,
                 nargo: Some(nargo),
-                definition_provider: Some(lsp_types::OneOf::Left(true)),
+                definition_provider: Some(OneOf::Left(true)),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                references_provider: Some(OneOf::Left(true)),
+                rename_provider: Some(OneOf::Left(true)),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                completion_provider: Some(CompletionOptions {
+                    trigger_characters: Some(vec![".".to_string(), ":".to_string()]),
+                    ..Default::default()
+                }),
             },
             server_info: None,
         })
     }
 }
 
+/// Type checks the package containing the document at `uri` and calls `callback` with the
+/// resulting [NodeInterner], the id of the document and the files of the workspace.
+///
+/// The interner cached for the package when its documents were last checked is used if there is one.
+fn process_document_request<F, T>(
+    state: &LspState,
+    uri: &Url,
+    callback: F,
+) -> Result<T, ResponseError>
+where
+    F: FnOnce(FileId, &NodeInterner, &FileMap) -> T,
+{
+    let file_path = uri.to_file_path().map_err(|_| {
+        ResponseError::new(ErrorCode::REQUEST_FAILED, "URI is not a valid file path")
+    })?;
+
+    let workspace = resolve_workspace_for_source_path(&file_path)
+        .map_err(|err| ResponseError::new(ErrorCode::REQUEST_FAILED, err))?;
+    let package = find_package(&workspace, &file_path)?;
+
+    let workspace_file_manager = workspace_file_manager(state, &workspace);
+    let file_id = file_id_for_path(&workspace_file_manager, &file_path)?;
+
+    Ok(with_package_interner(state, &workspace_file_manager, package, |interner| {
+        callback(file_id, interner, workspace_file_manager.as_file_map())
+    }))
+}
+
+/// Like [process_document_request] but calls `callback` with the [noirc_errors::Location]
+/// of the requested position within the document.
+fn process_request<F, T>(
+    state: &LspState,
+    text_document_position_params: TextDocumentPositionParams,
+    callback: F,
+) -> Result<T, ResponseError>
+where
+    F: FnOnce(noirc_errors::Location, &NodeInterner, &FileMap) -> T,
+{
+    let TextDocumentPositionParams { text_document, position } = text_document_position_params;
+    process_document_request(state, &text_document.uri, |file_id, interner, files| {
+        let location = position_to_location(files, file_id, &position)?;
+        Ok(callback(location, interner, files))
+    })?
+}
+
+/// Type checks every package of the workspace containing the document and calls `callback`
+/// with the [NodeInterner] of each of them, so that requests can be answered across all of
+/// the workspace's crates.
+///
+/// `callback` is also given the workspace's root directory along with the files of the
+/// workspace and the [noirc_errors::Location] of the requested position.
+fn process_workspace_request<F, T>(
+    state: &LspState,
+    text_document_position_params: TextDocumentPositionParams,
+    mut callback: F,
+) -> Result<Vec<T>, ResponseError>
+where
+    F: FnMut(noirc_errors::Location, &NodeInterner, &FileMap, &Path) -> T,
+{
+    let TextDocumentPositionParams { text_document, position } = text_document_position_params;
+    let file_path = text_document.uri.to_file_path().map_err(|_| {
+        ResponseError::new(ErrorCode::REQUEST_FAILED, "URI is not a valid file path")
+    })?;
+
+    let workspace = resolve_workspace_for_source_path(&file_path)
+        .map_err(|err| ResponseError::new(ErrorCode::REQUEST_FAILED, err))?;
+
+    let workspace_file_manager = workspace_file_manager(state, &workspace);
+    let files = workspace_file_manager.as_file_map();
+    let file_id = file_id_for_path(&workspace_file_manager, &file_path)?;
+    let location = position_to_location(files, file_id, &position)?;
+
+    Ok(workspace
+        .members
+        .iter()
+        .map(|package| {
+            with_package_interner(state, &workspace_file_manager, package, |interner| {
+                callback(location, interner, files, &workspace.root_dir)
+            })
+        })
+        .collect())
+}
+
+/// Returns the member of the workspace containing the given file, falling back to the first
+/// member for files outside of every package.
+fn find_package<'a>(
+    workspace: &'a Workspace,
+    file_path: &Path,
+) -> Result<&'a Package, ResponseError> {
+    workspace
+        .members
+        .iter()
+        .find(|package| file_path.starts_with(&package.root_dir))
+        .or_else(|| workspace.members.first())
+        .ok_or_else(|| ResponseError::new(ErrorCode::REQUEST_FAILED, "Workspace is empty"))
+}
+
+fn with_package_interner<T>(
+    state: &LspState,
+    workspace_file_manager: &FileManager,
+    package: &Package,
+    callback: impl FnOnce(&NodeInterner) -> T,
+) -> T {
+    // The interner cached when the package was last checked is only used if the sources it was
+    // built from are still those of the open documents. It is cached along with the diagnostics.
+    let package_root_path: String = package.root_dir.as_os_str().to_string_lossy().into();
+    let fingerprint = package_fingerprint(workspace_file_manager, package);
+    let is_up_to_date = state
+        .cached_diagnostics
+        .get(&package_root_path)
+        .is_some_and(|cached| cached.fingerprint == fingerprint);
+    if let Some(interner) = state.cached_definitions.get(&package_root_path) {
+        if is_up_to_date {
+            return callback(interner);
+        }
+    }
+
+    let (mut context, crate_id) = nargo::prepare_package(workspace_file_manager, package);
+    // We ignore the warnings and errors produced by compilation while processing the request
+    let _ = noirc_driver::check_crate(&mut context, crate_id, false, false);
+    callback(&context.def_interner)
+}
+
+fn file_id_for_path(file_manager: &FileManager, file_path: &Path) -> Result<FileId, ResponseError> {
+    file_manager.name_to_id(file_path.to_path_buf()).ok_or_else(|| {
+        ResponseError::new(
+            ErrorCode::REQUEST_FAILED,
+            format!("Could not find file in file manager. File path: {:?}", file_path),
+        )
+    })
+}
+
+fn position_to_location(
+    files: &FileMap,
+    file_id: FileId,
+    position: &Position,
+) -> Result<noirc_errors::Location, ResponseError> {
+    let byte_index = position_to_byte_index(files, file_id, position).map_err(|err| {
+        ResponseError::new(
+            ErrorCode::REQUEST_FAILED,
+            format!("Could not convert position to byte index. Error: {:?}", err),
+        )
+    })?;
+
+    Ok(noirc_errors::Location {
+        file: file_id,
+        span: noirc_errors::Span::single_char(byte_index as u32),
+    })
+}
+
 pub(crate) fn on_formatting(
     state: &mut LspState,
     params: lsp_types::DocumentFormattingParams,
@@ -170,3 +345,35 @@ mod initialization {
         assert!(response.server_info.is_none());
     }
 }
+
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use async_lsp::ClientSocket;
+    use lsp_types::{Position, TextDocumentIdentifier, TextDocumentPositionParams, Url};
+
+    use crate::{solver::MockBackend, LspState};
+
+    /// Creates a server state along with the URI of the `main.nr` of the given test program.
+    pub(crate) fn init_lsp_server(program: &str) -> (LspState, Url) {
+        let client = ClientSocket::new_closed();
+        let state = LspState::new(&client, MockBackend);
+
+        let root_path = std::env::current_dir()
+            .unwrap()
+            .join("../../test_programs/execution_success")
+            .join(program)
+            .canonicalize()
+            .expect("Could not resolve root path");
+        let noir_text_document = Url::from_file_path(root_path.join("src/main.nr").as_path())
+            .expect("Could not convert text document path to URI");
+
+        (state, noir_text_document)
+    }
+
+    pub(super) fn text_document_position(
+        uri: Url,
+        position: Position,
+    ) -> TextDocumentPositionParams {
+        TextDocumentPositionParams { text_document: TextDocumentIdentifier { uri }, position }
+    }
+}
@@ -6,7 +6,7 @@ use std::{
 use acvm::ExpressionWidth;
 use async_lsp::{ErrorCode, ResponseError};
 use nargo::{artifacts::debug::DebugArtifact, insert_all_files_for_workspace_into_file_manager};
-use nargo_toml::{find_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use nargo_toml::{find_package_manifest, resolve_workspace_from_toml, LockMode, PackageSelection};
 use noirc_driver::{
     file_manager_with_stdlib, CompileOptions, DebugFile, NOIR_ARTIFACT_VERSION_STRING,
 };
@@ -44,6 +44,7 @@ fn on_profile_run_request_inner(
         &toml_path,
         PackageSelection::DefaultOrAll,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        LockMode::Update,
     )
     .map_err(|err| {
         // If we found a manifest, but the workspace is invalid, we raise an error about it
@@ -0,0 +1,75 @@
+use std::future::{self, Future};
+
+use async_lsp::ResponseError;
+use lsp_types::ReferenceParams;
+
+use crate::{types::ReferencesResult, LspState};
+
+use super::{goto_definition::to_lsp_location, process_workspace_request};
+
+pub(crate) fn on_references_request(
+    state: &mut LspState,
+    params: ReferenceParams,
+) -> impl Future<Output = Result<ReferencesResult, ResponseError>> {
+    let include_declaration = params.context.include_declaration;
+    let result = process_workspace_request(
+        state,
+        params.text_document_position,
+        |location, interner, files, _root_dir| {
+            let Some((reference_id, _)) = interner.find_reference_at(location) else {
+                return Vec::new();
+            };
+            interner
+                .find_all_references(reference_id, include_declaration)
+                .into_iter()
+                .filter_map(|location| to_lsp_location(files, location.file, location.span))
+                .collect()
+        },
+    )
+    .map(|references_per_package| {
+        // Packages which depend on each other will find the same references
+        let mut references = Vec::new();
+        for reference in references_per_package.into_iter().flatten() {
+            if !references.contains(&reference) {
+                references.push(reference);
+            }
+        }
+        (!references.is_empty()).then_some(references)
+    });
+    future::ready(result)
+}
+
+#[cfg(test)]
+mod references_tests {
+    use lsp_types::{Position, ReferenceContext};
+    use tokio::test;
+
+    use crate::requests::test_utils::{init_lsp_server, text_document_position};
+
+    use super::*;
+
+    #[test]
+    async fn test_on_references_request() {
+        let (mut state, noir_text_document) = init_lsp_server("7_function");
+
+        // `f2` in `x = f2(x);`
+        let params = ReferenceParams {
+            text_document_position: text_document_position(
+                noir_text_document.clone(),
+                Position { line: 3, character: 9 },
+            ),
+            context: ReferenceContext { include_declaration: true },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+
+        let references = on_references_request(&mut state, params)
+            .await
+            .expect("Could execute on_references_request")
+            .expect("Expected references to be found");
+
+        let lines: Vec<u32> = references.iter().map(|location| location.range.start.line).collect();
+        assert_eq!(lines, vec![3, 7, 13, 101]);
+        assert!(references.iter().all(|location| location.uri == noir_text_document));
+    }
+}
@@ -0,0 +1,143 @@
+use std::{
+    collections::HashMap,
+    future::{self, Future},
+    path::Path,
+};
+
+use async_lsp::{ErrorCode, ResponseError};
+use fm::codespan_files::Files;
+use lsp_types::{RenameParams, TextEdit, Url, WorkspaceEdit};
+use noirc_frontend::lexer::{token::Token, Lexer};
+
+use crate::{types::RenameResult, LspState};
+
+use super::{goto_definition::to_lsp_location, process_workspace_request};
+
+pub(crate) fn on_rename_request(
+    state: &mut LspState,
+    params: RenameParams,
+) -> impl Future<Output = Result<RenameResult, ResponseError>> {
+    future::ready(on_rename_inner(state, params))
+}
+
+fn on_rename_inner(state: &LspState, params: RenameParams) -> Result<RenameResult, ResponseError> {
+    let new_name = params.new_name;
+    if !is_valid_identifier(&new_name) {
+        return Err(ResponseError::new(
+            ErrorCode::INVALID_PARAMS,
+            format!("`{new_name}` is not a valid identifier"),
+        ));
+    }
+
+    let locations_per_package = process_workspace_request(
+        state,
+        params.text_document_position,
+        |location, interner, files, root_dir| {
+            let Some((reference_id, _)) = interner.find_reference_at(location) else {
+                return Ok(Vec::new());
+            };
+            let Some(declaration) = interner.reference_declaration(reference_id) else {
+                return Ok(Vec::new());
+            };
+
+            // Items from the stdlib or from dependencies outside of the workspace can't be renamed
+            let declared_in_workspace = files
+                .name(declaration.file)
+                .is_ok_and(|name| Path::new(&name.to_string()).starts_with(root_dir));
+            if !declared_in_workspace {
+                return Err(ResponseError::new(
+                    ErrorCode::REQUEST_FAILED,
+                    "Cannot rename a definition declared outside of the workspace",
+                ));
+            }
+
+            // Skip any reference which is not spelled out in the source, such as `Self`, so that
+            // renaming never replaces anything other than the old name.
+            let source_text = |location: &noirc_errors::Location| {
+                let span = location.span.start() as usize..location.span.end() as usize;
+                files.source(location.file).ok().and_then(|source| source.get(span))
+            };
+            let old_name = source_text(&declaration);
+            Ok(interner
+                .find_all_references(reference_id, true)
+                .into_iter()
+                .filter(|location| source_text(location) == old_name)
+                .filter_map(|location| to_lsp_location(files, location.file, location.span))
+                .collect())
+        },
+    )?;
+
+    let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+    for locations in locations_per_package {
+        for location in locations? {
+            let edits = changes.entry(location.uri).or_default();
+            let edit = TextEdit { range: location.range, new_text: new_name.clone() };
+            // Packages which depend on each other will find the same references
+            if !edits.contains(&edit) {
+                edits.push(edit);
+            }
+        }
+    }
+
+    if changes.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(WorkspaceEdit { changes: Some(changes), ..Default::default() }))
+    }
+}
+
+fn is_valid_identifier(name: &str) -> bool {
+    let (tokens, errors) = Lexer::lex(name);
+    let mut tokens = tokens.0.into_iter().map(|token| token.into_token());
+    errors.is_empty()
+        && matches!(tokens.next(), Some(Token::Ident(ident)) if ident == name)
+        && tokens.all(|token| token == Token::EOF)
+}
+
+#[cfg(test)]
+mod rename_tests {
+    use lsp_types::Position;
+    use tokio::test;
+
+    use crate::requests::test_utils::{init_lsp_server, text_document_position};
+
+    use super::*;
+
+    fn rename_params(uri: Url, position: Position, new_name: &str) -> RenameParams {
+        RenameParams {
+            text_document_position: text_document_position(uri, position),
+            new_name: new_name.to_string(),
+            work_done_progress_params: Default::default(),
+        }
+    }
+
+    #[test]
+    async fn test_on_rename_request() {
+        let (mut state, noir_text_document) = init_lsp_server("7_function");
+
+        // The local `ab` in `let mut ab = f2(a);`
+        let params = rename_params(noir_text_document.clone(), Position::new(101, 17), "sum");
+        let workspace_edit = on_rename_request(&mut state, params)
+            .await
+            .expect("Could execute on_rename_request")
+            .expect("Expected a workspace edit");
+
+        let changes = workspace_edit.changes.expect("Expected changes");
+        let edits = &changes[&noir_text_document];
+        let lines: Vec<(u32, u32)> =
+            edits.iter().map(|edit| (edit.range.start.line, edit.range.start.character)).collect();
+        assert_eq!(lines, vec![(101, 16), (102, 8), (102, 13), (103, 12)]);
+        assert!(edits.iter().all(|edit| edit.new_text == "sum"));
+    }
+
+    #[test]
+    async fn test_on_rename_request_rejects_invalid_names() {
+        let (mut state, noir_text_document) = init_lsp_server("7_function");
+
+        for new_name in ["", "1abc", "fn", "a b", "a::b"] {
+            let params =
+                rename_params(noir_text_document.clone(), Position::new(101, 17), new_name);
+            assert!(on_rename_request(&mut state, params).await.is_err());
+        }
+    }
+}
@@ -1,12 +1,14 @@
 use std::future::{self, Future};
+use std::time::{Duration, Instant};
 
+use acvm::brillig_vm::ExecutionLimits;
 use async_lsp::{ErrorCode, ResponseError};
 use nargo::{
     insert_all_files_for_workspace_into_file_manager,
-    ops::{run_test, TestStatus},
+    ops::{run_test, DefaultForeignCallExecutor, TestStatus},
     prepare_package,
 };
-use nargo_toml::{find_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use nargo_toml::{find_package_manifest, resolve_workspace_from_toml, LockMode, PackageSelection};
 use noirc_driver::{
     check_crate, file_manager_with_stdlib, CompileOptions, NOIR_ARTIFACT_VERSION_STRING,
 };
@@ -17,6 +19,9 @@ use crate::{
     LspState,
 };
 
+/// How long the unconstrained code of a test may run for, so that it can't hang the language server.
+const TEST_TIMEOUT: Duration = Duration::from_secs(60);
+
 pub(crate) fn on_test_run_request(
     state: &mut LspState,
     params: NargoTestRunParams,
@@ -44,6 +49,7 @@ fn on_test_run_request_inner(
         &toml_path,
         PackageSelection::Selected(crate_name.clone()),
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        LockMode::Update,
     )
     .map_err(|err| {
         // If we found a manifest, but the workspace is invalid, we raise an error about it
@@ -78,15 +84,18 @@ fn on_test_run_request_inner(
                 )
             })?;
 
-            let test_result = run_test(
+            let test_report = run_test(
                 &state.solver,
                 &context,
                 test_function,
-                false,
-                None,
+                &mut DefaultForeignCallExecutor::new(false, None),
                 &CompileOptions::default(),
+                ExecutionLimits {
+                    deadline: Some(Instant::now() + TEST_TIMEOUT),
+                    ..ExecutionLimits::default()
+                },
             );
-            let result = match test_result {
+            let result = match test_report.status {
                 TestStatus::Pass => NargoTestRunResult {
                     id: params.id.clone(),
                     result: "pass".to_string(),
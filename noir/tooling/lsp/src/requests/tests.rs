@@ -3,7 +3,7 @@ use std::future::{self, Future};
 use async_lsp::{ErrorCode, LanguageClient, ResponseError};
 use lsp_types::{LogMessageParams, MessageType};
 use nargo::{insert_all_files_for_workspace_into_file_manager, prepare_package};
-use nargo_toml::{find_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use nargo_toml::{find_package_manifest, resolve_workspace_from_toml, LockMode, PackageSelection};
 use noirc_driver::{check_crate, file_manager_with_stdlib, NOIR_ARTIFACT_VERSION_STRING};
 
 use crate::{
@@ -44,6 +44,7 @@ fn on_tests_request_inner(
         &toml_path,
         PackageSelection::All,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        LockMode::Update,
     )
     .map_err(|err| {
         // If we found a manifest, but the workspace is invalid, we raise an error about it
@@ -1,5 +1,8 @@
 use fm::FileId;
-use lsp_types::{DefinitionOptions, OneOf};
+use lsp_types::{
+    CompletionOptions, DefinitionOptions, DocumentSymbolOptions, HoverProviderCapability, OneOf,
+    ReferencesOptions, RenameOptions,
+};
 use noirc_driver::DebugFile;
 use noirc_errors::{debug_info::OpCodesCount, Location};
 use noirc_frontend::graph::CrateName;
@@ -25,7 +28,8 @@ pub(crate) mod request {
 
     // Re-providing lsp_types that we don't need to override
     pub(crate) use lsp_types::request::{
-        CodeLensRequest as CodeLens, Formatting, GotoDefinition, Shutdown,
+        CodeLensRequest as CodeLens, Completion, DocumentSymbolRequest as DocumentSymbol,
+        Formatting, GotoDefinition, HoverRequest as Hover, References, Rename, Shutdown,
     };
 
     #[derive(Debug)]
@@ -114,6 +118,26 @@ pub(crate) struct ServerCapabilities {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) definition_provider: Option<OneOf<bool, DefinitionOptions>>,
 
+    /// The server provides hover support.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) hover_provider: Option<HoverProviderCapability>,
+
+    /// The server provides find references support.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) references_provider: Option<OneOf<bool, ReferencesOptions>>,
+
+    /// The server provides rename support.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) rename_provider: Option<OneOf<bool, RenameOptions>>,
+
+    /// The server provides document symbol support.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) document_symbol_provider: Option<OneOf<bool, DocumentSymbolOptions>>,
+
+    /// The server provides completion support.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) completion_provider: Option<CompletionOptions>,
+
     /// The server provides code lens.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) code_lens_provider: Option<CodeLensOptions>,
@@ -222,3 +246,8 @@ pub(crate) struct NargoProfileRunResult {
 
 pub(crate) type CodeLensResult = Option<Vec<CodeLens>>;
 pub(crate) type GotoDefinitionResult = Option<lsp_types::GotoDefinitionResponse>;
+pub(crate) type HoverResult = Option<lsp_types::Hover>;
+pub(crate) type ReferencesResult = Option<Vec<lsp_types::Location>>;
+pub(crate) type RenameResult = Option<lsp_types::WorkspaceEdit>;
+pub(crate) type DocumentSymbolResult = Option<lsp_types::DocumentSymbolResponse>;
+pub(crate) type CompletionResult = Option<lsp_types::CompletionResponse>;
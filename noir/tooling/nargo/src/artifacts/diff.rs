@@ -0,0 +1,316 @@
+//! Compares the circuits of two compilations of the same program or contract.
+//!
+//! The opcodes of each function are counted by kind and by the source line which produced them,
+//! so that changes in circuit size can be traced back to the code responsible for them.
+use std::collections::{BTreeMap, BTreeSet};
+
+use acvm::acir::circuit::{directives::Directive, Circuit, Opcode, OpcodeLocation};
+use noirc_errors::debug_info::DebugInfo;
+
+use super::{contract::ContractArtifact, debug::DebugArtifact, program::ProgramArtifact};
+
+/// The key under which opcodes without a known source location are counted.
+const UNKNOWN_LOCATION: &str = "unknown";
+
+/// The number of ACIR opcodes in a function's circuit.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct OpcodeCounts {
+    pub total: usize,
+    /// The number of opcodes of each kind, with black box function calls counted per function.
+    pub by_kind: BTreeMap<String, usize>,
+    /// The number of opcodes produced by each source line, written `<path>:<line>`.
+    ///
+    /// Opcodes are attributed to the innermost location of their call stack.
+    pub by_location: BTreeMap<String, usize>,
+}
+
+impl OpcodeCounts {
+    /// Counts the opcodes of `circuit`, attributing them to source lines using `debug_info` if provided.
+    pub fn new(circuit: &Circuit, debug_info: Option<(&DebugInfo, &DebugArtifact)>) -> Self {
+        let mut counts = OpcodeCounts { total: circuit.opcodes.len(), ..Default::default() };
+        for (index, opcode) in circuit.opcodes.iter().enumerate() {
+            *counts.by_kind.entry(opcode_kind(opcode)).or_default() += 1;
+
+            let location = debug_info.and_then(|(debug_info, debug_artifact)| {
+                let location = debug_info.opcode_location(&OpcodeLocation::Acir(index))?;
+                let location = *location.last()?;
+                let file = debug_artifact.file_map.get(&location.file)?;
+                let line = debug_artifact.location_line_number(location).ok()?;
+                Some(format!("{}:{line}", file.path.display()))
+            });
+            let location = location.unwrap_or_else(|| UNKNOWN_LOCATION.to_string());
+            *counts.by_location.entry(location).or_default() += 1;
+        }
+        counts
+    }
+}
+
+/// Describes the kind of an opcode, such as `AssertZero` or `BlackBox::sha256`.
+fn opcode_kind(opcode: &Opcode) -> String {
+    match opcode {
+        Opcode::AssertZero(_) => "AssertZero".to_string(),
+        Opcode::BlackBoxFuncCall(call) => format!("BlackBox::{}", call.name()),
+        Opcode::Directive(Directive::Quotient(_)) => "Directive::Quotient".to_string(),
+        Opcode::Directive(Directive::ToLeRadix { .. }) => "Directive::ToLeRadix".to_string(),
+        Opcode::Directive(Directive::PermutationSort { .. }) => {
+            "Directive::PermutationSort".to_string()
+        }
+        Opcode::Brillig(_) => "Brillig".to_string(),
+        Opcode::MemoryOp { .. } => "MemoryOp".to_string(),
+        Opcode::MemoryInit { .. } => "MemoryInit".to_string(),
+    }
+}
+
+/// Counts the opcodes of a program, whose single function is named `main`.
+pub fn program_opcode_counts(
+    program: &ProgramArtifact,
+    debug_artifact: Option<&DebugArtifact>,
+) -> BTreeMap<String, OpcodeCounts> {
+    let debug_info = debug_artifact.and_then(|debug_artifact| {
+        debug_artifact.debug_symbols.first().map(|debug_info| (debug_info, debug_artifact))
+    });
+    BTreeMap::from([("main".to_string(), OpcodeCounts::new(&program.bytecode, debug_info))])
+}
+
+/// Counts the opcodes of each function of a contract.
+pub fn contract_opcode_counts(
+    contract: &ContractArtifact,
+    debug_artifact: Option<&DebugArtifact>,
+) -> BTreeMap<String, OpcodeCounts> {
+    contract
+        .functions
+        .iter()
+        .enumerate()
+        .map(|(index, function)| {
+            // The debug artifact of a contract holds the debug info of each function in order.
+            let debug_info = debug_artifact.and_then(|debug_artifact| {
+                debug_artifact
+                    .debug_symbols
+                    .get(index)
+                    .map(|debug_info| (debug_info, debug_artifact))
+            });
+            (function.name.clone(), OpcodeCounts::new(&function.bytecode, debug_info))
+        })
+        .collect()
+}
+
+/// The change of a count between two compilations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CountDelta {
+    pub key: String,
+    pub old: usize,
+    pub new: usize,
+}
+
+impl CountDelta {
+    pub fn delta(&self) -> i64 {
+        self.new as i64 - self.old as i64
+    }
+}
+
+/// The changes to the opcodes of a function between two compilations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionDiff {
+    pub name: String,
+    /// The opcode counts of the function in the old compilation, if it existed.
+    pub old: Option<OpcodeCounts>,
+    /// The opcode counts of the function in the new compilation, if it exists.
+    pub new: Option<OpcodeCounts>,
+}
+
+impl FunctionDiff {
+    pub fn total(&self) -> CountDelta {
+        CountDelta {
+            key: self.name.clone(),
+            old: self.old.as_ref().map_or(0, |counts| counts.total),
+            new: self.new.as_ref().map_or(0, |counts| counts.total),
+        }
+    }
+
+    /// Returns the opcode kinds whose count changed, largest changes first.
+    pub fn kind_deltas(&self) -> Vec<CountDelta> {
+        self.deltas(|counts| &counts.by_kind)
+    }
+
+    /// Returns the source lines whose opcode count changed, largest changes first.
+    pub fn location_deltas(&self) -> Vec<CountDelta> {
+        self.deltas(|counts| &counts.by_location)
+    }
+
+    fn deltas(&self, map: impl Fn(&OpcodeCounts) -> &BTreeMap<String, usize>) -> Vec<CountDelta> {
+        let empty = BTreeMap::new();
+        let old = self.old.as_ref().map_or(&empty, &map);
+        let new = self.new.as_ref().map_or(&empty, &map);
+
+        let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+        let mut deltas: Vec<CountDelta> = keys
+            .into_iter()
+            .map(|key| CountDelta {
+                key: key.clone(),
+                old: old.get(key).copied().unwrap_or_default(),
+                new: new.get(key).copied().unwrap_or_default(),
+            })
+            .filter(|delta| delta.delta() != 0)
+            .collect();
+        deltas.sort_by_key(|delta| std::cmp::Reverse(delta.delta().abs()));
+        deltas
+    }
+}
+
+/// Compares the opcode counts of the functions of two compilations.
+///
+/// Functions are matched by name, and returned in the order of their names.
+pub fn diff_opcode_counts(
+    old: BTreeMap<String, OpcodeCounts>,
+    mut new: BTreeMap<String, OpcodeCounts>,
+) -> Vec<FunctionDiff> {
+    let mut diffs: Vec<FunctionDiff> = old
+        .into_iter()
+        .map(|(name, old_counts)| {
+            let new_counts = new.remove(&name);
+            FunctionDiff { name, old: Some(old_counts), new: new_counts }
+        })
+        .collect();
+    diffs.extend(new.into_iter().map(|(name, new_counts)| FunctionDiff {
+        name,
+        old: None,
+        new: Some(new_counts),
+    }));
+    diffs.sort_by(|a, b| a.name.cmp(&b.name));
+    diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::path::PathBuf;
+
+    use acvm::acir::circuit::{
+        opcodes::{BlackBoxFuncCall, FunctionInput},
+        Circuit, Opcode, OpcodeLocation,
+    };
+    use acvm::acir::native_types::{Expression, Witness};
+    use fm::FileId;
+    use noirc_abi::Abi;
+    use noirc_driver::DebugFile;
+    use noirc_errors::{debug_info::DebugInfo, Location, Span};
+
+    use super::{diff_opcode_counts, program_opcode_counts, CountDelta};
+    use crate::artifacts::{debug::DebugArtifact, program::ProgramArtifact};
+
+    const SOURCE: &str = "fn main(x: u8) {\n    let y = x + 1;\n    assert(y == 2);\n}\n";
+
+    fn program(opcodes: Vec<Opcode>) -> ProgramArtifact {
+        ProgramArtifact {
+            noir_version: String::new(),
+            hash: 0,
+            abi: Abi {
+                parameters: Vec::new(),
+                param_witnesses: BTreeMap::new(),
+                return_type: None,
+                return_witnesses: Vec::new(),
+            },
+            bytecode: Circuit { opcodes, ..Circuit::default() },
+        }
+    }
+
+    /// Attributes each opcode to the source line of the code at the same index of `code`.
+    fn debug_artifact(code: &[&str]) -> DebugArtifact {
+        let file = FileId::dummy();
+        let locations = code
+            .iter()
+            .enumerate()
+            .map(|(index, code)| {
+                let start = SOURCE.find(code).unwrap() as u32;
+                let location = Location::new(Span::from(start..start + code.len() as u32), file);
+                (OpcodeLocation::Acir(index), vec![location])
+            })
+            .collect();
+        let file_map = BTreeMap::from([(
+            file,
+            DebugFile { source: SOURCE.to_string(), path: PathBuf::from("src/main.nr") },
+        )]);
+        DebugArtifact {
+            debug_symbols: vec![DebugInfo::new(locations)],
+            file_map,
+            warnings: Vec::new(),
+        }
+    }
+
+    fn range(witness: u32) -> Opcode {
+        Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+            input: FunctionInput { witness: Witness(witness), num_bits: 8 },
+        })
+    }
+
+    fn assert_zero() -> Opcode {
+        Opcode::AssertZero(Expression::default())
+    }
+
+    #[test]
+    fn attributes_opcode_changes_to_kinds_and_lines() {
+        let old = program(vec![range(1), assert_zero(), range(2), assert_zero()]);
+        let old_debug = debug_artifact(&["x: u8", "x + 1", "x + 1", "y == 2"]);
+        let new = program(vec![range(1), assert_zero(), assert_zero()]);
+        let new_debug = debug_artifact(&["x: u8", "x + 1", "y == 2"]);
+
+        let diffs = diff_opcode_counts(
+            program_opcode_counts(&old, Some(&old_debug)),
+            program_opcode_counts(&new, Some(&new_debug)),
+        );
+
+        assert_eq!(diffs.len(), 1);
+        let diff = &diffs[0];
+        assert_eq!(diff.name, "main");
+        assert_eq!(diff.total().delta(), -1);
+        assert_eq!(
+            diff.kind_deltas(),
+            vec![CountDelta { key: "BlackBox::range".to_string(), old: 2, new: 1 }]
+        );
+        assert_eq!(
+            diff.location_deltas(),
+            vec![CountDelta { key: "src/main.nr:2".to_string(), old: 2, new: 1 }]
+        );
+    }
+
+    #[test]
+    fn counts_opcodes_without_debug_info_as_unknown() {
+        let old = program(vec![assert_zero()]);
+        let new = program(vec![assert_zero(), assert_zero()]);
+
+        let diffs = diff_opcode_counts(
+            program_opcode_counts(&old, None),
+            program_opcode_counts(&new, None),
+        );
+
+        assert_eq!(
+            diffs[0].location_deltas(),
+            vec![CountDelta { key: "unknown".to_string(), old: 1, new: 2 }]
+        );
+    }
+
+    #[test]
+    fn reports_added_and_removed_functions() {
+        let counts = |names: &[&str]| {
+            let names: BTreeSet<_> = names.iter().map(|name| name.to_string()).collect();
+            names
+                .into_iter()
+                .map(|name| {
+                    let counts = program_opcode_counts(&program(vec![assert_zero()]), None);
+                    (name, counts["main"].clone())
+                })
+                .collect()
+        };
+
+        let diffs = diff_opcode_counts(counts(&["bar", "foo"]), counts(&["baz", "foo"]));
+
+        let summary: Vec<_> = diffs
+            .iter()
+            .map(|diff| (diff.name.as_str(), diff.old.is_some(), diff.new.is_some()))
+            .collect();
+        assert_eq!(summary, vec![("bar", true, false), ("baz", false, true), ("foo", true, true)]);
+        assert_eq!(diffs[0].total().delta(), -1);
+        assert_eq!(diffs[1].total().delta(), 1);
+        assert!(diffs[2].kind_deltas().is_empty());
+    }
+}
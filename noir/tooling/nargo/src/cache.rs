@@ -0,0 +1,219 @@
+//! A persistent cache of compiled packages, kept in the workspace's `target/cache` directory.
+//!
+//! Each package has a single cache entry which holds its last successful compilation along with the
+//! warnings it raised. An entry is only reused if it was compiled from exactly the same sources
+//! (those of the package, its dependencies and the stdlib), by the same compiler, with the same
+//! options, in which case type checking and code generation are skipped entirely.
+
+use std::path::{Path, PathBuf};
+
+use acvm::ExpressionWidth;
+use fm::{FileManager, NormalizePath};
+use noirc_driver::{CompileOptions, Warnings, NOIR_ARTIFACT_VERSION_STRING};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::package::{Dependency, Package};
+
+#[derive(Serialize)]
+struct CacheEntryRef<'a, T> {
+    key: &'a str,
+    artifact: &'a T,
+    warnings: &'a Warnings,
+}
+
+#[derive(Deserialize)]
+struct CacheEntry<T> {
+    key: String,
+    artifact: T,
+    warnings: Warnings,
+}
+
+/// Returns the key under which the compilation of `package` is cached.
+///
+/// Returns `None` if the compilation shouldn't be cached, as the options ask for intermediate
+/// representations to be printed while compiling.
+pub fn compilation_cache_key(
+    file_manager: &FileManager,
+    package: &Package,
+    compile_options: &CompileOptions,
+    expression_width: ExpressionWidth,
+) -> Option<String> {
+    if compile_options.show_ssa || compile_options.show_brillig || compile_options.print_acir {
+        return None;
+    }
+
+    let mut hasher = Sha256::new();
+    let mut update = |bytes: &[u8]| {
+        // Prefix each field with its length so that adjacent fields can't run into each other.
+        hasher.update((bytes.len() as u64).to_le_bytes());
+        hasher.update(bytes);
+    };
+    update(NOIR_ARTIFACT_VERSION_STRING.as_bytes());
+    update(format!("{compile_options:?}").as_bytes());
+    update(format!("{expression_width:?}").as_bytes());
+    update(package.name.to_string().as_bytes());
+    update(package.package_type.to_string().as_bytes());
+    update(package.entry_path.to_string_lossy().as_bytes());
+
+    let mut source_dirs = Vec::new();
+    collect_source_dirs(package, &mut update, &mut source_dirs);
+
+    // File ids are part of the key as the cached debug info and warnings refer to files by their ids.
+    // Files with relative paths are those of the stdlib, which is embedded in the compiler.
+    let mut files: Vec<_> = file_manager
+        .paths()
+        .filter(|(_, path)| {
+            path.is_relative() || source_dirs.iter().any(|dir| path.starts_with(dir))
+        })
+        .collect();
+    files.sort_by_key(|(file_id, _)| *file_id);
+    for (file_id, path) in files {
+        update(&(file_id.as_usize() as u64).to_le_bytes());
+        update(path.to_string_lossy().as_bytes());
+        update(file_manager.fetch_file(file_id).as_bytes());
+    }
+
+    Some(hex::encode(hasher.finalize()))
+}
+
+/// Collects the source directories of `package` and its dependencies, while adding the names and
+/// entry points of its dependencies to the key.
+fn collect_source_dirs(package: &Package, update: &mut impl FnMut(&[u8]), dirs: &mut Vec<PathBuf>) {
+    if let Some(source_dir) = package.entry_path.parent() {
+        dirs.push(source_dir.normalize());
+    }
+    for (name, dependency) in &package.dependencies {
+        let (Dependency::Local { package } | Dependency::Remote { package }) = dependency;
+        update(name.to_string().as_bytes());
+        update(package.entry_path.to_string_lossy().as_bytes());
+        collect_source_dirs(package, update, dirs);
+    }
+}
+
+/// Reads the cached compilation of `package` from `cache_dir`, if it was cached under `key`.
+pub fn read_cached_compilation<T: DeserializeOwned>(
+    cache_dir: &Path,
+    package: &Package,
+    key: &str,
+) -> Option<(T, Warnings)> {
+    let contents = std::fs::read(cache_path(cache_dir, package)).ok()?;
+    let entry: CacheEntry<T> = serde_json::from_slice(&contents).ok()?;
+    (entry.key == key).then_some((entry.artifact, entry.warnings))
+}
+
+/// Caches the compilation of `package` in `cache_dir` under `key`, replacing any previous entry.
+///
+/// Failing to write the cache isn't an error, as the package will just be compiled again next time.
+pub fn save_cached_compilation<T: Serialize>(
+    cache_dir: &Path,
+    package: &Package,
+    key: &str,
+    artifact: &T,
+    warnings: &Warnings,
+) {
+    let entry = CacheEntryRef { key, artifact, warnings };
+    let Ok(contents) = serde_json::to_vec(&entry) else {
+        return;
+    };
+
+    // Write the entry next to its destination first so that it's never read half-written.
+    let path = cache_path(cache_dir, package);
+    let partial_path = path.with_extension("json.partial");
+    let saved = std::fs::create_dir_all(cache_dir)
+        .and_then(|_| std::fs::write(&partial_path, contents))
+        .and_then(|_| std::fs::rename(&partial_path, &path));
+    if saved.is_err() {
+        let _ = std::fs::remove_file(partial_path);
+    }
+}
+
+fn cache_path(cache_dir: &Path, package: &Package) -> PathBuf {
+    cache_dir.join(format!("{}.json", package.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::Path;
+
+    use acvm::ExpressionWidth;
+    use noirc_driver::{file_manager_with_stdlib, CompileOptions};
+
+    use super::{compilation_cache_key, read_cached_compilation, save_cached_compilation};
+    use crate::package::{Dependency, Package, PackageType};
+
+    fn package(root_dir: &Path, name: &str, dependencies: BTreeMap<String, Package>) -> Package {
+        Package {
+            version: None,
+            compiler_required_version: None,
+            root_dir: root_dir.to_path_buf(),
+            package_type: PackageType::Binary,
+            entry_path: root_dir.join("src").join("main.nr"),
+            name: name.parse().unwrap(),
+            dependencies: dependencies
+                .into_iter()
+                .map(|(name, package)| (name.parse().unwrap(), Dependency::Local { package }))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn keys_depend_on_the_package_sources_and_options() {
+        let workspace = tempfile::tempdir().unwrap();
+        let root = workspace.path();
+        let dependency = package(&root.join("dependency"), "dependency", BTreeMap::new());
+        let package = package(
+            &root.join("main"),
+            "main",
+            BTreeMap::from([("dependency".to_string(), dependency)]),
+        );
+        let options = CompileOptions::default();
+        let width = ExpressionWidth::Bounded { width: 3 };
+
+        let key = |files: &[(&str, &str)], options: &CompileOptions| {
+            let mut file_manager = file_manager_with_stdlib(root);
+            for (path, source) in files {
+                file_manager.add_file_with_source(Path::new(path), source.to_string());
+            }
+            compilation_cache_key(&file_manager, &package, options, width).unwrap()
+        };
+
+        let sources =
+            [("main/src/main.nr", "fn main() {}"), ("dependency/src/main.nr", "fn foo() {}")];
+        let original = key(&sources, &options);
+        assert_eq!(key(&sources, &options), original);
+
+        // Files of other packages in the workspace don't affect the key, unless they shift the ids of files
+        // which do.
+        let unrelated = [sources[0], sources[1], ("other/src/main.nr", "fn bar() {}")];
+        assert_eq!(key(&unrelated, &options), original);
+        let shifted = [("other/src/main.nr", "fn bar() {}"), sources[0], sources[1]];
+        assert_ne!(key(&shifted, &options), original);
+
+        let changed_dependency = [sources[0], ("dependency/src/main.nr", "fn foo() { }")];
+        assert_ne!(key(&changed_dependency, &options), original);
+
+        let deny_warnings = CompileOptions { deny_warnings: true, ..CompileOptions::default() };
+        assert_ne!(key(&sources, &deny_warnings), original);
+
+        let mut file_manager = file_manager_with_stdlib(root);
+        let show_ssa = CompileOptions { show_ssa: true, ..CompileOptions::default() };
+        file_manager.add_file_with_source(Path::new(sources[0].0), sources[0].1.to_string());
+        assert!(compilation_cache_key(&file_manager, &package, &show_ssa, width).is_none());
+    }
+
+    #[test]
+    fn only_reads_entries_cached_under_the_same_key() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let package = package(cache_dir.path(), "main", BTreeMap::new());
+
+        assert_eq!(read_cached_compilation::<String>(cache_dir.path(), &package, "key"), None);
+        save_cached_compilation(cache_dir.path(), &package, "key", &"program", &Vec::new());
+        assert_eq!(
+            read_cached_compilation::<String>(cache_dir.path(), &package, "key"),
+            Some(("program".to_string(), Vec::new()))
+        );
+        assert_eq!(read_cached_compilation::<String>(cache_dir.path(), &package, "other"), None);
+    }
+}
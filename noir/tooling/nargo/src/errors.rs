@@ -64,7 +64,8 @@ impl NargoError {
             ExecutionError::SolvingError(error) => match error {
                 OpcodeResolutionError::IndexOutOfBounds { .. }
                 | OpcodeResolutionError::OpcodeNotSolvable(_)
-                | OpcodeResolutionError::UnsatisfiedConstrain { .. } => None,
+                | OpcodeResolutionError::UnsatisfiedConstrain { .. }
+                | OpcodeResolutionError::BrilligLimitExceeded { .. } => None,
                 OpcodeResolutionError::BrilligFunctionFailed { message, .. } => Some(message),
                 OpcodeResolutionError::BlackBoxFunctionFailed(_, reason) => Some(reason),
             },
@@ -91,6 +92,10 @@ fn extract_locations_from_error(
             call_stack,
             ..
         })
+        | ExecutionError::SolvingError(OpcodeResolutionError::BrilligLimitExceeded {
+            call_stack,
+            ..
+        })
         | ExecutionError::AssertionFailed(_, call_stack) => Some(call_stack.clone()),
         ExecutionError::SolvingError(OpcodeResolutionError::IndexOutOfBounds {
             opcode_location: error_location,
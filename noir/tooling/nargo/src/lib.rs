@@ -8,6 +8,7 @@
 //! Noir Package Manager abbreviated is npm, which is already taken.
 
 pub mod artifacts;
+pub mod cache;
 pub mod constants;
 pub mod errors;
 pub mod ops;
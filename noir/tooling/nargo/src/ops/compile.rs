@@ -82,7 +82,8 @@ pub fn compile_program(
         };
 
     // Apply backend specific optimizations.
-    let optimized_program = crate::ops::optimize_program(program, expression_width);
+    let optimized_program =
+        crate::ops::optimize_program(program, expression_width, compile_options.optimization_level);
 
     Ok((optimized_program, warnings))
 }
@@ -102,7 +103,11 @@ fn compile_contract(
             }
         };
 
-    let optimized_contract = crate::ops::optimize_contract(contract, expression_width);
+    let optimized_contract = crate::ops::optimize_contract(
+        contract,
+        expression_width,
+        compile_options.optimization_level,
+    );
 
     Ok((optimized_contract, warnings))
 }
@@ -1,4 +1,10 @@
-use acvm::pwg::{ACVMStatus, ErrorLocation, OpcodeResolutionError, ACVM};
+use std::collections::BTreeMap;
+
+use acvm::acir::circuit::OpcodeLocation;
+use acvm::brillig_vm::ExecutionLimits;
+use acvm::pwg::{
+    ACVMStatus, BrilligSolverStatus, ErrorLocation, OpcodeResolutionError, StepResult, ACVM,
+};
 use acvm::BlackBoxFunctionSolver;
 use acvm::{acir::circuit::Circuit, acir::native_types::WitnessMap};
 
@@ -7,6 +13,15 @@ use crate::NargoError;
 
 use super::foreign_calls::ForeignCallExecutor;
 
+/// A summary of the work performed while executing a circuit.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionStats {
+    /// The number of ACIR opcodes which were executed, including any opcode which failed.
+    pub acir_opcodes: usize,
+    /// The number of Brillig opcodes which were executed across all unconstrained calls.
+    pub brillig_steps: usize,
+}
+
 #[tracing::instrument(level = "trace", skip_all)]
 pub fn execute_circuit<B: BlackBoxFunctionSolver, F: ForeignCallExecutor>(
     circuit: &Circuit,
@@ -14,8 +29,47 @@ pub fn execute_circuit<B: BlackBoxFunctionSolver, F: ForeignCallExecutor>(
     blackbox_solver: &B,
     foreign_call_executor: &mut F,
 ) -> Result<WitnessMap, NargoError> {
-    let mut acvm = ACVM::new(blackbox_solver, &circuit.opcodes, initial_witness);
+    execute_circuit_with_stats(
+        circuit,
+        initial_witness,
+        blackbox_solver,
+        foreign_call_executor,
+        ExecutionLimits::default(),
+    )
+    .0
+}
+
+/// Executes the circuit as [`execute_circuit`] does, halting any Brillig call which exceeds
+/// `brillig_limits` and additionally returning [`ExecutionStats`] which are gathered regardless
+/// of whether execution succeeded.
+#[tracing::instrument(level = "trace", skip_all)]
+pub fn execute_circuit_with_stats<B: BlackBoxFunctionSolver, F: ForeignCallExecutor>(
+    circuit: &Circuit,
+    initial_witness: WitnessMap,
+    blackbox_solver: &B,
+    foreign_call_executor: &mut F,
+    brillig_limits: ExecutionLimits,
+) -> (Result<WitnessMap, NargoError>, ExecutionStats) {
+    let mut acvm = ACVM::new(blackbox_solver, &circuit.opcodes, initial_witness)
+        .with_brillig_limits(brillig_limits);
+    let result = solve_circuit(circuit, &mut acvm, foreign_call_executor);
 
+    let acir_opcodes = if *acvm.get_status() == ACVMStatus::Solved {
+        circuit.opcodes.len()
+    } else {
+        // The opcode at the instruction pointer was attempted before execution halted.
+        acvm.instruction_pointer() + 1
+    };
+    let stats = ExecutionStats { acir_opcodes, brillig_steps: acvm.brillig_steps() };
+
+    (result.map(|()| acvm.finalize()), stats)
+}
+
+fn solve_circuit<B: BlackBoxFunctionSolver, F: ForeignCallExecutor>(
+    circuit: &Circuit,
+    acvm: &mut ACVM<B>,
+    foreign_call_executor: &mut F,
+) -> Result<(), NargoError> {
     loop {
         let solver_status = acvm.solve();
 
@@ -24,30 +78,88 @@ pub fn execute_circuit<B: BlackBoxFunctionSolver, F: ForeignCallExecutor>(
             ACVMStatus::InProgress => {
                 unreachable!("Execution should not stop while in `InProgress` state.")
             }
-            ACVMStatus::Failure(error) => {
-                let call_stack = match &error {
-                    OpcodeResolutionError::UnsatisfiedConstrain {
-                        opcode_location: ErrorLocation::Resolved(opcode_location),
-                    } => Some(vec![*opcode_location]),
-                    OpcodeResolutionError::BrilligFunctionFailed { call_stack, .. } => {
-                        Some(call_stack.clone())
+            ACVMStatus::Failure(error) => return Err(execution_failure(circuit, error)),
+            ACVMStatus::RequiresForeignCall(foreign_call) => {
+                let foreign_call_result = foreign_call_executor.execute(&foreign_call)?;
+                acvm.resolve_pending_foreign_call(foreign_call_result);
+            }
+        }
+    }
+
+    foreign_call_executor.finish()?;
+    Ok(())
+}
+
+/// Converts an error encountered while solving the circuit into a [`NargoError`],
+/// attaching the user's assertion message where one exists.
+fn execution_failure(circuit: &Circuit, error: OpcodeResolutionError) -> NargoError {
+    let call_stack = match &error {
+        OpcodeResolutionError::UnsatisfiedConstrain {
+            opcode_location: ErrorLocation::Resolved(opcode_location),
+        } => Some(vec![*opcode_location]),
+        OpcodeResolutionError::BrilligFunctionFailed { call_stack, .. } => Some(call_stack.clone()),
+        _ => None,
+    };
+
+    NargoError::ExecutionError(match call_stack {
+        Some(call_stack) => {
+            if let Some(assert_message) = circuit
+                .get_assert_message(*call_stack.last().expect("Call stacks should not be empty"))
+            {
+                ExecutionError::AssertionFailed(assert_message.to_owned(), call_stack)
+            } else {
+                ExecutionError::SolvingError(error)
+            }
+        }
+        None => ExecutionError::SolvingError(error),
+    })
+}
+
+/// The number of times each Brillig opcode was executed, keyed by its [`OpcodeLocation`].
+pub type BrilligOpcodeCounts = BTreeMap<OpcodeLocation, usize>;
+
+/// Executes the circuit as [`execute_circuit`] does, stepping through each Brillig call one opcode
+/// at a time in order to count how many times each Brillig opcode is executed.
+#[tracing::instrument(level = "trace", skip_all)]
+pub fn execute_circuit_with_brillig_profiling<B: BlackBoxFunctionSolver, F: ForeignCallExecutor>(
+    circuit: &Circuit,
+    initial_witness: WitnessMap,
+    blackbox_solver: &B,
+    foreign_call_executor: &mut F,
+) -> Result<(WitnessMap, BrilligOpcodeCounts), NargoError> {
+    let mut acvm = ACVM::new(blackbox_solver, &circuit.opcodes, initial_witness);
+    let mut brillig_opcode_counts = BrilligOpcodeCounts::new();
+
+    while *acvm.get_status() == ACVMStatus::InProgress {
+        let solver_status = match acvm.step_into_brillig_opcode() {
+            StepResult::Status(status) => status,
+            StepResult::IntoBrillig(mut solver) => {
+                let acir_index = acvm.instruction_pointer();
+                loop {
+                    let brillig_index = solver.program_counter();
+                    let status = solver.step();
+                    if !matches!(status, Ok(BrilligSolverStatus::ForeignCallWait(_))) {
+                        let location = OpcodeLocation::Brillig { acir_index, brillig_index };
+                        *brillig_opcode_counts.entry(location).or_default() += 1;
                     }
-                    _ => None,
-                };
-
-                return Err(NargoError::ExecutionError(match call_stack {
-                    Some(call_stack) => {
-                        if let Some(assert_message) = circuit.get_assert_message(
-                            *call_stack.last().expect("Call stacks should not be empty"),
-                        ) {
-                            ExecutionError::AssertionFailed(assert_message.to_owned(), call_stack)
-                        } else {
-                            ExecutionError::SolvingError(error)
+
+                    match status.map_err(|error| execution_failure(circuit, error))? {
+                        BrilligSolverStatus::InProgress => (),
+                        BrilligSolverStatus::Finished => break,
+                        BrilligSolverStatus::ForeignCallWait(foreign_call) => {
+                            let foreign_call_result =
+                                foreign_call_executor.execute(&foreign_call)?;
+                            solver.resolve_pending_foreign_call(foreign_call_result);
                         }
                     }
-                    None => ExecutionError::SolvingError(error),
-                }));
+                }
+                acvm.finish_brillig_with_solver(solver)
             }
+        };
+
+        match solver_status {
+            ACVMStatus::Solved | ACVMStatus::InProgress => (),
+            ACVMStatus::Failure(error) => return Err(execution_failure(circuit, error)),
             ACVMStatus::RequiresForeignCall(foreign_call) => {
                 let foreign_call_result = foreign_call_executor.execute(&foreign_call)?;
                 acvm.resolve_pending_foreign_call(foreign_call_result);
@@ -55,5 +167,6 @@ pub fn execute_circuit<B: BlackBoxFunctionSolver, F: ForeignCallExecutor>(
         }
     }
 
-    Ok(acvm.finalize())
+    foreign_call_executor.finish()?;
+    Ok((acvm.finalize(), brillig_opcode_counts))
 }
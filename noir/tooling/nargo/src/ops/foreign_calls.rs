@@ -1,15 +1,46 @@
+use std::collections::BTreeMap;
+
 use acvm::{
     acir::brillig::{ForeignCallParam, ForeignCallResult, Value},
     pwg::ForeignCallWaitInfo,
 };
-use jsonrpc::{arg as build_json_rpc_arg, minreq_http::Builder, Client};
+use jsonrpc::{arg as build_json_rpc_arg, Client};
 use noirc_printable_type::{decode_string_value, ForeignCallError, PrintableValueDisplay};
 
+use self::transport::build_oracle_resolver_client;
+
+pub use self::transcript::{
+    read_foreign_call_transcript, RecordedForeignCall, RecordingForeignCallExecutor,
+    ReplayForeignCallExecutor,
+};
+
+mod transcript;
+mod transport;
+
 pub trait ForeignCallExecutor {
     fn execute(
         &mut self,
         foreign_call: &ForeignCallWaitInfo,
     ) -> Result<ForeignCallResult, ForeignCallError>;
+
+    /// Called once execution has completed successfully, allowing the executor to check
+    /// that every foreign call it expected to resolve was made.
+    fn finish(&mut self) -> Result<(), ForeignCallError> {
+        Ok(())
+    }
+}
+
+impl<E: ForeignCallExecutor + ?Sized> ForeignCallExecutor for Box<E> {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<ForeignCallResult, ForeignCallError> {
+        self.as_mut().execute(foreign_call)
+    }
+
+    fn finish(&mut self) -> Result<(), ForeignCallError> {
+        self.as_mut().finish()
+    }
 }
 
 /// This enumeration represents the Brillig foreign calls that are natively supported by nargo.
@@ -18,9 +49,13 @@ pub(crate) enum ForeignCall {
     Print,
     CreateMock,
     SetMockParams,
+    SetMockParam,
     SetMockReturns,
+    AddMockReturns,
     SetMockTimes,
     ClearMock,
+    GetMockTimesCalled,
+    GetMockParams,
 }
 
 impl std::fmt::Display for ForeignCall {
@@ -35,9 +70,13 @@ impl ForeignCall {
             ForeignCall::Print => "print",
             ForeignCall::CreateMock => "create_mock",
             ForeignCall::SetMockParams => "set_mock_params",
+            ForeignCall::SetMockParam => "set_mock_param",
             ForeignCall::SetMockReturns => "set_mock_returns",
+            ForeignCall::AddMockReturns => "add_mock_returns",
             ForeignCall::SetMockTimes => "set_mock_times",
             ForeignCall::ClearMock => "clear_mock",
+            ForeignCall::GetMockTimesCalled => "get_mock_times_called",
+            ForeignCall::GetMockParams => "get_mock_params",
         }
     }
 
@@ -46,9 +85,13 @@ impl ForeignCall {
             "print" => Some(ForeignCall::Print),
             "create_mock" => Some(ForeignCall::CreateMock),
             "set_mock_params" => Some(ForeignCall::SetMockParams),
+            "set_mock_param" => Some(ForeignCall::SetMockParam),
             "set_mock_returns" => Some(ForeignCall::SetMockReturns),
+            "add_mock_returns" => Some(ForeignCall::AddMockReturns),
             "set_mock_times" => Some(ForeignCall::SetMockTimes),
             "clear_mock" => Some(ForeignCall::ClearMock),
+            "get_mock_times_called" => Some(ForeignCall::GetMockTimesCalled),
+            "get_mock_params" => Some(ForeignCall::GetMockParams),
             _ => None,
         }
     }
@@ -63,10 +106,14 @@ struct MockedCall {
     name: String,
     /// Optionally match the parameters
     params: Option<Vec<ForeignCallParam>>,
-    /// The result to return when this mock is called
-    result: ForeignCallResult,
-    /// How many times should this mock be called before it is removed
+    /// Parameters which must match at the given positions, leaving the others unmatched
+    param_matchers: BTreeMap<usize, ForeignCallParam>,
+    /// The results to return on successive calls to this mock, the last one being repeated
+    results: Vec<ForeignCallResult>,
+    /// How many times should this mock be called before it stops matching
     times_left: Option<u64>,
+    /// The parameters of every call made to this mock
+    calls: Vec<Vec<ForeignCallParam>>,
 }
 
 impl MockedCall {
@@ -75,15 +122,36 @@ impl MockedCall {
             id,
             name,
             params: None,
-            result: ForeignCallResult { values: vec![] },
+            param_matchers: BTreeMap::new(),
+            results: vec![],
             times_left: None,
+            calls: vec![],
         }
     }
 }
 
 impl MockedCall {
     fn matches(&self, name: &str, params: &Vec<ForeignCallParam>) -> bool {
-        self.name == name && (self.params.is_none() || self.params.as_ref() == Some(params))
+        self.name == name
+            && self.times_left != Some(0)
+            && (self.params.is_none() || self.params.as_ref() == Some(params))
+            && self
+                .param_matchers
+                .iter()
+                .all(|(index, param_matcher)| params.get(*index) == Some(param_matcher))
+    }
+
+    /// Records a call to this mock, returning the result it should resolve to.
+    fn call(&mut self, params: &[ForeignCallParam]) -> ForeignCallResult {
+        let result_index = self.calls.len().min(self.results.len().saturating_sub(1));
+        let result =
+            self.results.get(result_index).cloned().unwrap_or(ForeignCallResult { values: vec![] });
+
+        self.calls.push(params.to_vec());
+        if let Some(times_left) = &mut self.times_left {
+            *times_left -= 1;
+        }
+        result
     }
 }
 
@@ -95,16 +163,22 @@ pub struct DefaultForeignCallExecutor {
     mocked_responses: Vec<MockedCall>,
     /// Whether to print [`ForeignCall::Print`] output.
     show_output: bool,
+    /// Whether [`ForeignCall::Print`] output is written to stderr rather than stdout.
+    print_to_stderr: bool,
     /// JSON RPC client to resolve foreign calls
     external_resolver: Option<Client>,
 }
 
 impl DefaultForeignCallExecutor {
+    /// Creates a new executor, optionally resolving unknown foreign calls through the oracle
+    /// resolver at `resolver_url`.
+    ///
+    /// The URL scheme selects how the resolver is reached: `http://` for a JSON-RPC HTTP server,
+    /// `exec:<command> [args...]` to spawn a process and talk to it over stdin and stdout,
+    /// or `unix:<path>` to connect to a Unix domain socket.
     pub fn new(show_output: bool, resolver_url: Option<&str>) -> Self {
         let oracle_resolver = resolver_url.map(|resolver_url| {
-            let transport_builder =
-                Builder::new().url(resolver_url).expect("Invalid oracle resolver URL");
-            Client::with_transport(transport_builder.build())
+            build_oracle_resolver_client(resolver_url).unwrap_or_else(|err| panic!("{err}"))
         });
         DefaultForeignCallExecutor {
             show_output,
@@ -112,6 +186,12 @@ impl DefaultForeignCallExecutor {
             ..DefaultForeignCallExecutor::default()
         }
     }
+
+    /// Writes any printed output to stderr, for when stdout is reserved for a machine readable report.
+    pub fn with_output_to_stderr(mut self) -> Self {
+        self.print_to_stderr = true;
+        self
+    }
 }
 
 impl DefaultForeignCallExecutor {
@@ -132,14 +212,22 @@ impl DefaultForeignCallExecutor {
         decode_string_value(&fields)
     }
 
-    fn execute_print(foreign_call_inputs: &[ForeignCallParam]) -> Result<(), ForeignCallError> {
+    fn execute_print(
+        &self,
+        foreign_call_inputs: &[ForeignCallParam],
+    ) -> Result<(), ForeignCallError> {
         let skip_newline = foreign_call_inputs[0].unwrap_value().is_zero();
         let display_values: PrintableValueDisplay = foreign_call_inputs
             .split_first()
             .ok_or(ForeignCallError::MissingForeignCallInputs)?
             .1
             .try_into()?;
-        print!("{display_values}{}", if skip_newline { "" } else { "\n" });
+        let newline = if skip_newline { "" } else { "\n" };
+        if self.print_to_stderr {
+            eprint!("{display_values}{newline}");
+        } else {
+            print!("{display_values}{newline}");
+        }
         Ok(())
     }
 }
@@ -153,7 +241,7 @@ impl ForeignCallExecutor for DefaultForeignCallExecutor {
         match ForeignCall::lookup(foreign_call_name) {
             Some(ForeignCall::Print) => {
                 if self.show_output {
-                    Self::execute_print(&foreign_call.inputs)?;
+                    self.execute_print(&foreign_call.inputs)?;
                 }
                 Ok(ForeignCallResult { values: vec![] })
             }
@@ -174,11 +262,39 @@ impl ForeignCallExecutor for DefaultForeignCallExecutor {
 
                 Ok(ForeignCallResult { values: vec![] })
             }
+            Some(ForeignCall::SetMockParam) => {
+                let (id, params) = Self::extract_mock_id(&foreign_call.inputs)?;
+                let (index, param) =
+                    params.split_first().ok_or(ForeignCallError::MissingForeignCallInputs)?;
+                let index = index
+                    .unwrap_value()
+                    .to_field()
+                    .try_to_u64()
+                    .expect("Invalid bit size of param index") as usize;
+
+                // A parameter spanning several foreign call inputs, e.g. a struct, matches them all
+                let mock =
+                    self.find_mock_by_id(id).unwrap_or_else(|| panic!("Unknown mock id {}", id));
+                for (offset, param) in param.iter().enumerate() {
+                    mock.param_matchers.insert(index + offset, param.clone());
+                }
+
+                Ok(ForeignCallResult { values: vec![] })
+            }
             Some(ForeignCall::SetMockReturns) => {
                 let (id, params) = Self::extract_mock_id(&foreign_call.inputs)?;
                 self.find_mock_by_id(id)
                     .unwrap_or_else(|| panic!("Unknown mock id {}", id))
-                    .result = ForeignCallResult { values: params.to_vec() };
+                    .results = vec![ForeignCallResult { values: params.to_vec() }];
+
+                Ok(ForeignCallResult { values: vec![] })
+            }
+            Some(ForeignCall::AddMockReturns) => {
+                let (id, params) = Self::extract_mock_id(&foreign_call.inputs)?;
+                self.find_mock_by_id(id)
+                    .unwrap_or_else(|| panic!("Unknown mock id {}", id))
+                    .results
+                    .push(ForeignCallResult { values: params.to_vec() });
 
                 Ok(ForeignCallResult { values: vec![] })
             }
@@ -201,6 +317,38 @@ impl ForeignCallExecutor for DefaultForeignCallExecutor {
                 self.mocked_responses.retain(|response| response.id != id);
                 Ok(ForeignCallResult { values: vec![] })
             }
+            Some(ForeignCall::GetMockTimesCalled) => {
+                let (id, _) = Self::extract_mock_id(&foreign_call.inputs)?;
+                let times_called = self
+                    .find_mock_by_id(id)
+                    .unwrap_or_else(|| panic!("Unknown mock id {}", id))
+                    .calls
+                    .len();
+
+                Ok(ForeignCallResult { values: vec![Value::from(times_called).into()] })
+            }
+            Some(ForeignCall::GetMockParams) => {
+                let (id, params) = Self::extract_mock_id(&foreign_call.inputs)?;
+                let call_index = params[0]
+                    .unwrap_value()
+                    .to_field()
+                    .try_to_u64()
+                    .expect("Invalid bit size of call index")
+                    as usize;
+
+                let calls = &self
+                    .find_mock_by_id(id)
+                    .unwrap_or_else(|| panic!("Unknown mock id {}", id))
+                    .calls;
+                let call_params = calls.get(call_index).unwrap_or_else(|| {
+                    panic!(
+                        "Mock {id} was called {} times, it has no call {call_index}",
+                        calls.len()
+                    )
+                });
+
+                Ok(ForeignCallResult { values: call_params.clone() })
+            }
             None => {
                 let mock_response_position = self
                     .mocked_responses
@@ -213,16 +361,10 @@ impl ForeignCallExecutor for DefaultForeignCallExecutor {
                             .mocked_responses
                             .get_mut(response_position)
                             .expect("Invalid position of mocked response");
-                        let result = mock.result.values.clone();
 
-                        if let Some(times_left) = &mut mock.times_left {
-                            *times_left -= 1;
-                            if *times_left == 0 {
-                                self.mocked_responses.remove(response_position);
-                            }
-                        }
-
-                        Ok(ForeignCallResult { values: result })
+                        // Mocks which have been called as many times as they should are kept,
+                        // so that they can still be inspected, but no longer match any call.
+                        Ok(mock.call(&foreign_call.inputs))
                     }
                     (None, Some(external_resolver)) => {
                         let encoded_params: Vec<_> =
@@ -299,6 +441,29 @@ mod tests {
         (server, url)
     }
 
+    /// Serves the oracle resolver on a Unix domain socket, handling one request per line.
+    #[cfg(unix)]
+    fn build_oracle_socket(socket_path: &std::path::Path) -> String {
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::net::UnixListener;
+
+        let mut io = jsonrpc_core::IoHandler::new();
+        io.extend_with(OracleResolverImpl.to_delegate());
+
+        let listener = UnixListener::bind(socket_path).expect("Could not bind socket");
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("Could not accept connection");
+            let reader = BufReader::new(stream.try_clone().expect("Could not clone stream"));
+            for line in reader.lines() {
+                let line = line.expect("Could not read request");
+                let response = io.handle_request_sync(&line).expect("Expected a response");
+                writeln!(stream, "{response}").expect("Could not write response");
+            }
+        });
+
+        format!("unix:{}", socket_path.display())
+    }
+
     #[serial]
     #[test]
     fn test_oracle_resolver_echo() {
@@ -334,4 +499,86 @@ mod tests {
 
         server.close();
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_oracle_resolver_unix_socket() {
+        let temp_dir = tempfile::tempdir().expect("Could not create temp dir");
+        let url = build_oracle_socket(&temp_dir.path().join("oracle.sock"));
+
+        let mut executor = DefaultForeignCallExecutor::new(false, Some(&url));
+
+        let foreign_call = ForeignCallWaitInfo {
+            function: "sum".to_string(),
+            inputs: vec![ForeignCallParam::Array(vec![1_usize.into(), 2_usize.into()])],
+        };
+        let result = executor.execute(&foreign_call);
+        assert_eq!(result.unwrap(), Value::from(3_usize).into());
+
+        // Following requests reuse the same connection
+        let foreign_call = ForeignCallWaitInfo {
+            function: "echo".to_string(),
+            inputs: vec![ForeignCallParam::Single(1_u128.into())],
+        };
+        let result = executor.execute(&foreign_call);
+        assert_eq!(result.unwrap(), ForeignCallResult { values: foreign_call.inputs });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_oracle_resolver_exec() {
+        let temp_dir = tempfile::tempdir().expect("Could not create temp dir");
+        let script_path = temp_dir.path().join("oracle.sh");
+        std::fs::write(
+            &script_path,
+            r#"while read -r request; do echo '{"jsonrpc":"2.0","result":{"values":[]},"id":1}'; done"#,
+        )
+        .expect("Could not write oracle script");
+
+        let url = format!("exec:sh {}", script_path.display());
+        let mut executor = DefaultForeignCallExecutor::new(false, Some(&url));
+
+        let foreign_call = ForeignCallWaitInfo { function: "noop".to_string(), inputs: vec![] };
+        for _ in 0..2 {
+            let result = executor.execute(&foreign_call);
+            assert_eq!(result.unwrap(), ForeignCallResult { values: vec![] });
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_oracle_resolver_exec_respawns_after_failure() {
+        let temp_dir = tempfile::tempdir().expect("Could not create temp dir");
+        // The directory name checks that quoted arguments containing spaces are kept whole
+        let script_dir = temp_dir.path().join("oracle resolver");
+        std::fs::create_dir(&script_dir).expect("Could not create script dir");
+        let script_path = script_dir.join("oracle.sh");
+        // Responds to a single request before exiting
+        std::fs::write(
+            &script_path,
+            r#"read -r request; echo '{"jsonrpc":"2.0","result":{"values":[]},"id":1}'"#,
+        )
+        .expect("Could not write oracle script");
+
+        let url = format!("exec:sh '{}'", script_path.display());
+        let mut executor = DefaultForeignCallExecutor::new(false, Some(&url));
+
+        let foreign_call = ForeignCallWaitInfo { function: "noop".to_string(), inputs: vec![] };
+        let result = executor.execute(&foreign_call);
+        assert_eq!(result.unwrap(), ForeignCallResult { values: vec![] });
+
+        // The resolver has exited, so this request fails and the next one spawns it again
+        assert!(executor.execute(&foreign_call).is_err());
+        let result = executor.execute(&foreign_call);
+        assert_eq!(result.unwrap(), ForeignCallResult { values: vec![] });
+    }
+
+    #[test]
+    fn test_oracle_resolver_exec_failure() {
+        let mut executor =
+            DefaultForeignCallExecutor::new(false, Some("exec:nargo-missing-oracle-resolver"));
+
+        let foreign_call = ForeignCallWaitInfo { function: "noop".to_string(), inputs: vec![] };
+        assert!(executor.execute(&foreign_call).is_err());
+    }
 }
@@ -0,0 +1,290 @@
+//! Recording and replaying of foreign calls.
+//!
+//! A transcript is a JSON array holding every foreign call made during an execution,
+//! in order, along with the result which was returned for it. Replaying a transcript
+//! allows re-running a program without access to the oracle resolver it was recorded against.
+
+use std::{
+    fs::File,
+    io::{Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use acvm::{
+    acir::brillig::{ForeignCallParam, ForeignCallResult},
+    pwg::ForeignCallWaitInfo,
+};
+use noirc_printable_type::ForeignCallError;
+use serde::{Deserialize, Serialize};
+
+use super::{ForeignCall, ForeignCallExecutor};
+
+/// A single foreign call and the result it resolved to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedForeignCall {
+    pub function: String,
+    pub inputs: Vec<ForeignCallParam>,
+    pub result: ForeignCallResult,
+}
+
+/// Reads a transcript previously written by a [`RecordingForeignCallExecutor`].
+pub fn read_foreign_call_transcript(
+    transcript_path: &Path,
+) -> Result<Vec<RecordedForeignCall>, ForeignCallError> {
+    let transcript_error = |err| ForeignCallError::TranscriptError(transcript_path.into(), err);
+    let transcript = std::fs::read(transcript_path).map_err(transcript_error)?;
+    serde_json::from_slice(&transcript).map_err(|err| transcript_error(err.into()))
+}
+
+/// Wraps a [`ForeignCallExecutor`], writing every foreign call it resolves to a transcript.
+///
+/// The transcript is updated after every call so that it remains complete
+/// even if execution fails or is interrupted.
+#[derive(Debug)]
+pub struct RecordingForeignCallExecutor<E> {
+    executor: E,
+    transcript_path: PathBuf,
+    transcript: File,
+    recorded_calls: usize,
+}
+
+/// The closing bracket of the transcript's JSON array, which is overwritten by the next call.
+const TRANSCRIPT_END: &[u8] = b"\n]\n";
+
+impl<E: ForeignCallExecutor> RecordingForeignCallExecutor<E> {
+    /// Creates a new transcript at `transcript_path`, replacing any existing one
+    /// and creating its parent directory if it doesn't exist.
+    pub fn new(executor: E, transcript_path: &Path) -> Result<Self, ForeignCallError> {
+        let transcript_error = |err| ForeignCallError::TranscriptError(transcript_path.into(), err);
+        if let Some(parent) = transcript_path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|err| ForeignCallError::TranscriptError(parent.into(), err))?;
+        }
+        let mut transcript = File::create(transcript_path).map_err(transcript_error)?;
+        transcript.write_all(b"[").map_err(transcript_error)?;
+        transcript.write_all(TRANSCRIPT_END).map_err(transcript_error)?;
+        Ok(RecordingForeignCallExecutor {
+            executor,
+            transcript_path: transcript_path.to_path_buf(),
+            transcript,
+            recorded_calls: 0,
+        })
+    }
+
+    fn record(&mut self, recorded_call: &RecordedForeignCall) -> std::io::Result<()> {
+        let separator: &[u8] = if self.recorded_calls == 0 { b"\n" } else { b",\n" };
+        let mut entry = separator.to_vec();
+        serde_json::to_writer(&mut entry, recorded_call)?;
+        entry.extend_from_slice(TRANSCRIPT_END);
+
+        self.transcript.seek(SeekFrom::End(-(TRANSCRIPT_END.len() as i64)))?;
+        self.transcript.write_all(&entry)?;
+        self.transcript.flush()?;
+        self.recorded_calls += 1;
+        Ok(())
+    }
+}
+
+impl<E: ForeignCallExecutor> ForeignCallExecutor for RecordingForeignCallExecutor<E> {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<ForeignCallResult, ForeignCallError> {
+        let result = self.executor.execute(foreign_call)?;
+
+        let recorded_call = RecordedForeignCall {
+            function: foreign_call.function.clone(),
+            inputs: foreign_call.inputs.clone(),
+            result,
+        };
+        self.record(&recorded_call)
+            .map_err(|err| ForeignCallError::TranscriptError(self.transcript_path.clone(), err))?;
+
+        Ok(recorded_call.result)
+    }
+
+    fn finish(&mut self) -> Result<(), ForeignCallError> {
+        self.executor.finish()
+    }
+}
+
+/// Resolves foreign calls with the results held in a transcript, failing as soon as
+/// execution makes a call which differs from the one recorded at the same point,
+/// or when [`ForeignCallExecutor::finish`] is called before every recorded call was made.
+///
+/// Calls to [`ForeignCall::Print`] are still passed on to the wrapped executor
+/// so that the program's output is displayed as it was when recording.
+#[derive(Debug)]
+pub struct ReplayForeignCallExecutor<E> {
+    executor: E,
+    recorded_calls: std::vec::IntoIter<RecordedForeignCall>,
+    replayed_calls: usize,
+}
+
+impl<E: ForeignCallExecutor> ReplayForeignCallExecutor<E> {
+    pub fn new(executor: E, recorded_calls: Vec<RecordedForeignCall>) -> Self {
+        ReplayForeignCallExecutor {
+            executor,
+            recorded_calls: recorded_calls.into_iter(),
+            replayed_calls: 0,
+        }
+    }
+}
+
+impl<E: ForeignCallExecutor> ForeignCallExecutor for ReplayForeignCallExecutor<E> {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<ForeignCallResult, ForeignCallError> {
+        let call_index = self.replayed_calls;
+        let function = &foreign_call.function;
+        let Some(recorded_call) = self.recorded_calls.next() else {
+            return Err(ForeignCallError::TranscriptMismatch(format!(
+                "Call #{call_index} to `{function}` was not recorded"
+            )));
+        };
+        if &recorded_call.function != function {
+            return Err(ForeignCallError::TranscriptMismatch(format!(
+                "Call #{call_index} was recorded as `{}` but `{function}` was called",
+                recorded_call.function
+            )));
+        }
+        if recorded_call.inputs != foreign_call.inputs {
+            return Err(ForeignCallError::TranscriptMismatch(format!(
+                "Call #{call_index} to `{function}` was made with different inputs than were recorded"
+            )));
+        }
+        self.replayed_calls += 1;
+
+        if matches!(ForeignCall::lookup(function), Some(ForeignCall::Print)) {
+            self.executor.execute(foreign_call)?;
+        }
+        Ok(recorded_call.result)
+    }
+
+    fn finish(&mut self) -> Result<(), ForeignCallError> {
+        let unreplayed_calls = self.recorded_calls.len();
+        if let Some(next_call) = self.recorded_calls.as_slice().first() {
+            return Err(ForeignCallError::TranscriptMismatch(format!(
+                "{unreplayed_calls} recorded call(s) were not made, starting with call #{} to `{}`",
+                self.replayed_calls, next_call.function
+            )));
+        }
+        self.executor.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::{
+        acir::brillig::{ForeignCallParam, ForeignCallResult},
+        pwg::ForeignCallWaitInfo,
+    };
+    use noirc_printable_type::ForeignCallError;
+
+    use super::{
+        read_foreign_call_transcript, RecordingForeignCallExecutor, ReplayForeignCallExecutor,
+    };
+    use crate::ops::ForeignCallExecutor;
+
+    /// Resolves every foreign call by echoing back its inputs.
+    struct EchoExecutor;
+
+    impl ForeignCallExecutor for EchoExecutor {
+        fn execute(
+            &mut self,
+            foreign_call: &ForeignCallWaitInfo,
+        ) -> Result<ForeignCallResult, ForeignCallError> {
+            Ok(ForeignCallResult { values: foreign_call.inputs.clone() })
+        }
+    }
+
+    /// Fails on any foreign call, as replaying should never need to resolve one.
+    struct UnreachableExecutor;
+
+    impl ForeignCallExecutor for UnreachableExecutor {
+        fn execute(
+            &mut self,
+            foreign_call: &ForeignCallWaitInfo,
+        ) -> Result<ForeignCallResult, ForeignCallError> {
+            panic!("Unexpected foreign call {}", foreign_call.function)
+        }
+    }
+
+    fn foreign_call(function: &str, value: u128) -> ForeignCallWaitInfo {
+        ForeignCallWaitInfo {
+            function: function.to_string(),
+            inputs: vec![ForeignCallParam::Single(value.into())],
+        }
+    }
+
+    #[test]
+    fn replays_recorded_foreign_calls() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let transcript_path = temp_dir.path().join("transcripts").join("transcript.json");
+        let foreign_calls = [foreign_call("oracle", 1), foreign_call("other_oracle", 2)];
+
+        let mut recorder = RecordingForeignCallExecutor::new(EchoExecutor, &transcript_path)
+            .expect("Could not create transcript");
+        // The transcript is valid even before anything has been recorded
+        assert!(read_foreign_call_transcript(&transcript_path).unwrap().is_empty());
+        let results: Vec<_> =
+            foreign_calls.iter().map(|call| recorder.execute(call).unwrap()).collect();
+
+        let recorded_calls = read_foreign_call_transcript(&transcript_path).unwrap();
+        assert_eq!(recorded_calls.len(), 2);
+        assert_eq!(recorded_calls[1].function, "other_oracle");
+        assert_eq!(recorded_calls[1].result, results[1]);
+
+        let mut replayer = ReplayForeignCallExecutor::new(UnreachableExecutor, recorded_calls);
+        for (call, result) in foreign_calls.iter().zip(results) {
+            assert_eq!(replayer.execute(call).unwrap(), result);
+        }
+        replayer.finish().expect("Every recorded call was replayed");
+    }
+
+    #[test]
+    fn fails_when_replay_diverges() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let transcript_path = temp_dir.path().join("transcript.json");
+
+        let mut recorder = RecordingForeignCallExecutor::new(EchoExecutor, &transcript_path)
+            .expect("Could not create transcript");
+        recorder.execute(&foreign_call("oracle", 1)).unwrap();
+        let recorded_calls = read_foreign_call_transcript(&transcript_path).unwrap();
+
+        let diverging_calls = [foreign_call("other_oracle", 1), foreign_call("oracle", 2)];
+        for call in diverging_calls {
+            let mut replayer =
+                ReplayForeignCallExecutor::new(UnreachableExecutor, recorded_calls.clone());
+            assert!(matches!(
+                replayer.execute(&call),
+                Err(ForeignCallError::TranscriptMismatch(_))
+            ));
+        }
+
+        // Making more calls than were recorded also diverges
+        let mut replayer = ReplayForeignCallExecutor::new(UnreachableExecutor, recorded_calls);
+        replayer.execute(&foreign_call("oracle", 1)).unwrap();
+        assert!(matches!(
+            replayer.execute(&foreign_call("oracle", 1)),
+            Err(ForeignCallError::TranscriptMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn fails_when_recorded_calls_are_not_replayed() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let transcript_path = temp_dir.path().join("transcript.json");
+
+        let mut recorder = RecordingForeignCallExecutor::new(EchoExecutor, &transcript_path)
+            .expect("Could not create transcript");
+        recorder.execute(&foreign_call("oracle", 1)).unwrap();
+        recorder.execute(&foreign_call("other_oracle", 2)).unwrap();
+        let recorded_calls = read_foreign_call_transcript(&transcript_path).unwrap();
+
+        let mut replayer = ReplayForeignCallExecutor::new(UnreachableExecutor, recorded_calls);
+        replayer.execute(&foreign_call("oracle", 1)).unwrap();
+        assert!(matches!(replayer.finish(), Err(ForeignCallError::TranscriptMismatch(_))));
+    }
+}
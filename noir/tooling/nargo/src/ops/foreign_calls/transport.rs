@@ -0,0 +1,333 @@
+//! Transports used to reach an external oracle resolver.
+//!
+//! The resolver is selected with a URL whose scheme picks the transport:
+//!
+//! - `http://` and `https://` send each request over HTTP.
+//! - `exec:<command> [args...]` spawns the command and talks to it over its stdin and stdout.
+//!   The command line is split into words following shell quoting rules, so arguments
+//!   containing spaces can be quoted.
+//! - `unix:<path>` connects to a Unix domain socket listening at `path`.
+//!
+//! All transports exchange the same JSON-RPC messages. The `exec:` and `unix:` transports
+//! write each request as a single line of JSON and expect a single line of JSON in response
+//! within [RESPONSE_TIMEOUT]. After a failed request, the next request spawns the command or
+//! connects to the socket again.
+
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError},
+        Mutex,
+    },
+    time::Duration,
+};
+
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+
+use jsonrpc::{minreq_http::Builder, Client, Request, Response, Transport};
+
+const EXEC_SCHEME: &str = "exec:";
+const UNIX_SCHEME: &str = "unix:";
+
+/// How long to wait for the oracle resolver to respond to a request.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Builds a JSON-RPC client which reaches the oracle resolver at `resolver_url`.
+pub(crate) fn build_oracle_resolver_client(resolver_url: &str) -> Result<Client, String> {
+    if let Some(command_line) = resolver_url.strip_prefix(EXEC_SCHEME) {
+        let transport = ExecTransport::new(command_line, RESPONSE_TIMEOUT)?;
+        Ok(Client::with_transport(transport))
+    } else if let Some(path) = resolver_url.strip_prefix(UNIX_SCHEME) {
+        let transport = UnixSocketTransport::new(path, RESPONSE_TIMEOUT)?;
+        Ok(Client::with_transport(transport))
+    } else {
+        let transport_builder = Builder::new()
+            .url(resolver_url)
+            .map_err(|err| format!("Invalid oracle resolver URL `{resolver_url}`: {err}"))?;
+        Ok(Client::with_transport(transport_builder.build()))
+    }
+}
+
+/// The receiving half of a [LineStream].
+trait LineReader {
+    /// Reads the next line, returning `None` once the other end has closed the stream.
+    fn read_line(&mut self) -> io::Result<Option<String>>;
+}
+
+impl<R: io::Read> LineReader for BufReader<R> {
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        let length = BufRead::read_line(self, &mut line)?;
+        Ok((length != 0).then_some(line))
+    }
+}
+
+/// Lines read from the stdout of a child process on a separate thread,
+/// as reading from a pipe cannot time out.
+struct ChildLines {
+    lines: Receiver<io::Result<String>>,
+    timeout: Duration,
+}
+
+impl ChildLines {
+    fn new(stdout: impl io::Read + Send + 'static, timeout: Duration) -> Self {
+        let (sender, lines) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let is_error = line.is_err();
+                if sender.send(line).is_err() || is_error {
+                    break;
+                }
+            }
+        });
+        ChildLines { lines, timeout }
+    }
+}
+
+impl LineReader for ChildLines {
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        match self.lines.recv_timeout(self.timeout) {
+            Ok(line) => line.map(Some),
+            Err(RecvTimeoutError::Timeout) => Err(response_timeout_error(self.timeout)),
+            Err(RecvTimeoutError::Disconnected) => Ok(None),
+        }
+    }
+}
+
+fn response_timeout_error(timeout: Duration) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::TimedOut,
+        format!("oracle resolver did not respond within {} seconds", timeout.as_secs()),
+    )
+}
+
+/// A bidirectional stream over which requests and responses are exchanged one line at a time.
+struct LineStream<R, W> {
+    reader: R,
+    writer: W,
+}
+
+impl<R: LineReader, W: Write> LineStream<R, W> {
+    fn new(reader: R, writer: W) -> Self {
+        LineStream { reader, writer }
+    }
+
+    fn send_request(&mut self, request: &Request) -> Result<Response, jsonrpc::Error> {
+        let mut message = serde_json::to_vec(request)?;
+        message.push(b'\n');
+        self.writer.write_all(&message).map_err(transport_error)?;
+        self.writer.flush().map_err(transport_error)?;
+
+        let Some(line) = self.reader.read_line().map_err(transport_error)? else {
+            return Err(transport_error(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "oracle resolver closed the connection",
+            )));
+        };
+        Ok(serde_json::from_str(&line)?)
+    }
+
+    fn send_batch(&mut self, requests: &[Request]) -> Result<Vec<Response>, jsonrpc::Error> {
+        requests.iter().map(|request| self.send_request(request)).collect()
+    }
+}
+
+fn transport_error(err: io::Error) -> jsonrpc::Error {
+    jsonrpc::Error::Transport(Box::new(err))
+}
+
+/// Spawns the oracle resolver as a child process on the first request,
+/// sending requests to its stdin and reading responses from its stdout.
+struct ExecTransport {
+    program: String,
+    args: Vec<String>,
+    response_timeout: Duration,
+    process: Mutex<Option<(Child, LineStream<ChildLines, ChildStdin>)>>,
+}
+
+impl ExecTransport {
+    fn new(command_line: &str, response_timeout: Duration) -> Result<Self, String> {
+        let words = shell_words::split(command_line).map_err(|err| {
+            format!("Invalid command in oracle resolver URL `{EXEC_SCHEME}{command_line}`: {err}")
+        })?;
+        let mut words = words.into_iter();
+        let program = words.next().ok_or_else(|| {
+            format!("Missing command in oracle resolver URL `{EXEC_SCHEME}{command_line}`")
+        })?;
+        Ok(ExecTransport {
+            program,
+            args: words.collect(),
+            response_timeout,
+            process: Mutex::new(None),
+        })
+    }
+
+    fn spawn(&self) -> io::Result<(Child, LineStream<ChildLines, ChildStdin>)> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("stdin of oracle resolver should be piped");
+        let stdout = child.stdout.take().expect("stdout of oracle resolver should be piped");
+        let lines = ChildLines::new(stdout, self.response_timeout);
+        Ok((child, LineStream::new(lines, stdin)))
+    }
+
+    fn with_stream<T>(
+        &self,
+        f: impl FnOnce(&mut LineStream<ChildLines, ChildStdin>) -> Result<T, jsonrpc::Error>,
+    ) -> Result<T, jsonrpc::Error> {
+        let mut process = self.process.lock().expect("oracle resolver lock should not be poisoned");
+        if process.is_none() {
+            *process = Some(self.spawn().map_err(transport_error)?);
+        }
+        let (_, stream) = process.as_mut().expect("oracle resolver should have been spawned");
+        let result = f(stream);
+        if result.is_err() {
+            // Respawn the resolver on the next request rather than reading a stale response.
+            if let Some((child, _)) = process.take() {
+                kill_child(child);
+            }
+        }
+        result
+    }
+}
+
+fn kill_child(mut child: Child) {
+    // Ignore errors as the resolver may have already exited.
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+impl Transport for ExecTransport {
+    fn send_request(&self, request: Request) -> Result<Response, jsonrpc::Error> {
+        self.with_stream(|stream| stream.send_request(&request))
+    }
+
+    fn send_batch(&self, requests: &[Request]) -> Result<Vec<Response>, jsonrpc::Error> {
+        self.with_stream(|stream| stream.send_batch(requests))
+    }
+
+    fn fmt_target(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let command = std::iter::once(&self.program).chain(&self.args);
+        write!(f, "{EXEC_SCHEME}{}", shell_words::join(command))
+    }
+}
+
+impl Drop for ExecTransport {
+    fn drop(&mut self) {
+        if let Ok(process) = self.process.get_mut() {
+            if let Some((child, _)) = process.take() {
+                kill_child(child);
+            }
+        }
+    }
+}
+
+/// Connects to an oracle resolver listening on a Unix domain socket on the first request,
+/// reusing that connection for every following request.
+struct UnixSocketTransport {
+    path: String,
+    #[cfg(unix)]
+    response_timeout: Duration,
+    #[cfg(unix)]
+    stream: Mutex<Option<LineStream<BufReader<UnixStream>, UnixStream>>>,
+}
+
+impl UnixSocketTransport {
+    #[cfg(unix)]
+    fn new(path: &str, response_timeout: Duration) -> Result<Self, String> {
+        if path.is_empty() {
+            return Err(format!("Missing socket path in oracle resolver URL `{UNIX_SCHEME}`"));
+        }
+        Ok(UnixSocketTransport {
+            path: path.to_owned(),
+            response_timeout,
+            stream: Mutex::new(None),
+        })
+    }
+
+    #[cfg(not(unix))]
+    fn new(path: &str, _response_timeout: Duration) -> Result<Self, String> {
+        Err(format!(
+            "Oracle resolver URL `{UNIX_SCHEME}{path}` is not supported: Unix domain sockets are not available on this platform"
+        ))
+    }
+
+    #[cfg(unix)]
+    fn with_stream<T>(
+        &self,
+        f: impl FnOnce(&mut LineStream<BufReader<UnixStream>, UnixStream>) -> Result<T, jsonrpc::Error>,
+    ) -> Result<T, jsonrpc::Error> {
+        let mut stream = self.stream.lock().expect("oracle resolver lock should not be poisoned");
+        if stream.is_none() {
+            let socket = UnixStream::connect(&self.path).map_err(transport_error)?;
+            socket.set_read_timeout(Some(self.response_timeout)).map_err(transport_error)?;
+            let writer = socket.try_clone().map_err(transport_error)?;
+            *stream = Some(LineStream::new(BufReader::new(socket), writer));
+        }
+        let result = f(stream.as_mut().expect("oracle resolver should be connected"));
+        if result.is_err() {
+            // Reconnect on the next request rather than reading a stale response.
+            *stream = None;
+        }
+        result
+    }
+}
+
+impl Transport for UnixSocketTransport {
+    #[cfg(unix)]
+    fn send_request(&self, request: Request) -> Result<Response, jsonrpc::Error> {
+        self.with_stream(|stream| stream.send_request(&request))
+    }
+
+    #[cfg(unix)]
+    fn send_batch(&self, requests: &[Request]) -> Result<Vec<Response>, jsonrpc::Error> {
+        self.with_stream(|stream| stream.send_batch(requests))
+    }
+
+    #[cfg(not(unix))]
+    fn send_request(&self, _request: Request) -> Result<Response, jsonrpc::Error> {
+        unreachable!("Unix domain socket transports cannot be built on this platform")
+    }
+
+    #[cfg(not(unix))]
+    fn send_batch(&self, _requests: &[Request]) -> Result<Vec<Response>, jsonrpc::Error> {
+        unreachable!("Unix domain socket transports cannot be built on this platform")
+    }
+
+    fn fmt_target(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{UNIX_SCHEME}{}", self.path)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use jsonrpc::Client;
+
+    use super::ExecTransport;
+
+    #[test]
+    fn exec_transport_times_out_waiting_for_a_response() {
+        let transport = ExecTransport::new("sh -c 'sleep 10'", Duration::from_millis(100))
+            .expect("Could not build transport");
+        let client = Client::with_transport(transport);
+
+        let start = Instant::now();
+        let request = client.build_request("noop", &[]);
+        assert!(client.send_request(request).is_err());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn exec_transport_rejects_unterminated_quotes() {
+        assert!(ExecTransport::new("sh 'oracle.sh", Duration::from_secs(1)).is_err());
+    }
+}
@@ -0,0 +1,454 @@
+//! Differential fuzzing of the constrained ACIR and unconstrained Brillig lowerings of a program.
+//!
+//! Both lowerings of a program must compute the same return values for any inputs, and must agree on
+//! which inputs are rejected. Any input on which they disagree points to a bug in the compiler.
+use acvm::{acir::native_types::WitnessMap, BlackBoxFunctionSolver, FieldElement};
+use noirc_abi::{errors::AbiError, input_parser::InputValue, Abi, AbiType, InputMap};
+use noirc_driver::CompiledProgram;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::{execute_circuit, DefaultForeignCallExecutor};
+
+/// Options for [`fuzz_program`].
+#[derive(Debug, Clone, Copy)]
+pub struct FuzzingConfig {
+    /// The number of random inputs to execute the program with.
+    pub runs: usize,
+    /// The seed from which inputs are generated, so that a run can be reproduced.
+    pub seed: u64,
+    /// The maximum number of candidate inputs which are tried when minimizing a divergent input.
+    pub max_shrink_steps: usize,
+}
+
+impl Default for FuzzingConfig {
+    fn default() -> Self {
+        FuzzingConfig { runs: 100, seed: 0, max_shrink_steps: 1000 }
+    }
+}
+
+/// How the ACIR and Brillig lowerings of a program disagree on an input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DivergenceKind {
+    /// Both lowerings were executed successfully but returned different values.
+    ReturnValues { acir: Option<InputValue>, brillig: Option<InputValue> },
+    /// The ACIR lowering failed, such as by an unsatisfied constraint, while the Brillig lowering succeeded.
+    AcirFailed { error: String },
+    /// The Brillig lowering failed while the ACIR lowering succeeded.
+    BrilligFailed { error: String },
+}
+
+/// An input on which the ACIR and Brillig lowerings of a program disagree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    pub inputs: InputMap,
+    pub kind: DivergenceKind,
+}
+
+/// The outcome of fuzzing a program.
+#[derive(Debug, Clone)]
+pub struct FuzzReport {
+    /// The number of random inputs the program was executed with.
+    pub runs: usize,
+    /// The number of inputs which both lowerings rejected, such as by failing an assertion.
+    pub rejected: usize,
+    /// The first divergence which was found, with its inputs minimized.
+    pub divergence: Option<Divergence>,
+}
+
+/// The result of executing both lowerings of a program with the same input.
+enum Outcome {
+    Agreed,
+    Rejected,
+    Diverged(DivergenceKind),
+}
+
+/// Executes `acir_program` and `brillig_program`, the constrained and unconstrained lowerings of the
+/// same program, with random inputs generated from their ABI until they disagree on an input.
+///
+/// A divergent input is minimized before being reported, by simplifying its values as long as the
+/// lowerings keep disagreeing.
+pub fn fuzz_program<B: BlackBoxFunctionSolver>(
+    acir_program: &CompiledProgram,
+    brillig_program: &CompiledProgram,
+    blackbox_solver: &B,
+    config: &FuzzingConfig,
+) -> Result<FuzzReport, AbiError> {
+    let lowerings = Lowerings { acir_program, brillig_program, blackbox_solver };
+    let mut rng = StdRng::seed_from_u64(config.seed);
+
+    let mut report = FuzzReport { runs: 0, rejected: 0, divergence: None };
+    while report.runs < config.runs {
+        let inputs = random_inputs(&mut rng, &acir_program.abi);
+        report.runs += 1;
+        match lowerings.compare(&inputs)? {
+            Outcome::Agreed => (),
+            Outcome::Rejected => report.rejected += 1,
+            Outcome::Diverged(kind) => {
+                let divergence = lowerings.minimize(Divergence { inputs, kind }, config)?;
+                report.divergence = Some(divergence);
+                break;
+            }
+        }
+    }
+    Ok(report)
+}
+
+struct Lowerings<'a, B: BlackBoxFunctionSolver> {
+    acir_program: &'a CompiledProgram,
+    brillig_program: &'a CompiledProgram,
+    blackbox_solver: &'a B,
+}
+
+impl<B: BlackBoxFunctionSolver> Lowerings<'_, B> {
+    fn compare(&self, inputs: &InputMap) -> Result<Outcome, AbiError> {
+        let acir_result = self.execute(self.acir_program, inputs)?;
+        let brillig_result = self.execute(self.brillig_program, inputs)?;
+
+        let outcome = match (acir_result, brillig_result) {
+            (Ok(acir), Ok(brillig)) if acir == brillig => Outcome::Agreed,
+            (Ok(acir), Ok(brillig)) => {
+                Outcome::Diverged(DivergenceKind::ReturnValues { acir, brillig })
+            }
+            (Err(error), Ok(_)) => Outcome::Diverged(DivergenceKind::AcirFailed { error }),
+            (Ok(_), Err(error)) => Outcome::Diverged(DivergenceKind::BrilligFailed { error }),
+            (Err(_), Err(_)) => Outcome::Rejected,
+        };
+        Ok(outcome)
+    }
+
+    /// Executes `program` with `inputs`, returning its return value or the reason its execution failed.
+    fn execute(
+        &self,
+        program: &CompiledProgram,
+        inputs: &InputMap,
+    ) -> Result<Result<Option<InputValue>, String>, AbiError> {
+        let initial_witness = program.abi.encode(inputs, None)?;
+        let solved_witness = execute_circuit(
+            &program.circuit,
+            initial_witness,
+            self.blackbox_solver,
+            &mut DefaultForeignCallExecutor::new(false, None),
+        );
+        match solved_witness {
+            Ok(solved_witness) => Ok(Ok(return_value(&program.abi, &solved_witness)?)),
+            Err(error) => Ok(Err(error.to_string())),
+        }
+    }
+
+    /// Simplifies the inputs of `divergence` one value at a time, keeping each simplification on which
+    /// the lowerings still disagree.
+    fn minimize(
+        &self,
+        mut divergence: Divergence,
+        config: &FuzzingConfig,
+    ) -> Result<Divergence, AbiError> {
+        let mut remaining_steps = config.max_shrink_steps;
+        'shrink: loop {
+            for parameter in &self.acir_program.abi.parameters {
+                let value = &divergence.inputs[&parameter.name];
+                for candidate in simplifications(value, &parameter.typ) {
+                    if remaining_steps == 0 {
+                        break 'shrink;
+                    }
+                    remaining_steps -= 1;
+
+                    let mut inputs = divergence.inputs.clone();
+                    inputs.insert(parameter.name.clone(), candidate);
+                    if let Outcome::Diverged(kind) = self.compare(&inputs)? {
+                        divergence = Divergence { inputs, kind };
+                        continue 'shrink;
+                    }
+                }
+            }
+            break;
+        }
+        Ok(divergence)
+    }
+}
+
+fn return_value(abi: &Abi, solved_witness: &WitnessMap) -> Result<Option<InputValue>, AbiError> {
+    let (_, return_value) = abi.decode(solved_witness)?;
+    Ok(return_value)
+}
+
+/// Generates random values for each parameter of `abi`.
+fn random_inputs(rng: &mut impl Rng, abi: &Abi) -> InputMap {
+    abi.parameters
+        .iter()
+        .map(|parameter| (parameter.name.clone(), random_value(rng, &parameter.typ)))
+        .collect()
+}
+
+fn random_value(rng: &mut impl Rng, typ: &AbiType) -> InputValue {
+    match typ {
+        AbiType::Field => InputValue::Field(random_field(rng)),
+        AbiType::Integer { width, .. } => InputValue::Field(random_integer(rng, *width)),
+        AbiType::Boolean => InputValue::Field(FieldElement::from(rng.gen::<bool>())),
+        AbiType::Array { length, typ } => {
+            InputValue::Vec((0..*length).map(|_| random_value(rng, typ)).collect())
+        }
+        AbiType::Tuple { fields } => {
+            InputValue::Vec(fields.iter().map(|typ| random_value(rng, typ)).collect())
+        }
+        AbiType::Struct { fields, .. } => InputValue::Struct(
+            fields.iter().map(|(name, typ)| (name.clone(), random_value(rng, typ))).collect(),
+        ),
+        AbiType::String { length } => {
+            InputValue::String((0..*length).map(|_| rng.gen_range(b' '..=b'~') as char).collect())
+        }
+    }
+}
+
+/// Generates a field element, biased towards the values at the edges of the field.
+fn random_field(rng: &mut impl Rng) -> FieldElement {
+    match rng.gen_range(0..4) {
+        0 => [FieldElement::zero(), FieldElement::one(), -FieldElement::one()][rng.gen_range(0..3)],
+        1 => FieldElement::from(rng.gen::<u64>() as u128),
+        _ => FieldElement::from_be_bytes_reduce(&rng.gen::<[u8; 32]>()),
+    }
+}
+
+/// Generates an integer with `width` bits, biased towards the values at the edges of its range.
+///
+/// Signed integers are encoded in two's complement, so this covers their whole range as well.
+fn random_integer(rng: &mut impl Rng, width: u32) -> FieldElement {
+    let max = if width >= 128 { u128::MAX } else { (1 << width) - 1 };
+    let value = if rng.gen_ratio(1, 4) {
+        [0, 1, max, max >> 1, (max >> 1) + 1][rng.gen_range(0..5)]
+    } else {
+        rng.gen::<u128>() & max
+    };
+    FieldElement::from(value)
+}
+
+/// Returns the values which are simpler than `value` in a single position.
+fn simplifications(value: &InputValue, typ: &AbiType) -> Vec<InputValue> {
+    match (value, typ) {
+        (InputValue::Field(field), _) => {
+            simplify_field(*field).into_iter().map(InputValue::Field).collect()
+        }
+        (InputValue::String(string), _) => {
+            // Replace the first character which isn't an `a`.
+            let Some(index) = string.chars().position(|char| char != 'a') else {
+                return Vec::new();
+            };
+            let simplified =
+                string.chars().enumerate().map(|(i, char)| if i == index { 'a' } else { char });
+            vec![InputValue::String(simplified.collect())]
+        }
+        (InputValue::Vec(elements), AbiType::Array { typ, .. }) => {
+            simplify_elements(elements, |_| typ)
+        }
+        (InputValue::Vec(elements), AbiType::Tuple { fields }) => {
+            simplify_elements(elements, |index| &fields[index])
+        }
+        (InputValue::Struct(values), AbiType::Struct { fields, .. }) => fields
+            .iter()
+            .flat_map(|(name, typ)| {
+                simplifications(&values[name], typ).into_iter().map(|candidate| {
+                    let mut values = values.clone();
+                    values.insert(name.clone(), candidate);
+                    InputValue::Struct(values)
+                })
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn simplify_elements<'a>(
+    elements: &[InputValue],
+    element_type: impl Fn(usize) -> &'a AbiType,
+) -> Vec<InputValue> {
+    elements
+        .iter()
+        .enumerate()
+        .flat_map(|(index, element)| {
+            simplifications(element, element_type(index)).into_iter().map(move |candidate| {
+                let mut elements = elements.to_vec();
+                elements[index] = candidate;
+                InputValue::Vec(elements)
+            })
+        })
+        .collect()
+}
+
+/// Returns smaller values than `field`, trying the simplest ones first.
+fn simplify_field(field: FieldElement) -> Vec<FieldElement> {
+    if field.is_zero() {
+        return Vec::new();
+    }
+    let mut candidates = vec![FieldElement::zero()];
+    if field.is_one() {
+        return candidates;
+    }
+    candidates.push(FieldElement::one());
+    match field.try_into_u128() {
+        Some(value) if value > 3 => candidates.push(FieldElement::from(value / 2)),
+        Some(_) => (),
+        // Values which don't fit in 128 bits are truncated first.
+        None => candidates.push(FieldElement::from(field.to_u128())),
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use acvm::{
+        acir::{
+            circuit::{Circuit, Opcode, PublicInputs},
+            native_types::{Expression, Witness},
+        },
+        FieldElement,
+    };
+    use noirc_abi::{
+        input_parser::InputValue, Abi, AbiParameter, AbiReturnType, AbiType, AbiVisibility, Sign,
+    };
+    use noirc_driver::CompiledProgram;
+    use noirc_errors::debug_info::DebugInfo;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{fuzz_program, random_inputs, DivergenceKind, FuzzingConfig};
+    use crate::ops::stubbed_solver::StubbedSolver;
+
+    /// A program with a field parameter `x`, held in witness 1, whose return value is held in witness 2.
+    fn program(opcodes: Vec<Opcode>) -> CompiledProgram {
+        let abi = Abi {
+            parameters: vec![AbiParameter {
+                name: "x".to_string(),
+                typ: AbiType::Field,
+                visibility: AbiVisibility::Private,
+            }],
+            param_witnesses: BTreeMap::from([("x".to_string(), vec![Witness(1)..Witness(2)])]),
+            return_type: Some(AbiReturnType {
+                abi_type: AbiType::Field,
+                visibility: AbiVisibility::Public,
+            }),
+            return_witnesses: vec![Witness(2)],
+        };
+        let circuit = Circuit {
+            current_witness_index: 2,
+            opcodes,
+            private_parameters: [Witness(1)].into(),
+            return_values: PublicInputs([Witness(2)].into()),
+            ..Circuit::default()
+        };
+        CompiledProgram {
+            noir_version: String::new(),
+            hash: 0,
+            circuit,
+            abi,
+            debug: DebugInfo::default(),
+            file_map: BTreeMap::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Returns `w2 = expr`.
+    fn returns(expr: Expression) -> Opcode {
+        Opcode::AssertZero(&expr - Witness(2))
+    }
+
+    fn x() -> Expression {
+        Witness(1).into()
+    }
+
+    fn x_squared() -> Expression {
+        let mut expr = Expression::default();
+        expr.push_multiplication_term(FieldElement::one(), Witness(1), Witness(1));
+        expr
+    }
+
+    #[test]
+    fn reports_no_divergence_for_equivalent_programs() {
+        let acir_program = program(vec![returns(x())]);
+        let brillig_program = program(vec![returns(x())]);
+
+        let config = FuzzingConfig { runs: 20, ..FuzzingConfig::default() };
+        let report =
+            fuzz_program(&acir_program, &brillig_program, &StubbedSolver, &config).unwrap();
+
+        assert_eq!(report.runs, 20);
+        assert_eq!(report.rejected, 0);
+        assert!(report.divergence.is_none());
+    }
+
+    #[test]
+    fn minimizes_divergent_return_values() {
+        let acir_program = program(vec![returns(x())]);
+        let brillig_program = program(vec![returns(x_squared())]);
+
+        let report = fuzz_program(
+            &acir_program,
+            &brillig_program,
+            &StubbedSolver,
+            &FuzzingConfig::default(),
+        )
+        .unwrap();
+
+        let divergence = report.divergence.expect("x and x^2 differ for x > 1");
+        let InputValue::Field(x) = divergence.inputs["x"] else { panic!("x is a field") };
+        // Neither 0 nor 1, nor half of 2 or 3, are divergent inputs.
+        assert!(x == FieldElement::from(2_u128) || x == FieldElement::from(3_u128));
+        assert_eq!(
+            divergence.kind,
+            DivergenceKind::ReturnValues {
+                acir: Some(InputValue::Field(x)),
+                brillig: Some(InputValue::Field(x * x)),
+            }
+        );
+    }
+
+    #[test]
+    fn reports_unsatisfied_constraints() {
+        // The ACIR lowering constrains `x` to be zero, which the Brillig lowering doesn't.
+        let acir_program = program(vec![Opcode::AssertZero(x()), returns(Expression::zero())]);
+        let brillig_program = program(vec![returns(Expression::zero())]);
+
+        let report = fuzz_program(
+            &acir_program,
+            &brillig_program,
+            &StubbedSolver,
+            &FuzzingConfig::default(),
+        )
+        .unwrap();
+
+        let divergence = report.divergence.expect("x is not always zero");
+        assert_eq!(divergence.inputs["x"], InputValue::Field(FieldElement::one()));
+        assert!(matches!(divergence.kind, DivergenceKind::AcirFailed { .. }));
+    }
+
+    #[test]
+    fn generates_inputs_matching_the_abi() {
+        let typ = AbiType::Struct {
+            path: "foo::Bar".to_string(),
+            fields: vec![
+                ("a".to_string(), AbiType::Integer { sign: Sign::Unsigned, width: 8 }),
+                ("b".to_string(), AbiType::Integer { sign: Sign::Signed, width: 64 }),
+                ("c".to_string(), AbiType::Array { length: 3, typ: Box::new(AbiType::Boolean) }),
+                (
+                    "d".to_string(),
+                    AbiType::Tuple { fields: vec![AbiType::Field, AbiType::String { length: 5 }] },
+                ),
+            ],
+        };
+        let abi = Abi {
+            parameters: vec![AbiParameter {
+                name: "bar".to_string(),
+                typ: typ.clone(),
+                visibility: AbiVisibility::Private,
+            }],
+            param_witnesses: BTreeMap::new(),
+            return_type: None,
+            return_witnesses: Vec::new(),
+        };
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let inputs = random_inputs(&mut rng, &abi);
+            assert!(inputs["bar"].matches_abi(&typ));
+        }
+    }
+}
@@ -1,11 +1,21 @@
 pub use self::compile::{compile_program, compile_workspace};
-pub use self::execute::execute_circuit;
-pub use self::foreign_calls::{DefaultForeignCallExecutor, ForeignCallExecutor};
+pub use self::execute::{
+    execute_circuit, execute_circuit_with_brillig_profiling, execute_circuit_with_stats,
+    BrilligOpcodeCounts, ExecutionStats,
+};
+pub use self::foreign_calls::{
+    read_foreign_call_transcript, DefaultForeignCallExecutor, ForeignCallExecutor,
+    RecordedForeignCall, RecordingForeignCallExecutor, ReplayForeignCallExecutor,
+};
+pub use self::fuzz::{fuzz_program, Divergence, DivergenceKind, FuzzReport, FuzzingConfig};
 pub use self::optimize::{optimize_contract, optimize_program};
-pub use self::test::{run_test, TestStatus};
+pub use self::test::{run_test, TestReport, TestStatus};
 
 mod compile;
 mod execute;
 mod foreign_calls;
+mod fuzz;
 mod optimize;
+#[cfg(test)]
+mod stubbed_solver;
 mod test;
@@ -1,13 +1,14 @@
-use acvm::ExpressionWidth;
+use acvm::{compiler::OptimizationLevel, ExpressionWidth};
 use iter_extended::vecmap;
 use noirc_driver::{CompiledContract, CompiledProgram};
 
 pub fn optimize_program(
     mut program: CompiledProgram,
     expression_width: ExpressionWidth,
+    optimization_level: OptimizationLevel,
 ) -> CompiledProgram {
     let (optimized_circuit, location_map) =
-        acvm::compiler::compile(program.circuit, expression_width);
+        acvm::compiler::compile(program.circuit, expression_width, optimization_level);
 
     program.circuit = optimized_circuit;
     program.debug.update_acir(location_map);
@@ -17,10 +18,11 @@ pub fn optimize_program(
 pub fn optimize_contract(
     contract: CompiledContract,
     expression_width: ExpressionWidth,
+    optimization_level: OptimizationLevel,
 ) -> CompiledContract {
     let functions = vecmap(contract.functions, |mut func| {
         let (optimized_bytecode, location_map) =
-            acvm::compiler::compile(func.bytecode, expression_width);
+            acvm::compiler::compile(func.bytecode, expression_width, optimization_level);
         func.bytecode = optimized_bytecode;
         func.debug.update_acir(location_map);
         func
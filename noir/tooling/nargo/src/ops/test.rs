@@ -1,12 +1,22 @@
-use acvm::{acir::native_types::WitnessMap, BlackBoxFunctionSolver};
+use std::time::{Duration, Instant};
+
+use acvm::{
+    acir::native_types::WitnessMap,
+    brillig_vm::{ExceededLimit, ExecutionLimits},
+    pwg::OpcodeResolutionError,
+    BlackBoxFunctionSolver,
+};
 use noirc_driver::{compile_no_check, CompileOptions};
 use noirc_errors::{debug_info::DebugInfo, FileDiagnostic};
 use noirc_evaluator::errors::RuntimeError;
 use noirc_frontend::hir::{def_map::TestFunction, Context};
 
-use crate::{errors::try_to_diagnose_runtime_error, NargoError};
+use crate::{
+    errors::{try_to_diagnose_runtime_error, ExecutionError},
+    NargoError,
+};
 
-use super::{execute_circuit, DefaultForeignCallExecutor};
+use super::{execute_circuit_with_stats, ExecutionStats, ForeignCallExecutor};
 
 pub enum TestStatus {
     Pass,
@@ -14,29 +24,46 @@ pub enum TestStatus {
     CompileError(FileDiagnostic),
 }
 
-pub fn run_test<B: BlackBoxFunctionSolver>(
+/// The outcome of running a single test function.
+pub struct TestReport {
+    pub status: TestStatus,
+    /// Time taken to compile and execute the test.
+    pub duration: Duration,
+    /// Statistics from executing the test's circuit.
+    /// This is `None` if the test did not compile.
+    pub execution_stats: Option<ExecutionStats>,
+}
+
+/// Runs a test function, halting any unconstrained code it runs which exceeds `brillig_limits`.
+pub fn run_test<B: BlackBoxFunctionSolver, F: ForeignCallExecutor>(
     blackbox_solver: &B,
     context: &Context,
     test_function: TestFunction,
-    show_output: bool,
-    foreign_call_resolver_url: Option<&str>,
+    foreign_call_executor: &mut F,
     config: &CompileOptions,
-) -> TestStatus {
+    brillig_limits: ExecutionLimits,
+) -> TestReport {
+    let start = Instant::now();
     let program = compile_no_check(context, config, test_function.get_id(), None, false);
-    match program {
+    let (status, execution_stats) = match program {
         Ok(program) => {
             // Run the backend to ensure the PWG evaluates functions like std::hash::pedersen,
             // otherwise constraints involving these expressions will not error.
-            let circuit_execution = execute_circuit(
+            let (circuit_execution, stats) = execute_circuit_with_stats(
                 &program.circuit,
                 WitnessMap::new(),
                 blackbox_solver,
-                &mut DefaultForeignCallExecutor::new(show_output, foreign_call_resolver_url),
+                foreign_call_executor,
+                brillig_limits,
             );
-            test_status_program_compile_pass(test_function, program.debug, circuit_execution)
+            let status =
+                test_status_program_compile_pass(test_function, program.debug, circuit_execution);
+            (status, Some(stats))
         }
-        Err(err) => test_status_program_compile_fail(err, test_function),
-    }
+        Err(err) => (test_status_program_compile_fail(err, test_function), None),
+    };
+
+    TestReport { status, duration: start.elapsed(), execution_stats }
 }
 
 /// Test function failed to compile
@@ -86,9 +113,22 @@ fn test_status_program_compile_pass(
     };
 
     // If we reach here, then the circuit execution failed.
-    //
-    // Check if the function should have passed
     let diagnostic = try_to_diagnose_runtime_error(&circuit_execution_err, &debug);
+
+    // A test which was halted for exceeding its limits never finished, so it fails even if it
+    // should have failed.
+    if let NargoError::ExecutionError(ExecutionError::SolvingError(
+        OpcodeResolutionError::BrilligLimitExceeded { limit, .. },
+    )) = &circuit_execution_err
+    {
+        let message = match limit {
+            ExceededLimit::Deadline => "error: Test timed out".to_string(),
+            limit => format!("error: Test exceeded the {limit}"),
+        };
+        return TestStatus::Fail { message, error_diagnostic: diagnostic };
+    }
+
+    // Check if the function should have passed
     let test_should_have_passed = !test_function.should_fail();
     if test_should_have_passed {
         return TestStatus::Fail {
@@ -135,3 +175,65 @@ fn check_expected_failure_message(
         error_diagnostic,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use acvm::brillig_vm::ExecutionLimits;
+    use noirc_driver::{check_crate, file_manager_with_stdlib, prepare_crate, CompileOptions};
+    use noirc_frontend::hir::{Context, FunctionNameMatch};
+
+    use super::{run_test, TestStatus};
+    use crate::ops::{stubbed_solver::StubbedSolver, DefaultForeignCallExecutor};
+
+    const SOURCE: &str = "
+        #[test(should_fail)]
+        unconstrained fn counts_too_far() {
+            let mut count = 0;
+            for _ in 0..1000 {
+                count += 1;
+            }
+            assert(count == 0);
+        }
+    ";
+
+    #[test]
+    fn tests_exceeding_their_limits_fail_even_if_they_should_fail() {
+        let root = Path::new("/");
+        let main_path = root.join("main.nr");
+        let mut file_manager = file_manager_with_stdlib(root);
+        file_manager.add_file_with_source(&main_path, SOURCE.to_string());
+
+        let mut context = Context::new(file_manager);
+        let crate_id = prepare_crate(&mut context, &main_path);
+        check_crate(&mut context, crate_id, false, false).expect("crate should type check");
+
+        let run = |limits| {
+            let (_, test_function) = context
+                .get_all_test_functions_in_crate_matching(&crate_id, FunctionNameMatch::Anything)
+                .pop()
+                .expect("crate should have a test function");
+            run_test(
+                &StubbedSolver,
+                &context,
+                test_function,
+                &mut DefaultForeignCallExecutor::new(false, None),
+                &CompileOptions::default(),
+                limits,
+            )
+            .status
+        };
+
+        // Without limits the assertion fails, as the test expects
+        assert!(matches!(run(ExecutionLimits::default()), TestStatus::Pass));
+
+        let limits = ExecutionLimits { max_steps: Some(100), ..ExecutionLimits::default() };
+        match run(limits) {
+            TestStatus::Fail { message, .. } => {
+                assert_eq!(message, "error: Test exceeded the step limit of 100 opcodes");
+            }
+            _ => panic!("test exceeding its step limit should fail"),
+        }
+    }
+}
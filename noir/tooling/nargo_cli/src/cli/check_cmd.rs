@@ -50,6 +50,7 @@ pub(crate) fn run(
         &toml_path,
         selection,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        config.lock_mode(),
     )?;
 
     let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
@@ -6,6 +6,7 @@ use iter_extended::vecmap;
 use nargo::artifacts::contract::{ContractArtifact, ContractFunctionArtifact};
 use nargo::artifacts::debug::DebugArtifact;
 use nargo::artifacts::program::ProgramArtifact;
+use nargo::cache::{compilation_cache_key, read_cached_compilation, save_cached_compilation};
 use nargo::errors::CompileError;
 use nargo::insert_all_files_for_workspace_into_file_manager;
 use nargo::package::Package;
@@ -59,6 +60,7 @@ pub(crate) fn run(
         &toml_path,
         selection,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_owned()),
+        config.lock_mode(),
     )?;
     let circuit_dir = workspace.target_directory_path();
 
@@ -106,7 +108,9 @@ pub(super) fn compile_workspace(
         .collect();
     let contract_results: Vec<CompilationResult<CompiledContract>> = contract_packages
         .par_iter()
-        .map(|package| compile_contract(file_manager, package, compile_options, expression_width))
+        .map(|package| {
+            compile_contract(file_manager, workspace, package, compile_options, expression_width)
+        })
         .collect();
 
     // Report any warnings/errors which were encountered during compilation.
@@ -142,13 +146,59 @@ pub(crate) fn compile_bin_package(
     package: &Package,
     compile_options: &CompileOptions,
     expression_width: ExpressionWidth,
+) -> Result<CompiledProgram, CliError> {
+    compile_and_report_bin_package(
+        file_manager,
+        workspace,
+        package,
+        compile_options,
+        expression_width,
+        compile_program,
+    )
+}
+
+/// Compiles a binary package as [`compile_bin_package`] does, but without writing its artifacts
+/// to the target directory.
+///
+/// This is used for compilations with options that differ from those of `nargo compile`, which
+/// must not overwrite the artifacts that other commands read.
+pub(crate) fn compile_bin_package_without_saving(
+    file_manager: &FileManager,
+    workspace: &Workspace,
+    package: &Package,
+    compile_options: &CompileOptions,
+    expression_width: ExpressionWidth,
+) -> Result<CompiledProgram, CliError> {
+    compile_and_report_bin_package(
+        file_manager,
+        workspace,
+        package,
+        compile_options,
+        expression_width,
+        compile_program_without_saving,
+    )
+}
+
+fn compile_and_report_bin_package(
+    file_manager: &FileManager,
+    workspace: &Workspace,
+    package: &Package,
+    compile_options: &CompileOptions,
+    expression_width: ExpressionWidth,
+    compile: fn(
+        &FileManager,
+        &Workspace,
+        &Package,
+        &CompileOptions,
+        ExpressionWidth,
+    ) -> CompilationResult<CompiledProgram>,
 ) -> Result<CompiledProgram, CliError> {
     if package.is_library() {
         return Err(CompileError::LibraryCrate(package.name.clone()).into());
     }
 
     let compilation_result =
-        compile_program(file_manager, workspace, package, compile_options, expression_width);
+        compile(file_manager, workspace, package, compile_options, expression_width);
 
     let program = report_errors(
         compilation_result,
@@ -166,6 +216,60 @@ fn compile_program(
     package: &Package,
     compile_options: &CompileOptions,
     expression_width: ExpressionWidth,
+) -> CompilationResult<CompiledProgram> {
+    let (optimized_program, warnings) = compile_program_without_saving(
+        file_manager,
+        workspace,
+        package,
+        compile_options,
+        expression_width,
+    )?;
+
+    let only_acir = compile_options.only_acir;
+    save_program(optimized_program.clone(), package, &workspace.target_directory_path(), only_acir);
+
+    Ok((optimized_program, warnings))
+}
+
+fn compile_program_without_saving(
+    file_manager: &FileManager,
+    workspace: &Workspace,
+    package: &Package,
+    compile_options: &CompileOptions,
+    expression_width: ExpressionWidth,
+) -> CompilationResult<CompiledProgram> {
+    let cache_dir = workspace.cache_directory_path();
+    let cache_key = compilation_cache_key(file_manager, package, compile_options, expression_width);
+    let cached_compilation =
+        cache_key.as_ref().and_then(|key| read_cached_compilation(&cache_dir, package, key));
+
+    let (optimized_program, warnings) = match cached_compilation {
+        Some(program_and_warnings) => program_and_warnings,
+        None => {
+            let (program, warnings) =
+                compile_program_from_source(file_manager, workspace, package, compile_options)?;
+
+            // Apply backend specific optimizations.
+            let optimized_program = nargo::ops::optimize_program(
+                program,
+                expression_width,
+                compile_options.optimization_level,
+            );
+            if let Some(key) = &cache_key {
+                save_cached_compilation(&cache_dir, package, key, &optimized_program, &warnings);
+            }
+            (optimized_program, warnings)
+        }
+    };
+
+    Ok((optimized_program, warnings))
+}
+
+fn compile_program_from_source(
+    file_manager: &FileManager,
+    workspace: &Workspace,
+    package: &Package,
+    compile_options: &CompileOptions,
 ) -> CompilationResult<CompiledProgram> {
     let (mut context, crate_id) = prepare_package(file_manager, package);
 
@@ -191,33 +295,30 @@ fn compile_program(
 
     let force_recompile =
         cached_program.as_ref().map_or(false, |p| p.noir_version != NOIR_ARTIFACT_VERSION_STRING);
-    let (program, warnings) = match noirc_driver::compile_main(
+    noirc_driver::compile_main(
         &mut context,
         crate_id,
         compile_options,
         cached_program,
         force_recompile,
-    ) {
-        Ok(program_and_warnings) => program_and_warnings,
-        Err(errors) => {
-            return Err(errors);
-        }
-    };
-
-    // Apply backend specific optimizations.
-    let optimized_program = nargo::ops::optimize_program(program, expression_width);
-    let only_acir = compile_options.only_acir;
-    save_program(optimized_program.clone(), package, &workspace.target_directory_path(), only_acir);
-
-    Ok((optimized_program, warnings))
+    )
 }
 
 fn compile_contract(
     file_manager: &FileManager,
+    workspace: &Workspace,
     package: &Package,
     compile_options: &CompileOptions,
     expression_width: ExpressionWidth,
 ) -> CompilationResult<CompiledContract> {
+    let cache_dir = workspace.cache_directory_path();
+    let cache_key = compilation_cache_key(file_manager, package, compile_options, expression_width);
+    if let Some(contract_and_warnings) =
+        cache_key.as_ref().and_then(|key| read_cached_compilation(&cache_dir, package, key))
+    {
+        return Ok(contract_and_warnings);
+    }
+
     let (mut context, crate_id) = prepare_package(file_manager, package);
     let (contract, warnings) =
         match noirc_driver::compile_contract(&mut context, crate_id, compile_options) {
@@ -227,7 +328,14 @@ fn compile_contract(
             }
         };
 
-    let optimized_contract = nargo::ops::optimize_contract(contract, expression_width);
+    let optimized_contract = nargo::ops::optimize_contract(
+        contract,
+        expression_width,
+        compile_options.optimization_level,
+    );
+    if let Some(key) = &cache_key {
+        save_cached_compilation(&cache_dir, package, key, &optimized_contract, &warnings);
+    }
 
     Ok((optimized_contract, warnings))
 }
@@ -4,7 +4,7 @@ use clap::Args;
 use nargo::constants::PROVER_INPUT_FILE;
 use nargo::insert_all_files_for_workspace_into_file_manager;
 use nargo::workspace::Workspace;
-use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, LockMode, PackageSelection};
 use noirc_abi::input_parser::Format;
 use noirc_driver::{
     file_manager_with_stdlib, CompileOptions, CompiledProgram, NOIR_ARTIFACT_VERSION_STRING,
@@ -25,7 +25,7 @@ use super::compile_cmd::compile_bin_package;
 use super::fs::inputs::read_inputs_from_file;
 use crate::errors::CliError;
 
-use super::NargoConfig;
+use super::{BlackBoxSolver, NargoConfig};
 
 #[derive(Debug, Clone, Args)]
 pub(crate) struct DapCommand;
@@ -43,6 +43,7 @@ fn find_workspace(project_folder: &str, package: Option<&str>) -> Option<Workspa
         &toml_path,
         selection,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        LockMode::Update,
     ) {
         Ok(workspace) => Some(workspace),
         Err(err) => {
@@ -75,7 +76,7 @@ fn load_and_compile_project(
         &workspace_file_manager,
         &workspace,
         package,
-        &CompileOptions::default(),
+        &CompileOptions { instrument_debug: true, ..CompileOptions::default() },
         expression_width,
     )
     .map_err(|_| LoadError("Failed to compile project"))?;
@@ -107,6 +108,7 @@ fn loop_uninitialized_dap<R: Read, W: Write>(
                     supports_disassemble_request: Some(true),
                     supports_instruction_breakpoints: Some(true),
                     supports_stepping_granularity: Some(true),
+                    supports_step_back: Some(true),
                     ..Default::default()
                 }));
                 server.respond(rsp)?;
@@ -117,7 +119,8 @@ fn loop_uninitialized_dap<R: Read, W: Write>(
                     server.respond(req.error("Missing launch arguments"))?;
                     continue;
                 };
-                let Some(Value::String(ref project_folder)) = additional_data.get("projectFolder") else {
+                let Some(Value::String(ref project_folder)) = additional_data.get("projectFolder")
+                else {
                     server.respond(req.error("Missing project folder argument"))?;
                     continue;
                 };
@@ -137,7 +140,7 @@ fn loop_uninitialized_dap<R: Read, W: Write>(
                     Ok((compiled_program, initial_witness)) => {
                         server.respond(req.ack()?)?;
 
-                        let blackbox_solver = bn254_blackbox_solver::Bn254BlackBoxSolver::new();
+                        let blackbox_solver = BlackBoxSolver::new();
 
                         noir_debugger::run_dap_loop(
                             server,
@@ -1,14 +1,15 @@
 use std::path::PathBuf;
 
 use acvm::acir::native_types::WitnessMap;
-use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use clap::Args;
 
 use nargo::artifacts::debug::DebugArtifact;
 use nargo::constants::PROVER_INPUT_FILE;
 use nargo::insert_all_files_for_workspace_into_file_manager;
+use nargo::ops::DefaultForeignCallExecutor;
 use nargo::package::Package;
 use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use noir_debugger::ForeignCallExecutorFactory;
 use noirc_abi::input_parser::{Format, InputValue};
 use noirc_abi::InputMap;
 use noirc_driver::{
@@ -17,8 +18,9 @@ use noirc_driver::{
 use noirc_frontend::graph::CrateName;
 
 use super::compile_cmd::compile_bin_package;
+use super::foreign_calls::ForeignCallTranscriptArgs;
 use super::fs::{inputs::read_inputs_from_file, witness::save_witness_to_dir};
-use super::NargoConfig;
+use super::{BlackBoxSolver, NargoConfig};
 use crate::backends::Backend;
 use crate::errors::CliError;
 
@@ -38,6 +40,13 @@ pub(crate) struct DebugCommand {
 
     #[clap(flatten)]
     compile_options: CompileOptions,
+
+    /// JSON RPC url to solve oracle calls: `http://...`, `exec:<command> [args...]` or `unix:<socket path>`
+    #[clap(long)]
+    oracle_resolver: Option<String>,
+
+    #[clap(flatten)]
+    foreign_call_transcript: ForeignCallTranscriptArgs,
 }
 
 pub(crate) fn run(
@@ -51,6 +60,7 @@ pub(crate) fn run(
         &toml_path,
         selection,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        config.lock_mode(),
     )?;
     let target_dir = &workspace.target_directory_path();
     let expression_width = backend.get_backend_info()?;
@@ -65,15 +75,33 @@ pub(crate) fn run(
         return Ok(());
     };
 
+    let compile_options = CompileOptions { instrument_debug: true, ..args.compile_options };
     let compiled_program = compile_bin_package(
         &workspace_file_manager,
         &workspace,
         package,
-        &args.compile_options,
+        &compile_options,
         expression_width,
     )?;
 
-    run_async(package, compiled_program, &args.prover_name, &args.witness_name, target_dir)
+    let oracle_resolver = args.oracle_resolver;
+    let foreign_call_transcript = args.foreign_call_transcript;
+    let transcript_name = package.name.to_string();
+    let new_foreign_call_executor = move || {
+        foreign_call_transcript.wrap_executor(
+            DefaultForeignCallExecutor::new(true, oracle_resolver.as_deref()),
+            &transcript_name,
+        )
+    };
+
+    run_async(
+        package,
+        compiled_program,
+        &args.prover_name,
+        &args.witness_name,
+        target_dir,
+        &new_foreign_call_executor,
+    )
 }
 
 fn run_async(
@@ -82,6 +110,7 @@ fn run_async(
     prover_name: &str,
     witness_name: &Option<String>,
     target_dir: &PathBuf,
+    new_foreign_call_executor: &ForeignCallExecutorFactory,
 ) -> Result<(), CliError> {
     use tokio::runtime::Builder;
     let runtime = Builder::new_current_thread().enable_all().build().unwrap();
@@ -89,7 +118,7 @@ fn run_async(
     runtime.block_on(async {
         println!("[{}] Starting debugger", package.name);
         let (return_value, solved_witness) =
-            debug_program_and_decode(program, package, prover_name)?;
+            debug_program_and_decode(program, package, prover_name, new_foreign_call_executor)?;
 
         if let Some(solved_witness) = solved_witness {
             println!("[{}] Circuit witness successfully solved", package.name);
@@ -115,11 +144,12 @@ fn debug_program_and_decode(
     program: CompiledProgram,
     package: &Package,
     prover_name: &str,
+    new_foreign_call_executor: &ForeignCallExecutorFactory,
 ) -> Result<(Option<InputValue>, Option<WitnessMap>), CliError> {
     // Parse the initial witness values from Prover.toml
     let (inputs_map, _) =
         read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &program.abi)?;
-    let solved_witness = debug_program(&program, &inputs_map)?;
+    let solved_witness = debug_program(&program, &inputs_map, new_foreign_call_executor)?;
     let public_abi = program.abi.public_abi();
 
     match solved_witness {
@@ -134,8 +164,9 @@ fn debug_program_and_decode(
 pub(crate) fn debug_program(
     compiled_program: &CompiledProgram,
     inputs_map: &InputMap,
+    new_foreign_call_executor: &ForeignCallExecutorFactory,
 ) -> Result<Option<WitnessMap>, CliError> {
-    let blackbox_solver = Bn254BlackBoxSolver::new();
+    let blackbox_solver = BlackBoxSolver::new();
 
     let initial_witness = compiled_program.abi.encode(inputs_map, None)?;
 
@@ -150,6 +181,7 @@ pub(crate) fn debug_program(
         &compiled_program.circuit,
         debug_artifact,
         initial_witness,
+        new_foreign_call_executor,
     )
     .map_err(CliError::from)
 }
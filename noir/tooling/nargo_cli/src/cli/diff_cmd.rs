@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use nargo::artifacts::{
+    contract::ContractArtifact,
+    diff::{
+        contract_opcode_counts, diff_opcode_counts, program_opcode_counts, CountDelta, OpcodeCounts,
+    },
+    program::ProgramArtifact,
+};
+
+use super::fs::program::read_debug_artifact_from_file;
+use super::NargoConfig;
+use crate::errors::{CliError, FilesystemError};
+
+/// Compares the ACIR opcodes of two compiled artifacts
+///
+/// Changes in opcode counts are reported for each function, broken down by opcode kind
+/// and, where debug artifacts are available, by the source line responsible for them.
+#[derive(Debug, Clone, Args)]
+pub(crate) struct DiffCommand {
+    /// The path to the old program or contract artifact
+    old: PathBuf,
+
+    /// The path to the new program or contract artifact
+    new: PathBuf,
+
+    /// The path to the debug artifact of the old artifact, defaults to `debug_<name>.json` next to it
+    #[clap(long)]
+    old_debug: Option<PathBuf>,
+
+    /// The path to the debug artifact of the new artifact, defaults to `debug_<name>.json` next to it
+    #[clap(long)]
+    new_debug: Option<PathBuf>,
+}
+
+pub(crate) fn run(args: DiffCommand, _config: NargoConfig) -> Result<(), CliError> {
+    let old = read_opcode_counts(&args.old, args.old_debug.as_deref())?;
+    let new = read_opcode_counts(&args.new, args.new_debug.as_deref())?;
+
+    let diffs = diff_opcode_counts(old, new);
+    if diffs.iter().all(|diff| diff.total().delta() == 0 && diff.kind_deltas().is_empty()) {
+        println!("No changes in ACIR opcodes");
+        return Ok(());
+    }
+
+    for diff in diffs {
+        let total = diff.total();
+        let status = match (&diff.old, &diff.new) {
+            (None, _) => " (added)",
+            (_, None) => " (removed)",
+            _ => "",
+        };
+        println!(
+            "{}{status}: {} -> {} ACIR opcodes ({})",
+            diff.name,
+            total.old,
+            total.new,
+            format_delta(&total)
+        );
+        print_deltas("by opcode", &diff.kind_deltas());
+        print_deltas("by source location", &diff.location_deltas());
+    }
+
+    Ok(())
+}
+
+fn print_deltas(title: &str, deltas: &[CountDelta]) {
+    if deltas.is_empty() {
+        return;
+    }
+    println!("  {title}:");
+    for delta in deltas {
+        println!("    {}: {} -> {} ({})", delta.key, delta.old, delta.new, format_delta(delta));
+    }
+}
+
+fn format_delta(delta: &CountDelta) -> String {
+    format!("{:+}", delta.delta())
+}
+
+/// Reads the program or contract artifact at `artifact_path` and counts the opcodes of its functions.
+///
+/// Opcodes are attributed to source lines using the debug artifact at `debug_path`,
+/// or the one saved alongside the artifact if it exists.
+fn read_opcode_counts(
+    artifact_path: &Path,
+    debug_path: Option<&Path>,
+) -> Result<BTreeMap<String, OpcodeCounts>, CliError> {
+    let debug_artifact = match debug_path {
+        Some(debug_path) => Some(read_debug_artifact_from_file(debug_path)?),
+        None => match default_debug_artifact_path(artifact_path) {
+            Some(debug_path) if debug_path.is_file() => {
+                Some(read_debug_artifact_from_file(debug_path)?)
+            }
+            _ => None,
+        },
+    };
+
+    let bytes = std::fs::read(artifact_path)
+        .map_err(|_| FilesystemError::PathNotValid(artifact_path.to_path_buf()))?;
+    if let Ok(program) = serde_json::from_slice::<ProgramArtifact>(&bytes) {
+        return Ok(program_opcode_counts(&program, debug_artifact.as_ref()));
+    }
+    let contract: ContractArtifact = serde_json::from_slice(&bytes)
+        .map_err(|err| FilesystemError::ProgramSerializationError(err.to_string()))?;
+    Ok(contract_opcode_counts(&contract, debug_artifact.as_ref()))
+}
+
+/// Returns the path at which `nargo compile` saves the debug artifact for the artifact at `artifact_path`.
+fn default_debug_artifact_path(artifact_path: &Path) -> Option<PathBuf> {
+    let name = artifact_path.file_stem()?.to_str()?;
+    Some(artifact_path.with_file_name(format!("debug_{name}.json")))
+}
@@ -1,12 +1,11 @@
 use acvm::acir::native_types::WitnessMap;
-use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use clap::Args;
 
 use nargo::artifacts::debug::DebugArtifact;
 use nargo::constants::PROVER_INPUT_FILE;
 use nargo::errors::try_to_diagnose_runtime_error;
 use nargo::insert_all_files_for_workspace_into_file_manager;
-use nargo::ops::DefaultForeignCallExecutor;
+use nargo::ops::{DefaultForeignCallExecutor, ForeignCallExecutor};
 use nargo::package::Package;
 use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
 use noirc_abi::input_parser::{Format, InputValue};
@@ -17,8 +16,9 @@ use noirc_driver::{
 use noirc_frontend::graph::CrateName;
 
 use super::compile_cmd::compile_bin_package;
+use super::foreign_calls::ForeignCallTranscriptArgs;
 use super::fs::{inputs::read_inputs_from_file, witness::save_witness_to_dir};
-use super::NargoConfig;
+use super::{BlackBoxSolver, NargoConfig};
 use crate::backends::Backend;
 use crate::errors::CliError;
 
@@ -43,9 +43,12 @@ pub(crate) struct ExecuteCommand {
     #[clap(flatten)]
     compile_options: CompileOptions,
 
-    /// JSON RPC url to solve oracle calls
+    /// JSON RPC url to solve oracle calls: `http://...`, `exec:<command> [args...]` or `unix:<socket path>`
     #[clap(long)]
     oracle_resolver: Option<String>,
+
+    #[clap(flatten)]
+    foreign_call_transcript: ForeignCallTranscriptArgs,
 }
 
 pub(crate) fn run(
@@ -61,6 +64,7 @@ pub(crate) fn run(
         &toml_path,
         selection,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        config.lock_mode(),
     )?;
     let target_dir = &workspace.target_directory_path();
 
@@ -77,11 +81,15 @@ pub(crate) fn run(
             expression_width,
         )?;
 
+        let mut foreign_call_executor = args.foreign_call_transcript.wrap_executor(
+            DefaultForeignCallExecutor::new(true, args.oracle_resolver.as_deref()),
+            &package.name.to_string(),
+        )?;
         let (return_value, solved_witness) = execute_program_and_decode(
             compiled_program,
             package,
             &args.prover_name,
-            args.oracle_resolver.as_deref(),
+            &mut foreign_call_executor,
         )?;
 
         println!("[{}] Circuit witness successfully solved", package.name);
@@ -97,28 +105,28 @@ pub(crate) fn run(
     Ok(())
 }
 
-fn execute_program_and_decode(
+fn execute_program_and_decode<F: ForeignCallExecutor>(
     program: CompiledProgram,
     package: &Package,
     prover_name: &str,
-    foreign_call_resolver_url: Option<&str>,
+    foreign_call_executor: &mut F,
 ) -> Result<(Option<InputValue>, WitnessMap), CliError> {
     // Parse the initial witness values from Prover.toml
     let (inputs_map, _) =
         read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &program.abi)?;
-    let solved_witness = execute_program(&program, &inputs_map, foreign_call_resolver_url)?;
+    let solved_witness = execute_program(&program, &inputs_map, foreign_call_executor)?;
     let public_abi = program.abi.public_abi();
     let (_, return_value) = public_abi.decode(&solved_witness)?;
 
     Ok((return_value, solved_witness))
 }
 
-pub(crate) fn execute_program(
+pub(crate) fn execute_program<F: ForeignCallExecutor>(
     compiled_program: &CompiledProgram,
     inputs_map: &InputMap,
-    foreign_call_resolver_url: Option<&str>,
+    foreign_call_executor: &mut F,
 ) -> Result<WitnessMap, CliError> {
-    let blackbox_solver = Bn254BlackBoxSolver::new();
+    let blackbox_solver = BlackBoxSolver::new();
 
     let initial_witness = compiled_program.abi.encode(inputs_map, None)?;
 
@@ -126,7 +134,7 @@ pub(crate) fn execute_program(
         &compiled_program.circuit,
         initial_witness,
         &blackbox_solver,
-        &mut DefaultForeignCallExecutor::new(true, foreign_call_resolver_url),
+        foreign_call_executor,
     );
     match solved_witness_err {
         Ok(solved_witness) => Ok(solved_witness),
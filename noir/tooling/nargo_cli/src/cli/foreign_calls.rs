@@ -0,0 +1,48 @@
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use nargo::{
+    ops::{
+        read_foreign_call_transcript, ForeignCallExecutor, RecordingForeignCallExecutor,
+        ReplayForeignCallExecutor,
+    },
+    NargoError,
+};
+
+/// Options for recording foreign calls to JSON transcripts or replaying them from those transcripts
+#[derive(Debug, Clone, Args)]
+pub(crate) struct ForeignCallTranscriptArgs {
+    /// Record every foreign call and its result to a JSON transcript in this directory
+    #[clap(long, conflicts_with = "replay_foreign_calls")]
+    record_foreign_calls: Option<PathBuf>,
+
+    /// Resolve foreign calls with the transcripts written to this directory by `--record-foreign-calls`,
+    /// failing if execution diverges from them
+    #[clap(long)]
+    replay_foreign_calls: Option<PathBuf>,
+}
+
+impl ForeignCallTranscriptArgs {
+    /// Wraps `executor` so that its foreign calls are recorded to, or replayed from,
+    /// the transcript named `transcript_name`.
+    pub(crate) fn wrap_executor<E: ForeignCallExecutor + 'static>(
+        &self,
+        executor: E,
+        transcript_name: &str,
+    ) -> Result<Box<dyn ForeignCallExecutor>, NargoError> {
+        if let Some(transcript_dir) = &self.record_foreign_calls {
+            let transcript_path = transcript_path(transcript_dir, transcript_name);
+            Ok(Box::new(RecordingForeignCallExecutor::new(executor, &transcript_path)?))
+        } else if let Some(transcript_dir) = &self.replay_foreign_calls {
+            let transcript_path = transcript_path(transcript_dir, transcript_name);
+            let recorded_calls = read_foreign_call_transcript(&transcript_path)?;
+            Ok(Box::new(ReplayForeignCallExecutor::new(executor, recorded_calls)))
+        } else {
+            Ok(Box::new(executor))
+        }
+    }
+}
+
+fn transcript_path(transcript_dir: &Path, transcript_name: &str) -> PathBuf {
+    transcript_dir.join(format!("{transcript_name}.json"))
+}
@@ -0,0 +1,135 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::Args;
+use nargo::insert_all_files_for_workspace_into_file_manager;
+use nargo::ops::{fuzz_program, Divergence, DivergenceKind, FuzzingConfig};
+use nargo::package::Package;
+use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use noirc_abi::input_parser::Format;
+use noirc_abi::Abi;
+use noirc_driver::{file_manager_with_stdlib, CompileOptions, NOIR_ARTIFACT_VERSION_STRING};
+use noirc_frontend::graph::CrateName;
+
+use super::compile_cmd::compile_bin_package_without_saving;
+use super::{BlackBoxSolver, NargoConfig};
+use crate::backends::Backend;
+use crate::errors::{CliError, FilesystemError};
+
+/// Checks that the constrained and unconstrained compilations of a program agree on random inputs
+///
+/// Each program is compiled both to ACIR and, as if `main` were unconstrained, to Brillig. Both are
+/// executed with random inputs generated from the program's ABI, and any input on which their return
+/// values differ or which only one of them rejects is reported after being minimized.
+#[derive(Debug, Clone, Args)]
+pub(crate) struct FuzzCommand {
+    /// The name of the package to fuzz
+    #[clap(long, conflicts_with = "workspace")]
+    package: Option<CrateName>,
+
+    /// Fuzz all packages in the workspace
+    #[clap(long, conflicts_with = "package")]
+    workspace: bool,
+
+    /// The number of random inputs to execute each program with
+    #[clap(long, default_value_t = 100)]
+    runs: usize,
+
+    /// The seed from which inputs are generated, defaults to a random seed
+    #[clap(long)]
+    seed: Option<u64>,
+
+    #[clap(flatten)]
+    compile_options: CompileOptions,
+}
+
+pub(crate) fn run(
+    backend: &Backend,
+    args: FuzzCommand,
+    config: NargoConfig,
+) -> Result<(), CliError> {
+    let toml_path = get_package_manifest(&config.program_dir)?;
+    let default_selection =
+        if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
+    let selection = args.package.map_or(default_selection, PackageSelection::Selected);
+    let workspace = resolve_workspace_from_toml(
+        &toml_path,
+        selection,
+        Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        config.lock_mode(),
+    )?;
+
+    let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
+    insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
+
+    let seed = args.seed.unwrap_or_else(|| {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos() as u64
+    });
+    let fuzzing_config = FuzzingConfig { runs: args.runs, seed, ..FuzzingConfig::default() };
+    let brillig_compile_options =
+        CompileOptions { force_brillig: true, ..args.compile_options.clone() };
+
+    let expression_width = backend.get_backend_info_or_default();
+    let mut divergent_packages = Vec::new();
+    for package in workspace.into_iter().filter(|package| package.is_binary()) {
+        let acir_program = compile_bin_package_without_saving(
+            &workspace_file_manager,
+            &workspace,
+            package,
+            &args.compile_options,
+            expression_width,
+        )?;
+        let brillig_program = compile_bin_package_without_saving(
+            &workspace_file_manager,
+            &workspace,
+            package,
+            &brillig_compile_options,
+            expression_width,
+        )?;
+
+        let report =
+            fuzz_program(&acir_program, &brillig_program, &BlackBoxSolver::new(), &fuzzing_config)?;
+        match report.divergence {
+            None => println!(
+                "[{}] ACIR and Brillig agreed on {} inputs ({} rejected by both)",
+                package.name, report.runs, report.rejected
+            ),
+            Some(divergence) => {
+                report_divergence(package, &acir_program.abi, &divergence, seed)?;
+                divergent_packages.push(package.name.to_string());
+            }
+        }
+    }
+
+    if divergent_packages.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::Generic(format!(
+            "ACIR and Brillig diverged in packages: {}",
+            divergent_packages.join(", ")
+        )))
+    }
+}
+
+fn report_divergence(
+    package: &Package,
+    abi: &Abi,
+    divergence: &Divergence,
+    seed: u64,
+) -> Result<(), CliError> {
+    match &divergence.kind {
+        DivergenceKind::ReturnValues { acir, brillig } => {
+            println!("[{}] ACIR and Brillig returned different values", package.name);
+            println!("ACIR output: {acir:?}");
+            println!("Brillig output: {brillig:?}");
+        }
+        DivergenceKind::AcirFailed { error } => {
+            println!("[{}] ACIR failed on an input accepted by Brillig: {error}", package.name);
+        }
+        DivergenceKind::BrilligFailed { error } => {
+            println!("[{}] Brillig failed on an input accepted by ACIR: {error}", package.name);
+        }
+    }
+    let inputs = Format::Toml.serialize(&divergence.inputs, abi).map_err(FilesystemError::from)?;
+    println!("Minimized inputs (seed {seed}):\n{inputs}");
+    Ok(())
+}
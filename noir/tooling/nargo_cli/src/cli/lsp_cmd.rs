@@ -2,12 +2,11 @@ use async_lsp::{
     concurrency::ConcurrencyLayer, panic::CatchUnwindLayer, server::LifecycleLayer,
     tracing::TracingLayer,
 };
-use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use clap::Args;
 use noir_lsp::NargoLspService;
 use tower::ServiceBuilder;
 
-use super::NargoConfig;
+use super::{BlackBoxSolver, NargoConfig};
 use crate::backends::Backend;
 use crate::errors::CliError;
 
@@ -31,7 +30,7 @@ pub(crate) fn run(
 
     runtime.block_on(async {
         let (server, _) = async_lsp::MainLoop::new_server(|client| {
-            let blackbox_solver = Bn254BlackBoxSolver::new();
+            let blackbox_solver = BlackBoxSolver::new();
             let router = NargoLspService::new(&client, blackbox_solver);
 
             ServiceBuilder::new()
@@ -1,6 +1,6 @@
 use clap::{Args, Parser, Subcommand};
 use const_format::formatcp;
-use nargo_toml::find_package_root;
+use nargo_toml::{find_package_root, LockMode};
 use noirc_driver::NOIR_ARTIFACT_VERSION_STRING;
 use std::path::PathBuf;
 
@@ -8,6 +8,7 @@ use color_eyre::eyre;
 
 use crate::backends::get_active_backend;
 
+mod foreign_calls;
 mod fs;
 
 mod backend_cmd;
@@ -16,17 +17,33 @@ mod codegen_verifier_cmd;
 mod compile_cmd;
 mod dap_cmd;
 mod debug_cmd;
+mod diff_cmd;
 mod execute_cmd;
 mod export_cmd;
 mod fmt_cmd;
+mod fuzz_cmd;
 mod info_cmd;
 mod init_cmd;
 mod lsp_cmd;
 mod new_cmd;
+mod profile_cmd;
 mod prove_cmd;
 mod test_cmd;
+mod vendor_cmd;
 mod verify_cmd;
 
+/// The solver for the black box functions over the field which nargo is compiled for,
+/// as selected by its `bn254` (default) or `bls12_381` feature.
+#[cfg(feature = "bn254")]
+pub(crate) type BlackBoxSolver = bn254_blackbox_solver::Bn254BlackBoxSolver;
+#[cfg(all(feature = "bls12_381", not(feature = "bn254")))]
+pub(crate) type BlackBoxSolver = bls12_381_blackbox_solver::Bls12381BlackBoxSolver;
+
+#[cfg(all(feature = "bn254", feature = "bls12_381"))]
+compile_error!("features \"bn254\" and \"bls12_381\" cannot be used together");
+#[cfg(not(any(feature = "bn254", feature = "bls12_381")))]
+compile_error!("please enable either the \"bn254\" or the \"bls12_381\" feature");
+
 const GIT_HASH: &str = env!("GIT_COMMIT");
 const IS_DIRTY: &str = env!("GIT_DIRTY");
 const NARGO_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -55,6 +72,20 @@ pub(crate) struct NargoConfig {
     // REMINDER: Also change this flag in the LSP test lens if renamed
     #[arg(long, hide = true, global = true, default_value = "./")]
     program_dir: PathBuf,
+
+    /// Require Nargo.lock to be up to date, failing instead of updating it
+    #[arg(long, global = true)]
+    locked: bool,
+}
+
+impl NargoConfig {
+    fn lock_mode(&self) -> LockMode {
+        if self.locked {
+            LockMode::Locked
+        } else {
+            LockMode::Update
+        }
+    }
 }
 
 #[non_exhaustive]
@@ -77,6 +108,10 @@ enum NargoCommand {
     Verify(verify_cmd::VerifyCommand),
     Test(test_cmd::TestCommand),
     Info(info_cmd::InfoCommand),
+    Fuzz(fuzz_cmd::FuzzCommand),
+    Diff(diff_cmd::DiffCommand),
+    Vendor(vendor_cmd::VendorCommand),
+    Profile(profile_cmd::ProfileCommand),
     Lsp(lsp_cmd::LspCommand),
     #[command(hide = true)]
     Dap(dap_cmd::DapCommand),
@@ -98,6 +133,7 @@ pub(crate) fn start_cli() -> eyre::Result<()> {
             | NargoCommand::Lsp(_)
             | NargoCommand::Backend(_)
             | NargoCommand::Dap(_)
+            | NargoCommand::Diff(_)
     ) {
         config.program_dir = find_package_root(&config.program_dir)?;
     }
@@ -117,6 +153,10 @@ pub(crate) fn start_cli() -> eyre::Result<()> {
         NargoCommand::Verify(args) => verify_cmd::run(&backend, args, config),
         NargoCommand::Test(args) => test_cmd::run(&backend, args, config),
         NargoCommand::Info(args) => info_cmd::run(&backend, args, config),
+        NargoCommand::Fuzz(args) => fuzz_cmd::run(&backend, args, config),
+        NargoCommand::Diff(args) => diff_cmd::run(args, config),
+        NargoCommand::Vendor(args) => vendor_cmd::run(args, config),
+        NargoCommand::Profile(args) => profile_cmd::run(&backend, args, config),
         NargoCommand::CodegenVerifier(args) => codegen_verifier_cmd::run(&backend, args, config),
         NargoCommand::Backend(args) => backend_cmd::run(args),
         NargoCommand::Lsp(args) => lsp_cmd::run(&backend, args, config),
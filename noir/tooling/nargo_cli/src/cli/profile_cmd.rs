@@ -0,0 +1,348 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use acvm::acir::circuit::OpcodeLocation;
+use clap::{Args, ValueEnum};
+use inferno::flamegraph;
+use iter_extended::vecmap;
+use nargo::artifacts::debug::DebugArtifact;
+use nargo::constants::PROVER_INPUT_FILE;
+use nargo::errors::try_to_diagnose_runtime_error;
+use nargo::insert_all_files_for_workspace_into_file_manager;
+use nargo::ops::{
+    execute_circuit_with_brillig_profiling, BrilligOpcodeCounts, DefaultForeignCallExecutor,
+};
+use nargo::package::Package;
+use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use noirc_abi::input_parser::Format;
+use noirc_driver::{
+    file_manager_with_stdlib, CompileOptions, CompiledProgram, NOIR_ARTIFACT_VERSION_STRING,
+};
+use noirc_errors::Location;
+use noirc_frontend::graph::CrateName;
+
+use super::compile_cmd::compile_bin_package;
+use super::fs::{create_named_dir, inputs::read_inputs_from_file, write_to_file};
+use super::{BlackBoxSolver, NargoConfig};
+use crate::backends::Backend;
+use crate::errors::CliError;
+
+/// Profiles a program, attributing its opcodes to the source code which produced them
+///
+/// ACIR opcodes are attributed from the compiled circuit while Brillig opcodes are counted
+/// as they are executed, using the inputs from the prover file.
+#[derive(Debug, Clone, Args)]
+pub(crate) struct ProfileCommand {
+    /// The name of the toml file which contains the inputs for the prover
+    #[clap(long, short, default_value = PROVER_INPUT_FILE)]
+    prover_name: String,
+
+    /// The name of the package to profile
+    #[clap(long, conflicts_with = "workspace")]
+    package: Option<CrateName>,
+
+    /// Profile all packages in the workspace
+    #[clap(long, conflicts_with = "package")]
+    workspace: bool,
+
+    /// Only profile the ACIR opcodes, without executing the program
+    #[clap(long)]
+    acir_only: bool,
+
+    /// The format in which profiles are written
+    #[clap(long, value_enum, default_value_t = ProfileFormat::Svg)]
+    format: ProfileFormat,
+
+    /// The directory to write profiles to, defaults to the workspace's target directory
+    #[clap(long)]
+    output: Option<PathBuf>,
+
+    #[clap(flatten)]
+    compile_options: CompileOptions,
+
+    /// JSON RPC url to solve oracle calls: `http://...`, `exec:<command> [args...]` or `unix:<socket path>`
+    #[clap(long)]
+    oracle_resolver: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ProfileFormat {
+    /// Folded stacks, as consumed by flamegraph tools such as `inferno` or `flamegraph.pl`
+    Folded,
+    /// A flamegraph SVG
+    Svg,
+}
+
+pub(crate) fn run(
+    backend: &Backend,
+    args: ProfileCommand,
+    config: NargoConfig,
+) -> Result<(), CliError> {
+    let toml_path = get_package_manifest(&config.program_dir)?;
+    let default_selection =
+        if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
+    let selection = args.package.map_or(default_selection, PackageSelection::Selected);
+    let workspace = resolve_workspace_from_toml(
+        &toml_path,
+        selection,
+        Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        config.lock_mode(),
+    )?;
+    let output_dir = args.output.unwrap_or_else(|| workspace.target_directory_path());
+    let output_dir = create_named_dir(&output_dir, "profile");
+
+    let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
+    insert_all_files_for_workspace_into_file_manager(&workspace, &mut workspace_file_manager);
+
+    let expression_width = backend.get_backend_info_or_default();
+    for package in workspace.into_iter().filter(|package| package.is_binary()) {
+        let compiled_program = compile_bin_package(
+            &workspace_file_manager,
+            &workspace,
+            package,
+            &args.compile_options,
+            expression_width,
+        )?;
+        let debug_artifact = DebugArtifact {
+            debug_symbols: vec![compiled_program.debug.clone()],
+            file_map: compiled_program.file_map.clone(),
+            warnings: compiled_program.warnings.clone(),
+        };
+
+        let acir_opcode_counts = (0..compiled_program.circuit.opcodes.len())
+            .map(|acir_index| (OpcodeLocation::Acir(acir_index), 1));
+        let acir_stacks =
+            fold_call_stacks(acir_opcode_counts, &debug_artifact, &workspace.root_dir);
+        if acir_stacks.is_empty() {
+            println!("[{}] No ACIR opcodes were generated", package.name);
+        } else {
+            let path = write_profile(
+                &acir_stacks,
+                &output_dir,
+                &format!("{}_acir_opcodes", package.name),
+                "ACIR opcodes",
+                "opcodes",
+                args.format,
+            )?;
+            println!("[{}] ACIR opcode profile saved to {path}", package.name);
+        }
+
+        if args.acir_only {
+            continue;
+        }
+
+        let brillig_opcode_counts = profile_brillig_execution(
+            &compiled_program,
+            &debug_artifact,
+            package,
+            &args.prover_name,
+            args.oracle_resolver.as_deref(),
+        )?;
+        if brillig_opcode_counts.is_empty() {
+            println!("[{}] No Brillig opcodes were executed", package.name);
+            continue;
+        }
+
+        let brillig_stacks =
+            fold_call_stacks(brillig_opcode_counts, &debug_artifact, &workspace.root_dir);
+        let path = write_profile(
+            &brillig_stacks,
+            &output_dir,
+            &format!("{}_brillig_steps", package.name),
+            "Brillig steps",
+            "steps",
+            args.format,
+        )?;
+        println!("[{}] Brillig execution profile saved to {path}", package.name);
+    }
+    Ok(())
+}
+
+fn profile_brillig_execution(
+    compiled_program: &CompiledProgram,
+    debug_artifact: &DebugArtifact,
+    package: &Package,
+    prover_name: &str,
+    foreign_call_resolver_url: Option<&str>,
+) -> Result<BrilligOpcodeCounts, CliError> {
+    let (inputs_map, _) =
+        read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &compiled_program.abi)?;
+    let initial_witness = compiled_program.abi.encode(&inputs_map, None)?;
+
+    let blackbox_solver = BlackBoxSolver::new();
+    let result = execute_circuit_with_brillig_profiling(
+        &compiled_program.circuit,
+        initial_witness,
+        &blackbox_solver,
+        &mut DefaultForeignCallExecutor::new(true, foreign_call_resolver_url),
+    );
+    match result {
+        Ok((_, brillig_opcode_counts)) => Ok(brillig_opcode_counts),
+        Err(err) => {
+            if let Some(diagnostic) = try_to_diagnose_runtime_error(&err, &compiled_program.debug) {
+                diagnostic.report(debug_artifact, false);
+            }
+            Err(CliError::NargoError(err))
+        }
+    }
+}
+
+/// Attributes each opcode's count to the source call stack which produced it, returning the
+/// total count for each call stack in the folded format where frames are separated by `;`.
+///
+/// The call stack of a Brillig opcode is prefixed by that of the ACIR opcode which called it.
+fn fold_call_stacks(
+    opcode_counts: impl IntoIterator<Item = (OpcodeLocation, usize)>,
+    debug_artifact: &DebugArtifact,
+    root_dir: &Path,
+) -> BTreeMap<String, usize> {
+    let debug_info = &debug_artifact.debug_symbols[0];
+    let locations_of = |opcode_location| {
+        debug_info.locations.get(&opcode_location).map(Vec::as_slice).unwrap_or_default()
+    };
+
+    let mut folded_stacks = BTreeMap::new();
+    for (opcode_location, count) in opcode_counts {
+        let mut call_stack = Vec::new();
+        if let OpcodeLocation::Brillig { acir_index, .. } = opcode_location {
+            call_stack.extend_from_slice(locations_of(OpcodeLocation::Acir(acir_index)));
+        }
+        call_stack.extend_from_slice(locations_of(opcode_location));
+
+        let folded_stack = if call_stack.is_empty() {
+            "unknown".to_string()
+        } else {
+            let frames =
+                vecmap(call_stack, |location| frame_label(location, debug_artifact, root_dir));
+            frames.join(";")
+        };
+        *folded_stacks.entry(folded_stack).or_default() += count;
+    }
+    folded_stacks
+}
+
+/// Describes a source location as the code it spans followed by its file, line and column.
+fn frame_label(location: Location, debug_artifact: &DebugArtifact, root_dir: &Path) -> String {
+    let Some(file) = debug_artifact.file_map.get(&location.file) else {
+        return "unknown".to_string();
+    };
+    let path = file.path.strip_prefix(root_dir).unwrap_or(&file.path);
+    let line = debug_artifact.location_line_number(location).unwrap_or_default();
+    let column = debug_artifact.location_column_number(location).unwrap_or_default();
+
+    let span = location.span.start() as usize..location.span.end() as usize;
+    let code = file.source.get(span).and_then(|code| code.lines().next()).unwrap_or_default();
+    let mut code = code.trim().to_string();
+    if code.len() > 40 {
+        let end = (0..=37).rev().find(|index| code.is_char_boundary(*index)).unwrap_or_default();
+        code.truncate(end);
+        code.push_str("...");
+    }
+
+    // `;` separates frames in the folded format.
+    format!("{code} {}:{line}:{column}", path.display()).replace(';', ",")
+}
+
+fn write_profile(
+    folded_stacks: &BTreeMap<String, usize>,
+    output_dir: &Path,
+    name: &str,
+    title: &str,
+    count_name: &str,
+    format: ProfileFormat,
+) -> Result<String, CliError> {
+    let lines: Vec<String> =
+        folded_stacks.iter().map(|(stack, count)| format!("{stack} {count}")).collect();
+
+    match format {
+        ProfileFormat::Folded => {
+            let contents = lines.join("\n") + "\n";
+            Ok(write_to_file(contents.as_bytes(), &output_dir.join(name).with_extension("folded")))
+        }
+        ProfileFormat::Svg => {
+            let mut options = flamegraph::Options::default();
+            options.title = title.to_string();
+            options.count_name = count_name.to_string();
+
+            let mut svg = Vec::new();
+            flamegraph::from_lines(&mut options, lines.iter().map(String::as_str), &mut svg)
+                .map_err(|err| {
+                    CliError::Generic(format!("Failed to render the {title} flamegraph: {err}"))
+                })?;
+            Ok(write_to_file(&svg, &output_dir.join(name).with_extension("svg")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::{Path, PathBuf};
+
+    use acvm::acir::circuit::OpcodeLocation;
+    use fm::FileId;
+    use nargo::artifacts::debug::DebugArtifact;
+    use noirc_driver::DebugFile;
+    use noirc_errors::{debug_info::DebugInfo, Location, Span};
+
+    use super::{fold_call_stacks, write_profile, ProfileFormat};
+
+    #[test]
+    fn folds_opcodes_into_call_stacks() {
+        let source = "fn main() {\n    foo(1);\n}\nfn foo(x: Field) {\n    assert(x == 1);\n}\n";
+        let file = FileId::dummy();
+        let location_of = |code: &str| {
+            let start = source.find(code).unwrap() as u32;
+            Location::new(Span::from(start..start + code.len() as u32), file)
+        };
+        let call = location_of("foo(1)");
+        let assertion = location_of("x == 1");
+
+        let mut locations = BTreeMap::new();
+        locations.insert(OpcodeLocation::Acir(0), vec![call, assertion]);
+        locations.insert(OpcodeLocation::Acir(1), vec![call, assertion]);
+        locations.insert(OpcodeLocation::Acir(2), vec![call]);
+        let brillig_opcode = OpcodeLocation::Brillig { acir_index: 2, brillig_index: 0 };
+        locations.insert(brillig_opcode, vec![assertion]);
+
+        let mut file_map = BTreeMap::new();
+        file_map.insert(
+            file,
+            DebugFile { source: source.to_string(), path: PathBuf::from("/project/src/main.nr") },
+        );
+        let debug_artifact = DebugArtifact {
+            debug_symbols: vec![DebugInfo::new(locations)],
+            file_map,
+            warnings: Vec::new(),
+        };
+
+        let brillig_counts =
+            [(brillig_opcode, 5), (OpcodeLocation::Brillig { acir_index: 2, brillig_index: 1 }, 3)];
+        let counts = (0..4).map(|index| (OpcodeLocation::Acir(index), 1)).chain(brillig_counts);
+        let folded = fold_call_stacks(counts, &debug_artifact, Path::new("/project"));
+
+        // Opcodes without a location of their own fall back to that of their ACIR call site.
+        let expected: BTreeMap<String, usize> = [
+            ("foo(1) src/main.nr:2:5".to_string(), 1 + 3),
+            ("foo(1) src/main.nr:2:5;x == 1 src/main.nr:5:12".to_string(), 2 + 5),
+            ("unknown".to_string(), 1),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(folded, expected);
+    }
+
+    #[test]
+    fn empty_profiles_fail_to_render_without_panicking() {
+        let output_dir = tempfile::tempdir().unwrap();
+        let result = write_profile(
+            &BTreeMap::new(),
+            output_dir.path(),
+            "main_acir_opcodes",
+            "ACIR opcodes",
+            "opcodes",
+            ProfileFormat::Svg,
+        );
+        assert!(result.is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use clap::Args;
 use nargo::constants::{PROVER_INPUT_FILE, VERIFIER_INPUT_FILE};
 use nargo::insert_all_files_for_workspace_into_file_manager;
+use nargo::ops::DefaultForeignCallExecutor;
 use nargo::package::Package;
 use nargo::workspace::Workspace;
 use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
@@ -44,7 +45,7 @@ pub(crate) struct ProveCommand {
     #[clap(flatten)]
     compile_options: CompileOptions,
 
-    /// JSON RPC url to solve oracle calls
+    /// JSON RPC url to solve oracle calls: `http://...`, `exec:<command> [args...]` or `unix:<socket path>`
     #[clap(long)]
     oracle_resolver: Option<String>,
 }
@@ -62,6 +63,7 @@ pub(crate) fn run(
         &toml_path,
         selection,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        config.lock_mode(),
     )?;
 
     let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
@@ -107,8 +109,11 @@ pub(crate) fn prove_package(
     let (inputs_map, _) =
         read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &compiled_program.abi)?;
 
-    let solved_witness =
-        execute_program(&compiled_program, &inputs_map, foreign_call_resolver_url)?;
+    let solved_witness = execute_program(
+        &compiled_program,
+        &inputs_map,
+        &mut DefaultForeignCallExecutor::new(true, foreign_call_resolver_url),
+    )?;
 
     // Write public inputs into Verifier.toml
     let public_abi = compiled_program.abi.public_abi();
@@ -1,23 +1,36 @@
-use std::io::Write;
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    sync::{mpsc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
 
-use acvm::BlackBoxFunctionSolver;
-use bn254_blackbox_solver::Bn254BlackBoxSolver;
+use acvm::brillig_vm::ExecutionLimits;
 use clap::Args;
 use fm::FileManager;
 use nargo::{
     insert_all_files_for_workspace_into_file_manager,
-    ops::{run_test, TestStatus},
+    ops::{run_test, DefaultForeignCallExecutor, ForeignCallExecutor, TestReport, TestStatus},
     package::Package,
-    prepare_package,
+    prepare_package, NargoError,
 };
 use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
-use noirc_driver::{file_manager_with_stdlib, CompileOptions, NOIR_ARTIFACT_VERSION_STRING};
-use noirc_frontend::{graph::CrateName, hir::FunctionNameMatch};
-use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+use noirc_driver::{
+    check_crate, file_manager_with_stdlib, CompileOptions, NOIR_ARTIFACT_VERSION_STRING,
+};
+use noirc_frontend::{
+    graph::CrateName,
+    hir::{def_map::TestFunction, Context, FunctionNameMatch},
+};
 
 use crate::{backends::Backend, cli::check_cmd::check_crate_and_report_errors, errors::CliError};
 
-use super::NargoConfig;
+use self::formatters::{Format, Formatter};
+
+use super::{foreign_calls::ForeignCallTranscriptArgs, BlackBoxSolver, NargoConfig};
+
+mod formatters;
 
 /// Run the tests for this program
 #[derive(Debug, Clone, Args)]
@@ -44,9 +57,68 @@ pub(crate) struct TestCommand {
     #[clap(flatten)]
     compile_options: CompileOptions,
 
-    /// JSON RPC url to solve oracle calls
+    /// JSON RPC url to solve oracle calls: `http://...`, `exec:<command> [args...]` or `unix:<socket path>`
     #[clap(long)]
     oracle_resolver: Option<String>,
+
+    // Transcripts are named `<package>/<test name>.json`
+    #[clap(flatten)]
+    foreign_call_transcript: ForeignCallTranscriptArgs,
+
+    /// Number of threads used for running tests in parallel
+    #[clap(long, default_value_t = default_test_threads())]
+    test_threads: NonZeroUsize,
+
+    /// How test results are reported
+    #[clap(long, value_enum, default_value_t = Format::Pretty)]
+    format: Format,
+
+    #[clap(flatten)]
+    limits: TestLimits,
+}
+
+/// Limits on the unconstrained code run by each test, so that a test which never finishes fails
+/// instead of blocking the test run.
+#[derive(Debug, Clone, Args)]
+struct TestLimits {
+    /// Maximum number of Brillig opcodes which each unconstrained call in a test may execute
+    #[clap(long)]
+    brillig_step_limit: Option<usize>,
+
+    /// Maximum number of values which each unconstrained call in a test may hold in memory
+    #[clap(long)]
+    brillig_memory_limit: Option<usize>,
+
+    /// Number of seconds after which a test's unconstrained code is halted
+    ///
+    /// This doesn't bound the time taken to compile the test or to solve its constrained code.
+    #[clap(long, value_name = "SECONDS")]
+    timeout: Option<u64>,
+}
+
+impl TestLimits {
+    /// Returns the limits for a test which is started now.
+    fn brillig_limits(&self) -> ExecutionLimits {
+        ExecutionLimits {
+            max_steps: self.brillig_step_limit,
+            max_memory: self.brillig_memory_limit,
+            deadline: self.timeout.map(|timeout| Instant::now() + Duration::from_secs(timeout)),
+        }
+    }
+}
+
+fn default_test_threads() -> NonZeroUsize {
+    thread::available_parallelism().unwrap_or(NonZeroUsize::MIN)
+}
+
+/// Creates the executor resolving the foreign calls of the test with the given transcript name.
+type ForeignCallExecutorFactory<'a> =
+    dyn Fn(&str) -> Result<Box<dyn ForeignCallExecutor>, NargoError> + Sync + 'a;
+
+/// The result of running a single test function.
+pub(super) struct TestResult {
+    name: String,
+    report: TestReport,
 }
 
 pub(crate) fn run(
@@ -62,6 +134,7 @@ pub(crate) fn run(
         &toml_path,
         selection,
         Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+        config.lock_mode(),
     )?;
 
     let mut workspace_file_manager = file_manager_with_stdlib(&workspace.root_dir);
@@ -78,33 +151,61 @@ pub(crate) fn run(
         None => FunctionNameMatch::Anything,
     };
 
-    let blackbox_solver = Bn254BlackBoxSolver::new();
+    let new_foreign_call_executor = |transcript_name: &str| {
+        let mut executor =
+            DefaultForeignCallExecutor::new(args.show_output, args.oracle_resolver.as_deref());
+        // Only the pretty format leaves stdout free for the output of the tests.
+        if args.format != Format::Pretty {
+            executor = executor.with_output_to_stderr();
+        }
+        args.foreign_call_transcript.wrap_executor(executor, transcript_name)
+    };
+
+    let mut formatter = args.format.formatter();
+    let mut tests_failed = false;
     for package in &workspace {
-        // By unwrapping here with `?`, we stop the test runner upon a package failing
-        // TODO: We should run the whole suite even if there are failures in a package
-        run_tests(
+        // We stop the test runner upon a package failing to compile, after finishing the report.
+        // Failing tests do not stop the tests of the remaining packages from being run.
+        let results = match run_tests(
             &workspace_file_manager,
-            &blackbox_solver,
             package,
             pattern,
-            args.show_output,
-            args.oracle_resolver.as_deref(),
+            &new_foreign_call_executor,
             &args.compile_options,
-        )?;
+            &args.limits,
+            args.test_threads,
+            formatter.as_mut(),
+        ) {
+            Ok(results) => results,
+            Err(err) => {
+                formatter.package_error(package, &err.to_string());
+                formatter.finish();
+                return Err(err);
+            }
+        };
+        tests_failed |=
+            results.iter().any(|result| !matches!(result.report.status, TestStatus::Pass));
     }
+    formatter.finish();
 
-    Ok(())
+    if tests_failed {
+        Err(CliError::Generic(String::new()))
+    } else {
+        Ok(())
+    }
 }
 
-fn run_tests<S: BlackBoxFunctionSolver>(
+#[allow(clippy::too_many_arguments)]
+fn run_tests(
     file_manager: &FileManager,
-    blackbox_solver: &S,
     package: &Package,
     fn_name: FunctionNameMatch,
-    show_output: bool,
-    foreign_call_resolver_url: Option<&str>,
+    new_foreign_call_executor: &ForeignCallExecutorFactory,
     compile_options: &CompileOptions,
-) -> Result<(), CliError> {
+    limits: &TestLimits,
+    test_threads: NonZeroUsize,
+    formatter: &mut dyn Formatter,
+) -> Result<Vec<TestResult>, CliError> {
     let (mut context, crate_id) = prepare_package(file_manager, package);
     check_crate_and_report_errors(
         &mut context,
@@ -135,88 +236,125 @@ fn run_tests<S: BlackBoxFunctionSolver>(
         };
     }
 
-    let plural = if count_all == 1 { "" } else { "s" };
-    println!("[{}] Running {count_all} test function{plural}", package.name);
-    let mut count_failed = 0;
+    formatter.package_start(package, count_all);
+    let start = Instant::now();
 
-    let writer = StandardStream::stderr(ColorChoice::Always);
-    let mut writer = writer.lock();
-
-    for (test_name, test_function) in test_functions {
-        write!(writer, "[{}] Testing {test_name}... ", package.name)
-            .expect("Failed to write to stderr");
-        writer.flush().expect("Failed to flush writer");
+    let mut results = Vec::with_capacity(count_all);
+    let mut on_result = |result: TestResult| {
+        formatter.test_end(package, &result);
+        if let TestStatus::Fail { error_diagnostic: Some(diagnostic), .. }
+        | TestStatus::CompileError(diagnostic) = &result.report.status
+        {
+            noirc_errors::reporter::report_all(
+                file_manager.as_file_map(),
+                std::slice::from_ref(diagnostic),
+                compile_options.deny_warnings,
+                compile_options.silence_warnings,
+            );
+        }
+        results.push(result);
+    };
 
-        match run_test(
-            blackbox_solver,
-            &context,
-            test_function,
-            show_output,
-            foreign_call_resolver_url,
-            compile_options,
-        ) {
-            TestStatus::Pass { .. } => {
-                writer
-                    .set_color(ColorSpec::new().set_fg(Some(Color::Green)))
-                    .expect("Failed to set color");
-                writeln!(writer, "ok").expect("Failed to write to stderr");
-            }
-            TestStatus::Fail { message, error_diagnostic } => {
-                writer
-                    .set_color(ColorSpec::new().set_fg(Some(Color::Red)))
-                    .expect("Failed to set color");
-                writeln!(writer, "FAIL\n{message}\n").expect("Failed to write to stderr");
-                if let Some(diag) = error_diagnostic {
-                    noirc_errors::reporter::report_all(
-                        context.file_manager.as_file_map(),
-                        &[diag],
+    let thread_count = test_threads.get().min(count_all);
+    if thread_count <= 1 {
+        let blackbox_solver = BlackBoxSolver::new();
+        for (name, test_function) in test_functions {
+            let report = run_test_function(
+                &blackbox_solver,
+                &context,
+                package,
+                &name,
+                test_function,
+                new_foreign_call_executor,
+                compile_options,
+                limits,
+            );
+            on_result(TestResult { name, report });
+        }
+    } else {
+        // A `Context` cannot be shared between threads so each thread prepares its own
+        // and then takes tests from the queue until it is empty.
+        let queue = Mutex::new(test_functions.into_iter().map(|(name, _)| name));
+        let (sender, receiver) = mpsc::channel();
+        thread::scope(|scope| {
+            for _ in 0..thread_count {
+                let sender = sender.clone();
+                let queue = &queue;
+                scope.spawn(move || {
+                    let blackbox_solver = BlackBoxSolver::new();
+                    let (mut context, crate_id) = prepare_package(file_manager, package);
+                    // Any errors in the crate have already been reported above.
+                    let _ = check_crate(
+                        &mut context,
+                        crate_id,
                         compile_options.deny_warnings,
-                        compile_options.silence_warnings,
+                        compile_options.disable_macros,
                     );
-                }
-                count_failed += 1;
-            }
-            TestStatus::CompileError(err) => {
-                noirc_errors::reporter::report_all(
-                    context.file_manager.as_file_map(),
-                    &[err],
-                    compile_options.deny_warnings,
-                    compile_options.silence_warnings,
-                );
-                count_failed += 1;
+                    let mut test_functions: HashMap<_, _> = context
+                        .get_all_test_functions_in_crate_matching(&crate_id, fn_name)
+                        .into_iter()
+                        .collect();
+
+                    loop {
+                        let Some(name) = queue.lock().expect("Test queue was poisoned").next()
+                        else {
+                            break;
+                        };
+                        let test_function = test_functions
+                            .remove(&name)
+                            .expect("Test should exist in every context");
+                        let report = run_test_function(
+                            &blackbox_solver,
+                            &context,
+                            package,
+                            &name,
+                            test_function,
+                            new_foreign_call_executor,
+                            compile_options,
+                            limits,
+                        );
+                        if sender.send(TestResult { name, report }).is_err() {
+                            break;
+                        }
+                    }
+                });
             }
-        }
-        writer.reset().expect("Failed to reset writer");
+            // Drop our own sender so the receiver finishes once every thread has.
+            drop(sender);
+            receiver.into_iter().for_each(&mut on_result);
+        });
     }
 
-    write!(writer, "[{}] ", package.name).expect("Failed to write to stderr");
-
-    if count_failed == 0 {
-        writer.set_color(ColorSpec::new().set_fg(Some(Color::Green))).expect("Failed to set color");
-        write!(writer, "{count_all} test{plural} passed").expect("Failed to write to stderr");
-        writer.reset().expect("Failed to reset writer");
-        writeln!(writer).expect("Failed to write to stderr");
-
-        Ok(())
-    } else {
-        let count_passed = count_all - count_failed;
-        let plural_failed = if count_failed == 1 { "" } else { "s" };
-        let plural_passed = if count_passed == 1 { "" } else { "s" };
-
-        if count_passed != 0 {
-            writer
-                .set_color(ColorSpec::new().set_fg(Some(Color::Green)))
-                .expect("Failed to set color");
-            write!(writer, "{count_passed} test{plural_passed} passed, ",)
-                .expect("Failed to write to stderr");
-        }
-
-        writer.set_color(ColorSpec::new().set_fg(Some(Color::Red))).expect("Failed to set color");
-        write!(writer, "{count_failed} test{plural_failed} failed")
-            .expect("Failed to write to stderr");
-        writer.reset().expect("Failed to reset writer");
+    formatter.package_end(package, &results, start.elapsed());
+    Ok(results)
+}
 
-        // Writes final newline.
-        Err(CliError::Generic(String::new()))
+#[allow(clippy::too_many_arguments)]
+fn run_test_function(
+    blackbox_solver: &BlackBoxSolver,
+    context: &Context,
+    package: &Package,
+    name: &str,
+    test_function: TestFunction,
+    new_foreign_call_executor: &ForeignCallExecutorFactory,
+    compile_options: &CompileOptions,
+    limits: &TestLimits,
+) -> TestReport {
+    // Test names contain `::`, which can't be used in file names on every platform.
+    let transcript_name = format!("{}/{}", package.name, name.replace("::", "."));
+    match new_foreign_call_executor(&transcript_name) {
+        Ok(mut foreign_call_executor) => run_test(
+            blackbox_solver,
+            context,
+            test_function,
+            &mut foreign_call_executor,
+            compile_options,
+            limits.brillig_limits(),
+        ),
+        Err(err) => TestReport {
+            status: TestStatus::Fail { message: err.to_string(), error_diagnostic: None },
+            duration: Duration::ZERO,
+            execution_stats: None,
+        },
     }
 }
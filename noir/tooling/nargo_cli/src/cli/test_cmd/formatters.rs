@@ -0,0 +1,478 @@
+use std::{io::Write, time::Duration};
+
+use clap::ValueEnum;
+use nargo::{
+    ops::{ExecutionStats, TestStatus},
+    package::Package,
+};
+use serde_json::{json, Value};
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+
+use super::TestResult;
+
+/// The format in which test results are reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum Format {
+    /// Human readable output written to stderr
+    Pretty,
+    /// A JSON object written to stdout for each event
+    Json,
+    /// A JUnit XML report written to stdout once all tests have run
+    Junit,
+}
+
+impl Format {
+    pub(super) fn formatter(self) -> Box<dyn Formatter> {
+        match self {
+            Format::Pretty => Box::new(PrettyFormatter),
+            Format::Json => Box::new(JsonFormatter { writer: std::io::stdout() }),
+            Format::Junit => Box::new(JunitFormatter::new(std::io::stdout())),
+        }
+    }
+}
+
+/// Reports the progress and results of running the tests of each package.
+///
+/// Test results are reported in the order in which the tests finish.
+pub(super) trait Formatter {
+    fn package_start(&mut self, package: &Package, test_count: usize);
+
+    fn test_end(&mut self, package: &Package, test: &TestResult);
+
+    fn package_end(&mut self, package: &Package, tests: &[TestResult], duration: Duration);
+
+    /// Called instead of the methods above for a package whose tests couldn't be run, e.g. as it
+    /// failed to compile. Any compile errors have already been reported to stderr.
+    fn package_error(&mut self, _package: &Package, _message: &str) {}
+
+    /// Called once the tests of all packages have been run.
+    fn finish(&mut self) {}
+}
+
+fn failure_message(status: &TestStatus) -> Option<&str> {
+    match status {
+        TestStatus::Pass => None,
+        TestStatus::Fail { message, .. } => Some(message),
+        TestStatus::CompileError(diagnostic) => Some(&diagnostic.diagnostic.message),
+    }
+}
+
+fn count_failed(tests: &[TestResult]) -> usize {
+    tests.iter().filter(|test| !matches!(test.report.status, TestStatus::Pass)).count()
+}
+
+struct PrettyFormatter;
+
+impl Formatter for PrettyFormatter {
+    fn package_start(&mut self, package: &Package, test_count: usize) {
+        let plural = if test_count == 1 { "" } else { "s" };
+        eprintln!("[{}] Running {test_count} test function{plural}", package.name);
+    }
+
+    fn test_end(&mut self, package: &Package, test: &TestResult) {
+        let writer = StandardStream::stderr(ColorChoice::Always);
+        let mut writer = writer.lock();
+
+        write!(writer, "[{}] Testing {}... ", package.name, test.name)
+            .expect("Failed to write to stderr");
+        match &test.report.status {
+            TestStatus::Pass => {
+                writer
+                    .set_color(ColorSpec::new().set_fg(Some(Color::Green)))
+                    .expect("Failed to set color");
+                write!(writer, "ok").expect("Failed to write to stderr");
+            }
+            TestStatus::Fail { .. } | TestStatus::CompileError(_) => {
+                writer
+                    .set_color(ColorSpec::new().set_fg(Some(Color::Red)))
+                    .expect("Failed to set color");
+                write!(writer, "FAIL").expect("Failed to write to stderr");
+            }
+        }
+        writer.reset().expect("Failed to reset writer");
+
+        write!(writer, " ({:.2?}", test.report.duration).expect("Failed to write to stderr");
+        if let Some(ExecutionStats { acir_opcodes, brillig_steps }) = test.report.execution_stats {
+            write!(writer, ", {acir_opcodes} ACIR opcodes, {brillig_steps} Brillig steps")
+                .expect("Failed to write to stderr");
+        }
+        writeln!(writer, ")").expect("Failed to write to stderr");
+
+        if let TestStatus::Fail { message, .. } = &test.report.status {
+            writeln!(writer, "{message}\n").expect("Failed to write to stderr");
+        }
+    }
+
+    fn package_end(&mut self, package: &Package, tests: &[TestResult], _duration: Duration) {
+        let writer = StandardStream::stderr(ColorChoice::Always);
+        let mut writer = writer.lock();
+
+        let count_all = tests.len();
+        let count_failed = count_failed(tests);
+        let plural = if count_all == 1 { "" } else { "s" };
+
+        write!(writer, "[{}] ", package.name).expect("Failed to write to stderr");
+
+        if count_failed == 0 {
+            writer
+                .set_color(ColorSpec::new().set_fg(Some(Color::Green)))
+                .expect("Failed to set color");
+            write!(writer, "{count_all} test{plural} passed").expect("Failed to write to stderr");
+        } else {
+            let count_passed = count_all - count_failed;
+            let plural_failed = if count_failed == 1 { "" } else { "s" };
+            let plural_passed = if count_passed == 1 { "" } else { "s" };
+
+            if count_passed != 0 {
+                writer
+                    .set_color(ColorSpec::new().set_fg(Some(Color::Green)))
+                    .expect("Failed to set color");
+                write!(writer, "{count_passed} test{plural_passed} passed, ",)
+                    .expect("Failed to write to stderr");
+            }
+
+            writer
+                .set_color(ColorSpec::new().set_fg(Some(Color::Red)))
+                .expect("Failed to set color");
+            write!(writer, "{count_failed} test{plural_failed} failed")
+                .expect("Failed to write to stderr");
+        }
+        writer.reset().expect("Failed to reset writer");
+        writeln!(writer).expect("Failed to write to stderr");
+    }
+}
+
+/// Writes one JSON object per line to stdout, following the shape of libtest's JSON output.
+struct JsonFormatter<W> {
+    writer: W,
+}
+
+impl<W: Write> JsonFormatter<W> {
+    fn write_event(&mut self, event: Value) {
+        writeln!(self.writer, "{event}").expect("Failed to write to stdout");
+    }
+}
+
+impl<W: Write> Formatter for JsonFormatter<W> {
+    fn package_start(&mut self, package: &Package, test_count: usize) {
+        self.write_event(json!({
+            "type": "suite",
+            "event": "started",
+            "package": package.name.to_string(),
+            "test_count": test_count,
+        }));
+    }
+
+    fn test_end(&mut self, package: &Package, test: &TestResult) {
+        let report = &test.report;
+        let event = if matches!(report.status, TestStatus::Pass) { "ok" } else { "failed" };
+        let mut object = json!({
+            "type": "test",
+            "event": event,
+            "package": package.name.to_string(),
+            "name": test.name,
+            "exec_time": report.duration.as_secs_f64(),
+            "acir_opcodes": report.execution_stats.map(|stats| stats.acir_opcodes),
+            "brillig_steps": report.execution_stats.map(|stats| stats.brillig_steps),
+        });
+        if let Some(message) = failure_message(&report.status) {
+            object["message"] = message.into();
+        }
+        self.write_event(object);
+    }
+
+    fn package_end(&mut self, package: &Package, tests: &[TestResult], duration: Duration) {
+        let failed = count_failed(tests);
+        self.write_event(json!({
+            "type": "suite",
+            "event": if failed == 0 { "ok" } else { "failed" },
+            "package": package.name.to_string(),
+            "passed": tests.len() - failed,
+            "failed": failed,
+            "exec_time": duration.as_secs_f64(),
+        }));
+    }
+
+    fn package_error(&mut self, package: &Package, message: &str) {
+        self.write_event(json!({
+            "type": "suite",
+            "event": "failed",
+            "package": package.name.to_string(),
+            "passed": 0,
+            "failed": 0,
+            "message": message,
+        }));
+    }
+}
+
+/// Collects a `<testsuite>` element for each package, which are written to stdout on [`Formatter::finish`].
+struct JunitFormatter<W> {
+    writer: W,
+    suites: Vec<String>,
+    tests: usize,
+    failures: usize,
+    errors: usize,
+}
+
+impl<W> JunitFormatter<W> {
+    fn new(writer: W) -> Self {
+        JunitFormatter { writer, suites: Vec::new(), tests: 0, failures: 0, errors: 0 }
+    }
+}
+
+impl<W: Write> Formatter for JunitFormatter<W> {
+    fn package_start(&mut self, _package: &Package, _test_count: usize) {}
+
+    fn test_end(&mut self, _package: &Package, _test: &TestResult) {}
+
+    fn package_end(&mut self, package: &Package, tests: &[TestResult], duration: Duration) {
+        let package_name = escape_xml(&package.name.to_string());
+        let failures = count_failed(tests);
+        self.tests += tests.len();
+        self.failures += failures;
+
+        let mut suite = format!(
+            "  <testsuite name=\"{package_name}\" tests=\"{}\" failures=\"{failures}\" errors=\"0\" time=\"{:.6}\">\n",
+            tests.len(),
+            duration.as_secs_f64()
+        );
+        for test in tests {
+            let report = &test.report;
+            suite.push_str(&format!(
+                "    <testcase name=\"{}\" classname=\"{package_name}\" time=\"{:.6}\">\n",
+                escape_xml(&test.name),
+                report.duration.as_secs_f64()
+            ));
+            if let Some(ExecutionStats { acir_opcodes, brillig_steps }) = report.execution_stats {
+                suite.push_str("      <properties>\n");
+                suite.push_str(&format!(
+                    "        <property name=\"acir_opcodes\" value=\"{acir_opcodes}\"/>\n"
+                ));
+                suite.push_str(&format!(
+                    "        <property name=\"brillig_steps\" value=\"{brillig_steps}\"/>\n"
+                ));
+                suite.push_str("      </properties>\n");
+            }
+            if let Some(message) = failure_message(&report.status) {
+                suite.push_str(&format!(
+                    "      <failure message=\"{}\"/>\n",
+                    escape_xml(message.trim())
+                ));
+            }
+            suite.push_str("    </testcase>\n");
+        }
+        suite.push_str("  </testsuite>\n");
+
+        self.suites.push(suite);
+    }
+
+    fn package_error(&mut self, package: &Package, message: &str) {
+        // The package is reported as a single test case which errored, as JUnit has no notion of
+        // a test suite which failed before any of its tests ran.
+        let package_name = escape_xml(&package.name.to_string());
+        self.tests += 1;
+        self.errors += 1;
+        let mut suite = format!(
+            "  <testsuite name=\"{package_name}\" tests=\"1\" failures=\"0\" errors=\"1\" time=\"0.000000\">\n"
+        );
+        suite.push_str(&format!(
+            "    <testcase name=\"{package_name}\" classname=\"{package_name}\" time=\"0.000000\">\n"
+        ));
+        suite.push_str(&format!("      <error message=\"{}\"/>\n", escape_xml(message.trim())));
+        suite.push_str("    </testcase>\n");
+        suite.push_str("  </testsuite>\n");
+
+        self.suites.push(suite);
+    }
+
+    fn finish(&mut self) {
+        let writer = &mut self.writer;
+        writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")
+            .expect("Failed to write to stdout");
+        writeln!(
+            writer,
+            "<testsuites tests=\"{}\" failures=\"{}\" errors=\"{}\">",
+            self.tests, self.failures, self.errors
+        )
+        .expect("Failed to write to stdout");
+        for suite in &self.suites {
+            write!(writer, "{suite}").expect("Failed to write to stdout");
+        }
+        writeln!(writer, "</testsuites>").expect("Failed to write to stdout");
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, path::PathBuf, time::Duration};
+
+    use nargo::{
+        ops::{ExecutionStats, TestReport, TestStatus},
+        package::{Package, PackageType},
+    };
+    use serde_json::{json, Value};
+
+    use super::{escape_xml, Formatter, JsonFormatter, JunitFormatter, TestResult};
+
+    fn package(name: &str) -> Package {
+        Package {
+            version: None,
+            compiler_required_version: None,
+            root_dir: PathBuf::from(name),
+            package_type: PackageType::Binary,
+            entry_path: PathBuf::from(name).join("src/main.nr"),
+            name: name.parse().unwrap(),
+            dependencies: BTreeMap::new(),
+        }
+    }
+
+    fn test_results() -> Vec<TestResult> {
+        vec![
+            TestResult {
+                name: "test_passes".to_string(),
+                report: TestReport {
+                    status: TestStatus::Pass,
+                    duration: Duration::from_millis(250),
+                    execution_stats: Some(ExecutionStats { acir_opcodes: 3, brillig_steps: 7 }),
+                },
+            },
+            TestResult {
+                name: "test_fails".to_string(),
+                report: TestReport {
+                    status: TestStatus::Fail {
+                        message: "Failed assertion: 'x < y'".to_string(),
+                        error_diagnostic: None,
+                    },
+                    duration: Duration::from_millis(500),
+                    execution_stats: Some(ExecutionStats { acir_opcodes: 2, brillig_steps: 0 }),
+                },
+            },
+        ]
+    }
+
+    /// Reports the results of `tested` followed by `broken` failing to compile.
+    fn report(formatter: &mut dyn Formatter, tested: &Package, broken: &Package) {
+        let results = test_results();
+        formatter.package_start(tested, results.len());
+        for result in &results {
+            formatter.test_end(tested, result);
+        }
+        formatter.package_end(tested, &results, Duration::from_secs(1));
+        formatter.package_error(broken, "Aborting due to 1 previous error");
+        formatter.finish();
+    }
+
+    #[test]
+    fn writes_json_events() {
+        let mut formatter = JsonFormatter { writer: Vec::new() };
+        report(&mut formatter, &package("tested"), &package("broken"));
+
+        let events: Vec<Value> = String::from_utf8(formatter.writer)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                json!({
+                    "type": "suite",
+                    "event": "started",
+                    "package": "tested",
+                    "test_count": 2,
+                }),
+                json!({
+                    "type": "test",
+                    "event": "ok",
+                    "package": "tested",
+                    "name": "test_passes",
+                    "exec_time": 0.25,
+                    "acir_opcodes": 3,
+                    "brillig_steps": 7,
+                }),
+                json!({
+                    "type": "test",
+                    "event": "failed",
+                    "package": "tested",
+                    "name": "test_fails",
+                    "exec_time": 0.5,
+                    "acir_opcodes": 2,
+                    "brillig_steps": 0,
+                    "message": "Failed assertion: 'x < y'",
+                }),
+                json!({
+                    "type": "suite",
+                    "event": "failed",
+                    "package": "tested",
+                    "passed": 1,
+                    "failed": 1,
+                    "exec_time": 1.0,
+                }),
+                json!({
+                    "type": "suite",
+                    "event": "failed",
+                    "package": "broken",
+                    "passed": 0,
+                    "failed": 0,
+                    "message": "Aborting due to 1 previous error",
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_junit_report() {
+        let mut formatter = JunitFormatter::new(Vec::new());
+        report(&mut formatter, &package("tested"), &package("broken"));
+
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites tests="3" failures="1" errors="1">
+  <testsuite name="tested" tests="2" failures="1" errors="0" time="1.000000">
+    <testcase name="test_passes" classname="tested" time="0.250000">
+      <properties>
+        <property name="acir_opcodes" value="3"/>
+        <property name="brillig_steps" value="7"/>
+      </properties>
+    </testcase>
+    <testcase name="test_fails" classname="tested" time="0.500000">
+      <properties>
+        <property name="acir_opcodes" value="2"/>
+        <property name="brillig_steps" value="0"/>
+      </properties>
+      <failure message="Failed assertion: &apos;x &lt; y&apos;"/>
+    </testcase>
+  </testsuite>
+  <testsuite name="broken" tests="1" failures="0" errors="1" time="0.000000">
+    <testcase name="broken" classname="broken" time="0.000000">
+      <error message="Aborting due to 1 previous error"/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#;
+        assert_eq!(String::from_utf8(formatter.writer).unwrap(), expected);
+    }
+
+    #[test]
+    fn escapes_xml_special_characters() {
+        assert_eq!(
+            escape_xml("Expected: <\"a\" & 'b'>\nGot: c"),
+            "Expected: &lt;&quot;a&quot; &amp; &apos;b&apos;&gt;&#10;Got: c"
+        );
+    }
+}
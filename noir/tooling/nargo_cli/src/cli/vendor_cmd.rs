@@ -0,0 +1,26 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use nargo_toml::{get_package_manifest, vendor_workspace};
+
+use super::NargoConfig;
+use crate::errors::CliError;
+
+/// Copy the sources of all git and registry dependencies into a directory
+///
+/// The workspace is configured to use these copies in `.nargo/config.toml`,
+/// so that it can be built without fetching its dependencies.
+#[derive(Debug, Clone, Args)]
+pub(crate) struct VendorCommand {
+    /// The directory to copy dependencies into, relative to the workspace root
+    #[clap(default_value = "vendor")]
+    path: PathBuf,
+}
+
+pub(crate) fn run(args: VendorCommand, config: NargoConfig) -> Result<(), CliError> {
+    let toml_path = get_package_manifest(&config.program_dir)?;
+    let vendored = vendor_workspace(&toml_path, &args.path, config.lock_mode())?;
+
+    println!("Vendored {vendored} sources into {}", args.path.display());
+    Ok(())
+}
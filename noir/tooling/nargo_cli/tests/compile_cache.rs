@@ -0,0 +1,47 @@
+//! Checks that a second `nargo compile` of an unchanged package reuses the compilation cached by the first one.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+use assert_fs::prelude::{FileWriteStr, PathAssert, PathChild};
+
+#[test]
+fn second_compile_uses_the_cache() {
+    let test_dir = assert_fs::TempDir::new().unwrap();
+
+    let project_name = "cached";
+    let project_dir = test_dir.child(project_name);
+
+    // `nargo new cached`
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.current_dir(&test_dir).arg("new").arg(project_name);
+    cmd.assert().success();
+
+    // The unused variable raises a warning, which is cached alongside the compiled program.
+    project_dir
+        .child("src")
+        .child("main.nr")
+        .write_str("fn main(x: Field) { let y = x; }")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.current_dir(&project_dir).arg("compile");
+    cmd.assert().success().stderr(predicate::str::contains("unused variable y"));
+
+    let cache_entry =
+        project_dir.child("target").child("cache").child(format!("{project_name}.json"));
+    cache_entry.assert(predicate::path::is_file());
+
+    // Only a cache hit can report the warning as it was rewritten in the cache entry.
+    let contents = std::fs::read_to_string(cache_entry.path()).unwrap();
+    assert!(contents.contains("unused variable y"));
+    cache_entry.write_str(&contents.replace("unused variable y", "cached variable y")).unwrap();
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.current_dir(&project_dir).arg("compile");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("cached variable y"))
+        .stderr(predicate::str::contains("unused variable y").not());
+}
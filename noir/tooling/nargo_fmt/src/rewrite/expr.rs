@@ -149,7 +149,9 @@ pub(crate) fn rewrite(
 
             visitor.format_if(*if_expr)
         }
-        ExpressionKind::Lambda(_) | ExpressionKind::Variable(_) => visitor.slice(span).to_string(),
+        ExpressionKind::Lambda(_) | ExpressionKind::Match(_) | ExpressionKind::Variable(_) => {
+            visitor.slice(span).to_string()
+        }
         ExpressionKind::Error => unreachable!(),
     }
 }
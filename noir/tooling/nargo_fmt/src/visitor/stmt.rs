@@ -95,6 +95,15 @@ impl super::FmtVisitor<'_> {
                     let result = format!("for {identifier} in {range} {block}");
                     self.push_rewrite(result, span);
                 }
+                StatementKind::While(while_stmt) => {
+                    let condition = rewrite::sub_expr(self, self.shape(), while_stmt.condition);
+                    let block = rewrite::sub_expr(self, self.shape(), while_stmt.block);
+
+                    let result = format!("while {condition} {block}");
+                    self.push_rewrite(result, span);
+                }
+                StatementKind::Break => self.push_rewrite("break;".to_string(), span),
+                StatementKind::Continue => self.push_rewrite("continue;".to_string(), span),
                 StatementKind::Assign(_) => {
                     self.push_rewrite(self.slice(span).to_string(), span);
                 }
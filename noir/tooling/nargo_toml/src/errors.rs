@@ -15,6 +15,9 @@ pub enum ManifestError {
     #[error("Cannot read file {0} - does it exist?")]
     ReadFailed(PathBuf),
 
+    #[error("Cannot write file {0}")]
+    WriteFailed(PathBuf),
+
     #[error("Nargo.toml is missing a parent directory")]
     MissingParent,
 
@@ -48,6 +51,12 @@ pub enum ManifestError {
     #[error("Invalid directory path {directory} in {toml}: It must point to a subdirectory")]
     InvalidDirectory { toml: PathBuf, directory: PathBuf },
 
+    #[error("Dependency `{name}` in {toml} must specify exactly one of `tag`, `branch` or `rev`")]
+    InvalidGitReference { toml: PathBuf, name: String },
+
+    #[error("Invalid `rev` {rev} in {toml}: It must be a full 40 character commit hash")]
+    InvalidGitRevision { toml: PathBuf, rev: String },
+
     /// Encountered error while downloading git repository.
     #[error("{0}")]
     GitError(String),
@@ -72,6 +81,45 @@ pub enum ManifestError {
 
     #[error("Cyclic package dependency found when processing {cycle}")]
     CyclicDependency { cycle: String },
+
+    /// Lockfile is unreadable.
+    #[error("{lockfile} is badly formed, could not parse.\n\n {error}")]
+    MalformedLockfile { lockfile: PathBuf, error: toml::de::Error },
+
+    #[error("{lockfile} has version {version} which is not supported by this version of nargo")]
+    UnsupportedLockfileVersion { lockfile: PathBuf, version: u32 },
+
+    #[error("Dependency in {path} does not match Nargo.lock: expected {expected} but found {found}. Delete {path} to download it again, or run `nargo vendor` if it is vendored")]
+    ChecksumMismatch { path: PathBuf, expected: String, found: String },
+
+    #[error("{0} needs to be updated but `--locked` was passed to prevent this")]
+    LockfileOutOfDate(PathBuf),
+
+    #[error("Multiple versions of {dependency} are required by the workspace:\n{requirements}")]
+    DependencyConflict { dependency: String, requirements: String },
+
+    #[error("{config} is badly formed, could not parse.\n\n {error}")]
+    MalformedConfig { config: PathBuf, error: toml::de::Error },
+
+    #[error("Dependency `{name}` in {toml} is a registry dependency but no registry is configured in .nargo/config.toml")]
+    MissingRegistry { toml: PathBuf, name: String },
+
+    #[error("{index} is badly formed, could not parse.\n\n {error}")]
+    MalformedRegistryIndex { index: PathBuf, error: toml::de::Error },
+
+    #[error("Cannot find package `{name}` matching version {version} in the registry")]
+    MissingRegistryPackage { name: String, version: String },
+
+    #[error(
+        "{archive} is not a gzipped tarball containing the package in a `{directory}` directory"
+    )]
+    InvalidRegistryPackage { archive: PathBuf, directory: String },
+
+    #[error("{dependency} is not vendored in {vendor}. Run `nargo vendor` to vendor it")]
+    NotVendored { dependency: String, vendor: PathBuf },
+
+    #[error("Cannot vendor dependencies into {0} as it is not empty")]
+    VendorDirectoryNotEmpty(PathBuf),
 }
 
 #[allow(clippy::enum_variant_names)]
@@ -87,4 +135,19 @@ pub enum SemverError {
     CouldNotParseRequiredVersion { package_name: String, error: String },
     #[error("Could not parse the package version for package {package_name} in Nargo.toml. Error: {error}")]
     CouldNotParsePackageVersion { package_name: String, error: String },
+    #[error("Could not parse the version requirement for dependency {dependency_name} in Nargo.toml. Error: {error}")]
+    CouldNotParseDependencyVersion { dependency_name: String, error: String },
+    #[error("Incompatible version of dependency {dependency_name}. Required version is {required_version} but package {package_name} has version {version_found}")]
+    IncompatibleDependencyVersion {
+        dependency_name: String,
+        package_name: CrateName,
+        required_version: String,
+        version_found: String,
+    },
+    #[error("Dependency {dependency_name} requires version {required_version} but package {package_name} does not specify a version")]
+    MissingDependencyVersion {
+        dependency_name: String,
+        package_name: CrateName,
+        required_version: String,
+    },
 }
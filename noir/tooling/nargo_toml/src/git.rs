@@ -1,4 +1,33 @@
-use std::path::PathBuf;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+/// The reference at which a git dependency is checked out.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum GitReference {
+    Tag(String),
+    Branch(String),
+    /// A full commit hash.
+    Rev(String),
+}
+
+impl Display for GitReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tag(tag) => write!(f, "tag {tag}"),
+            Self::Branch(branch) => write!(f, "branch {branch}"),
+            Self::Rev(rev) => write!(f, "rev {rev}"),
+        }
+    }
+}
+
+/// Returns whether `rev` is a full commit hash, as only those can be fetched from a remote.
+pub(crate) fn is_commit_hash(rev: &str) -> bool {
+    rev.len() == 40 && rev.chars().all(|c| c.is_ascii_hexdigit())
+}
 
 /// Creates a unique folder name for a GitHub repo
 /// by using its URL and tag
@@ -9,7 +38,8 @@ fn resolve_folder_name(base: &url::Url, tag: &str) -> String {
     folder_name
 }
 
-fn nargo_crates() -> PathBuf {
+/// The directory in which nargo keeps the dependencies it downloads.
+pub(crate) fn nargo_crates() -> PathBuf {
     dirs::home_dir().unwrap().join("nargo")
 }
 
@@ -19,6 +49,14 @@ fn git_dep_location(base: &url::Url, tag: &str) -> PathBuf {
     nargo_crates().join(folder_name)
 }
 
+/// Returns the location of a checkout of a specific commit, which is kept apart from the checkouts
+/// of tags so that tags moving to other commits don't affect it.
+fn git_commit_location(base: &url::Url, commit: &str) -> PathBuf {
+    let folder_name = resolve_folder_name(base, &format!("@{commit}"));
+
+    nargo_crates().join(folder_name)
+}
+
 /// XXX: I'd prefer to use a GitHub library however, there
 /// does not seem to be an easy way to download a repo at a specific
 /// tag
@@ -26,8 +64,6 @@ fn git_dep_location(base: &url::Url, tag: &str) -> PathBuf {
 ///
 /// One advantage of using "git clone" is that there is effectively no rate limit
 pub(crate) fn clone_git_repo(url: &str, tag: &str) -> Result<PathBuf, String> {
-    use std::process::Command;
-
     let base = match url::Url::parse(url) {
         Ok(base) => base,
         Err(err) => return Err(err.to_string()),
@@ -38,7 +74,7 @@ pub(crate) fn clone_git_repo(url: &str, tag: &str) -> Result<PathBuf, String> {
         return Ok(loc);
     }
 
-    Command::new("git")
+    let status = Command::new("git")
         .arg("-c")
         .arg("advice.detachedHead=false")
         .arg("clone")
@@ -49,7 +85,99 @@ pub(crate) fn clone_git_repo(url: &str, tag: &str) -> Result<PathBuf, String> {
         .arg(base.as_str())
         .arg(&loc)
         .status()
-        .expect("git clone command failed to start");
+        .map_err(failed_to_run_git)?;
+
+    if !status.success() {
+        // Don't leave a partial clone behind to be mistaken for a complete one.
+        let _ = std::fs::remove_dir_all(&loc);
+        return Err(format!("Failed to clone {url} at tag {tag}"));
+    }
+
+    Ok(loc)
+}
+
+/// Downloads the repository at `url` at `commit`, returning the location of the checkout.
+pub(crate) fn fetch_git_commit(url: &str, commit: &str) -> Result<PathBuf, String> {
+    let base = url::Url::parse(url).map_err(|err| err.to_string())?;
+
+    let loc = git_commit_location(&base, commit);
+    if loc.exists() {
+        return Ok(loc);
+    }
+    std::fs::create_dir_all(&loc).map_err(|err| err.to_string())?;
+
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(["-c", "advice.detachedHead=false"])
+            .args(args)
+            .current_dir(&loc)
+            .status()
+            .map(|status| status.success())
+    };
+    let fetched = git(&["init", "--quiet"]).and_then(|success| {
+        Ok(success
+            && git(&["fetch", "--quiet", "--depth", "1", base.as_str(), commit])?
+            && git(&["checkout", "--quiet", "FETCH_HEAD"])?)
+    });
+
+    match fetched {
+        Ok(true) => (),
+        Ok(false) => {
+            let _ = std::fs::remove_dir_all(&loc);
+            return Err(format!("Failed to fetch commit {commit} of {url}"));
+        }
+        Err(err) => {
+            let _ = std::fs::remove_dir_all(&loc);
+            return Err(failed_to_run_git(err));
+        }
+    }
 
     Ok(loc)
 }
+
+/// Returns the hash of the commit at the head of `branch` in the repository at `url`.
+pub(crate) fn git_branch_commit(url: &str, branch: &str) -> Result<String, String> {
+    let base = url::Url::parse(url).map_err(|err| err.to_string())?;
+
+    let output = Command::new("git")
+        .arg("ls-remote")
+        .arg(base.as_str())
+        .arg(format!("refs/heads/{branch}"))
+        .output()
+        .map_err(failed_to_run_git)?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.split_whitespace().next() {
+        Some(commit) if output.status.success() && is_commit_hash(commit) => Ok(commit.to_string()),
+        _ => Err(format!("Failed to find branch {branch} of {url}")),
+    }
+}
+
+/// Returns the hash of the commit checked out in `checkout`.
+pub(crate) fn git_commit_hash(checkout: &Path) -> Result<String, String> {
+    let incomplete = || {
+        format!(
+            "{} is not a complete git checkout. Delete it to download it again",
+            checkout.display()
+        )
+    };
+    // Without this check, git would look for a repository in the parent directories.
+    if !checkout.join(".git").exists() {
+        return Err(incomplete());
+    }
+
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(checkout)
+        .output()
+        .map_err(failed_to_run_git)?;
+    if !output.status.success() {
+        return Err(incomplete());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn failed_to_run_git(err: std::io::Error) -> String {
+    format!("Failed to run git: {err}. Dependencies vendored with `nargo vendor` can be used without git")
+}
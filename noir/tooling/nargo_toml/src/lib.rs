@@ -10,6 +10,7 @@ use std::{
 
 use errors::SemverError;
 use fm::{NormalizePath, FILE_EXTENSION};
+use git::GitReference;
 use nargo::{
     package::{Dependency, Package, PackageType},
     workspace::Workspace,
@@ -19,10 +20,16 @@ use serde::Deserialize;
 
 mod errors;
 mod git;
+mod lock;
+mod registry;
 mod semver;
+mod source;
+mod vendor;
 
 pub use errors::ManifestError;
-use git::clone_git_repo;
+use lock::DependencyLock;
+pub use lock::LockMode;
+use source::SourceConfig;
 
 /// Searches for a `Nargo.toml` file in the current directory and all parent directories.
 /// For example, if the current directory is `/workspace/package/src`, then this function
@@ -124,6 +131,7 @@ impl PackageConfig {
         &self,
         root_dir: &Path,
         processed: &mut Vec<String>,
+        lock: &mut DependencyLock,
     ) -> Result<Package, ManifestError> {
         let name: CrateName = if let Some(name) = &self.package.name {
             name.parse().map_err(|_| ManifestError::InvalidPackageName {
@@ -136,11 +144,13 @@ impl PackageConfig {
 
         let mut dependencies: BTreeMap<CrateName, Dependency> = BTreeMap::new();
         for (name, dep_config) in self.dependencies.iter() {
-            let name = name.parse().map_err(|_| ManifestError::InvalidDependencyName {
-                toml: root_dir.join("Nargo.toml"),
-                name: name.into(),
-            })?;
-            let resolved_dep = dep_config.resolve_to_dependency(root_dir, processed)?;
+            let name: CrateName =
+                name.parse().map_err(|_| ManifestError::InvalidDependencyName {
+                    toml: root_dir.join("Nargo.toml"),
+                    name: name.into(),
+                })?;
+            let resolved_dep =
+                dep_config.resolve_to_dependency(&name, root_dir, processed, lock)?;
 
             dependencies.insert(name, resolved_dep);
         }
@@ -282,61 +292,100 @@ struct PackageMetadata {
 /// Enum representing the different types of ways to
 /// supply a source for the dependency
 enum DependencyConfig {
-    Github { git: String, tag: String, directory: Option<String> },
-    Path { path: String },
+    Github {
+        git: String,
+        tag: Option<String>,
+        branch: Option<String>,
+        rev: Option<String>,
+        directory: Option<String>,
+        /// A semver requirement on the `version` of the dependency's package.
+        version: Option<String>,
+    },
+    Path {
+        path: String,
+        /// A semver requirement on the `version` of the dependency's package.
+        version: Option<String>,
+    },
+    /// A package from the registry configured in `.nargo/config.toml`, with the same name as the dependency.
+    Registry { version: String },
 }
 
 impl DependencyConfig {
     fn resolve_to_dependency(
         &self,
+        name: &CrateName,
         pkg_root: &Path,
         processed: &mut Vec<String>,
+        lock: &mut DependencyLock,
     ) -> Result<Dependency, ManifestError> {
-        let dep = match self {
-            Self::Github { git, tag, directory } => {
-                let dir_path = clone_git_repo(git, tag).map_err(ManifestError::GitError)?;
-                let project_path = if let Some(directory) = directory {
-                    let internal_path = dir_path.join(directory).normalize();
-                    if !internal_path.starts_with(&dir_path) {
-                        return Err(ManifestError::InvalidDirectory {
+        let (dep, version) = match self {
+            Self::Github { git, tag, branch, rev, directory, version } => {
+                let reference = match (tag, branch, rev) {
+                    (Some(tag), None, None) => GitReference::Tag(tag.clone()),
+                    (None, Some(branch), None) => GitReference::Branch(branch.clone()),
+                    (None, None, Some(rev)) if git::is_commit_hash(rev) => {
+                        GitReference::Rev(rev.to_lowercase())
+                    }
+                    (None, None, Some(rev)) => {
+                        return Err(ManifestError::InvalidGitRevision {
                             toml: pkg_root.join("Nargo.toml"),
-                            directory: directory.into(),
-                        });
+                            rev: rev.clone(),
+                        })
+                    }
+                    _ => {
+                        return Err(ManifestError::InvalidGitReference {
+                            toml: pkg_root.join("Nargo.toml"),
+                            name: name.to_string(),
+                        })
                     }
-                    internal_path
-                } else {
-                    dir_path
                 };
+                let project_path =
+                    lock.resolve_git_dependency(pkg_root, git, &reference, directory.as_ref())?;
                 let toml_path = project_path.join("Nargo.toml");
-                let package = resolve_package_from_toml(&toml_path, processed)?;
-                Dependency::Remote { package }
+                let package = resolve_package_from_toml(&toml_path, processed, lock)?;
+                (Dependency::Remote { package }, version.as_ref())
             }
-            Self::Path { path } => {
+            Self::Path { path, version } => {
                 let dir_path = pkg_root.join(path);
                 let toml_path = dir_path.join("Nargo.toml");
-                let package = resolve_package_from_toml(&toml_path, processed)?;
-                Dependency::Local { package }
+                let package = resolve_package_from_toml(&toml_path, processed, lock)?;
+                (Dependency::Local { package }, version.as_ref())
+            }
+            Self::Registry { version } => {
+                let project_path =
+                    lock.resolve_registry_dependency(pkg_root, &name.to_string(), version)?;
+                let toml_path = project_path.join("Nargo.toml");
+                let package = resolve_package_from_toml(&toml_path, processed, lock)?;
+                (Dependency::Remote { package }, Some(version))
             }
         };
 
         // Cannot depend on a binary
         // TODO: Can we depend upon contracts?
         if dep.is_binary() {
-            Err(ManifestError::BinaryDependency(dep.package_name().clone()))
-        } else {
-            Ok(dep)
+            return Err(ManifestError::BinaryDependency(dep.package_name().clone()));
         }
+
+        if let Some(version) = version {
+            let (Dependency::Local { package } | Dependency::Remote { package }) = &dep;
+            semver::semver_check_dependency(&name.to_string(), version, package)
+                .map_err(ManifestError::SemverError)?;
+        }
+
+        Ok(dep)
     }
 }
 
 fn toml_to_workspace(
     nargo_toml: NargoToml,
     package_selection: PackageSelection,
+    lock: &mut DependencyLock,
 ) -> Result<Workspace, ManifestError> {
     let mut resolved = Vec::new();
     let workspace = match nargo_toml.config {
         Config::Package { package_config } => {
-            let member = package_config.resolve_to_package(&nargo_toml.root_dir, &mut resolved)?;
+            let member =
+                package_config.resolve_to_package(&nargo_toml.root_dir, &mut resolved, lock)?;
             match &package_selection {
                 PackageSelection::Selected(selected_name) if selected_name != &member.name => {
                     return Err(ManifestError::MissingSelectedPackage(member.name))
@@ -354,7 +403,7 @@ fn toml_to_workspace(
             for (index, member_path) in workspace_config.members.into_iter().enumerate() {
                 let package_root_dir = nargo_toml.root_dir.join(&member_path);
                 let package_toml_path = package_root_dir.join("Nargo.toml");
-                let member = resolve_package_from_toml(&package_toml_path, &mut resolved)?;
+                let member = resolve_package_from_toml(&package_toml_path, &mut resolved, lock)?;
 
                 match &package_selection {
                     PackageSelection::Selected(selected_name) => {
@@ -414,6 +463,7 @@ fn read_toml(toml_path: &Path) -> Result<NargoToml, ManifestError> {
 fn resolve_package_from_toml(
     toml_path: &Path,
     processed: &mut Vec<String>,
+    lock: &mut DependencyLock,
 ) -> Result<Package, ManifestError> {
     // Checks for cyclic dependencies
     let str_path = toml_path.to_str().expect("ICE - path is empty");
@@ -438,7 +488,7 @@ fn resolve_package_from_toml(
 
     let result = match nargo_toml.config {
         Config::Package { package_config } => {
-            package_config.resolve_to_package(&nargo_toml.root_dir, processed)
+            package_config.resolve_to_package(&nargo_toml.root_dir, processed, lock)
         }
         Config::Workspace { .. } => {
             Err(ManifestError::UnexpectedWorkspace(toml_path.to_path_buf()))
@@ -458,19 +508,58 @@ pub enum PackageSelection {
 }
 
 /// Resolves a Nargo.toml file into a `Workspace` struct as defined by our `nargo` core.
+///
+/// Git dependencies are resolved to the commits pinned by the workspace's `Nargo.lock`,
+/// which is updated with any new dependencies unless `lock_mode` is [`LockMode::Locked`].
+/// All dependencies on the same git package must be resolved to the same commit.
 pub fn resolve_workspace_from_toml(
     toml_path: &Path,
     package_selection: PackageSelection,
     current_compiler_version: Option<String>,
+    lock_mode: LockMode,
 ) -> Result<Workspace, ManifestError> {
     let nargo_toml = read_toml(toml_path)?;
-    let workspace = toml_to_workspace(nargo_toml, package_selection)?;
+    let sources = SourceConfig::read(&nargo_toml.root_dir)?;
+    let mut lock = DependencyLock::read(&nargo_toml.root_dir, lock_mode, sources)?;
+    let workspace = toml_to_workspace(nargo_toml, package_selection, &mut lock)?;
+    lock.check_conflicts()?;
+    lock.finish()?;
     if let Some(current_compiler_version) = current_compiler_version {
         semver::semver_check_workspace(&workspace, current_compiler_version)?;
     }
     Ok(workspace)
 }
 
+/// Copies the sources of all git and registry dependencies of the workspace at `toml_path` into
+/// `vendor_dir`, relative to the workspace root, and configures the workspace to use these copies
+/// instead of fetching its dependencies.
+///
+/// Returns the number of sources which were vendored.
+pub fn vendor_workspace(
+    toml_path: &Path,
+    vendor_dir: &Path,
+    lock_mode: LockMode,
+) -> Result<usize, ManifestError> {
+    let nargo_toml = read_toml(toml_path)?;
+    let root_dir = nargo_toml.root_dir.clone();
+
+    // Dependencies are fetched again rather than copied from the current vendor directory,
+    // so that new dependencies can be vendored.
+    let sources = SourceConfig::read(&root_dir)?;
+    let current_vendor_dir = sources.vendor.clone();
+    let mut lock =
+        DependencyLock::read(&root_dir, lock_mode, SourceConfig { vendor: None, ..sources })?;
+    toml_to_workspace(nargo_toml, PackageSelection::All, &mut lock)?;
+    lock.check_conflicts()?;
+    let vendored_sources = lock.vendored_sources().clone();
+    lock.finish()?;
+
+    let vendor_path = root_dir.join(vendor_dir);
+    vendor::vendor_sources(&vendored_sources, &vendor_path, current_vendor_dir.as_deref())?;
+    SourceConfig::write_vendor(&root_dir, vendor_dir)?;
+    Ok(vendored_sources.len())
+}
+
 #[test]
 fn parse_standard_toml() {
     let src = r#"
@@ -525,3 +614,62 @@ fn parse_workspace_default_member_toml() {
     assert!(Config::try_from(String::from(src)).is_ok());
     assert!(Config::try_from(src).is_ok());
 }
+
+#[test]
+fn parse_git_dependency_references() {
+    let src = r#"
+        [package]
+        name = "test"
+        type = "bin"
+
+        [dependencies]
+        tagged = { tag = "v0.1.0", git = "https://github.com/noir-lang/example", version = "^0.1" }
+        branch = { branch = "main", git = "https://github.com/noir-lang/example" }
+        rev = { rev = "0123456789abcdef0123456789abcdef01234567", git = "https://github.com/noir-lang/example" }
+        local = { path = "../local", version = "0.2.0" }
+        registry = { version = "^0.2" }
+    "#;
+
+    let Ok(Config::Package { package_config }) = Config::try_from(src) else {
+        panic!("expected a package config");
+    };
+    assert!(matches!(
+        &package_config.dependencies["branch"],
+        DependencyConfig::Github { tag: None, branch: Some(_), rev: None, .. }
+    ));
+    assert!(matches!(
+        &package_config.dependencies["local"],
+        DependencyConfig::Path { version: Some(_), .. }
+    ));
+    assert!(matches!(&package_config.dependencies["registry"], DependencyConfig::Registry { .. }));
+}
+
+#[test]
+fn reject_invalid_git_references() {
+    let workspace = std::env::temp_dir();
+    let mut lock =
+        DependencyLock::read(&workspace.join("no_lockfile"), LockMode::Update, Default::default())
+            .unwrap();
+    let name: CrateName = "dep".parse().unwrap();
+    let git_dependency =
+        |tag: Option<&str>, branch: Option<&str>, rev: Option<&str>| DependencyConfig::Github {
+            git: "https://github.com/noir-lang/example".to_string(),
+            tag: tag.map(str::to_string),
+            branch: branch.map(str::to_string),
+            rev: rev.map(str::to_string),
+            directory: None,
+            version: None,
+        };
+
+    let ambiguous = git_dependency(Some("v0.1.0"), Some("main"), None);
+    let result = ambiguous.resolve_to_dependency(&name, &workspace, &mut Vec::new(), &mut lock);
+    assert!(matches!(result, Err(ManifestError::InvalidGitReference { .. })));
+
+    let missing = git_dependency(None, None, None);
+    let result = missing.resolve_to_dependency(&name, &workspace, &mut Vec::new(), &mut lock);
+    assert!(matches!(result, Err(ManifestError::InvalidGitReference { .. })));
+
+    let short_rev = git_dependency(None, None, Some("0123abc"));
+    let result = short_rev.resolve_to_dependency(&name, &workspace, &mut Vec::new(), &mut lock);
+    assert!(matches!(result, Err(ManifestError::InvalidGitRevision { .. })));
+}
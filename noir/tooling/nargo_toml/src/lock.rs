@@ -0,0 +1,683 @@
+//! `Nargo.lock` pins each git and registry dependency of a workspace, including transitive ones, to the
+//! commit or version it was resolved to along with a checksum of its source tree.
+//!
+//! Dependencies are resolved to their locked commit even if their tag has since moved, and a source tree
+//! which doesn't match its checksum, such as a corrupted checkout, is rejected.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use fm::NormalizePath;
+use semver::VersionReq;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::errors::{ManifestError, SemverError};
+use crate::git::{
+    clone_git_repo, fetch_git_commit, git_branch_commit, git_commit_hash, nargo_crates,
+    GitReference,
+};
+use crate::registry::Registry;
+use crate::source::SourceConfig;
+use crate::vendor::{git_vendor_name, registry_vendor_name};
+
+const LOCKFILE_NAME: &str = "Nargo.lock";
+
+/// The version of the lockfile format.
+const LOCKFILE_VERSION: u32 = 1;
+
+const LOCKFILE_HEADER: &str = "# This file is automatically generated by nargo.
+# It is not intended for manual editing.
+";
+
+/// Whether resolving a workspace may update its `Nargo.lock`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LockMode {
+    /// Dependencies missing from `Nargo.lock` are added to it, and dependencies which are no longer
+    /// used are removed from it.
+    #[default]
+    Update,
+    /// `Nargo.lock` must already pin exactly the git and registry dependencies of the workspace.
+    Locked,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Lockfile {
+    version: u32,
+    #[serde(default, rename = "dependency", skip_serializing_if = "Vec::is_empty")]
+    dependencies: Vec<LockedDependency>,
+    #[serde(default, rename = "registry-dependency", skip_serializing_if = "Vec::is_empty")]
+    registry_dependencies: Vec<LockedRegistryDependency>,
+}
+
+/// Identifies a git dependency by its source, as written in `Nargo.toml`.
+type DependencyKey = (String, GitReference, Option<String>);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct LockedDependency {
+    git: String,
+    #[serde(flatten)]
+    reference: GitReference,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    directory: Option<String>,
+    commit: String,
+    checksum: String,
+}
+
+impl LockedDependency {
+    fn key(&self) -> DependencyKey {
+        (self.git.clone(), self.reference.clone(), self.directory.clone())
+    }
+}
+
+/// Identifies a registry dependency by its name and version requirement, as written in `Nargo.toml`.
+type RegistryKey = (String, String);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct LockedRegistryDependency {
+    name: String,
+    requirement: String,
+    version: String,
+    checksum: String,
+}
+
+impl LockedRegistryDependency {
+    fn key(&self) -> RegistryKey {
+        (self.name.clone(), self.requirement.clone())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct LockedDependencies {
+    git: BTreeMap<DependencyKey, LockedDependency>,
+    registry: BTreeMap<RegistryKey, LockedRegistryDependency>,
+}
+
+impl LockedDependencies {
+    fn is_empty(&self) -> bool {
+        self.git.is_empty() && self.registry.is_empty()
+    }
+}
+
+/// Resolves the git and registry dependencies of a workspace according to its `Nargo.lock`, recording
+/// them so that the lockfile can be updated once the whole workspace has been resolved.
+pub(crate) struct DependencyLock {
+    path: PathBuf,
+    mode: LockMode,
+    sources: SourceConfig,
+    /// The registry configured in `sources`, which is read once it is first needed.
+    registry: Option<Registry>,
+    /// The dependencies pinned by the existing lockfile, or `None` if there is no lockfile.
+    locked: Option<LockedDependencies>,
+    resolved: LockedDependencies,
+    /// The manifests of the packages which depend on each resolved dependency.
+    dependents: BTreeMap<DependencyKey, BTreeSet<PathBuf>>,
+    registry_dependents: BTreeMap<RegistryKey, BTreeSet<PathBuf>>,
+    /// The sources of the resolved dependencies, by the name of the directory they are vendored into.
+    vendored_sources: BTreeMap<String, PathBuf>,
+}
+
+impl DependencyLock {
+    /// Reads the lockfile of the workspace rooted at `workspace_root`, if it exists.
+    pub(crate) fn read(
+        workspace_root: &Path,
+        mode: LockMode,
+        sources: SourceConfig,
+    ) -> Result<Self, ManifestError> {
+        let path = workspace_root.join(LOCKFILE_NAME);
+        let locked = if path.exists() {
+            let contents = std::fs::read_to_string(&path)
+                .map_err(|_| ManifestError::ReadFailed(path.clone()))?;
+            let lockfile: Lockfile = toml::from_str(&contents).map_err(|error| {
+                ManifestError::MalformedLockfile { lockfile: path.clone(), error }
+            })?;
+            if lockfile.version > LOCKFILE_VERSION {
+                return Err(ManifestError::UnsupportedLockfileVersion {
+                    lockfile: path,
+                    version: lockfile.version,
+                });
+            }
+            Some(LockedDependencies {
+                git: lockfile.dependencies.into_iter().map(|dep| (dep.key(), dep)).collect(),
+                registry: lockfile
+                    .registry_dependencies
+                    .into_iter()
+                    .map(|dep| (dep.key(), dep))
+                    .collect(),
+            })
+        } else {
+            None
+        };
+
+        Ok(DependencyLock {
+            path,
+            mode,
+            sources,
+            registry: None,
+            locked,
+            resolved: LockedDependencies::default(),
+            dependents: BTreeMap::new(),
+            registry_dependents: BTreeMap::new(),
+            vendored_sources: BTreeMap::new(),
+        })
+    }
+
+    /// Checks out the `directory` of the repository at `url` at `reference`, or at the commit pinned by
+    /// the lockfile, returning the path of the dependency's package.
+    pub(crate) fn resolve_git_dependency(
+        &mut self,
+        pkg_root: &Path,
+        url: &str,
+        reference: &GitReference,
+        directory: Option<&String>,
+    ) -> Result<PathBuf, ManifestError> {
+        let key = (url.to_string(), reference.clone(), directory.cloned());
+        let locked = self.locked.as_ref().and_then(|locked| locked.git.get(&key)).cloned();
+
+        let (checkout, commit) = match &self.sources.vendor {
+            Some(vendor) => {
+                // Vendored dependencies can only be found through the commits they are locked to.
+                let vendored = locked.as_ref().map(|locked| {
+                    (vendor.join(git_vendor_name(url, &locked.commit)), locked.commit.clone())
+                });
+                match vendored {
+                    Some((checkout, commit)) if checkout.is_dir() => (checkout, commit),
+                    _ => {
+                        return Err(ManifestError::NotVendored {
+                            dependency: format!("{url} at {reference}"),
+                            vendor: vendor.clone(),
+                        })
+                    }
+                }
+            }
+            None => checkout_git_dependency(url, reference, locked.as_ref())?,
+        };
+
+        let project_path = if let Some(directory) = directory {
+            let internal_path = checkout.join(directory).normalize();
+            if !internal_path.starts_with(&checkout) {
+                return Err(ManifestError::InvalidDirectory {
+                    toml: pkg_root.join("Nargo.toml"),
+                    directory: directory.into(),
+                });
+            }
+            internal_path
+        } else {
+            checkout.clone()
+        };
+
+        let checksum = source_checksum(&project_path)
+            .map_err(|_| ManifestError::ReadFailed(project_path.clone()))?;
+        if let Some(locked) = locked {
+            if locked.commit != commit || locked.checksum != checksum {
+                return Err(ManifestError::ChecksumMismatch {
+                    path: project_path,
+                    expected: format!("{} ({})", locked.checksum, locked.commit),
+                    found: format!("{checksum} ({commit})"),
+                });
+            }
+        }
+
+        // The whole repository is vendored so that paths between its packages keep working.
+        self.vendored_sources.insert(git_vendor_name(url, &commit), checkout);
+        let dependency = LockedDependency {
+            git: key.0.clone(),
+            reference: key.1.clone(),
+            directory: key.2.clone(),
+            commit,
+            checksum,
+        };
+        self.dependents.entry(key.clone()).or_default().insert(pkg_root.join("Nargo.toml"));
+        self.resolved.git.insert(key, dependency);
+        Ok(project_path)
+    }
+
+    /// Unpacks the package called `name` with the highest version in the registry which satisfies
+    /// `requirement`, or the version pinned by the lockfile, returning the path of the package.
+    pub(crate) fn resolve_registry_dependency(
+        &mut self,
+        pkg_root: &Path,
+        name: &str,
+        requirement: &str,
+    ) -> Result<PathBuf, ManifestError> {
+        let key = (name.to_string(), requirement.to_string());
+        let locked = self.locked.as_ref().and_then(|locked| locked.registry.get(&key)).cloned();
+
+        let (project_path, version) = match &self.sources.vendor {
+            Some(vendor) => {
+                let vendored = locked.as_ref().map(|locked| {
+                    (
+                        vendor.join(registry_vendor_name(name, &locked.version)),
+                        locked.version.clone(),
+                    )
+                });
+                match vendored {
+                    Some((project_path, version)) if project_path.is_dir() => {
+                        (project_path, version)
+                    }
+                    _ => {
+                        return Err(ManifestError::NotVendored {
+                            dependency: format!("{name} {requirement}"),
+                            vendor: vendor.clone(),
+                        })
+                    }
+                }
+            }
+            None => {
+                let registry = self.registry(pkg_root, name)?;
+                let package = match &locked {
+                    Some(locked) => registry.get(name, &locked.version),
+                    None => {
+                        let requirement = VersionReq::parse(requirement).map_err(|err| {
+                            ManifestError::SemverError(
+                                SemverError::CouldNotParseDependencyVersion {
+                                    dependency_name: name.to_string(),
+                                    error: err.to_string(),
+                                },
+                            )
+                        })?;
+                        registry.select(name, &requirement)
+                    }
+                }
+                .ok_or_else(|| ManifestError::MissingRegistryPackage {
+                    name: name.to_string(),
+                    version: locked
+                        .as_ref()
+                        .map_or(requirement, |locked| &locked.version)
+                        .to_string(),
+                })?;
+                let project_path = registry.unpack(package, &nargo_crates().join("registry"))?;
+                (project_path, package.version.clone())
+            }
+        };
+
+        let checksum = source_checksum(&project_path)
+            .map_err(|_| ManifestError::ReadFailed(project_path.clone()))?;
+        if let Some(locked) = locked {
+            if locked.version != version || locked.checksum != checksum {
+                return Err(ManifestError::ChecksumMismatch {
+                    path: project_path,
+                    expected: format!("{} ({})", locked.checksum, locked.version),
+                    found: format!("{checksum} ({version})"),
+                });
+            }
+        }
+
+        self.vendored_sources.insert(registry_vendor_name(name, &version), project_path.clone());
+        let dependency = LockedRegistryDependency {
+            name: key.0.clone(),
+            requirement: key.1.clone(),
+            version,
+            checksum,
+        };
+        self.registry_dependents
+            .entry(key.clone())
+            .or_default()
+            .insert(pkg_root.join("Nargo.toml"));
+        self.resolved.registry.insert(key, dependency);
+        Ok(project_path)
+    }
+
+    fn registry(&mut self, pkg_root: &Path, name: &str) -> Result<&Registry, ManifestError> {
+        if self.registry.is_none() {
+            let root =
+                self.sources.registry.as_ref().ok_or_else(|| ManifestError::MissingRegistry {
+                    toml: pkg_root.join("Nargo.toml"),
+                    name: name.to_string(),
+                })?;
+            self.registry = Some(Registry::read(root)?);
+        }
+        Ok(self.registry.as_ref().expect("registry was read above"))
+    }
+
+    /// Returns the sources of the resolved dependencies, by the name of the directory they should be
+    /// vendored into.
+    pub(crate) fn vendored_sources(&self) -> &BTreeMap<String, PathBuf> {
+        &self.vendored_sources
+    }
+
+    /// Checks that every package from the same source was resolved to the same commit or version,
+    /// as otherwise the workspace would depend on multiple unrelated copies of it.
+    pub(crate) fn check_conflicts(&self) -> Result<(), ManifestError> {
+        let mut sources: BTreeMap<(&String, &Option<String>), Vec<&LockedDependency>> =
+            BTreeMap::new();
+        for dependency in self.resolved.git.values() {
+            sources.entry((&dependency.git, &dependency.directory)).or_default().push(dependency);
+        }
+
+        for ((git, directory), dependencies) in sources {
+            let commits: BTreeSet<_> = dependencies.iter().map(|dep| &dep.commit).collect();
+            if commits.len() < 2 {
+                continue;
+            }
+
+            let dependency = match directory {
+                Some(directory) => format!("{git} (directory {directory})"),
+                None => git.clone(),
+            };
+            let mut requirements = Vec::new();
+            for dependency in dependencies {
+                for dependent in &self.dependents[&dependency.key()] {
+                    requirements.push(format!(
+                        "{} requires {} (commit {})",
+                        dependent.display(),
+                        dependency.reference,
+                        dependency.commit
+                    ));
+                }
+            }
+            return Err(ManifestError::DependencyConflict {
+                dependency,
+                requirements: requirements.join("\n"),
+            });
+        }
+
+        let mut packages: BTreeMap<&String, Vec<&LockedRegistryDependency>> = BTreeMap::new();
+        for dependency in self.resolved.registry.values() {
+            packages.entry(&dependency.name).or_default().push(dependency);
+        }
+
+        for (name, dependencies) in packages {
+            let versions: BTreeSet<_> = dependencies.iter().map(|dep| &dep.version).collect();
+            if versions.len() < 2 {
+                continue;
+            }
+
+            let mut requirements = Vec::new();
+            for dependency in dependencies {
+                for dependent in &self.registry_dependents[&dependency.key()] {
+                    requirements.push(format!(
+                        "{} requires {name} {} (version {})",
+                        dependent.display(),
+                        dependency.requirement,
+                        dependency.version
+                    ));
+                }
+            }
+            return Err(ManifestError::DependencyConflict {
+                dependency: format!("registry package {name}"),
+                requirements: requirements.join("\n"),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Writes the dependencies which were resolved to the lockfile, or checks that the lockfile
+    /// already pins exactly these dependencies when using [`LockMode::Locked`].
+    pub(crate) fn finish(self) -> Result<(), ManifestError> {
+        let up_to_date = match &self.locked {
+            Some(locked) => locked == &self.resolved,
+            // Workspaces without git or registry dependencies don't need a lockfile.
+            None => self.resolved.is_empty(),
+        };
+        if up_to_date {
+            return Ok(());
+        }
+
+        match self.mode {
+            LockMode::Locked => Err(ManifestError::LockfileOutOfDate(self.path)),
+            LockMode::Update => {
+                let lockfile = Lockfile {
+                    version: LOCKFILE_VERSION,
+                    dependencies: self.resolved.git.into_values().collect(),
+                    registry_dependencies: self.resolved.registry.into_values().collect(),
+                };
+                let contents = toml::to_string(&lockfile).expect("lockfile must serialize to TOML");
+                std::fs::write(&self.path, format!("{LOCKFILE_HEADER}{contents}"))
+                    .map_err(|_| ManifestError::WriteFailed(self.path))
+            }
+        }
+    }
+}
+
+/// Checks out the repository at `url` at `reference`, or at the commit it was locked to if the reference
+/// has since moved, returning the checkout along with its commit.
+fn checkout_git_dependency(
+    url: &str,
+    reference: &GitReference,
+    locked: Option<&LockedDependency>,
+) -> Result<(PathBuf, String), ManifestError> {
+    let mut checkout = match (reference, locked) {
+        (GitReference::Tag(tag), _) => clone_git_repo(url, tag),
+        // The head of a branch is only looked up if it isn't locked, as it is expected to move.
+        (GitReference::Branch(_), Some(locked)) => fetch_git_commit(url, &locked.commit),
+        (GitReference::Branch(branch), None) => {
+            git_branch_commit(url, branch).and_then(|commit| fetch_git_commit(url, &commit))
+        }
+        (GitReference::Rev(rev), _) => fetch_git_commit(url, rev),
+    }
+    .map_err(ManifestError::GitError)?;
+    let mut commit = git_commit_hash(&checkout).map_err(ManifestError::GitError)?;
+    if let Some(locked) = locked {
+        if locked.commit != commit {
+            // The reference has moved since the dependency was locked.
+            checkout = fetch_git_commit(url, &locked.commit).map_err(ManifestError::GitError)?;
+            commit = git_commit_hash(&checkout).map_err(ManifestError::GitError)?;
+        }
+    }
+    Ok((checkout, commit))
+}
+
+/// Hashes the relative paths and contents of the files in `dir`, ignoring git metadata.
+fn source_checksum(dir: &Path) -> std::io::Result<String> {
+    let mut files = Vec::new();
+    collect_files(dir, &mut Vec::new(), &mut files)?;
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for (relative_path, path) in files {
+        let metadata = std::fs::symlink_metadata(&path)?;
+        let contents = if metadata.is_symlink() {
+            std::fs::read_link(&path)?.to_string_lossy().into_owned().into_bytes()
+        } else {
+            std::fs::read(&path)?
+        };
+        hasher.update(relative_path.as_bytes());
+        hasher.update([0]);
+        hasher.update((contents.len() as u64).to_le_bytes());
+        hasher.update(&contents);
+    }
+    Ok(format!("sha256:{}", hex::encode(hasher.finalize())))
+}
+
+/// Collects the files in `dir` along with their paths relative to the root directory,
+/// written with `/` separators so that checksums don't depend on the platform.
+fn collect_files(
+    dir: &Path,
+    components: &mut Vec<String>,
+    files: &mut Vec<(String, PathBuf)>,
+) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name == ".git" {
+            continue;
+        }
+        components.push(name);
+        if entry.file_type()?.is_dir() {
+            collect_files(&entry.path(), components, files)?;
+        } else {
+            files.push((components.join("/"), entry.path()));
+        }
+        components.pop();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{
+        source_checksum, DependencyLock, LockMode, LockedDependencies, LockedDependency,
+        LockedRegistryDependency, LOCKFILE_HEADER, LOCKFILE_NAME,
+    };
+    use crate::errors::ManifestError;
+    use crate::git::GitReference;
+    use crate::source::SourceConfig;
+
+    fn dependency() -> LockedDependency {
+        LockedDependency {
+            git: "https://github.com/noir-lang/example".to_string(),
+            reference: GitReference::Tag("v0.1.0".to_string()),
+            directory: Some("lib".to_string()),
+            commit: "0123456789abcdef0123456789abcdef01234567".to_string(),
+            checksum: "sha256:00".to_string(),
+        }
+    }
+
+    fn registry_dependency() -> LockedRegistryDependency {
+        LockedRegistryDependency {
+            name: "bigint".to_string(),
+            requirement: "^0.2".to_string(),
+            version: "0.2.1".to_string(),
+            checksum: "sha256:00".to_string(),
+        }
+    }
+
+    fn read(workspace: &std::path::Path, mode: LockMode) -> DependencyLock {
+        DependencyLock::read(workspace, mode, SourceConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn writes_and_reads_resolved_dependencies() {
+        let workspace = tempfile::tempdir().unwrap();
+
+        let mut lock = read(workspace.path(), LockMode::Update);
+        assert!(lock.locked.is_none());
+        lock.resolved.git.insert(dependency().key(), dependency());
+        lock.resolved.registry.insert(registry_dependency().key(), registry_dependency());
+        lock.finish().unwrap();
+
+        let contents = std::fs::read_to_string(workspace.path().join(LOCKFILE_NAME)).unwrap();
+        assert!(contents.starts_with(LOCKFILE_HEADER));
+        assert!(contents.contains("[[dependency]]"));
+        assert!(contents.contains(r#"tag = "v0.1.0""#));
+        assert!(contents.contains("[[registry-dependency]]"));
+
+        // The lockfile is now up to date in locked mode.
+        let mut lock = read(workspace.path(), LockMode::Locked);
+        let expected = LockedDependencies {
+            git: BTreeMap::from([(dependency().key(), dependency())]),
+            registry: BTreeMap::from([(registry_dependency().key(), registry_dependency())]),
+        };
+        assert_eq!(lock.locked, Some(expected));
+        lock.resolved.git.insert(dependency().key(), dependency());
+        lock.resolved.registry.insert(registry_dependency().key(), registry_dependency());
+        lock.finish().unwrap();
+    }
+
+    #[test]
+    fn rejects_outdated_lockfiles_in_locked_mode() {
+        let workspace = tempfile::tempdir().unwrap();
+
+        // A missing lockfile is only an error if there are git or registry dependencies.
+        read(workspace.path(), LockMode::Locked).finish().unwrap();
+        assert!(!workspace.path().join(LOCKFILE_NAME).exists());
+
+        let mut lock = read(workspace.path(), LockMode::Locked);
+        lock.resolved.git.insert(dependency().key(), dependency());
+        assert!(matches!(lock.finish(), Err(ManifestError::LockfileOutOfDate(_))));
+        assert!(!workspace.path().join(LOCKFILE_NAME).exists());
+    }
+
+    fn resolve(lock: &mut DependencyLock, dependency: LockedDependency, dependent: &str) {
+        let manifest = lock.path.with_file_name(dependent).join("Nargo.toml");
+        lock.dependents.entry(dependency.key()).or_default().insert(manifest);
+        lock.resolved.git.insert(dependency.key(), dependency);
+    }
+
+    #[test]
+    fn detects_dependencies_resolved_to_different_commits() {
+        let workspace = tempfile::tempdir().unwrap();
+        let mut lock = read(workspace.path(), LockMode::Update);
+
+        resolve(&mut lock, dependency(), "a");
+        // A branch pointing at the same commit refers to the same sources.
+        let branch = LockedDependency {
+            reference: GitReference::Branch("main".to_string()),
+            ..dependency()
+        };
+        resolve(&mut lock, branch, "b");
+        // A different package from the same repository.
+        let other_package = LockedDependency {
+            reference: GitReference::Tag("v0.2.0".to_string()),
+            directory: None,
+            commit: "1123456789abcdef0123456789abcdef01234567".to_string(),
+            ..dependency()
+        };
+        resolve(&mut lock, other_package, "c");
+        lock.check_conflicts().unwrap();
+
+        let conflicting = LockedDependency {
+            reference: GitReference::Tag("v0.2.0".to_string()),
+            commit: "1123456789abcdef0123456789abcdef01234567".to_string(),
+            ..dependency()
+        };
+        resolve(&mut lock, conflicting, "d");
+        let Err(ManifestError::DependencyConflict { dependency, requirements }) =
+            lock.check_conflicts()
+        else {
+            panic!("expected a dependency conflict");
+        };
+        assert_eq!(dependency, "https://github.com/noir-lang/example (directory lib)");
+        assert_eq!(requirements.lines().count(), 3);
+        assert!(requirements.contains("requires tag v0.2.0 (commit 1123456789"));
+    }
+
+    #[test]
+    fn detects_registry_dependencies_resolved_to_different_versions() {
+        let workspace = tempfile::tempdir().unwrap();
+        let mut lock = read(workspace.path(), LockMode::Update);
+
+        let mut resolve = |dependency: LockedRegistryDependency, dependent: &str| {
+            let manifest = workspace.path().join(dependent).join("Nargo.toml");
+            lock.registry_dependents.entry(dependency.key()).or_default().insert(manifest);
+            lock.resolved.registry.insert(dependency.key(), dependency);
+        };
+        resolve(registry_dependency(), "a");
+        resolve(
+            LockedRegistryDependency { requirement: "=0.2.1".to_string(), ..registry_dependency() },
+            "b",
+        );
+        resolve(
+            LockedRegistryDependency {
+                requirement: "^0.3".to_string(),
+                version: "0.3.0".to_string(),
+                ..registry_dependency()
+            },
+            "c",
+        );
+
+        let Err(ManifestError::DependencyConflict { dependency, requirements }) =
+            lock.check_conflicts()
+        else {
+            panic!("expected a dependency conflict");
+        };
+        assert_eq!(dependency, "registry package bigint");
+        assert_eq!(requirements.lines().count(), 3);
+        assert!(requirements.contains("requires bigint ^0.3 (version 0.3.0)"));
+    }
+
+    #[test]
+    fn checksums_depend_on_paths_and_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("Nargo.toml"), "[package]").unwrap();
+        std::fs::write(dir.path().join("src").join("lib.nr"), "fn foo() {}").unwrap();
+        let checksum = source_checksum(dir.path()).unwrap();
+
+        // Git metadata is ignored.
+        std::fs::create_dir_all(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join(".git").join("HEAD"), "ref: refs/heads/main").unwrap();
+        assert_eq!(source_checksum(dir.path()).unwrap(), checksum);
+
+        std::fs::write(dir.path().join("src").join("lib.nr"), "fn bar() {}").unwrap();
+        assert_ne!(source_checksum(dir.path()).unwrap(), checksum);
+
+        std::fs::write(dir.path().join("src").join("lib.nr"), "fn foo() {}").unwrap();
+        std::fs::rename(dir.path().join("src"), dir.path().join("lib")).unwrap();
+        assert_ne!(source_checksum(dir.path()).unwrap(), checksum);
+    }
+}
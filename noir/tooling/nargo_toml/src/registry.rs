@@ -0,0 +1,243 @@
+//! A package registry on the local file system, from which packages can be depended on by name and version.
+//!
+//! A registry is a directory containing an `index.toml` which lists its packages,
+//!
+//! ```toml
+//! [[package]]
+//! name = "bigint"
+//! version = "0.2.1"
+//! checksum = "sha256:<hex>"
+//! ```
+//!
+//! along with a gzipped tarball of each package, such as `bigint-0.2.1.tar.gz`, which contains the package
+//! in a `bigint-0.2.1` directory. The checksum of a package is the SHA-256 hash of its tarball.
+
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tar::Archive;
+
+use crate::errors::ManifestError;
+
+const INDEX_NAME: &str = "index.toml";
+
+#[derive(Debug, Deserialize)]
+struct RegistryIndex {
+    #[serde(default, rename = "package")]
+    packages: Vec<RegistryPackage>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct RegistryPackage {
+    pub(crate) name: String,
+    pub(crate) version: String,
+    checksum: String,
+}
+
+impl RegistryPackage {
+    fn archive_name(&self) -> String {
+        format!("{}-{}.tar.gz", self.name, self.version)
+    }
+
+    fn directory_name(&self) -> String {
+        format!("{}-{}", self.name, self.version)
+    }
+}
+
+pub(crate) struct Registry {
+    root: PathBuf,
+    packages: Vec<(Version, RegistryPackage)>,
+}
+
+impl Registry {
+    /// Reads the index of the registry at `root`.
+    pub(crate) fn read(root: &Path) -> Result<Self, ManifestError> {
+        let index_path = root.join(INDEX_NAME);
+        let contents = std::fs::read_to_string(&index_path)
+            .map_err(|_| ManifestError::ReadFailed(index_path.clone()))?;
+        let index: RegistryIndex = toml::from_str(&contents)
+            .map_err(|error| ManifestError::MalformedRegistryIndex { index: index_path, error })?;
+
+        // Packages without a valid version can't be depended upon.
+        let packages = index
+            .packages
+            .into_iter()
+            .filter_map(|package| Some((Version::parse(&package.version).ok()?, package)))
+            .collect();
+        Ok(Registry { root: root.to_path_buf(), packages })
+    }
+
+    /// Returns the package called `name` with the highest version satisfying `requirement`.
+    pub(crate) fn select(&self, name: &str, requirement: &VersionReq) -> Option<&RegistryPackage> {
+        self.packages
+            .iter()
+            .filter(|(version, package)| package.name == name && requirement.matches(version))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, package)| package)
+    }
+
+    /// Returns the package called `name` at exactly `version`.
+    pub(crate) fn get(&self, name: &str, version: &str) -> Option<&RegistryPackage> {
+        self.packages
+            .iter()
+            .map(|(_, package)| package)
+            .find(|package| package.name == name && package.version == version)
+    }
+
+    /// Unpacks the tarball of `package` into `cache_dir` after checking it against the index,
+    /// returning the path of the unpacked package.
+    pub(crate) fn unpack(
+        &self,
+        package: &RegistryPackage,
+        cache_dir: &Path,
+    ) -> Result<PathBuf, ManifestError> {
+        let expected = package.checksum.trim_start_matches("sha256:").to_lowercase();
+        let location = cache_dir.join(format!(
+            "{}-{}",
+            package.directory_name(),
+            &expected[..expected.len().min(16)]
+        ));
+        if location.exists() {
+            return Ok(location);
+        }
+
+        let archive_path = self.root.join(package.archive_name());
+        let archive = std::fs::read(&archive_path)
+            .map_err(|_| ManifestError::ReadFailed(archive_path.clone()))?;
+        let found = hex::encode(Sha256::digest(&archive));
+        if found != expected {
+            return Err(ManifestError::ChecksumMismatch {
+                path: archive_path,
+                expected: format!("sha256:{expected}"),
+                found: format!("sha256:{found}"),
+            });
+        }
+
+        // Unpack into a staging directory so that an interrupted unpack isn't mistaken for a complete one.
+        let staging = cache_dir.join(format!(".{}.partial", package.directory_name()));
+        let _ = std::fs::remove_dir_all(&staging);
+        std::fs::create_dir_all(&staging)
+            .map_err(|_| ManifestError::WriteFailed(staging.clone()))?;
+        let unpacked = Archive::new(GzDecoder::new(archive.as_slice())).unpack(&staging);
+
+        let package_dir = staging.join(package.directory_name());
+        let result = match unpacked {
+            Ok(()) if package_dir.is_dir() => std::fs::rename(&package_dir, &location)
+                .map_err(|_| ManifestError::WriteFailed(location.clone())),
+            _ => Err(ManifestError::InvalidRegistryPackage {
+                archive: archive_path,
+                directory: package.directory_name(),
+            }),
+        };
+        let _ = std::fs::remove_dir_all(&staging);
+        result.map(|_| location)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use flate2::{write::GzEncoder, Compression};
+    use semver::VersionReq;
+    use sha2::{Digest, Sha256};
+
+    use super::Registry;
+    use crate::errors::ManifestError;
+
+    fn write_package(registry: &std::path::Path, name: &str, version: &str) -> String {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        let manifest =
+            format!("[package]\nname = \"{name}\"\nversion = \"{version}\"\ntype = \"lib\"\n");
+        let mut header = tar::Header::new_gnu();
+        header.set_size(manifest.len() as u64);
+        header.set_mode(0o644);
+        builder
+            .append_data(&mut header, format!("{name}-{version}/Nargo.toml"), manifest.as_bytes())
+            .unwrap();
+        let archive = builder.into_inner().unwrap().finish().unwrap();
+        std::fs::write(registry.join(format!("{name}-{version}.tar.gz")), &archive).unwrap();
+        format!("sha256:{}", hex::encode(Sha256::digest(&archive)))
+    }
+
+    #[test]
+    fn selects_the_highest_matching_version() {
+        let registry = tempfile::tempdir().unwrap();
+        std::fs::write(
+            registry.path().join("index.toml"),
+            r#"
+            [[package]]
+            name = "bigint"
+            version = "0.1.0"
+            checksum = "sha256:00"
+
+            [[package]]
+            name = "bigint"
+            version = "0.1.3"
+            checksum = "sha256:00"
+
+            [[package]]
+            name = "bigint"
+            version = "0.2.0"
+            checksum = "sha256:00"
+
+            [[package]]
+            name = "other"
+            version = "0.1.5"
+            checksum = "sha256:00"
+            "#,
+        )
+        .unwrap();
+        let registry = Registry::read(registry.path()).unwrap();
+
+        let select = |requirement: &str| {
+            let requirement = VersionReq::parse(requirement).unwrap();
+            registry.select("bigint", &requirement).map(|package| package.version.clone())
+        };
+        assert_eq!(select("^0.1").as_deref(), Some("0.1.3"));
+        assert_eq!(select("*").as_deref(), Some("0.2.0"));
+        assert_eq!(select("=0.1.0").as_deref(), Some("0.1.0"));
+        assert_eq!(select("^0.3"), None);
+        assert!(registry.get("bigint", "0.1.0").is_some());
+        assert!(registry.get("bigint", "0.1.5").is_none());
+    }
+
+    #[test]
+    fn unpacks_packages_matching_their_checksum() {
+        let registry_dir = tempfile::tempdir().unwrap();
+        let cache_dir = tempfile::tempdir().unwrap();
+        let version = "0.1.0";
+        let checksum = write_package(registry_dir.path(), "registry_test", version);
+        let index = format!(
+            "[[package]]\nname = \"registry_test\"\nversion = \"{version}\"\nchecksum = \"{checksum}\"\n\n[[package]]\nname = \"registry_test\"\nversion = \"1.0.0\"\nchecksum = \"{checksum}\"\n"
+        );
+        std::fs::write(registry_dir.path().join("index.toml"), index).unwrap();
+        std::fs::copy(
+            registry_dir.path().join(format!("registry_test-{version}.tar.gz")),
+            registry_dir.path().join("registry_test-1.0.0.tar.gz"),
+        )
+        .unwrap();
+        let registry = Registry::read(registry_dir.path()).unwrap();
+
+        let package = registry.get("registry_test", version).unwrap();
+        let unpacked = registry.unpack(package, cache_dir.path()).unwrap();
+        assert!(unpacked.join("Nargo.toml").is_file());
+        std::fs::remove_dir_all(unpacked).unwrap();
+
+        // The tarball of version 1.0.0 contains a directory for another version.
+        let package = registry.get("registry_test", "1.0.0").unwrap();
+        assert!(matches!(
+            registry.unpack(package, cache_dir.path()),
+            Err(ManifestError::InvalidRegistryPackage { .. })
+        ));
+
+        std::fs::write(registry_dir.path().join(format!("registry_test-{version}.tar.gz")), "")
+            .unwrap();
+        let package = registry.get("registry_test", version).unwrap();
+        assert!(matches!(
+            registry.unpack(package, cache_dir.path()),
+            Err(ManifestError::ChecksumMismatch { .. })
+        ));
+    }
+}
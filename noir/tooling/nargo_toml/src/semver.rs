@@ -61,6 +61,44 @@ pub(crate) fn semver_check_package(
     Ok(())
 }
 
+// Check that the version of the package resolved for a dependency satisfies the dependency's version requirement
+pub(crate) fn semver_check_dependency(
+    dependency_name: &str,
+    required_version: &str,
+    package: &Package,
+) -> Result<(), SemverError> {
+    let version_req = VersionReq::parse(required_version).map_err(|err| {
+        SemverError::CouldNotParseDependencyVersion {
+            dependency_name: dependency_name.to_string(),
+            error: err.to_string(),
+        }
+    })?;
+    let Some(version) = &package.version else {
+        return Err(SemverError::MissingDependencyVersion {
+            dependency_name: dependency_name.to_string(),
+            package_name: package.name.clone(),
+            required_version: required_version.to_string(),
+        });
+    };
+    let parsed_version = parse_semver_compatible_version(version).map_err(|err| {
+        SemverError::CouldNotParsePackageVersion {
+            package_name: package.name.clone().into(),
+            error: err.to_string(),
+        }
+    })?;
+
+    if !version_req.matches(&parsed_version) {
+        return Err(SemverError::IncompatibleDependencyVersion {
+            dependency_name: dependency_name.to_string(),
+            package_name: package.name.clone(),
+            required_version: required_version.to_string(),
+            version_found: version.clone(),
+        });
+    }
+
+    Ok(())
+}
+
 // Strip the build meta data from the version string since it is ignored by semver.
 fn strip_build_meta_data(version: &Version) -> String {
     let version_string = version.to_string();
@@ -204,4 +242,35 @@ mod tests {
             panic!("semver check should have passed. compiler version is 0.1.0+build_data and required version from the package is 0.1.0\n The build data should be ignored\n error: {err:?}")
         };
     }
+
+    #[test]
+    fn test_semver_dependency_version() {
+        let mut package = Package {
+            compiler_required_version: None,
+            root_dir: PathBuf::new(),
+            package_type: PackageType::Library,
+            entry_path: PathBuf::new(),
+            name: CrateName::from_str("lib").unwrap(),
+            dependencies: BTreeMap::new(),
+            version: Some("0.2.1".to_string()),
+        };
+
+        semver_check_dependency("dep", "^0.2.0", &package)
+            .expect("version 0.2.1 should satisfy the requirement ^0.2.0");
+
+        let got_err = semver_check_dependency("dep", "^0.3.0", &package)
+            .expect_err("version 0.2.1 should not satisfy the requirement ^0.3.0");
+        let expected_version_error = SemverError::IncompatibleDependencyVersion {
+            dependency_name: "dep".to_string(),
+            package_name: CrateName::from_str("lib").unwrap(),
+            required_version: "^0.3.0".to_string(),
+            version_found: "0.2.1".to_string(),
+        };
+        assert_eq!(got_err, expected_version_error);
+
+        package.version = None;
+        let got_err = semver_check_dependency("dep", "^0.2.0", &package)
+            .expect_err("a package without a version cannot satisfy a version requirement");
+        assert!(matches!(got_err, SemverError::MissingDependencyVersion { .. }));
+    }
 }
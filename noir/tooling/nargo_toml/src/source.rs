@@ -0,0 +1,81 @@
+//! `.nargo/config.toml` configures where the dependencies of a workspace are fetched from.
+//!
+//! ```toml
+//! [source]
+//! # Use the copies of git and registry dependencies made by `nargo vendor` instead of fetching them.
+//! vendor = "vendor"
+//! # Resolve registry dependencies from a registry on the local file system.
+//! registry = "/srv/noir-registry"
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::ManifestError;
+
+const CONFIG_DIR: &str = ".nargo";
+const CONFIG_NAME: &str = "config.toml";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    source: SourceConfig,
+}
+
+/// The sources of a workspace's dependencies, with paths relative to the workspace root.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SourceConfig {
+    /// The directory containing vendored copies of the workspace's git and registry dependencies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) vendor: Option<PathBuf>,
+    /// The directory of a local package registry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) registry: Option<PathBuf>,
+}
+
+impl SourceConfig {
+    /// Reads the source configuration of the workspace rooted at `workspace_root`, resolving
+    /// its paths against the workspace root.
+    pub(crate) fn read(workspace_root: &Path) -> Result<Self, ManifestError> {
+        let path = config_path(workspace_root);
+        if !path.exists() {
+            return Ok(SourceConfig::default());
+        }
+
+        let contents =
+            std::fs::read_to_string(&path).map_err(|_| ManifestError::ReadFailed(path.clone()))?;
+        let config: ConfigFile = toml::from_str(&contents)
+            .map_err(|error| ManifestError::MalformedConfig { config: path, error })?;
+
+        let source = config.source;
+        Ok(SourceConfig {
+            vendor: source.vendor.map(|vendor| workspace_root.join(vendor)),
+            registry: source.registry.map(|registry| workspace_root.join(registry)),
+        })
+    }
+
+    /// Configures the workspace rooted at `workspace_root` to use the dependencies vendored in
+    /// `vendor`, keeping the rest of its source configuration.
+    pub(crate) fn write_vendor(workspace_root: &Path, vendor: &Path) -> Result<(), ManifestError> {
+        let path = config_path(workspace_root);
+        let mut config = if path.exists() {
+            let contents = std::fs::read_to_string(&path)
+                .map_err(|_| ManifestError::ReadFailed(path.clone()))?;
+            toml::from_str(&contents)
+                .map_err(|error| ManifestError::MalformedConfig { config: path.clone(), error })?
+        } else {
+            ConfigFile::default()
+        };
+        config.source.vendor = Some(vendor.to_path_buf());
+
+        let contents = toml::to_string(&config).expect("config must serialize to TOML");
+        std::fs::create_dir_all(workspace_root.join(CONFIG_DIR))
+            .and_then(|_| std::fs::write(&path, contents))
+            .map_err(|_| ManifestError::WriteFailed(path))
+    }
+}
+
+fn config_path(workspace_root: &Path) -> PathBuf {
+    workspace_root.join(CONFIG_DIR).join(CONFIG_NAME)
+}
@@ -0,0 +1,126 @@
+//! `nargo vendor` copies the sources of a workspace's git and registry dependencies into a directory
+//! so that the workspace can be built without fetching them.
+//!
+//! Each source is vendored into a directory named after the commit or version it is locked to,
+//! which is how they are found again when resolving the workspace.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::errors::ManifestError;
+
+/// Returns the name of the directory into which the repository at `url` is vendored at `commit`.
+pub(crate) fn git_vendor_name(url: &str, commit: &str) -> String {
+    let repository = url.trim_end_matches('/').rsplit('/').next().unwrap_or(url);
+    let repository = repository.trim_end_matches(".git");
+    format!("{repository}-{}", &commit[..commit.len().min(12)])
+}
+
+/// Returns the name of the directory into which the registry package `name` is vendored at `version`.
+pub(crate) fn registry_vendor_name(name: &str, version: &str) -> String {
+    format!("{name}-{version}")
+}
+
+/// Replaces the contents of `vendor_dir` with copies of `sources`, keyed by the name of their
+/// vendored directory.
+///
+/// An existing `vendor_dir` is only replaced if it is the workspace's current vendor directory,
+/// or if it is empty.
+pub(crate) fn vendor_sources(
+    sources: &BTreeMap<String, PathBuf>,
+    vendor_dir: &Path,
+    current_vendor_dir: Option<&Path>,
+) -> Result<(), ManifestError> {
+    if vendor_dir.exists() {
+        let is_empty = std::fs::read_dir(vendor_dir)
+            .map_err(|_| ManifestError::ReadFailed(vendor_dir.to_path_buf()))?
+            .next()
+            .is_none();
+        if !is_empty && current_vendor_dir != Some(vendor_dir) {
+            return Err(ManifestError::VendorDirectoryNotEmpty(vendor_dir.to_path_buf()));
+        }
+        std::fs::remove_dir_all(vendor_dir)
+            .map_err(|_| ManifestError::WriteFailed(vendor_dir.to_path_buf()))?;
+    }
+
+    for (name, source) in sources {
+        let destination = vendor_dir.join(name);
+        copy_dir(source, &destination).map_err(|_| ManifestError::WriteFailed(destination))?;
+    }
+    std::fs::create_dir_all(vendor_dir)
+        .map_err(|_| ManifestError::WriteFailed(vendor_dir.to_path_buf()))
+}
+
+/// Recursively copies `source` to `destination`, leaving out git metadata.
+fn copy_dir(source: &Path, destination: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(destination)?;
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        if entry.file_name() == ".git" {
+            continue;
+        }
+
+        let path = entry.path();
+        let target = destination.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir(&path, &target)?;
+        } else if file_type.is_symlink() {
+            // Symlinks are kept as they are, as they are part of a source's checksum.
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(std::fs::read_link(&path)?, &target)?;
+            #[cfg(not(unix))]
+            std::fs::copy(&path, &target)?;
+        } else {
+            std::fs::copy(&path, &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{git_vendor_name, vendor_sources};
+    use crate::errors::ManifestError;
+
+    #[test]
+    fn names_vendored_git_sources_after_their_repository() {
+        let commit = "0123456789abcdef0123456789abcdef01234567";
+        assert_eq!(
+            git_vendor_name("https://github.com/noir-lang/example", commit),
+            "example-0123456789ab"
+        );
+        assert_eq!(
+            git_vendor_name("https://github.com/noir-lang/example.git/", commit),
+            "example-0123456789ab"
+        );
+    }
+
+    #[test]
+    fn only_replaces_vendor_directories() {
+        let workspace = tempfile::tempdir().unwrap();
+        let source = workspace.path().join("source");
+        std::fs::create_dir_all(source.join(".git")).unwrap();
+        std::fs::create_dir_all(source.join("src")).unwrap();
+        std::fs::write(source.join("src").join("lib.nr"), "fn foo() {}").unwrap();
+        let sources = BTreeMap::from([("example-0123456789ab".to_string(), source)]);
+
+        let vendor_dir = workspace.path().join("vendor");
+        vendor_sources(&sources, &vendor_dir, None).unwrap();
+        let vendored = vendor_dir.join("example-0123456789ab");
+        assert!(vendored.join("src").join("lib.nr").is_file());
+        assert!(!vendored.join(".git").exists());
+
+        // Only the current vendor directory is replaced once it has other contents.
+        std::fs::create_dir_all(vendor_dir.join("stale")).unwrap();
+        assert!(matches!(
+            vendor_sources(&sources, &vendor_dir, None),
+            Err(ManifestError::VendorDirectoryNotEmpty(_))
+        ));
+        vendor_sources(&sources, &vendor_dir, Some(&vendor_dir)).unwrap();
+        assert!(!vendor_dir.join("stale").exists());
+        assert!(vendored.join("src").join("lib.nr").is_file());
+    }
+}